@@ -76,6 +76,9 @@ pub enum Commands {
 
     /// Environment commands
     Environment(EnvironmentArgs),
+
+    /// Fuzz commands
+    Fuzz(FuzzArgs),
 }
 
 #[derive(Args)]
@@ -473,3 +476,51 @@ pub struct InstallPluginArgs {
     /// Local plugin directory path, or registry plugin spec (@org/plugin[@version])
     pub source: String,
 }
+
+#[derive(Args)]
+#[command(disable_help_subcommand = true)]
+pub struct FuzzArgs {
+    #[command(subcommand)]
+    pub command: FuzzCommands,
+}
+
+#[derive(Subcommand)]
+pub enum FuzzCommands {
+    /// Run a saved attack, writing each result as a line of JSON
+    Run {
+        /// Attack JSON file
+        path: PathBuf,
+
+        /// Workspace ID to run in, instead of the attack's
+        #[arg(long = "workspace", value_name = "WORKSPACE_ID")]
+        workspace_id: Option<String>,
+
+        /// Set an environment variable for this run only, e.g.
+        /// --var base_url=https://staging.example.com (repeatable)
+        #[arg(long = "var", value_name = "NAME=VALUE")]
+        vars: Vec<String>,
+
+        /// Write results to a file instead of stdout
+        #[arg(long, short, value_name = "FILE")]
+        output: Option<PathBuf>,
+
+        /// Exit with status 2 when any result matches
+        #[arg(long)]
+        fail_on_match: bool,
+    },
+
+    /// Work as an agent of the app's distributed runs, sending the payloads
+    /// it hands out until stopped
+    Agent {
+        /// Address the app listens for agents on, e.g. 10.0.0.2:7420
+        address: String,
+
+        /// Token the app showed when it started listening
+        #[arg(long)]
+        token: String,
+
+        /// Name the app lists the agent under (defaults to the hostname)
+        #[arg(long)]
+        name: Option<String>,
+    },
+}
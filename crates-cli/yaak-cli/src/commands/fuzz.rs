@@ -0,0 +1,198 @@
+use crate::context::{CliContext, CliExecutionContext};
+use std::fs;
+use std::path::Path;
+use tokio::sync::{mpsc, watch};
+use yaak_fuzz::agents::AgentClient;
+use yaak_fuzz::output::JsonlWriter;
+use yaak_fuzz::types::{FuzzEvent, FuzzRequest};
+use yaak_fuzz::{FuzzRunPersister, HttpFuzzSender, run_fuzz, validate_fuzz_request};
+use yaak_http::manager::HttpConnectionManager;
+use yaak_models::util::UpdateSource;
+use yaak_plugins::events::PluginContext;
+
+pub async fn run(
+    ctx: &CliContext,
+    request: FuzzRequest,
+    output: Option<&Path>,
+    fail_on_match: bool,
+) -> i32 {
+    match run_attack(ctx, request, output).await {
+        Ok(matched) if matched && fail_on_match => 2,
+        Ok(_) => 0,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            1
+        }
+    }
+}
+
+/// Pull and send shards from the app at `address` until the connection
+/// closes. Dropping out mid-shard hands the shard to another agent.
+pub async fn run_agent(ctx: &CliContext, address: &str, token: &str, name: Option<&str>) -> i32 {
+    match work_as_agent(ctx, address, token, name).await {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            1
+        }
+    }
+}
+
+/// Read a saved attack, running it in `workspace_id` and `environment` when
+/// they're given, with `vars` set over the environment's variables
+pub fn load_attack(
+    path: &Path,
+    workspace_id: Option<&str>,
+    environment: Option<&str>,
+    vars: &[String],
+) -> Result<FuzzRequest, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read attack {}: {e}", path.display()))?;
+    let mut request: FuzzRequest = serde_json::from_str(&contents)
+        .map_err(|e| format!("Failed to parse attack {}: {e}", path.display()))?;
+    if let Some(workspace_id) = workspace_id {
+        request.set_workspace_id(workspace_id);
+    }
+    if request.request.workspace_id.is_empty() {
+        return Err("The attack has no workspace, pass one with --workspace".to_string());
+    }
+    if let Some(environment) = environment {
+        request.environment_id = Some(environment.to_string());
+    }
+    for var in vars {
+        let (name, value) = var
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid --var {var}, expected NAME=VALUE"))?;
+        request.environment_overrides.insert(name.to_string(), value.to_string());
+    }
+    Ok(request)
+}
+
+/// Workspace and environment for plugins to render `request`'s templates
+/// with
+pub fn execution_context(request: &FuzzRequest) -> CliExecutionContext {
+    CliExecutionContext {
+        workspace_id: Some(request.request.workspace_id.clone()),
+        environment_id: request.environment_id.clone(),
+        ..Default::default()
+    }
+}
+
+/// Run `request` to the end, or until Ctrl-C, writing results as JSON lines
+/// to `output` or stdout. Returns whether any result matched.
+async fn run_attack(
+    ctx: &CliContext,
+    mut request: FuzzRequest,
+    output: Option<&Path>,
+) -> Result<bool, String> {
+    validate_fuzz_request(&request).map_err(|e| e.to_string())?;
+    ctx.db()
+        .get_workspace(&request.request.workspace_id)
+        .map_err(|e| format!("Failed to get workspace: {e}"))?;
+    request.pick_seeds();
+
+    let mut jsonl_writer = match output {
+        Some(path) => JsonlWriter::create(path).map_err(|e| e.to_string())?,
+        None => JsonlWriter::stdout(),
+    };
+    let mut persister = FuzzRunPersister::start(
+        ctx.query_manager().clone(),
+        ctx.blob_manager().clone(),
+        ctx.encryption_manager.clone(),
+        &request,
+        UpdateSource::Sync,
+    )
+    .map_err(|e| e.to_string())?;
+    if request.record_responses {
+        persister = persister.with_response_history(ctx.data_dir().join("responses"));
+    }
+    let run_id = persister.run_id().to_string();
+
+    let (cancel_tx, cancelled_rx) = watch::channel(false);
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            let _ = cancel_tx.send(true);
+        }
+    });
+
+    let connection_manager = HttpConnectionManager::new();
+    let plugin_context =
+        PluginContext::new(Some("cli".to_string()), Some(request.request.workspace_id.clone()));
+    let sender = HttpFuzzSender::new(
+        ctx.query_manager().clone(),
+        &connection_manager,
+        ctx.plugin_manager(),
+        ctx.encryption_manager.clone(),
+        &plugin_context,
+        request.environment_id.clone(),
+        cancelled_rx.clone(),
+    )
+    .with_config(&request)
+    .map_err(|e| e.to_string())?;
+
+    let (events_tx, mut events_rx) = mpsc::unbounded_channel::<FuzzEvent>();
+    let forward_handle = tokio::spawn(async move {
+        while let Some(mut event) = events_rx.recv().await {
+            if let Err(e) = persister.record(&mut event) {
+                eprintln!("Warning: Failed to persist fuzz event: {e}");
+            }
+            if let Err(e) = jsonl_writer.write(&event) {
+                eprintln!("Warning: Failed to write fuzz result: {e}");
+            }
+        }
+    });
+
+    let summary = run_fuzz(&run_id, &request, &sender, &events_tx, cancelled_rx).await;
+    if let Err(e) = sender.save_cookies() {
+        eprintln!("Warning: Failed to save fuzz run cookies: {e}");
+    }
+    drop(events_tx);
+    let _ = forward_handle.await;
+
+    let summary = summary.map_err(|e| e.to_string())?;
+    let state = if summary.cancelled { "cancelled" } else { "finished" };
+    eprintln!("Fuzz run {run_id} {state}: {}", summary.describe());
+    eprintln!("Responses: {}", summary.statuses.describe());
+    Ok(summary.matched > 0)
+}
+
+async fn work_as_agent(
+    ctx: &CliContext,
+    address: &str,
+    token: &str,
+    name: Option<&str>,
+) -> Result<(), String> {
+    let hostname = std::env::var("HOSTNAME").unwrap_or_else(|_| "agent".to_string());
+    let name = name.unwrap_or(&hostname);
+    let mut client = AgentClient::connect(address, token, name)
+        .await
+        .map_err(|e| format!("Failed to connect to {address}: {e}"))?;
+    eprintln!("Connected to {address} as {name}, waiting for shards");
+
+    let connection_manager = HttpConnectionManager::new();
+    loop {
+        let (shard, cancelled_rx) = client.next_shard().await.map_err(|e| e.to_string())?;
+        shard.workspace.install(&ctx.db()).map_err(|e| e.to_string())?;
+        let plugin_context = PluginContext::new(
+            Some("cli".to_string()),
+            Some(shard.config.request.workspace_id.clone()),
+        );
+        let sender = HttpFuzzSender::new(
+            ctx.query_manager().clone(),
+            &connection_manager,
+            ctx.plugin_manager(),
+            ctx.encryption_manager.clone(),
+            &plugin_context,
+            shard.config.environment_id.clone(),
+            cancelled_rx.clone(),
+        )
+        .with_config(&shard.config)
+        .map_err(|e| e.to_string())?;
+        match client.run_shard(&shard, &sender, cancelled_rx).await {
+            Ok(summary) => {
+                eprintln!("Shard {} of {}: {}", shard.id, shard.run_id, summary.describe())
+            }
+            Err(e) => eprintln!("Shard {} of {} failed: {e}", shard.id, shard.run_id),
+        }
+    }
+}
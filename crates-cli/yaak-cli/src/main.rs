@@ -8,7 +8,7 @@ mod version;
 mod version_check;
 
 use clap::Parser;
-use cli::{Cli, Commands, PluginCommands, RequestCommands};
+use cli::{Cli, Commands, FuzzCommands, PluginCommands, RequestCommands};
 use context::{CliContext, CliExecutionContext};
 use yaak_models::queries::any_request::AnyRequest;
 
@@ -143,6 +143,42 @@ async fn main() {
             context.shutdown().await;
             exit_code
         }
+        Commands::Fuzz(args) => match args.command {
+            FuzzCommands::Run { path, workspace_id, vars, output, fail_on_match } => {
+                let mut context = CliContext::new(data_dir.clone(), app_id);
+                match commands::fuzz::load_attack(
+                    &path,
+                    workspace_id.as_deref(),
+                    environment.as_deref(),
+                    &vars,
+                ) {
+                    Ok(request) => {
+                        context.init_plugins(commands::fuzz::execution_context(&request)).await;
+                        let exit_code = commands::fuzz::run(
+                            &context,
+                            request,
+                            output.as_deref(),
+                            fail_on_match,
+                        )
+                        .await;
+                        context.shutdown().await;
+                        exit_code
+                    }
+                    Err(error) => {
+                        eprintln!("Error: {error}");
+                        1
+                    }
+                }
+            }
+            FuzzCommands::Agent { address, token, name } => {
+                let mut context = CliContext::new(data_dir.clone(), app_id);
+                context.init_plugins(CliExecutionContext::default()).await;
+                let exit_code =
+                    commands::fuzz::run_agent(&context, &address, &token, name.as_deref()).await;
+                context.shutdown().await;
+                exit_code
+            }
+        },
     };
 
     if exit_code != 0 {
@@ -0,0 +1,92 @@
+mod common;
+
+use common::http_server::TestHttpServer;
+use common::{cli_cmd, seed_workspace};
+use predicates::str::contains;
+use serde_json::json;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn fuzz_run_writes_results_as_json_lines() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    seed_workspace(data_dir, "wk_test");
+
+    let server = TestHttpServer::spawn_ok("fuzzed");
+    let attack = json!({
+        "request": {
+            "workspaceId": "wk_test",
+            "method": "GET",
+            "url": server.url.replace("/test", "/FUZZ"),
+        },
+        "marker": "FUZZ",
+        "wordlist": ["test", "admin"],
+    });
+    let attack_path = data_dir.join("attack.json");
+    fs::write(&attack_path, attack.to_string()).expect("Failed to write attack");
+
+    let assert = cli_cmd(data_dir)
+        .args(["fuzz", "run"])
+        .arg(&attack_path)
+        .assert()
+        .success()
+        .stderr(contains("of 2 requests"));
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+    let mut payloads = stdout
+        .lines()
+        .map(|l| serde_json::from_str::<serde_json::Value>(l).expect("Expected a JSON line"))
+        .map(|r| r["payload"].as_str().unwrap_or_default().to_string())
+        .collect::<Vec<_>>();
+    payloads.sort();
+    assert_eq!(payloads, vec!["admin", "test"]);
+}
+
+#[test]
+fn fuzz_run_vars_override_the_environment() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    seed_workspace(data_dir, "wk_test");
+
+    let server = TestHttpServer::spawn_ok("fuzzed");
+    let attack = json!({
+        "request": {
+            "workspaceId": "wk_test",
+            "method": "GET",
+            "url": "${[ base_url ]}/FUZZ",
+        },
+        "marker": "FUZZ",
+        "wordlist": ["test"],
+    });
+    let attack_path = data_dir.join("attack.json");
+    fs::write(&attack_path, attack.to_string()).expect("Failed to write attack");
+
+    let assert = cli_cmd(data_dir)
+        .args(["fuzz", "run"])
+        .arg(&attack_path)
+        .arg("--var")
+        .arg(format!("base_url={}", server.url.trim_end_matches("/test")))
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&assert.get_output().stdout).to_string();
+    let result: serde_json::Value =
+        serde_json::from_str(stdout.lines().next().expect("Expected a result"))
+            .expect("Expected a JSON line");
+    assert_eq!(result["status"], 200);
+}
+
+#[test]
+fn fuzz_run_without_a_workspace_fails() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = temp_dir.path();
+    let attack_path = data_dir.join("attack.json");
+    fs::write(&attack_path, json!({ "wordlist": ["a"] }).to_string())
+        .expect("Failed to write attack");
+
+    cli_cmd(data_dir)
+        .args(["fuzz", "run"])
+        .arg(&attack_path)
+        .assert()
+        .failure()
+        .stderr(contains("--workspace"));
+}
@@ -28,6 +28,9 @@ pub enum Error {
     #[error(transparent)]
     WebsocketError(#[from] yaak_ws::error::Error),
 
+    #[error(transparent)]
+    FuzzError(#[from] yaak_fuzz::error::Error),
+
     #[cfg(feature = "license")]
     #[error(transparent)]
     LicenseError(#[from] yaak_license::error::Error),
@@ -0,0 +1,957 @@
+//! Fuzzer Tauri command wrappers
+//! These wrap the core yaak-fuzz functionality for Tauri IPC.
+
+use crate::PluginContextExt;
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::models_ext::{BlobManagerExt, QueryManagerExt};
+use log::{info, warn};
+use std::collections::BTreeMap;
+use std::fs;
+use std::fs::File;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager, Runtime, State, WebviewWindow, command};
+use tauri_plugin_notification::NotificationExt;
+use tokio::sync::{mpsc, watch};
+use yaak_api::{ApiClientKind, yaak_api_client};
+use yaak_crypto::manager::EncryptionManager;
+use yaak_fuzz::agents::{AgentWorkspace, FuzzAgentInfo, FuzzAgentServer};
+use yaak_fuzz::attacks::{
+    FuzzAttackInstance, attack_config, default_config, instantiate_attack, save_attack_config,
+};
+use yaak_fuzz::compare::{FuzzRunComparison, compare_runs};
+use yaak_fuzz::diff::{FuzzResultDiff, diff_results};
+use yaak_fuzz::encryption::{open_result, open_results, open_run, open_runs};
+use yaak_fuzz::estimate::{FuzzEstimate, estimate_fuzz};
+use yaak_fuzz::export::FuzzExport;
+use yaak_fuzz::export::burp::build_burp_xml;
+use yaak_fuzz::export::ffuf::{FuzzFfufCommand, build_ffuf_command, ffuf_template};
+use yaak_fuzz::export::har::build_har;
+use yaak_fuzz::export::report::{FuzzReportFormat, build_report};
+use yaak_fuzz::findings::{finding_request, findings_folder};
+use yaak_fuzz::graphql::{FuzzGraphqlField, field_request, introspect};
+use yaak_fuzz::import::{FuzzImport, import_command};
+use yaak_fuzz::load::{FuzzLoadReport, FuzzLoadTest, run_load_test};
+use yaak_fuzz::output::JsonlWriter;
+use yaak_fuzz::payloads::{FuzzPayloadGenerator, generate_payloads};
+use yaak_fuzz::persist::{read_result_body, read_result_request_body};
+use yaak_fuzz::preview::{FuzzPreview, preview_fuzz};
+use yaak_fuzz::rate_limit::{FuzzRateLimitProbe, FuzzRateLimitReport, discover_rate_limit};
+use yaak_fuzz::replay::{FuzzReplay, replay_result, stored_request};
+use yaak_fuzz::smuggling::{FuzzSmugglingTechnique, smuggling_config};
+use yaak_fuzz::targets::{FuzzInsertionPoint, insertion_points};
+use yaak_fuzz::types::{FuzzCookies, FuzzEvent, FuzzRequest, FuzzSummary, FuzzWebhook};
+use yaak_fuzz::webhook::WebhookNotifier;
+use yaak_fuzz::{
+    FuzzManager, FuzzRunPersister, HttpFuzzSender, retry_fuzz, run_fuzz, validate_fuzz_request,
+};
+use yaak_http::manager::HttpConnectionManager;
+use yaak_models::models::{FuzzAttack, FuzzResult, FuzzRun, FuzzRunState, HttpRequest};
+use yaak_models::queries::{
+    FuzzResultFilter, FuzzResultGroup, FuzzResultPage, FuzzResultQueryResult, FuzzResultSort,
+};
+use yaak_models::util::UpdateSource;
+use yaak_plugins::events::{FuzzRunEvent, FuzzRunLifecycle, PluginContext};
+use yaak_plugins::manager::PluginManager;
+use yaak_tauri_utils::window::WorkspaceWindowTrait;
+
+/// Start a fuzz run in the background and stream its events to `channel`.
+/// The run and its results are persisted as they arrive. Returns the run ID,
+/// which can be passed to `cmd_fuzz_cancel`.
+#[command]
+pub async fn cmd_fuzz_start<R: Runtime>(
+    mut request: FuzzRequest,
+    channel: Channel<FuzzEvent>,
+    app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
+    fuzz_manager: State<'_, FuzzManager>,
+) -> Result<String> {
+    validate_fuzz_request(&request)?;
+    check_cookie_jar(&window, &request)?;
+    if request.agents.is_some() && fuzz_manager.agent_coordinator().await.is_none() {
+        return Err(GenericError("Listen for agents before running on them".to_string()));
+    }
+
+    // Draft or imported requests may carry a stale workspace ID, so always run
+    // against the window's active workspace
+    if let Some(workspace_id) = window.workspace_id() {
+        request.set_workspace_id(&workspace_id);
+    }
+
+    request.pick_seeds();
+
+    let update_source = UpdateSource::from_window_label(window.label());
+    let mut persister = FuzzRunPersister::start(
+        app_handle.db_manager().inner().clone(),
+        app_handle.blob_manager().inner().clone(),
+        Arc::new((*app_handle.state::<EncryptionManager>()).clone()),
+        &request,
+        update_source,
+    )?;
+    if request.record_responses {
+        let response_dir = app_handle.path().app_data_dir()?.join("responses");
+        persister = persister.with_response_history(response_dir);
+    }
+    let run_id = persister.run_id().to_string();
+    let jsonl_writer = request.output_path.as_ref().map(JsonlWriter::create).transpose()?;
+    let cancelled_rx = fuzz_manager.start(&run_id).await;
+    spawn_run(
+        app_handle,
+        &window,
+        request,
+        None,
+        persister,
+        jsonl_writer,
+        channel,
+        fuzz_manager.inner().clone(),
+        cancelled_rx,
+    );
+
+    Ok(run_id)
+}
+
+/// Send the payloads of a finished run that failed on the network or timed out
+/// again, streaming events to `channel`. Retried results replace the failed ones in
+/// the same run. Returns the run ID, which can be passed to `cmd_fuzz_cancel`.
+#[command]
+pub async fn cmd_fuzz_retry_failed<R: Runtime>(
+    run_id: &str,
+    channel: Channel<FuzzEvent>,
+    app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
+    fuzz_manager: State<'_, FuzzManager>,
+) -> Result<String> {
+    let encryption_manager = app_handle.state::<EncryptionManager>();
+    let (run, results) = {
+        let db = window.db();
+        (db.get_fuzz_run(run_id)?, db.list_fuzz_results(run_id)?)
+    };
+    let (run, results) =
+        (open_run(&encryption_manager, run)?, open_results(&encryption_manager, results)?);
+    if run.state == FuzzRunState::Running {
+        return Err(GenericError("Fuzz run is still in progress".to_string()));
+    }
+    let request = yaak_fuzz::export::run_config(&run);
+    validate_fuzz_request(&request)?;
+    check_cookie_jar(&window, &request)?;
+
+    let update_source = UpdateSource::from_window_label(window.label());
+    let mut persister = FuzzRunPersister::retry(
+        app_handle.db_manager().inner().clone(),
+        app_handle.blob_manager().inner().clone(),
+        Arc::new((*encryption_manager).clone()),
+        run_id,
+        update_source,
+    )?;
+    if request.record_responses {
+        let response_dir = app_handle.path().app_data_dir()?.join("responses");
+        persister = persister.with_response_history(response_dir);
+    }
+    let cancelled_rx = fuzz_manager.start(run_id).await;
+    spawn_run(
+        app_handle,
+        &window,
+        request,
+        Some(results),
+        persister,
+        None,
+        channel,
+        fuzz_manager.inner().clone(),
+        cancelled_rx,
+    );
+
+    Ok(run_id.to_string())
+}
+
+/// Make sure the cookie jar a run shares still exists before the run starts
+fn check_cookie_jar<R: Runtime>(window: &WebviewWindow<R>, request: &FuzzRequest) -> Result<()> {
+    if let FuzzCookies::Workspace { cookie_jar_id } = &request.cookies {
+        window.db().get_cookie_jar(cookie_jar_id)?;
+    }
+    Ok(())
+}
+
+/// Run `request` in the background, persisting and forwarding its events.
+/// With `retry`, only the failed payloads among those results are sent.
+#[allow(clippy::too_many_arguments)]
+fn spawn_run<R: Runtime>(
+    app_handle: AppHandle<R>,
+    window: &WebviewWindow<R>,
+    request: FuzzRequest,
+    retry: Option<Vec<FuzzResult>>,
+    mut persister: FuzzRunPersister,
+    mut jsonl_writer: Option<JsonlWriter>,
+    channel: Channel<FuzzEvent>,
+    fuzz_manager: FuzzManager,
+    cancelled_rx: watch::Receiver<bool>,
+) {
+    let plugin_context = window.plugin_context();
+    let window = window.clone();
+    let run_id = persister.run_id().to_string();
+    tauri::async_runtime::spawn(async move {
+        let plugin_manager = Arc::new((*app_handle.state::<PluginManager>()).clone());
+        let encryption_manager = Arc::new((*app_handle.state::<EncryptionManager>()).clone());
+        let connection_manager = app_handle.state::<HttpConnectionManager>();
+        let sender = HttpFuzzSender::new(
+            app_handle.db_manager().inner().clone(),
+            connection_manager.inner(),
+            plugin_manager.clone(),
+            encryption_manager,
+            &plugin_context,
+            request.environment_id.clone(),
+            cancelled_rx.clone(),
+        )
+        .with_config(&request);
+        let sender = match sender {
+            Ok(sender) => sender,
+            Err(e) => {
+                warn!("Fuzz run {run_id} failed: {e:?}");
+                fuzz_manager.finish(&run_id).await;
+                return;
+            }
+        };
+
+        let mut notify_webhook =
+            request.webhook.as_ref().map(|w| spawn_webhook(&app_handle, &run_id, w));
+        let mut notify_desktop = desktop_notifier(window);
+        let mut notify_plugins =
+            plugin_notifier(plugin_manager, plugin_context, &run_id, &request.request);
+        let (events_tx, mut events_rx) = mpsc::unbounded_channel::<FuzzEvent>();
+        let forward_handle = tauri::async_runtime::spawn(async move {
+            while let Some(mut event) = events_rx.recv().await {
+                if let Some(notify) = notify_webhook.as_mut() {
+                    notify(&event);
+                }
+                notify_desktop(&event);
+                if let Err(e) = persister.record(&mut event) {
+                    warn!("Failed to persist fuzz event: {e:?}");
+                }
+                notify_plugins(&event);
+                if let Some(Err(e)) = jsonl_writer.as_mut().map(|w| w.write(&event)) {
+                    warn!("Failed to write fuzz result to file: {e:?}");
+                }
+                if let Err(e) = channel.send(event) {
+                    warn!("Failed to send fuzz event: {e:?}");
+                }
+            }
+        });
+
+        // Retries are sent from the app, even for runs that were distributed
+        let summary = match (&retry, &request.agents) {
+            (Some(results), _) => {
+                retry_fuzz(&run_id, &request, results, &sender, &events_tx, cancelled_rx).await
+            }
+            (None, Some(_)) => {
+                run_on_agents(
+                    &app_handle,
+                    &fuzz_manager,
+                    &run_id,
+                    &request,
+                    &events_tx,
+                    cancelled_rx,
+                )
+                .await
+            }
+            (None, None) => run_fuzz(&run_id, &request, &sender, &events_tx, cancelled_rx).await,
+        };
+        match summary {
+            Ok(summary) => info!("Fuzz run {run_id} finished {summary:?}"),
+            Err(e) => warn!("Fuzz run {run_id} failed: {e:?}"),
+        }
+        if let Err(e) = sender.save_cookies() {
+            warn!("Failed to save fuzz run cookies: {e:?}");
+        }
+
+        drop(events_tx);
+        let _ = forward_handle.await;
+        fuzz_manager.finish(&run_id).await;
+    });
+}
+
+/// Hand `request`'s payloads to the agents connected to the app
+async fn run_on_agents<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    fuzz_manager: &FuzzManager,
+    run_id: &str,
+    request: &FuzzRequest,
+    events_tx: &mpsc::UnboundedSender<FuzzEvent>,
+    cancelled_rx: watch::Receiver<bool>,
+) -> yaak_fuzz::error::Result<FuzzSummary> {
+    let Some(coordinator) = fuzz_manager.agent_coordinator().await else {
+        return Err(yaak_fuzz::error::Error::GenericError(
+            "Stopped listening for agents".to_string(),
+        ));
+    };
+    let workspace = AgentWorkspace::load(&app_handle.db(), &request.request.workspace_id)?;
+    coordinator.run(run_id, request, workspace, events_tx, cancelled_rx).await
+}
+
+/// Post `webhook`'s message for each event the returned function is given.
+/// Messages are posted in order from a task of their own, so a slow webhook
+/// doesn't hold up the run's events.
+fn spawn_webhook<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    run_id: &str,
+    webhook: &FuzzWebhook,
+) -> impl FnMut(&FuzzEvent) + Send + use<R> {
+    let app_version = app_handle.package_info().version.to_string();
+    let url = webhook.url.trim().to_string();
+    let mut notifier = WebhookNotifier::new(run_id, webhook);
+    let (messages_tx, mut messages_rx) = mpsc::unbounded_channel::<serde_json::Value>();
+    tauri::async_runtime::spawn(async move {
+        let client = match yaak_api_client(ApiClientKind::App, &app_version) {
+            Ok(client) => client,
+            Err(e) => {
+                warn!("Failed to build fuzz webhook client: {e:?}");
+                return;
+            }
+        };
+        while let Some(message) = messages_rx.recv().await {
+            let posted = client.post(&url).json(&message).send().await;
+            if let Err(e) = posted.and_then(|r| r.error_for_status()) {
+                warn!("Failed to post fuzz webhook: {e:?}");
+            }
+        }
+    });
+    move |event: &FuzzEvent| {
+        if let Some(message) = notifier.message(event) {
+            let _ = messages_tx.send(message);
+        }
+    }
+}
+
+/// Show a desktop notification for the run's first match and when it
+/// finishes, while `window` is in the background
+fn desktop_notifier<R: Runtime>(
+    window: WebviewWindow<R>,
+) -> impl FnMut(&FuzzEvent) + Send + use<R> {
+    let mut matched = false;
+    move |event: &FuzzEvent| {
+        let (title, body) = match event {
+            FuzzEvent::Result { result, .. } if result.matched && !matched => {
+                matched = true;
+                let body = format!(
+                    "{}: {} {} {}",
+                    result.payload, result.status, result.method, result.url
+                );
+                ("Fuzz run found a match", body)
+            }
+            FuzzEvent::Finished { summary } if summary.cancelled => {
+                ("Fuzz run cancelled", summary.describe())
+            }
+            FuzzEvent::Finished { summary } => ("Fuzz run finished", summary.describe()),
+            _ => return,
+        };
+        if window.is_focused().unwrap_or(false) {
+            return;
+        }
+        let shown = window.app_handle().notification().builder().title(title).body(body).show();
+        if let Err(e) = shown {
+            warn!("Failed to show fuzz notification: {e:?}");
+        }
+    }
+}
+
+/// Tell plugins the run started with `request`, then about each match, its
+/// progress at most once a second, and its summary
+fn plugin_notifier(
+    plugin_manager: Arc<PluginManager>,
+    plugin_context: PluginContext,
+    run_id: &str,
+    request: &HttpRequest,
+) -> impl FnMut(&FuzzEvent) + Send + use<> {
+    let run_id = run_id.to_string();
+    let (lifecycle_tx, mut lifecycle_rx) = mpsc::unbounded_channel::<FuzzRunLifecycle>();
+    let _ = lifecycle_tx.send(FuzzRunLifecycle::Started { request: request.clone() });
+    tauri::async_runtime::spawn(async move {
+        while let Some(event) = lifecycle_rx.recv().await {
+            let event = FuzzRunEvent { run_id: run_id.clone(), event };
+            if let Err(e) = plugin_manager.notify_fuzz_run(&plugin_context, event).await {
+                warn!("Failed to send fuzz run event to plugins: {e:?}");
+            }
+        }
+    });
+    let mut last_progress: Option<Instant> = None;
+    move |event: &FuzzEvent| {
+        let lifecycle = match event {
+            FuzzEvent::Result { result, .. } if result.matched => {
+                FuzzRunLifecycle::Match { result: result.clone() }
+            }
+            FuzzEvent::Progress { completed, total, .. } => {
+                if last_progress.is_some_and(|at| at.elapsed() < Duration::from_secs(1)) {
+                    return;
+                }
+                last_progress = Some(Instant::now());
+                FuzzRunLifecycle::Progress { completed: *completed, total: *total }
+            }
+            FuzzEvent::Finished { summary } => FuzzRunLifecycle::Finished {
+                completed: summary.completed,
+                matched: summary.matched,
+                errors: summary.errors,
+                cancelled: summary.cancelled,
+                elapsed: summary.elapsed,
+            },
+            FuzzEvent::Result { .. }
+            | FuzzEvent::Interaction { .. }
+            | FuzzEvent::Metrics { .. } => {
+                return;
+            }
+        };
+        let _ = lifecycle_tx.send(lifecycle);
+    }
+}
+
+#[command]
+pub async fn cmd_fuzz_cancel(run_id: &str, fuzz_manager: State<'_, FuzzManager>) -> Result<bool> {
+    Ok(fuzz_manager.cancel(run_id).await)
+}
+
+/// Listen for worker agents on `address`, returning the token they connect
+/// with. Agents of an earlier listener are disconnected.
+#[command]
+pub async fn cmd_fuzz_start_agents(
+    address: &str,
+    fuzz_manager: State<'_, FuzzManager>,
+) -> Result<FuzzAgentServer> {
+    Ok(fuzz_manager.start_agents(address).await?)
+}
+
+#[command]
+pub async fn cmd_fuzz_stop_agents(fuzz_manager: State<'_, FuzzManager>) -> Result<()> {
+    fuzz_manager.stop_agents().await;
+    Ok(())
+}
+
+#[command]
+pub async fn cmd_fuzz_agents(fuzz_manager: State<'_, FuzzManager>) -> Result<Vec<FuzzAgentInfo>> {
+    Ok(fuzz_manager.agent_coordinator().await.map(|c| c.agents()).unwrap_or_default())
+}
+
+#[command]
+pub async fn cmd_fuzz_runs<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_id: &str,
+) -> Result<Vec<FuzzRun>> {
+    let runs = app_handle.db().list_fuzz_runs(workspace_id)?;
+    Ok(open_runs(&app_handle.state::<EncryptionManager>(), runs)?)
+}
+
+#[command]
+pub async fn cmd_fuzz_attacks<R: Runtime>(
+    app_handle: AppHandle<R>,
+    workspace_id: &str,
+) -> Result<Vec<FuzzAttack>> {
+    Ok(app_handle.db().list_fuzz_attacks(workspace_id)?)
+}
+
+/// A new fuzz configuration for a saved request, with the fuzz defaults of the
+/// folders it's in
+#[command]
+pub async fn cmd_fuzz_default_config<R: Runtime>(
+    app_handle: AppHandle<R>,
+    request_id: &str,
+) -> Result<FuzzRequest> {
+    let db = app_handle.db();
+    Ok(default_config(&db, &db.get_http_request(request_id)?)?)
+}
+
+/// Save `request` as the configuration of `attack`, creating the attack if it
+/// has no ID yet. Attacks are renamed and deleted like other models.
+#[command]
+pub async fn cmd_fuzz_save_attack<R: Runtime>(
+    window: WebviewWindow<R>,
+    attack: FuzzAttack,
+    request: FuzzRequest,
+) -> Result<FuzzAttack> {
+    let attack = save_attack_config(attack, &request)?;
+    Ok(window.db().upsert_fuzz_attack(&attack, &UpdateSource::from_window_label(window.label()))?)
+}
+
+/// Save a copy of an attack based on another request, e.g. to reuse an
+/// "auth brute" attack on each login endpoint. Positions the request has no
+/// place for are listed in the result.
+#[command]
+pub async fn cmd_fuzz_instantiate_attack<R: Runtime>(
+    window: WebviewWindow<R>,
+    attack_id: &str,
+    request_id: &str,
+) -> Result<FuzzAttackInstance> {
+    let db = window.db();
+    let instance =
+        instantiate_attack(&db.get_fuzz_attack(attack_id)?, &db.get_http_request(request_id)?)?;
+    let attack =
+        db.upsert_fuzz_attack(&instance.attack, &UpdateSource::from_window_label(window.label()))?;
+    Ok(FuzzAttackInstance { attack, ..instance })
+}
+
+/// Start a saved attack, like `cmd_fuzz_start` with the attack's
+/// configuration
+#[command]
+pub async fn cmd_fuzz_run_attack<R: Runtime>(
+    attack_id: &str,
+    channel: Channel<FuzzEvent>,
+    app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
+    fuzz_manager: State<'_, FuzzManager>,
+) -> Result<String> {
+    let request = {
+        let db = window.db();
+        attack_config(&db, &db.get_fuzz_attack(attack_id)?)?
+    };
+    cmd_fuzz_start(request, channel, app_handle, window, fuzz_manager).await
+}
+
+#[command]
+pub async fn cmd_fuzz_results<R: Runtime>(
+    app_handle: AppHandle<R>,
+    run_id: &str,
+) -> Result<Vec<FuzzResult>> {
+    let results = app_handle.db().list_fuzz_results(run_id)?;
+    Ok(open_results(&app_handle.state::<EncryptionManager>(), results)?)
+}
+
+/// Filter, sort, and page a run's persisted results
+#[command]
+pub async fn cmd_query_fuzz_results<R: Runtime>(
+    app_handle: AppHandle<R>,
+    run_id: &str,
+    filter: FuzzResultFilter,
+    sort: FuzzResultSort,
+    page: Option<FuzzResultPage>,
+) -> Result<FuzzResultQueryResult> {
+    let query = app_handle.db().query_fuzz_results(run_id, &filter, &sort, page.as_ref())?;
+    let results = open_results(&app_handle.state::<EncryptionManager>(), query.results)?;
+    Ok(FuzzResultQueryResult { results, ..query })
+}
+
+/// Collapse a run's results into groups of identical responses
+#[command]
+pub async fn cmd_group_fuzz_results<R: Runtime>(
+    app_handle: AppHandle<R>,
+    run_id: &str,
+    filter: FuzzResultFilter,
+) -> Result<Vec<FuzzResultGroup>> {
+    Ok(app_handle.db().group_fuzz_results(run_id, &filter)?)
+}
+
+/// Report which payloads behaved differently between two runs of the same
+/// request, e.g. before and after a deploy
+#[command]
+pub async fn cmd_compare_fuzz_runs<R: Runtime>(
+    app_handle: AppHandle<R>,
+    run_a: &str,
+    run_b: &str,
+    size_tolerance: Option<i32>,
+) -> Result<FuzzRunComparison> {
+    let db = app_handle.db();
+    let (a, b) = (db.get_fuzz_run(run_a)?, db.get_fuzz_run(run_b)?);
+    if matches!((&a.request_id, &b.request_id), (Some(x), Some(y)) if x != y) {
+        return Err(GenericError("Runs target different requests".to_string()));
+    }
+    let encryption_manager = app_handle.state::<EncryptionManager>();
+    let a_results = open_results(&encryption_manager, db.list_fuzz_results(&a.id)?)?;
+    let b_results = open_results(&encryption_manager, db.list_fuzz_results(&b.id)?)?;
+    Ok(compare_runs(&a_results, &b_results, size_tolerance.unwrap_or_default()))
+}
+
+/// Compare two results' status, headers, and bodies
+#[command]
+pub async fn cmd_diff_fuzz_results<R: Runtime>(
+    app_handle: AppHandle<R>,
+    result_a: &str,
+    result_b: &str,
+) -> Result<FuzzResultDiff> {
+    let blob_manager = app_handle.blob_manager();
+    let encryption_manager = app_handle.state::<EncryptionManager>();
+    let a = open_result(&encryption_manager, app_handle.db().get_fuzz_result(result_a)?)?;
+    let b = open_result(&encryption_manager, app_handle.db().get_fuzz_result(result_b)?)?;
+    let a_body = read_result_body(&blob_manager, &encryption_manager, &a)?;
+    let b_body = read_result_body(&blob_manager, &encryption_manager, &b)?;
+    Ok(diff_results(&a, &a_body, &b, &b_body))
+}
+
+/// Write a run's results to a Burp Suite XML items file. With `flagged_only`,
+/// only starred results are exported.
+#[command]
+pub async fn cmd_export_fuzz_burp<R: Runtime>(
+    app_handle: AppHandle<R>,
+    run_id: &str,
+    file_path: &str,
+    flagged_only: bool,
+) -> Result<()> {
+    let export = FuzzExport::load(
+        &app_handle.db_manager(),
+        &app_handle.blob_manager(),
+        &app_handle.state::<EncryptionManager>(),
+        run_id,
+        flagged_only,
+    )?;
+    let xml = build_burp_xml(&export, &app_handle.package_info().version.to_string()).await?;
+    fs::write(file_path, xml)?;
+    Ok(())
+}
+
+/// Write a run's request/response pairs to a HAR file. With `flagged_only`,
+/// only starred results are exported.
+#[command]
+pub async fn cmd_export_fuzz_har<R: Runtime>(
+    app_handle: AppHandle<R>,
+    run_id: &str,
+    file_path: &str,
+    flagged_only: bool,
+) -> Result<()> {
+    let export = FuzzExport::load(
+        &app_handle.db_manager(),
+        &app_handle.blob_manager(),
+        &app_handle.state::<EncryptionManager>(),
+        run_id,
+        flagged_only,
+    )?;
+    let har = build_har(&export, &app_handle.package_info().version.to_string()).await?;
+    let f = File::create(file_path)?;
+    serde_json::to_writer_pretty(&f, &har)?;
+    f.sync_all()?;
+    Ok(())
+}
+
+/// Build an ffuf command reproducing `request` and write its wordlist to
+/// `wordlist_path`. Template tags are rendered against the active
+/// environment so the command runs without Yaak.
+#[command]
+pub async fn cmd_export_fuzz_ffuf<R: Runtime>(
+    mut request: FuzzRequest,
+    wordlist_path: &str,
+    app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
+) -> Result<FuzzFfufCommand> {
+    if let Some(workspace_id) = window.workspace_id() {
+        request.request.workspace_id = workspace_id;
+    }
+
+    let connection_manager = app_handle.state::<HttpConnectionManager>();
+    let (_cancel_tx, cancelled_rx) = watch::channel(false);
+    let sender = HttpFuzzSender::new(
+        app_handle.db_manager().inner().clone(),
+        connection_manager.inner(),
+        Arc::new((*app_handle.state::<PluginManager>()).clone()),
+        Arc::new((*app_handle.state::<EncryptionManager>()).clone()),
+        &window.plugin_context(),
+        request.environment_id.clone(),
+        cancelled_rx,
+    );
+    let rendered = sender.render(&ffuf_template(&request), &BTreeMap::new()).await?;
+    let ffuf = build_ffuf_command(&request, rendered, wordlist_path)?;
+
+    let mut wordlist = request.wordlist.join("\n");
+    wordlist.push('\n');
+    fs::write(wordlist_path, wordlist)?;
+    Ok(ffuf)
+}
+
+/// Render the first `count` requests of `request` as they would be sent,
+/// without sending anything, to check payload positions and encoding
+#[command]
+pub async fn cmd_preview_fuzz_attack<R: Runtime>(
+    mut request: FuzzRequest,
+    count: usize,
+    app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
+) -> Result<Vec<FuzzPreview>> {
+    validate_fuzz_request(&request)?;
+    if let Some(workspace_id) = window.workspace_id() {
+        request.request.workspace_id = workspace_id;
+    }
+
+    let connection_manager = app_handle.state::<HttpConnectionManager>();
+    let (_cancel_tx, cancelled_rx) = watch::channel(false);
+    let sender = HttpFuzzSender::new(
+        app_handle.db_manager().inner().clone(),
+        connection_manager.inner(),
+        Arc::new((*app_handle.state::<PluginManager>()).clone()),
+        Arc::new((*app_handle.state::<EncryptionManager>()).clone()),
+        &window.plugin_context(),
+        request.environment_id.clone(),
+        cancelled_rx,
+    )
+    .with_config(&request)?;
+    Ok(preview_fuzz(&request, &sender, count).await)
+}
+
+/// Convert an ffuf or wfuzz command line into a fuzz configuration, loading
+/// the wordlist file it names
+#[command]
+pub async fn cmd_import_fuzz_command(command: &str) -> Result<FuzzImport> {
+    let mut import = import_command(command)?;
+    import.load_wordlist();
+    Ok(import)
+}
+
+/// Query parameters, form fields and JSON values that a run can target in
+/// `request`
+#[command]
+pub async fn cmd_fuzz_insertion_points(request: HttpRequest) -> Result<Vec<FuzzInsertionPoint>> {
+    Ok(insertion_points(&request))
+}
+
+/// Generate a built-in payload set to use as a run's wordlist
+#[command]
+pub async fn cmd_fuzz_generate_payloads(generator: FuzzPayloadGenerator) -> Result<Vec<String>> {
+    Ok(generate_payloads(&generator)?)
+}
+
+/// Count the requests `request` would send, to warn before starting a run
+/// far bigger than intended
+#[command]
+pub async fn cmd_fuzz_estimate(request: FuzzRequest) -> Result<FuzzEstimate> {
+    Ok(estimate_fuzz(&request)?)
+}
+
+/// List the fields of the GraphQL endpoint `request` points at through
+/// introspection, rendering the request against `environment_id`
+#[command]
+pub async fn cmd_fuzz_graphql_fields<R: Runtime>(
+    mut request: HttpRequest,
+    environment_id: Option<String>,
+    app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
+) -> Result<Vec<FuzzGraphqlField>> {
+    if let Some(workspace_id) = window.workspace_id() {
+        request.workspace_id = workspace_id;
+    }
+
+    let connection_manager = app_handle.state::<HttpConnectionManager>();
+    let (_cancel_tx, cancelled_rx) = watch::channel(false);
+    let sender = HttpFuzzSender::new(
+        app_handle.db_manager().inner().clone(),
+        connection_manager.inner(),
+        Arc::new((*app_handle.state::<PluginManager>()).clone()),
+        Arc::new((*app_handle.state::<EncryptionManager>()).clone()),
+        &window.plugin_context(),
+        environment_id,
+        cancelled_rx,
+    );
+    Ok(introspect(&sender, &request).await?)
+}
+
+/// `request` rewritten to call a single GraphQL field, with its arguments as
+/// variables that a run can target
+#[command]
+pub async fn cmd_fuzz_graphql_field_request(
+    request: HttpRequest,
+    field: FuzzGraphqlField,
+) -> Result<HttpRequest> {
+    Ok(field_request(&request, &field))
+}
+
+/// A run probing `request`'s URL for request smuggling with `technique`
+#[command]
+pub async fn cmd_fuzz_smuggling_config(
+    request: HttpRequest,
+    technique: FuzzSmugglingTechnique,
+) -> Result<FuzzRequest> {
+    Ok(smuggling_config(&request, technique))
+}
+
+/// Write a standalone HTML or Markdown report of a run, including every
+/// starred result with its response body
+#[command]
+pub async fn cmd_export_fuzz_report<R: Runtime>(
+    app_handle: AppHandle<R>,
+    run_id: &str,
+    file_path: &str,
+    format: FuzzReportFormat,
+) -> Result<()> {
+    let export = FuzzExport::load(
+        &app_handle.db_manager(),
+        &app_handle.blob_manager(),
+        &app_handle.state::<EncryptionManager>(),
+        run_id,
+        false,
+    )?;
+    fs::write(file_path, build_report(&export, format))?;
+    Ok(())
+}
+
+/// Save the fully rendered request for a result's payload as a new request in
+/// the workspace's "Fuzz findings" folder, for manual follow-up
+#[command]
+pub async fn cmd_fuzz_result_to_request<R: Runtime>(
+    result_id: &str,
+    app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
+) -> Result<HttpRequest> {
+    let (run, result) = {
+        let db = window.db();
+        let result = db.get_fuzz_result(result_id)?;
+        (db.get_fuzz_run(&result.run_id)?, result)
+    };
+    let encryption_manager = app_handle.state::<EncryptionManager>();
+    let (run, result) =
+        (open_run(&encryption_manager, run)?, open_result(&encryption_manager, result)?);
+    let environment_id = yaak_fuzz::export::run_config(&run).environment_id;
+
+    let connection_manager = app_handle.state::<HttpConnectionManager>();
+    let (_cancel_tx, cancelled_rx) = watch::channel(false);
+    let sender = HttpFuzzSender::new(
+        app_handle.db_manager().inner().clone(),
+        connection_manager.inner(),
+        Arc::new((*app_handle.state::<PluginManager>()).clone()),
+        Arc::new((*app_handle.state::<EncryptionManager>()).clone()),
+        &window.plugin_context(),
+        environment_id,
+        cancelled_rx,
+    );
+    let rendered =
+        sender.render_standalone(&finding_request(&run, &result), &BTreeMap::new()).await?;
+
+    let update_source = UpdateSource::from_window_label(window.label());
+    let db = window.db();
+    let folder = findings_folder(&db, &run.workspace_id, &update_source)?;
+    Ok(db.upsert_http_request(
+        &HttpRequest { folder_id: Some(folder.id), ..rendered },
+        &update_source,
+    )?)
+}
+
+/// Send a result's stored request again byte for byte, without rendering the
+/// template, and compare the response with the original
+#[command]
+pub async fn cmd_fuzz_replay_result<R: Runtime>(
+    result_id: &str,
+    app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
+) -> Result<FuzzReplay> {
+    let (run, result) = {
+        let db = window.db();
+        let result = db.get_fuzz_result(result_id)?;
+        (db.get_fuzz_run(&result.run_id)?, result)
+    };
+    let encryption_manager = app_handle.state::<EncryptionManager>();
+    let (run, result) =
+        (open_run(&encryption_manager, run)?, open_result(&encryption_manager, result)?);
+    let config = yaak_fuzz::export::run_config(&run);
+    let blob_manager = app_handle.blob_manager();
+    let body = read_result_body(&blob_manager, &encryption_manager, &result)?;
+    let request_body = read_result_request_body(&blob_manager, &encryption_manager, &result)?;
+    let request = stored_request(&result, request_body)?;
+
+    let connection_manager = app_handle.state::<HttpConnectionManager>();
+    let (_cancel_tx, cancelled_rx) = watch::channel(false);
+    let sender = HttpFuzzSender::new(
+        app_handle.db_manager().inner().clone(),
+        connection_manager.inner(),
+        Arc::new((*app_handle.state::<PluginManager>()).clone()),
+        Arc::new((*app_handle.state::<EncryptionManager>()).clone()),
+        &window.plugin_context(),
+        config.environment_id.clone(),
+        cancelled_rx,
+    )
+    .with_config(&config)?;
+    Ok(replay_result(&sender, &config, &result, &body, &request).await?)
+}
+
+/// Send `request`'s template at a rising rate until the endpoint turns
+/// requests away, and report where and for how long it did
+#[command]
+pub async fn cmd_fuzz_discover_rate_limit<R: Runtime>(
+    mut request: FuzzRequest,
+    probe: FuzzRateLimitProbe,
+    app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
+) -> Result<FuzzRateLimitReport> {
+    if let Some(workspace_id) = window.workspace_id() {
+        request.request.workspace_id = workspace_id;
+    }
+
+    let connection_manager = app_handle.state::<HttpConnectionManager>();
+    let (_cancel_tx, cancelled_rx) = watch::channel(false);
+    let sender = HttpFuzzSender::new(
+        app_handle.db_manager().inner().clone(),
+        connection_manager.inner(),
+        Arc::new((*app_handle.state::<PluginManager>()).clone()),
+        Arc::new((*app_handle.state::<EncryptionManager>()).clone()),
+        &window.plugin_context(),
+        request.environment_id.clone(),
+        cancelled_rx,
+    )
+    .with_config(&request)?;
+    Ok(discover_rate_limit(&sender, &request, &probe).await?)
+}
+
+/// Send `request`'s template at a constant rate for a fixed time, and
+/// report its latency and error rate
+#[command]
+pub async fn cmd_fuzz_load_test<R: Runtime>(
+    mut request: FuzzRequest,
+    load: FuzzLoadTest,
+    app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
+) -> Result<FuzzLoadReport> {
+    if let Some(workspace_id) = window.workspace_id() {
+        request.request.workspace_id = workspace_id;
+    }
+
+    let connection_manager = app_handle.state::<HttpConnectionManager>();
+    let (_cancel_tx, cancelled_rx) = watch::channel(false);
+    let sender = HttpFuzzSender::new(
+        app_handle.db_manager().inner().clone(),
+        connection_manager.inner(),
+        Arc::new((*app_handle.state::<PluginManager>()).clone()),
+        Arc::new((*app_handle.state::<EncryptionManager>()).clone()),
+        &window.plugin_context(),
+        request.environment_id.clone(),
+        cancelled_rx,
+    )
+    .with_config(&request)?;
+    Ok(run_load_test(&sender, &request, &load).await?)
+}
+
+/// Star or unstar a result during triage
+#[command]
+pub async fn cmd_fuzz_star_result<R: Runtime>(
+    window: WebviewWindow<R>,
+    result_id: &str,
+    starred: bool,
+) -> Result<FuzzResult> {
+    let db = window.db();
+    let result = db.get_fuzz_result(result_id)?;
+    let result = db.upsert_fuzz_result(
+        &FuzzResult { starred, ..result },
+        &UpdateSource::from_window_label(window.label()),
+    )?;
+    Ok(open_result(&window.state::<EncryptionManager>(), result)?)
+}
+
+/// Replace a result's tags, e.g. `confirmed` or `false-positive`
+#[command]
+pub async fn cmd_fuzz_tag_result<R: Runtime>(
+    window: WebviewWindow<R>,
+    result_id: &str,
+    tags: Vec<String>,
+) -> Result<FuzzResult> {
+    let db = window.db();
+    let result = db.get_fuzz_result(result_id)?;
+    let result = db.upsert_fuzz_result(
+        &FuzzResult { tags, ..result },
+        &UpdateSource::from_window_label(window.label()),
+    )?;
+    Ok(open_result(&window.state::<EncryptionManager>(), result)?)
+}
+
+/// Set a result's free-text note
+#[command]
+pub async fn cmd_fuzz_note_result<R: Runtime>(
+    window: WebviewWindow<R>,
+    result_id: &str,
+    note: String,
+) -> Result<FuzzResult> {
+    let db = window.db();
+    let result = db.get_fuzz_result(result_id)?;
+    let result = db.upsert_fuzz_result(
+        &FuzzResult { note, ..result },
+        &UpdateSource::from_window_label(window.label()),
+    )?;
+    Ok(open_result(&window.state::<EncryptionManager>(), result)?)
+}
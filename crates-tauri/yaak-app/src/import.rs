@@ -7,7 +7,7 @@ use std::fs::read_to_string;
 use tauri::{Manager, Runtime, WebviewWindow};
 use yaak_core::WorkspaceContext;
 use yaak_models::models::{
-    Environment, Folder, GrpcRequest, HttpRequest, WebsocketRequest, Workspace,
+    Environment, Folder, FuzzAttack, GrpcRequest, HttpRequest, WebsocketRequest, Workspace,
 };
 use yaak_models::util::{BatchUpsertResult, UpdateSource, maybe_gen_id, maybe_gen_id_opt};
 use yaak_plugins::manager::PluginManager;
@@ -111,6 +111,17 @@ pub(crate) async fn import_data<R: Runtime>(
         })
         .collect();
 
+    let fuzz_attacks: Vec<FuzzAttack> = resources
+        .fuzz_attacks
+        .into_iter()
+        .map(|mut v| {
+            v.id = maybe_gen_id::<FuzzAttack>(&ctx, v.id.as_str(), &mut id_map);
+            v.workspace_id = maybe_gen_id::<Workspace>(&ctx, v.workspace_id.as_str(), &mut id_map);
+            v.request_id = maybe_gen_id_opt::<HttpRequest>(&ctx, v.request_id, &mut id_map);
+            v
+        })
+        .collect();
+
     info!("Importing data");
 
     let upserted = window.with_tx(|tx| {
@@ -121,6 +132,7 @@ pub(crate) async fn import_data<R: Runtime>(
             http_requests,
             grpc_requests,
             websocket_requests,
+            fuzz_attacks,
             &UpdateSource::Import,
         )
     })?;
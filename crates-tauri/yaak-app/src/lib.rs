@@ -66,6 +66,7 @@ use yaak_tls::find_client_certificate;
 mod commands;
 mod encoding;
 mod error;
+mod fuzz_ext;
 mod git_ext;
 mod grpc;
 mod history;
@@ -77,6 +78,7 @@ mod plugin_events;
 mod plugins_ext;
 mod render;
 mod sync_ext;
+mod trace_export;
 mod updates;
 mod uri_scheme;
 mod window;
@@ -1544,6 +1546,7 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_os::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(yaak_mac_window::init())
         .plugin(models_ext::init()) // Database setup only. Must be before plugins_ext which depends on db
@@ -1624,9 +1627,20 @@ pub fn run() {
             let ws_manager = yaak_ws::WebsocketManager::new();
             app.manage(Mutex::new(ws_manager));
 
+            // Add fuzz manager
+            app.manage(yaak_fuzz::FuzzManager::new());
+
             // Specific settings
             let settings = app.db().get_settings();
             app.app_handle().set_native_titlebar(settings.use_native_titlebar);
+            match settings.otlp_endpoint.as_deref().map(str::trim) {
+                None | Some("") => {}
+                Some(endpoint) => {
+                    if let Err(e) = trace_export::init_trace_export(endpoint) {
+                        warn!("Failed to export traces: {e}");
+                    }
+                }
+            }
 
             monitor_plugin_events(&app.app_handle().clone());
 
@@ -1743,6 +1757,44 @@ pub fn run() {
             ws_ext::cmd_ws_send,
             ws_ext::cmd_ws_close,
             ws_ext::cmd_ws_connect,
+            //
+            // Fuzz commands
+            fuzz_ext::cmd_fuzz_start,
+            fuzz_ext::cmd_fuzz_cancel,
+            fuzz_ext::cmd_compare_fuzz_runs,
+            fuzz_ext::cmd_diff_fuzz_results,
+            fuzz_ext::cmd_export_fuzz_burp,
+            fuzz_ext::cmd_export_fuzz_ffuf,
+            fuzz_ext::cmd_export_fuzz_har,
+            fuzz_ext::cmd_export_fuzz_report,
+            fuzz_ext::cmd_fuzz_agents,
+            fuzz_ext::cmd_fuzz_attacks,
+            fuzz_ext::cmd_fuzz_default_config,
+            fuzz_ext::cmd_fuzz_discover_rate_limit,
+            fuzz_ext::cmd_fuzz_estimate,
+            fuzz_ext::cmd_fuzz_generate_payloads,
+            fuzz_ext::cmd_fuzz_graphql_fields,
+            fuzz_ext::cmd_fuzz_graphql_field_request,
+            fuzz_ext::cmd_fuzz_insertion_points,
+            fuzz_ext::cmd_fuzz_instantiate_attack,
+            fuzz_ext::cmd_fuzz_load_test,
+            fuzz_ext::cmd_fuzz_note_result,
+            fuzz_ext::cmd_fuzz_replay_result,
+            fuzz_ext::cmd_fuzz_result_to_request,
+            fuzz_ext::cmd_fuzz_results,
+            fuzz_ext::cmd_fuzz_retry_failed,
+            fuzz_ext::cmd_fuzz_run_attack,
+            fuzz_ext::cmd_fuzz_runs,
+            fuzz_ext::cmd_fuzz_save_attack,
+            fuzz_ext::cmd_fuzz_smuggling_config,
+            fuzz_ext::cmd_fuzz_star_result,
+            fuzz_ext::cmd_fuzz_start_agents,
+            fuzz_ext::cmd_fuzz_stop_agents,
+            fuzz_ext::cmd_fuzz_tag_result,
+            fuzz_ext::cmd_group_fuzz_results,
+            fuzz_ext::cmd_import_fuzz_command,
+            fuzz_ext::cmd_preview_fuzz_attack,
+            fuzz_ext::cmd_query_fuzz_results,
         ])
         .build(tauri::generate_context!())
         .expect("error while running tauri application")
@@ -1807,6 +1859,7 @@ pub fn run() {
                         info!("Saved window state");
                     };
                 }
+                RunEvent::Exit => trace_export::shutdown_trace_export(),
                 _ => {}
             };
         });
@@ -147,6 +147,7 @@ pub(crate) fn models_upsert<R: Runtime>(
         AnyModel::CookieJar(m) => db.upsert_cookie_jar(&m, source)?.id,
         AnyModel::Environment(m) => db.upsert_environment(&m, source)?.id,
         AnyModel::Folder(m) => db.upsert_folder(&m, source)?.id,
+        AnyModel::FuzzAttack(m) => db.upsert_fuzz_attack(&m, source)?.id,
         AnyModel::GrpcRequest(m) => db.upsert_grpc_request(&m, source)?.id,
         AnyModel::HttpRequest(m) => db.upsert_http_request(&m, source)?.id,
         AnyModel::HttpResponse(m) => db.upsert_http_response(&m, source, &blobs)?.id,
@@ -177,6 +178,7 @@ pub(crate) fn models_delete<R: Runtime>(
             AnyModel::CookieJar(m) => tx.delete_cookie_jar(&m, source)?.id,
             AnyModel::Environment(m) => tx.delete_environment(&m, source)?.id,
             AnyModel::Folder(m) => tx.delete_folder(&m, source)?.id,
+            AnyModel::FuzzAttack(m) => tx.delete_fuzz_attack(&m, source)?.id,
             AnyModel::GrpcConnection(m) => tx.delete_grpc_connection(&m, source)?.id,
             AnyModel::GrpcRequest(m) => tx.delete_grpc_request(&m, source)?.id,
             AnyModel::HttpRequest(m) => tx.delete_http_request(&m, source)?.id,
@@ -204,6 +206,7 @@ pub(crate) fn models_duplicate<R: Runtime>(
         let id = match model {
             AnyModel::Environment(m) => tx.duplicate_environment(&m, source)?.id,
             AnyModel::Folder(m) => tx.duplicate_folder(&m, source)?.id,
+            AnyModel::FuzzAttack(m) => tx.duplicate_fuzz_attack(&m, source)?.id,
             AnyModel::GrpcRequest(m) => tx.duplicate_grpc_request(&m, source)?.id,
             AnyModel::HttpRequest(m) => tx.duplicate_http_request(&m, source)?.id,
             AnyModel::WebsocketRequest(m) => tx.duplicate_websocket_request(&m, source)?.id,
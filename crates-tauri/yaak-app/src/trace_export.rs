@@ -0,0 +1,50 @@
+//! Exports `tracing` spans, e.g. the ones fuzz runs open, to an OTLP collector
+//! when the `otlp_endpoint` setting is set
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use log::info;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::{KeyValue, global};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::runtime::Tokio;
+use opentelemetry_sdk::trace::TracerProvider;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Send spans to the OTLP/HTTP traces `endpoint`, e.g.
+/// `http://localhost:4318/v1/traces`, and put their context in the
+/// `traceparent` header of requests sent under them
+pub(crate) fn init_trace_export(endpoint: &str) -> Result<()> {
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()
+        .map_err(|e| GenericError(format!("Failed to create trace exporter: {e}")))?;
+
+    // The batch processor runs on the app's Tokio runtime
+    let runtime = tauri::async_runtime::handle();
+    let _guard = runtime.inner().enter();
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, Tokio)
+        .with_resource(Resource::new([KeyValue::new("service.name", "yaak")]))
+        .build();
+    let tracer = provider.tracer("yaak");
+
+    tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .try_init()
+        .map_err(|e| GenericError(format!("Failed to install trace subscriber: {e}")))?;
+    global::set_tracer_provider(provider);
+    global::set_text_map_propagator(TraceContextPropagator::new());
+
+    info!("Exporting traces to {endpoint}");
+    Ok(())
+}
+
+/// Flush spans that haven't been exported yet
+pub(crate) fn shutdown_trace_export() {
+    global::shutdown_tracer_provider();
+}
@@ -0,0 +1,73 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DnsOverride = { hostname: string, ipv4: Array<string>, ipv6: Array<string>, enabled?: boolean, };
+
+export type FuzzAttack = { model: "fuzz_attack", id: string, createdAt: string, updatedAt: string, workspaceId: string, 
+/**
+ * Saved request the attack is based on. Runs use its current version, so
+ * edits to the request carry over.
+ */
+requestId: string | null, name: string, description: string, 
+/**
+ * Positions, payload sets, processors, matchers, and run options, as a
+ * fuzz configuration
+ */
+config: Record<string, any>, };
+
+/**
+ * Why a fuzz request failed without a response
+ */
+export type FuzzErrorKind = "timeout" | "network" | "certificate" | "other";
+
+export type FuzzFollowUpResult = { url: string, status: number, elapsed: number, contentLength: number, error: string | null, };
+
+export type FuzzResult = { model: "fuzz_result", id: string, createdAt: string, updatedAt: string, workspaceId: string, runId: string, 
+/**
+ * Position of the payload in the wordlist
+ */
+payloadIndex: number, payload: string, 
+/**
+ * Base URL of a multi-target run the request was sent to, empty for
+ * runs with a single target
+ */
+baseUrl: string, 
+/**
+ * Request as it was sent, after rendering and payload injection. The
+ * body is stored as a blob next to the response body.
+ */
+method: string, url: string, requestHeaders: Array<HttpResponseHeader>, 
+/**
+ * Size of the request as it was sent, in bytes, counting the request
+ * line, headers and body of an HTTP/1.1 message. Results that failed
+ * without a response count the request before rendering.
+ */
+requestSizeBytes: number, status: number, 
+/**
+ * Protocol the response came over, like `HTTP/2`. Empty without a response.
+ */
+httpVersion: string, elapsed: number, contentLength: number, 
+/**
+ * Hex SHA-256 digest of the response body, used to spot identical
+ * responses and to store each distinct body once
+ */
+bodyHash: string, headers: Array<HttpResponseHeader>, error: string | null, errorKind: FuzzErrorKind | null, matched: boolean, extracted: { [key in string]?: string }, followUp: FuzzFollowUpResult | null, 
+/**
+ * Response history entry recorded for this result, if the run records responses
+ */
+responseId: string | null, 
+/**
+ * Triage state set by the user
+ */
+starred: boolean, tags: Array<string>, 
+/**
+ * Free-text note, e.g. why the result is interesting or how to reproduce it
+ */
+note: string, };
+
+export type HttpRequest = { model: "http_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, body: Record<string, any>, bodyType: string | null, description: string, headers: Array<HttpRequestHeader>, method: string, name: string, sortPriority: number, url: string, urlParameters: Array<HttpUrlParameter>, };
+
+export type HttpRequestHeader = { enabled?: boolean, name: string, value: string, id?: string, };
+
+export type HttpResponseHeader = { name: string, value: string, };
+
+export type HttpUrlParameter = { enabled?: boolean, name: string, value: string, id?: string, };
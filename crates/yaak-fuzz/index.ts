@@ -0,0 +1,191 @@
+import { Channel, invoke } from '@tauri-apps/api/core';
+import type {
+  FuzzAttack,
+  FuzzResult,
+  FuzzResultFilter,
+  FuzzResultGroup,
+  FuzzResultPage,
+  FuzzResultQueryResult,
+  FuzzResultSort,
+  FuzzRun,
+  HttpRequest,
+} from '@yaakapp-internal/models';
+import type {
+  FuzzAgentInfo,
+  FuzzAttackInstance,
+  FuzzAgentServer,
+  FuzzEstimate,
+  FuzzEvent,
+  FuzzFfufCommand,
+  FuzzGraphqlField,
+  FuzzImport,
+  FuzzInsertionPoint,
+  FuzzLoadReport,
+  FuzzLoadTest,
+  FuzzPayloadGenerator,
+  FuzzPreview,
+  FuzzRateLimitProbe,
+  FuzzRateLimitReport,
+  FuzzReplay,
+  FuzzReportFormat,
+  FuzzRequest,
+  FuzzResultDiff,
+  FuzzRunComparison,
+} from './bindings/gen_fuzz';
+
+export * from './bindings/gen_fuzz';
+
+// Settings left out of the request take their defaults
+export function startFuzz(
+  request: Partial<FuzzRequest> & Pick<FuzzRequest, 'request'>,
+  callback: (e: FuzzEvent) => void,
+) {
+  const channel = new Channel<FuzzEvent>();
+  channel.onmessage = callback;
+  return invoke<string>('cmd_fuzz_start', { request, channel });
+}
+
+export function retryFailedFuzz(runId: string, callback: (e: FuzzEvent) => void) {
+  const channel = new Channel<FuzzEvent>();
+  channel.onmessage = callback;
+  return invoke<string>('cmd_fuzz_retry_failed', { runId, channel });
+}
+
+export function cancelFuzz(runId: string) {
+  return invoke<boolean>('cmd_fuzz_cancel', { runId });
+}
+
+export function startFuzzAgents(address: string) {
+  return invoke<FuzzAgentServer>('cmd_fuzz_start_agents', { address });
+}
+
+export function stopFuzzAgents() {
+  return invoke<void>('cmd_fuzz_stop_agents');
+}
+
+export function listFuzzAgents() {
+  return invoke<FuzzAgentInfo[]>('cmd_fuzz_agents');
+}
+
+export function runFuzzAttack(attackId: string, callback: (e: FuzzEvent) => void) {
+  const channel = new Channel<FuzzEvent>();
+  channel.onmessage = callback;
+  return invoke<string>('cmd_fuzz_run_attack', { attackId, channel });
+}
+
+export function getFuzzDefaultConfig(requestId: string) {
+  return invoke<FuzzRequest>('cmd_fuzz_default_config', { requestId });
+}
+
+export function listFuzzAttacks(workspaceId: string) {
+  return invoke<FuzzAttack[]>('cmd_fuzz_attacks', { workspaceId });
+}
+
+export function saveFuzzAttack(attack: FuzzAttack, request: FuzzRequest) {
+  return invoke<FuzzAttack>('cmd_fuzz_save_attack', { attack, request });
+}
+
+export function instantiateFuzzAttack(attackId: string, requestId: string) {
+  return invoke<FuzzAttackInstance>('cmd_fuzz_instantiate_attack', { attackId, requestId });
+}
+
+export function listFuzzRuns(workspaceId: string) {
+  return invoke<FuzzRun[]>('cmd_fuzz_runs', { workspaceId });
+}
+
+export function listFuzzResults(runId: string) {
+  return invoke<FuzzResult[]>('cmd_fuzz_results', { runId });
+}
+
+export function queryFuzzResults(
+  runId: string,
+  filter: FuzzResultFilter,
+  sort: FuzzResultSort,
+  page: FuzzResultPage | null,
+) {
+  return invoke<FuzzResultQueryResult>('cmd_query_fuzz_results', { runId, filter, sort, page });
+}
+
+export function compareFuzzRuns(runA: string, runB: string, sizeTolerance?: number) {
+  return invoke<FuzzRunComparison>('cmd_compare_fuzz_runs', { runA, runB, sizeTolerance });
+}
+
+export function diffFuzzResults(resultA: string, resultB: string) {
+  return invoke<FuzzResultDiff>('cmd_diff_fuzz_results', { resultA, resultB });
+}
+
+export function exportFuzzBurp(runId: string, filePath: string, flaggedOnly: boolean) {
+  return invoke<void>('cmd_export_fuzz_burp', { runId, filePath, flaggedOnly });
+}
+
+export function exportFuzzFfuf(request: FuzzRequest, wordlistPath: string) {
+  return invoke<FuzzFfufCommand>('cmd_export_fuzz_ffuf', { request, wordlistPath });
+}
+
+export function exportFuzzHar(runId: string, filePath: string, flaggedOnly: boolean) {
+  return invoke<void>('cmd_export_fuzz_har', { runId, filePath, flaggedOnly });
+}
+
+export function exportFuzzReport(runId: string, filePath: string, format: FuzzReportFormat) {
+  return invoke<void>('cmd_export_fuzz_report', { runId, filePath, format });
+}
+
+export function groupFuzzResults(runId: string, filter: FuzzResultFilter) {
+  return invoke<FuzzResultGroup[]>('cmd_group_fuzz_results', { runId, filter });
+}
+
+export function importFuzzCommand(command: string) {
+  return invoke<FuzzImport>('cmd_import_fuzz_command', { command });
+}
+
+export function previewFuzzAttack(request: FuzzRequest, count: number) {
+  return invoke<FuzzPreview[]>('cmd_preview_fuzz_attack', { request, count });
+}
+
+export function listFuzzInsertionPoints(request: HttpRequest) {
+  return invoke<FuzzInsertionPoint[]>('cmd_fuzz_insertion_points', { request });
+}
+
+export function estimateFuzz(request: FuzzRequest) {
+  return invoke<FuzzEstimate>('cmd_fuzz_estimate', { request });
+}
+
+export function discoverFuzzRateLimit(request: FuzzRequest, probe: FuzzRateLimitProbe) {
+  return invoke<FuzzRateLimitReport>('cmd_fuzz_discover_rate_limit', { request, probe });
+}
+
+export function runFuzzLoadTest(request: FuzzRequest, load: FuzzLoadTest) {
+  return invoke<FuzzLoadReport>('cmd_fuzz_load_test', { request, load });
+}
+
+export function generateFuzzPayloads(generator: FuzzPayloadGenerator) {
+  return invoke<string[]>('cmd_fuzz_generate_payloads', { generator });
+}
+
+export function listFuzzGraphqlFields(request: HttpRequest, environmentId: string | null) {
+  return invoke<FuzzGraphqlField[]>('cmd_fuzz_graphql_fields', { request, environmentId });
+}
+
+export function fuzzGraphqlFieldRequest(request: HttpRequest, field: FuzzGraphqlField) {
+  return invoke<HttpRequest>('cmd_fuzz_graphql_field_request', { request, field });
+}
+
+export function fuzzResultToRequest(resultId: string) {
+  return invoke<HttpRequest>('cmd_fuzz_result_to_request', { resultId });
+}
+
+export function replayFuzzResult(resultId: string) {
+  return invoke<FuzzReplay>('cmd_fuzz_replay_result', { resultId });
+}
+
+export function starFuzzResult(resultId: string, starred: boolean) {
+  return invoke<FuzzResult>('cmd_fuzz_star_result', { resultId, starred });
+}
+
+export function tagFuzzResult(resultId: string, tags: string[]) {
+  return invoke<FuzzResult>('cmd_fuzz_tag_result', { resultId, tags });
+}
+
+export function noteFuzzResult(resultId: string, note: string) {
+  return invoke<FuzzResult>('cmd_fuzz_note_result', { resultId, note });
+}
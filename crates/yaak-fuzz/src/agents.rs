@@ -0,0 +1,728 @@
+//! Worker agents: headless instances that connect to the app, prove they
+//! know its token, and pull shards of a run's payloads, streaming results
+//! back so one run can be spread across machines and source addresses.
+//! Messages are JSON lines over plain TCP, so agents on other networks
+//! should connect through a VPN or SSH tunnel.
+
+use crate::engine::{duration_to_i32, fuzz_queue, run_queue};
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::sender::FuzzSender;
+use crate::types::{FuzzCookies, FuzzEvent, FuzzRequest, FuzzSummary};
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use hmac::{Hmac, Mac};
+use log::warn;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+};
+use tokio::net::tcp::OwnedWriteHalf;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{Notify, mpsc, watch};
+use tokio::task::{AbortHandle, JoinHandle};
+use ts_rs::TS;
+use yaak_models::db_context::DbContext;
+use yaak_models::models::{Environment, Folder, FuzzResult, Workspace};
+use yaak_models::util::{UpdateSource, generate_prefixed_id};
+
+/// Payloads in a shard when the run doesn't say
+const DEFAULT_SHARD_SIZE: usize = 100;
+
+/// Longest message accepted before an agent has authenticated
+const HELLO_LIMIT: u64 = 4 * 1024;
+
+/// Longest message accepted afterwards, large enough for a shard's config
+/// and a result's response body
+const MESSAGE_LIMIT: u64 = 256 * 1024 * 1024;
+
+/// Spread a run across the agents connected to the app instead of sending
+/// from it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzAgents {
+    /// Payloads an agent pulls at a time. Zero is treated as 100.
+    pub shard_size: u32,
+}
+
+/// Where agents connect to, and the token they authenticate with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzAgentServer {
+    pub address: String,
+    pub token: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzAgentInfo {
+    pub id: String,
+    /// Name the agent connected with, e.g. its hostname
+    pub name: String,
+    pub address: String,
+    /// Shards the agent has finished
+    pub shards: u32,
+    pub busy: bool,
+}
+
+/// Check that a distributed run only uses what works away from the app
+pub(crate) fn validate_agents(config: &FuzzRequest) -> Result<()> {
+    if config.agents.is_none() {
+        return Ok(());
+    }
+    if config.recursion.is_some() {
+        return Err(GenericError("Distributed runs can't recurse".to_string()));
+    }
+    if config.callbacks.is_some() {
+        return Err(GenericError("Distributed runs can't listen for callbacks".to_string()));
+    }
+    if matches!(config.cookies, FuzzCookies::Workspace { .. }) {
+        return Err(GenericError(
+            "Distributed runs can't share a workspace cookie jar".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// The workspace, environments and folders a run's template renders with,
+/// sent along with each shard. Secrets encrypted with the workspace key
+/// only render on agents that have the key too.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct AgentWorkspace {
+    pub workspace: Workspace,
+    pub environments: Vec<Environment>,
+    pub folders: Vec<Folder>,
+}
+
+impl AgentWorkspace {
+    pub fn load(db: &DbContext, workspace_id: &str) -> Result<Self> {
+        Ok(Self {
+            workspace: db.get_workspace(workspace_id)?,
+            environments: db.list_environments_ensure_base(workspace_id)?,
+            folders: db.list_folders(workspace_id)?,
+        })
+    }
+
+    /// Save the workspace to an agent's own database, replacing the copy of
+    /// an earlier shard
+    pub fn install(&self, db: &DbContext) -> Result<()> {
+        db.upsert_workspace(&self.workspace, &UpdateSource::Sync)?;
+        for folder in &self.folders {
+            db.upsert_folder(folder, &UpdateSource::Sync)?;
+        }
+        for environment in &self.environments {
+            db.upsert_environment(environment, &UpdateSource::Sync)?;
+        }
+        Ok(())
+    }
+}
+
+/// Payloads of a run for one agent to send
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentShard {
+    pub id: String,
+    pub run_id: String,
+    pub config: FuzzRequest,
+    /// Results to fill in, as the run queued them
+    pub queue: Vec<FuzzResult>,
+    pub workspace: AgentWorkspace,
+}
+
+/// Messages the coordinator sends an agent
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum CoordinatorMessage {
+    Challenge { nonce: String },
+    Shard { shard: Box<AgentShard> },
+    Cancel { shard_id: String },
+}
+
+/// Messages an agent sends the coordinator. Bodies are base64.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AgentMessage {
+    Hello {
+        name: String,
+        proof: String,
+    },
+    Pull,
+    Result {
+        shard_id: String,
+        result: FuzzResult,
+        body: String,
+        url: String,
+        request_body: String,
+    },
+    Done {
+        shard_id: String,
+    },
+}
+
+/// What a distributed run hears about its shards
+enum ShardUpdate {
+    Result(FuzzEvent),
+    Done,
+}
+
+struct PendingShard {
+    shard: Arc<AgentShard>,
+    updates: mpsc::UnboundedSender<ShardUpdate>,
+    cancelled_rx: watch::Receiver<bool>,
+}
+
+#[derive(Default)]
+struct CoordinatorState {
+    pending: VecDeque<PendingShard>,
+    agents: BTreeMap<String, FuzzAgentInfo>,
+    connections: Vec<AbortHandle>,
+}
+
+struct Shared {
+    token: String,
+    state: Mutex<CoordinatorState>,
+    shard_added: Notify,
+}
+
+impl Shared {
+    /// Put a shard an agent dropped back at the front of the queue, or
+    /// let its run stop waiting for it once cancelled
+    fn requeue(&self, shard: PendingShard) {
+        if *shard.cancelled_rx.borrow() {
+            let _ = shard.updates.send(ShardUpdate::Done);
+            return;
+        }
+        self.state.lock().unwrap().pending.push_front(shard);
+        self.shard_added.notify_one();
+    }
+
+    /// The next shard to hand out, waiting for one if none are queued
+    async fn next_pending(&self) -> PendingShard {
+        loop {
+            let added = self.shard_added.notified();
+            let shard = self.state.lock().unwrap().pending.pop_front();
+            if let Some(shard) = shard {
+                return shard;
+            }
+            added.await;
+        }
+    }
+
+    fn update_agent(&self, agent_id: &str, update: impl FnOnce(&mut FuzzAgentInfo)) {
+        if let Some(agent) = self.state.lock().unwrap().agents.get_mut(agent_id) {
+            update(agent);
+        }
+    }
+}
+
+/// A shard handed to an agent, queued again if the agent goes away before
+/// finishing it
+struct Assigned {
+    shared: Arc<Shared>,
+    shard: Option<PendingShard>,
+}
+
+impl Drop for Assigned {
+    fn drop(&mut self) {
+        if let Some(shard) = self.shard.take() {
+            self.shared.requeue(shard);
+        }
+    }
+}
+
+/// Accepts agents and hands them the shards of distributed runs. Agents
+/// are disconnected when it's dropped.
+pub struct AgentCoordinator {
+    address: SocketAddr,
+    shared: Arc<Shared>,
+    accept_task: JoinHandle<()>,
+}
+
+impl AgentCoordinator {
+    /// Listen for agents on `address`, with a new random token
+    pub async fn bind(address: &str) -> Result<Self> {
+        let listener = TcpListener::bind(address.trim()).await?;
+        let shared = Arc::new(Shared {
+            token: hex::encode(rand::random::<[u8; 24]>()),
+            state: Default::default(),
+            shard_added: Notify::new(),
+        });
+        let address = listener.local_addr()?;
+        let accept_task = tokio::spawn(accept_agents(listener, shared.clone()));
+        Ok(Self { address, shared, accept_task })
+    }
+
+    pub fn server(&self) -> FuzzAgentServer {
+        FuzzAgentServer { address: self.address.to_string(), token: self.shared.token.clone() }
+    }
+
+    pub fn agents(&self) -> Vec<FuzzAgentInfo> {
+        self.shared.state.lock().unwrap().agents.values().cloned().collect()
+    }
+
+    /// Run `config` on the connected agents, emitting events like
+    /// `run_fuzz`. Shards wait until an agent pulls them, so a run with no
+    /// agents connected waits until one connects or the run is cancelled.
+    pub async fn run(
+        &self,
+        run_id: &str,
+        config: &FuzzRequest,
+        workspace: AgentWorkspace,
+        events_tx: &mpsc::UnboundedSender<FuzzEvent>,
+        mut cancelled_rx: watch::Receiver<bool>,
+    ) -> Result<FuzzSummary> {
+        let started_at = Instant::now();
+        let queue = fuzz_queue(config);
+        let shard_size = match config.agents.map_or(0, |a| a.shard_size) {
+            0 => DEFAULT_SHARD_SIZE,
+            size => size as usize,
+        };
+        let mut summary = FuzzSummary { total: queue.len(), ..Default::default() };
+
+        let (updates_tx, mut updates_rx) = mpsc::unbounded_channel();
+        let mut outstanding = 0;
+        {
+            let mut state = self.shared.state.lock().unwrap();
+            for queue in queue.chunks(shard_size) {
+                let shard = AgentShard {
+                    id: generate_prefixed_id("fs"),
+                    run_id: run_id.to_string(),
+                    config: config.clone(),
+                    queue: queue.to_vec(),
+                    workspace: workspace.clone(),
+                };
+                state.pending.push_back(PendingShard {
+                    shard: Arc::new(shard),
+                    updates: updates_tx.clone(),
+                    cancelled_rx: cancelled_rx.clone(),
+                });
+                outstanding += 1;
+            }
+        }
+        drop(updates_tx);
+        self.shared.shard_added.notify_waiters();
+
+        // A re-sent shard reports its earlier results again
+        let mut completed = HashSet::new();
+        while outstanding > 0 {
+            let update = tokio::select! {
+                update = updates_rx.recv() => match update {
+                    Some(update) => update,
+                    None => break,
+                },
+                Ok(_) = cancelled_rx.changed(), if !*cancelled_rx.borrow() => {
+                    let mut state = self.shared.state.lock().unwrap();
+                    let pending = state.pending.len();
+                    state.pending.retain(|p| p.shard.run_id != run_id);
+                    outstanding -= pending - state.pending.len();
+                    continue;
+                }
+            };
+            let event = match update {
+                ShardUpdate::Result(event) => event,
+                ShardUpdate::Done => {
+                    outstanding -= 1;
+                    continue;
+                }
+            };
+            match &event {
+                FuzzEvent::Result { result, .. } if completed.insert(result.id.clone()) => {
+                    summary.completed += 1;
+                    if result.matched {
+                        summary.matched += 1;
+                    }
+                    summary.statuses.count(result);
+                    if result.error.is_some() {
+                        summary.errors += 1;
+                    }
+                }
+                _ => {}
+            }
+            let _ = events_tx.send(event);
+            let _ = events_tx.send(FuzzEvent::Progress {
+                completed: summary.completed,
+                total: summary.total,
+                statuses: summary.statuses,
+            });
+        }
+
+        summary.cancelled = *cancelled_rx.borrow();
+        summary.elapsed = duration_to_i32(started_at.elapsed());
+        let _ = events_tx.send(FuzzEvent::Finished { summary: summary.clone() });
+        Ok(summary)
+    }
+}
+
+impl Drop for AgentCoordinator {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+        for connection in &self.shared.state.lock().unwrap().connections {
+            connection.abort();
+        }
+    }
+}
+
+async fn accept_agents(listener: TcpListener, shared: Arc<Shared>) {
+    while let Ok((stream, remote)) = listener.accept().await {
+        let connection = tokio::spawn(serve_agent(stream, remote, shared.clone()));
+        let mut state = shared.state.lock().unwrap();
+        state.connections.retain(|c| !c.is_finished());
+        state.connections.push(connection.abort_handle());
+    }
+}
+
+async fn serve_agent(stream: TcpStream, remote: SocketAddr, shared: Arc<Shared>) {
+    if let Err(e) = authenticate_and_serve(stream, remote, shared).await {
+        warn!("Fuzz agent {remote} disconnected: {e}");
+    }
+}
+
+async fn authenticate_and_serve(
+    stream: TcpStream,
+    remote: SocketAddr,
+    shared: Arc<Shared>,
+) -> Result<()> {
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let nonce = hex::encode(rand::random::<[u8; 16]>());
+    write_message(&mut writer, &CoordinatorMessage::Challenge { nonce: nonce.clone() }).await?;
+    let name = match read_message(&mut reader, HELLO_LIMIT).await? {
+        Some(AgentMessage::Hello { name, proof }) if verify(&shared.token, &nonce, &proof) => name,
+        _ => return Err(GenericError("Agent failed to authenticate".to_string())),
+    };
+
+    let agent_id = generate_prefixed_id("fa");
+    let agent = FuzzAgentInfo {
+        id: agent_id.clone(),
+        name,
+        address: remote.to_string(),
+        shards: 0,
+        busy: false,
+    };
+    shared.state.lock().unwrap().agents.insert(agent_id.clone(), agent);
+    let (messages_tx, messages_rx) = mpsc::unbounded_channel();
+    let reader_task = tokio::spawn(async move {
+        while let Ok(Some(message)) = read_message(&mut reader, MESSAGE_LIMIT).await {
+            if messages_tx.send(message).is_err() {
+                break;
+            }
+        }
+    });
+    let served = serve_shards(&agent_id, &shared, writer, messages_rx).await;
+    reader_task.abort();
+    shared.state.lock().unwrap().agents.remove(&agent_id);
+    served
+}
+
+/// Hand a shard to the agent each time it pulls one, until it disconnects
+async fn serve_shards(
+    agent_id: &str,
+    shared: &Arc<Shared>,
+    mut writer: OwnedWriteHalf,
+    mut messages_rx: mpsc::UnboundedReceiver<AgentMessage>,
+) -> Result<()> {
+    loop {
+        match messages_rx.recv().await {
+            Some(AgentMessage::Pull) => {}
+            Some(_) => return Err(GenericError("Agent sent results without a shard".to_string())),
+            None => return Ok(()),
+        }
+        let pending = tokio::select! {
+            pending = shared.next_pending() => pending,
+            // The agent went away while waiting
+            None = messages_rx.recv() => return Ok(()),
+        };
+        let shard_id = pending.shard.id.clone();
+        let updates = pending.updates.clone();
+        let mut cancelled_rx = pending.cancelled_rx.clone();
+        let message = CoordinatorMessage::Shard { shard: Box::new((*pending.shard).clone()) };
+        let mut assigned = Assigned { shared: shared.clone(), shard: Some(pending) };
+        shared.update_agent(agent_id, |a| a.busy = true);
+        write_message(&mut writer, &message).await?;
+
+        let mut cancel_sent = false;
+        loop {
+            if !cancel_sent && *cancelled_rx.borrow() {
+                cancel_sent = true;
+                let cancel = CoordinatorMessage::Cancel { shard_id: shard_id.clone() };
+                write_message(&mut writer, &cancel).await?;
+            }
+            let message = tokio::select! {
+                message = messages_rx.recv() => match message {
+                    Some(message) => message,
+                    None => return Ok(()),
+                },
+                Ok(_) = cancelled_rx.changed(), if !cancel_sent => continue,
+            };
+            match message {
+                AgentMessage::Result { shard_id: id, result, body, url, request_body }
+                    if id == shard_id =>
+                {
+                    let event = FuzzEvent::Result {
+                        result,
+                        body: decode(&body)?,
+                        url,
+                        request_body: decode(&request_body)?,
+                    };
+                    let _ = updates.send(ShardUpdate::Result(event));
+                }
+                AgentMessage::Done { shard_id: id } if id == shard_id => {
+                    assigned.shard = None;
+                    shared.update_agent(agent_id, |a| {
+                        a.busy = false;
+                        a.shards += 1;
+                    });
+                    let _ = updates.send(ShardUpdate::Done);
+                    break;
+                }
+                _ => return Err(GenericError("Agent sent a message out of turn".to_string())),
+            }
+        }
+    }
+}
+
+/// A headless instance's connection to the coordinator
+pub struct AgentClient {
+    writer: OwnedWriteHalf,
+    shards_rx: mpsc::UnboundedReceiver<(AgentShard, watch::Receiver<bool>)>,
+    reader_task: JoinHandle<()>,
+}
+
+impl AgentClient {
+    /// Connect to the coordinator at `address` and authenticate with its
+    /// token, under `name`
+    pub async fn connect(address: &str, token: &str, name: &str) -> Result<Self> {
+        let stream = TcpStream::connect(address.trim()).await?;
+        let (reader, mut writer) = stream.into_split();
+        let mut reader = BufReader::new(reader);
+        let nonce = match read_message(&mut reader, HELLO_LIMIT).await? {
+            Some(CoordinatorMessage::Challenge { nonce }) => nonce,
+            _ => return Err(GenericError("Coordinator didn't send a challenge".to_string())),
+        };
+        let proof = hex::encode(proof_mac(token, &nonce).finalize().into_bytes());
+        write_message(&mut writer, &AgentMessage::Hello { name: name.to_string(), proof }).await?;
+
+        let (shards_tx, shards_rx) = mpsc::unbounded_channel();
+        let reader_task = tokio::spawn(async move {
+            let mut cancels = HashMap::<String, watch::Sender<bool>>::new();
+            while let Ok(Some(message)) = read_message(&mut reader, MESSAGE_LIMIT).await {
+                match message {
+                    CoordinatorMessage::Shard { shard } => {
+                        let (cancel_tx, cancelled_rx) = watch::channel(false);
+                        cancels.insert(shard.id.clone(), cancel_tx);
+                        if shards_tx.send((*shard, cancelled_rx)).is_err() {
+                            break;
+                        }
+                    }
+                    CoordinatorMessage::Cancel { shard_id } => {
+                        if let Some(cancel_tx) = cancels.remove(&shard_id) {
+                            let _ = cancel_tx.send(true);
+                        }
+                    }
+                    CoordinatorMessage::Challenge { .. } => break,
+                }
+            }
+        });
+        Ok(Self { writer, shards_rx, reader_task })
+    }
+
+    /// Pull the next shard, waiting until the coordinator has one, with the
+    /// receiver that's set if the run is cancelled
+    pub async fn next_shard(&mut self) -> Result<(AgentShard, watch::Receiver<bool>)> {
+        write_message(&mut self.writer, &AgentMessage::Pull).await?;
+        self.shards_rx
+            .recv()
+            .await
+            .ok_or_else(|| GenericError("The coordinator closed the connection".to_string()))
+    }
+
+    /// Send `shard`'s payloads with `sender`, streaming each result back
+    pub async fn run_shard<S: FuzzSender>(
+        &mut self,
+        shard: &AgentShard,
+        sender: &S,
+        cancelled_rx: watch::Receiver<bool>,
+    ) -> Result<FuzzSummary> {
+        let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+        let run = async move {
+            let queue = shard.queue.clone();
+            run_queue(&shard.run_id, &shard.config, sender, queue, &events_tx, cancelled_rx).await
+        };
+        let writer = &mut self.writer;
+        let forward = async move {
+            while let Some(event) = events_rx.recv().await {
+                let FuzzEvent::Result { result, body, url, request_body } = event else {
+                    continue;
+                };
+                let message = AgentMessage::Result {
+                    shard_id: shard.id.clone(),
+                    result,
+                    body: BASE64_STANDARD.encode(body),
+                    url,
+                    request_body: BASE64_STANDARD.encode(request_body),
+                };
+                write_message(writer, &message).await?;
+            }
+            Ok::<_, crate::error::Error>(())
+        };
+        let (summary, forwarded) = tokio::join!(run, forward);
+        forwarded?;
+        let done = AgentMessage::Done { shard_id: shard.id.clone() };
+        write_message(&mut self.writer, &done).await?;
+        summary
+    }
+}
+
+impl Drop for AgentClient {
+    fn drop(&mut self) {
+        self.reader_task.abort();
+    }
+}
+
+/// Proof of the token for a challenge, without sending the token itself
+fn proof_mac(token: &str, nonce: &str) -> Hmac<Sha256> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(token.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(nonce.as_bytes());
+    mac
+}
+
+/// Check an agent's proof of the token in constant time
+fn verify(token: &str, nonce: &str, proof: &str) -> bool {
+    let Ok(proof) = hex::decode(proof) else {
+        return false;
+    };
+    proof_mac(token, nonce).verify_slice(&proof).is_ok()
+}
+
+fn decode(body: &str) -> Result<Vec<u8>> {
+    BASE64_STANDARD
+        .decode(body)
+        .map_err(|_| GenericError("Agent sent a body that isn't base64".to_string()))
+}
+
+async fn write_message(
+    writer: &mut (impl AsyncWrite + Unpin),
+    message: &impl Serialize,
+) -> Result<()> {
+    let mut line = serde_json::to_vec(message)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    Ok(())
+}
+
+/// The next message, none once the connection closes
+async fn read_message<T: DeserializeOwned>(
+    reader: &mut (impl AsyncBufRead + Unpin),
+    limit: u64,
+) -> Result<Option<T>> {
+    let mut line = String::new();
+    if reader.take(limit).read_line(&mut line).await? == 0 {
+        return Ok(None);
+    }
+    if !line.ends_with('\n') {
+        return Err(GenericError("Agent message is too long".to_string()));
+    }
+    Ok(Some(serde_json::from_str(&line)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sender::{FuzzResponse, SentRequest};
+    use crate::types::FuzzMatcher;
+    use async_trait::async_trait;
+    use yaak_http::cookies::CookieStore;
+    use yaak_models::models::HttpRequest;
+
+    /// Answers `/admin` with a 200 and everything else with a 404
+    struct PathSender;
+
+    #[async_trait]
+    impl FuzzSender for PathSender {
+        async fn send(
+            &self,
+            request: &HttpRequest,
+            _variables: &BTreeMap<String, String>,
+            _cookies: Option<&CookieStore>,
+        ) -> Result<FuzzResponse> {
+            let status = if request.url.ends_with("/admin") { 200 } else { 404 };
+            Ok(FuzzResponse {
+                url: request.url.clone(),
+                status,
+                body: b"ok".to_vec(),
+                ..Default::default()
+            })
+        }
+
+        async fn send_exact(&self, _: &str, _: &SentRequest) -> Result<FuzzResponse> {
+            unimplemented!()
+        }
+
+        async fn refresh_auth(&self, _: &HttpRequest, _: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn agents_with_the_token_send_every_shard() {
+        let coordinator = AgentCoordinator::bind("127.0.0.1:0").await.unwrap();
+        let server = coordinator.server();
+
+        let mut intruder =
+            AgentClient::connect(&server.address, "wrong", "intruder").await.unwrap();
+        assert!(intruder.next_shard().await.is_err());
+
+        let config = FuzzRequest {
+            request: HttpRequest {
+                url: "https://example.com/§x§".to_string(),
+                ..Default::default()
+            },
+            wordlist: ["a", "admin", "b", "c", "d"].map(String::from).to_vec(),
+            matchers: vec![FuzzMatcher::Status { codes: vec![200] }],
+            agents: Some(FuzzAgents { shard_size: 2 }),
+            ..Default::default()
+        };
+        let mut agent =
+            AgentClient::connect(&server.address, &server.token, "worker").await.unwrap();
+        let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+        let (_cancel_tx, cancelled_rx) = watch::channel(false);
+        let run = coordinator.run(
+            "fr_test",
+            &config,
+            AgentWorkspace::default(),
+            &events_tx,
+            cancelled_rx,
+        );
+        let work = async {
+            for _ in 0..3 {
+                let (shard, cancelled_rx) = agent.next_shard().await.unwrap();
+                assert!(shard.queue.len() <= 2);
+                agent.run_shard(&shard, &PathSender, cancelled_rx).await.unwrap();
+            }
+        };
+        let (summary, _) = tokio::join!(run, work);
+        let summary = summary.unwrap();
+
+        assert_eq!((summary.total, summary.completed, summary.matched), (5, 5, 1));
+        assert_eq!(coordinator.agents()[0].shards, 3);
+        drop(events_tx);
+        let mut bodies = Vec::new();
+        while let Some(event) = events_rx.recv().await {
+            if let FuzzEvent::Result { body, .. } = event {
+                bodies.push(body);
+            }
+        }
+        assert_eq!(bodies, vec![b"ok".to_vec(); 5]);
+    }
+}
@@ -0,0 +1,320 @@
+//! Saved attacks, so a configured attack can be run again without being
+//! rebuilt. An attack based on a saved request always runs against the
+//! request's current version, and can be moved onto other requests to use it
+//! as a template.
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::markers::has_markers;
+use crate::targets::{insertion_points, set_target_value};
+use crate::types::{FuzzRequest, FuzzTarget};
+use serde::Serialize;
+use serde_json::Value;
+use ts_rs::TS;
+use yaak_models::db_context::DbContext;
+use yaak_models::models::{FuzzAttack, HttpRequest};
+
+/// An unsaved copy of an attack based on another request
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzAttackInstance {
+    pub attack: FuzzAttack,
+    /// Payload positions the request has no place for, which the copy
+    /// doesn't fuzz
+    pub unmapped: Vec<String>,
+}
+
+/// Store `config` in `attack`, basing the attack on `config`'s request when
+/// that request is saved
+pub fn save_attack_config(attack: FuzzAttack, config: &FuzzRequest) -> Result<FuzzAttack> {
+    let request = &config.request;
+    Ok(FuzzAttack {
+        workspace_id: request.workspace_id.clone(),
+        request_id: if request.id.is_empty() { None } else { Some(request.id.clone()) },
+        config: match serde_json::to_value(config)? {
+            Value::Object(config) => config.into_iter().collect(),
+            _ => Default::default(),
+        },
+        ..attack
+    })
+}
+
+/// The configuration to run `attack` with, using the current version of the
+/// request it's based on with the attack's positions carried over
+pub fn attack_config(db: &DbContext, attack: &FuzzAttack) -> Result<FuzzRequest> {
+    let mut config = stored_config(attack)?;
+    if let Some(request_id) = &attack.request_id {
+        config = remap_config(&config, &db.get_http_request(request_id)?).0;
+    }
+    config.set_workspace_id(&attack.workspace_id);
+    Ok(config)
+}
+
+/// A new configuration for fuzzing `request`, starting from the fuzz defaults
+/// of the folders it's in
+pub fn default_config(db: &DbContext, request: &HttpRequest) -> Result<FuzzRequest> {
+    let defaults = match &request.folder_id {
+        Some(folder_id) => db.resolve_fuzz_defaults_for_folder(&db.get_folder(folder_id)?)?,
+        None => Default::default(),
+    };
+    let defaults = Value::Object(defaults.into_iter().collect());
+    let config: FuzzRequest = serde_json::from_value(defaults)
+        .map_err(|e| GenericError(format!("Invalid folder fuzz defaults: {e}")))?;
+    Ok(FuzzRequest { request: request.clone(), ..config })
+}
+
+/// A copy of `attack` based on `request`, with the attack's positions put at
+/// the same places in `request` where it has them
+pub fn instantiate_attack(
+    attack: &FuzzAttack,
+    request: &HttpRequest,
+) -> Result<FuzzAttackInstance> {
+    let (config, unmapped) = remap_config(&stored_config(attack)?, request);
+    let attack = FuzzAttack {
+        id: String::new(),
+        name: attack.name.clone(),
+        description: attack.description.clone(),
+        ..Default::default()
+    };
+    Ok(FuzzAttackInstance { attack: save_attack_config(attack, &config)?, unmapped })
+}
+
+fn stored_config(attack: &FuzzAttack) -> Result<FuzzRequest> {
+    let config = Value::Object(attack.config.clone().into_iter().collect());
+    Ok(serde_json::from_value(config)?)
+}
+
+/// `config` with `request` as its template. Marked values of the old
+/// template are copied to the same query parameters, path segments, headers,
+/// form fields, and JSON fields of `request`, except where `request` already
+/// marks them, and targets are kept where `request` has them. Also returns
+/// the positions that couldn't be placed.
+fn remap_config(config: &FuzzRequest, request: &HttpRequest) -> (FuzzRequest, Vec<String>) {
+    let template = &config.request;
+    let marked = |text: &str| has_markers(text, config.marker.as_deref());
+    let mut remapped = request.clone();
+    let mut unmapped = Vec::new();
+
+    let path = |url: &str| url.split(['?', '#']).next().unwrap_or_default().to_string();
+    let (template_path, request_path) = (path(&template.url), path(&request.url));
+    if marked(&template_path) && !marked(&request_path) {
+        let template_segments = template_path.split('/').collect::<Vec<_>>();
+        let request_segments = request_path.split('/').collect::<Vec<_>>();
+        if template_segments.len() == request_segments.len() {
+            let segments = template_segments
+                .iter()
+                .zip(&request_segments)
+                .map(|(t, r)| if marked(t) { *t } else { *r })
+                .collect::<Vec<_>>();
+            remapped.url = format!("{}{}", segments.join("/"), &request.url[request_path.len()..]);
+        } else {
+            unmapped.push("URL path".to_string());
+        }
+    }
+
+    let points = insertion_points(request);
+    for point in insertion_points(template) {
+        if !marked(&point.value) || matches!(point.target, FuzzTarget::GraphqlVariable { .. }) {
+            continue;
+        }
+        match points.iter().find(|p| p.target == point.target) {
+            Some(p) if marked(&p.value) => {}
+            Some(_) => set_target_value(&mut remapped, &point.target, &point.value),
+            None => unmapped.push(describe_target(&point.target)),
+        }
+    }
+
+    for parameter in template.url_parameters.iter().filter(|p| p.name.starts_with(':')) {
+        if !parameter.enabled || !marked(&parameter.value) {
+            continue;
+        }
+        match remapped.url_parameters.iter_mut().find(|p| p.name == parameter.name) {
+            Some(p) if marked(&p.value) => {}
+            Some(p) => p.value = parameter.value.clone(),
+            None => unmapped.push(format!("path parameter `{}`", parameter.name)),
+        }
+    }
+
+    for header in template.headers.iter().filter(|h| h.enabled) {
+        if !marked(&header.name) && !marked(&header.value) {
+            continue;
+        }
+        let existing = remapped
+            .headers
+            .iter_mut()
+            .find(|h| h.enabled && h.name.eq_ignore_ascii_case(&header.name));
+        match existing {
+            Some(h) if marked(&h.value) => {}
+            Some(h) => h.value = header.value.clone(),
+            None => remapped.headers.push(header.clone()),
+        }
+    }
+
+    let is_json = |r: &HttpRequest| r.body_type.as_deref() == Some("application/json");
+    let template_text = template.body.get("text").and_then(|t| t.as_str()).unwrap_or_default();
+    if !is_json(template) && marked(template_text) {
+        unmapped.push("body text".to_string());
+    }
+
+    let targets = config
+        .targets
+        .iter()
+        .filter(|target| match target {
+            FuzzTarget::QueryParameter { .. }
+            | FuzzTarget::FormField { .. }
+            | FuzzTarget::JsonPath { .. }
+            | FuzzTarget::GraphqlVariable { .. } => {
+                let present = points.iter().any(|p| p.target == **target);
+                if !present {
+                    unmapped.push(describe_target(target));
+                }
+                present
+            }
+            _ => true,
+        })
+        .cloned()
+        .collect();
+
+    (FuzzRequest { request: remapped, targets, ..config.clone() }, unmapped)
+}
+
+fn describe_target(target: &FuzzTarget) -> String {
+    match target {
+        FuzzTarget::QueryParameter { name } => format!("query parameter `{name}`"),
+        FuzzTarget::FormField { name, .. } => format!("form field `{name}`"),
+        FuzzTarget::JsonPath { path } => format!("JSON field `{path}`"),
+        FuzzTarget::GraphqlVariable { path } => format!("GraphQL variable `{path}`"),
+        FuzzTarget::Method => "method".to_string(),
+        FuzzTarget::HeaderName { .. } => "header name".to_string(),
+        FuzzTarget::AddedParameter { .. } => "added parameter".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use yaak_models::init_in_memory;
+    use yaak_models::models::{Folder, HttpRequestHeader, Workspace};
+    use yaak_models::util::UpdateSource;
+
+    #[test]
+    fn attacks_run_against_the_current_request() {
+        let (query_manager, _blob_manager, _rx) = init_in_memory().expect("Failed to init DB");
+        let source = UpdateSource::Background;
+        let db = query_manager.connect();
+        let workspace = db.upsert_workspace(&Workspace::default(), &source).unwrap();
+        let request = db
+            .upsert_http_request(
+                &HttpRequest {
+                    workspace_id: workspace.id.clone(),
+                    url: "https://example.com/FUZZ".to_string(),
+                    ..Default::default()
+                },
+                &source,
+            )
+            .unwrap();
+        let config = FuzzRequest {
+            request: request.clone(),
+            marker: Some("FUZZ".to_string()),
+            wordlist: vec!["admin".to_string()],
+            ..Default::default()
+        };
+        let attack = db
+            .upsert_fuzz_attack(
+                &save_attack_config(
+                    FuzzAttack { name: "Paths".to_string(), ..Default::default() },
+                    &config,
+                )
+                .unwrap(),
+                &source,
+            )
+            .unwrap();
+        assert_eq!(attack.request_id.as_deref(), Some(request.id.as_str()));
+
+        db.upsert_http_request(
+            &HttpRequest { url: "https://example.org/FUZZ".to_string(), ..request },
+            &source,
+        )
+        .unwrap();
+        let config = attack_config(&db, &db.get_fuzz_attack(&attack.id).unwrap()).unwrap();
+        assert_eq!(config.request.url, "https://example.org/FUZZ");
+        assert_eq!(config.wordlist, vec!["admin"]);
+        assert_eq!(config.marker.as_deref(), Some("FUZZ"));
+    }
+
+    #[test]
+    fn instances_carry_positions_to_the_same_places() {
+        let config = FuzzRequest {
+            request: HttpRequest {
+                url: "https://a.test/search?q=FUZZ&page=1".to_string(),
+                headers: vec![HttpRequestHeader {
+                    enabled: true,
+                    name: "X-Api-Key".to_string(),
+                    value: "FUZZ".to_string(),
+                    id: None,
+                }],
+                ..Default::default()
+            },
+            marker: Some("FUZZ".to_string()),
+            targets: vec![FuzzTarget::JsonPath { path: "$.user".to_string() }],
+            ..Default::default()
+        };
+        let attack = save_attack_config(FuzzAttack::default(), &config).unwrap();
+        let request = HttpRequest {
+            id: "rq_other".to_string(),
+            url: "https://b.test/find?q=shoes&sort=asc".to_string(),
+            ..Default::default()
+        };
+
+        let instance = instantiate_attack(&attack, &request).unwrap();
+        assert_eq!(instance.attack.request_id.as_deref(), Some("rq_other"));
+        assert_eq!(instance.unmapped, vec!["JSON field `$.user`"]);
+        let config = stored_config(&instance.attack).unwrap();
+        assert_eq!(config.request.url, "https://b.test/find?q=FUZZ&sort=asc");
+        assert_eq!(config.request.headers[0].value, "FUZZ");
+        assert!(config.targets.is_empty());
+    }
+
+    #[test]
+    fn defaults_of_nearer_folders_win() {
+        let (query_manager, _blob_manager, _rx) = init_in_memory().expect("Failed to init DB");
+        let source = UpdateSource::Background;
+        let db = query_manager.connect();
+        let workspace = db.upsert_workspace(&Workspace::default(), &source).unwrap();
+        let parent = db
+            .upsert_folder(
+                &Folder {
+                    workspace_id: workspace.id.clone(),
+                    fuzz_defaults: [("concurrency", json!(20)), ("delayMs", json!(100))]
+                        .into_iter()
+                        .map(|(k, v)| (k.to_string(), v))
+                        .collect(),
+                    ..Default::default()
+                },
+                &source,
+            )
+            .unwrap();
+        let child = db
+            .upsert_folder(
+                &Folder {
+                    workspace_id: workspace.id.clone(),
+                    folder_id: Some(parent.id),
+                    fuzz_defaults: [("concurrency".to_string(), json!(2))].into_iter().collect(),
+                    ..Default::default()
+                },
+                &source,
+            )
+            .unwrap();
+        let request = HttpRequest {
+            workspace_id: workspace.id,
+            folder_id: Some(child.id),
+            ..Default::default()
+        };
+
+        let config = default_config(&db, &request).unwrap();
+        assert_eq!(config.concurrency, 2);
+        assert_eq!(config.delay_ms, 100);
+    }
+}
@@ -0,0 +1,90 @@
+//! Auth bypass: endpoints that answer the same with credentials stripped
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::types::FuzzRequest;
+use std::collections::BTreeMap;
+use yaak_models::models::{HttpRequest, HttpRequestHeader};
+
+/// Tag given to results that come back the same without credentials
+pub(crate) const AUTH_BYPASS_TAG: &str = "auth_bypass";
+
+/// Headers stripped from every request of an auth bypass run
+const CREDENTIAL_HEADERS: &[&str] = &["Authorization", "Proxy-Authorization", "Cookie"];
+
+/// Check that a run's credentials can all be stripped
+pub(crate) fn validate_auth_bypass(config: &FuzzRequest) -> Result<()> {
+    let Some(bypass) = &config.auth_bypass else {
+        return Ok(());
+    };
+    // Every request is signed, which would leave credentials on the stripped ones
+    if config.signing.is_some() {
+        return Err(GenericError("Auth bypass diffing can't be combined with signing".to_string()));
+    }
+    if bypass.headers.iter().any(|h| h.trim().is_empty()) {
+        return Err(GenericError("Enter the name of every header to strip".to_string()));
+    }
+    Ok(())
+}
+
+/// `request` without its authentication, credential headers and `headers`.
+/// The request's own headers replace inherited ones of the same name, so a
+/// disabled one is left in place of each to strip them too.
+pub(crate) fn strip_auth(request: &HttpRequest, headers: &[String]) -> HttpRequest {
+    let names = CREDENTIAL_HEADERS
+        .iter()
+        .copied()
+        .chain(headers.iter().map(|h| h.trim()))
+        .collect::<Vec<_>>();
+    let mut request = request.clone();
+    request.authentication_type = Some("none".to_string());
+    request.authentication = BTreeMap::new();
+    request.headers.retain(|h| !names.iter().any(|n| n.eq_ignore_ascii_case(&h.name)));
+    request.headers.extend(names.iter().map(|name| HttpRequestHeader {
+        enabled: false,
+        name: name.to_string(),
+        value: String::new(),
+        id: None,
+    }));
+    request
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_credentials_and_hides_inherited_ones() {
+        let header = |name: &str, value: &str| HttpRequestHeader {
+            enabled: true,
+            name: name.to_string(),
+            value: value.to_string(),
+            id: None,
+        };
+        let request = HttpRequest {
+            authentication_type: Some("bearer".to_string()),
+            headers: vec![
+                header("authorization", "Bearer secret"),
+                header("X-Api-Key", "secret"),
+                header("Accept", "application/json"),
+            ],
+            ..Default::default()
+        };
+        let stripped = strip_auth(&request, &["x-api-key".to_string()]);
+
+        assert_eq!(stripped.authentication_type.as_deref(), Some("none"));
+        let enabled = stripped.headers.iter().filter(|h| h.enabled).collect::<Vec<_>>();
+        assert_eq!(enabled.len(), 1);
+        assert_eq!(enabled[0].name, "Accept");
+        let hidden = stripped.headers.iter().filter(|h| !h.enabled).map(|h| h.name.as_str());
+        assert_eq!(
+            hidden.collect::<Vec<_>>(),
+            vec![
+                "Authorization",
+                "Proxy-Authorization",
+                "Cookie",
+                "x-api-key"
+            ]
+        );
+    }
+}
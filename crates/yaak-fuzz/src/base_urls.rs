@@ -0,0 +1,165 @@
+//! Repeating a run against several base URLs, e.g. one endpoint across
+//! tenants or regions
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::markers::escape_markers;
+use crate::types::{FuzzBaseUrlOrder, FuzzRequest};
+use std::borrow::Cow;
+use yaak_models::models::FuzzResult;
+use yaak_templates::escape::escape_template;
+
+/// Check that the template's URL has an origin to replace and every base URL
+/// parses
+pub(crate) fn validate_base_urls(config: &FuzzRequest) -> Result<()> {
+    if config.base_urls.urls.is_empty() {
+        return Ok(());
+    }
+    if template_origin(&config.request.url).is_none() {
+        return Err(GenericError(
+            "Multi-target runs need a URL starting with http:// or https://".to_string(),
+        ));
+    }
+    for base_url in &config.base_urls.urls {
+        let parsed = url::Url::parse(&absolute_base_url(&config.request.url, base_url));
+        if !parsed.is_ok_and(|u| matches!(u.scheme(), "http" | "https") && u.has_host()) {
+            return Err(GenericError(format!("Invalid base URL {base_url}")));
+        }
+    }
+    Ok(())
+}
+
+/// `config` sending to `base_url` instead of its URL's scheme and host. An
+/// empty base URL keeps the template as it is.
+pub(crate) fn rebased_config<'a>(config: &'a FuzzRequest, base_url: &str) -> Cow<'a, FuzzRequest> {
+    let url = &config.request.url;
+    let rest = match template_origin(url) {
+        Some(origin) if !base_url.is_empty() => &url[origin.len()..],
+        _ => return Cow::Borrowed(config),
+    };
+    let base_url = absolute_base_url(url, base_url);
+    let base_url = escape_template(&escape_markers(base_url.trim_end_matches('/')));
+
+    let mut config = config.clone();
+    config.request.url = format!("{base_url}{rest}");
+    Cow::Owned(config)
+}
+
+/// `queue` split by base URL in order of first appearance, each part with
+/// the config sending to it
+pub(crate) fn group_by_base_url(
+    config: &FuzzRequest,
+    queue: Vec<FuzzResult>,
+) -> Vec<(Cow<'_, FuzzRequest>, Vec<FuzzResult>)> {
+    let mut groups: Vec<(String, Vec<FuzzResult>)> = Vec::new();
+    for entry in queue {
+        match groups.iter_mut().find(|(base_url, _)| *base_url == entry.base_url) {
+            Some((_, entries)) => entries.push(entry),
+            None => groups.push((entry.base_url.clone(), vec![entry])),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(base_url, entries)| (rebased_config(config, &base_url), entries))
+        .collect()
+}
+
+/// The entries of every group, tagged with the group's index, either one
+/// group after another or taking turns
+pub(crate) fn schedule<T>(groups: Vec<Vec<T>>, order: FuzzBaseUrlOrder) -> Vec<(usize, T)> {
+    match order {
+        FuzzBaseUrlOrder::Sequential => groups
+            .into_iter()
+            .enumerate()
+            .flat_map(|(group, entries)| entries.into_iter().map(move |e| (group, e)))
+            .collect(),
+        FuzzBaseUrlOrder::Interleaved => {
+            let mut groups = groups.into_iter().map(Vec::into_iter).collect::<Vec<_>>();
+            let mut scheduled = Vec::new();
+            loop {
+                let before = scheduled.len();
+                for (group, entries) in groups.iter_mut().enumerate() {
+                    scheduled.extend(entries.next().map(|e| (group, e)));
+                }
+                if scheduled.len() == before {
+                    return scheduled;
+                }
+            }
+        }
+    }
+}
+
+/// The `scheme://host` prefix of a template URL
+fn template_origin(url: &str) -> Option<&str> {
+    let scheme = ["http://", "https://"]
+        .into_iter()
+        .find(|s| url.get(..s.len()).is_some_and(|p| p.eq_ignore_ascii_case(s)))?;
+    let end = url[scheme.len()..].find(['/', '?', '#']).map_or(url.len(), |i| scheme.len() + i);
+    Some(&url[..end])
+}
+
+/// `base_url` with the template's scheme when it's only a host
+fn absolute_base_url(template_url: &str, base_url: &str) -> String {
+    if base_url.contains("://") {
+        return base_url.to_string();
+    }
+    let scheme = template_url.split_once("://").map_or("https", |(scheme, _)| scheme);
+    format!("{scheme}://{base_url}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FuzzBaseUrls;
+    use yaak_models::models::HttpRequest;
+
+    fn config(url: &str) -> FuzzRequest {
+        FuzzRequest {
+            request: HttpRequest { url: url.to_string(), ..Default::default() },
+            base_urls: FuzzBaseUrls {
+                urls: vec![
+                    "https://eu.example.com/".to_string(),
+                    "us.example.com".to_string(),
+                ],
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn replaces_origin_of_template_url() {
+        let config = config("http://localhost:8080/api/§id§?v=1");
+        assert!(validate_base_urls(&config).is_ok());
+        assert_eq!(
+            rebased_config(&config, "https://eu.example.com/").request.url,
+            "https://eu.example.com/api/§id§?v=1"
+        );
+        assert_eq!(
+            rebased_config(&config, "us.example.com").request.url,
+            "http://us.example.com/api/§id§?v=1"
+        );
+        assert_eq!(rebased_config(&config, "").request.url, config.request.url);
+    }
+
+    #[test]
+    fn requires_literal_origin() {
+        assert!(validate_base_urls(&config("${[ base_url ]}/api")).is_err());
+        let mut config = config("https://example.com/api");
+        config.base_urls.urls.push("ftp://files.example.com".to_string());
+        assert!(validate_base_urls(&config).is_err());
+    }
+
+    #[test]
+    fn interleaves_groups() {
+        let groups = vec![vec!["a1", "a2"], vec!["b1"]];
+        assert_eq!(
+            schedule(groups.clone(), FuzzBaseUrlOrder::Sequential),
+            vec![(0, "a1"), (0, "a2"), (1, "b1")]
+        );
+        assert_eq!(
+            schedule(groups, FuzzBaseUrlOrder::Interleaved),
+            vec![(0, "a1"), (1, "b1"), (0, "a2")]
+        );
+    }
+}
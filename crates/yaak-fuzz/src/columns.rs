@@ -0,0 +1,132 @@
+//! Derived columns, computed for each result from a template over its
+//! response, e.g. `${[ response.header.x-request-id ]}`. They're stored with
+//! the result's extracted values.
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::follow_up::PAYLOAD_VARIABLE;
+use crate::sender::FuzzSender;
+use crate::types::FuzzRequest;
+use std::collections::HashMap;
+use yaak_models::models::FuzzResult;
+use yaak_templates::error::Error::RenderError;
+use yaak_templates::escape::escape_template;
+use yaak_templates::{RenderOptions, TemplateCallback, parse_and_render};
+
+pub(crate) fn validate_columns(config: &FuzzRequest) -> Result<()> {
+    for column in &config.columns {
+        if column.name.trim().is_empty() {
+            return Err(GenericError("Columns need a name".to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Variables column templates are rendered with: the result's payload and
+/// extracted values under `fuzz.`, and its response's `response.status`,
+/// `response.url`, `response.body` and `response.header.<name>`, with header
+/// names lowercased
+pub(crate) fn column_variables(result: &FuzzResult, body: &[u8]) -> HashMap<String, String> {
+    let mut variables = HashMap::from([
+        (PAYLOAD_VARIABLE.to_string(), result.payload.clone()),
+        ("response.status".to_string(), result.status.to_string()),
+        ("response.url".to_string(), result.url.clone()),
+        ("response.body".to_string(), String::from_utf8_lossy(body).to_string()),
+    ]);
+    for (name, value) in &result.extracted {
+        variables.insert(format!("fuzz.{name}"), value.clone());
+    }
+    for header in &result.headers {
+        variables.insert(
+            format!("response.header.{}", header.name.to_lowercase()),
+            header.value.clone(),
+        );
+    }
+    // Values come from the response, so they're never rendered themselves
+    variables.into_iter().map(|(name, value)| (name, escape_template(&value))).collect()
+}
+
+/// Render `template` with `variables` alone, for senders without template
+/// functions
+pub(crate) async fn render_variables(
+    template: &str,
+    variables: &HashMap<String, String>,
+) -> Result<String> {
+    Ok(parse_and_render(template, variables, &VariablesOnly, &RenderOptions::throw()).await?)
+}
+
+struct VariablesOnly;
+
+impl TemplateCallback for VariablesOnly {
+    async fn run(
+        &self,
+        fn_name: &str,
+        _args: HashMap<String, serde_json::Value>,
+    ) -> yaak_templates::error::Result<String> {
+        Err(RenderError(format!("Template function {fn_name}() can't be used here")))
+    }
+
+    fn transform_arg(
+        &self,
+        _fn_name: &str,
+        _arg_name: &str,
+        arg_value: &str,
+    ) -> yaak_templates::error::Result<String> {
+        Ok(arg_value.to_string())
+    }
+}
+
+/// Compute the run's columns for `result`. A column that fails to render is
+/// left out and the failure is recorded as the result's error.
+pub(crate) async fn apply_columns<S: FuzzSender>(
+    sender: &S,
+    config: &FuzzRequest,
+    result: &mut FuzzResult,
+    body: &[u8],
+) {
+    if config.columns.is_empty() {
+        return;
+    }
+    let variables = column_variables(result, body);
+    for column in &config.columns {
+        match sender.render_template(&column.template, &variables).await {
+            Ok(value) => {
+                result.extracted.insert(column.name.clone(), value);
+            }
+            Err(e) => {
+                result.error_kind = Some(e.kind());
+                result.error = Some(format!("Column {} failed: {e}", column.name));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use yaak_models::models::HttpResponseHeader;
+
+    #[tokio::test]
+    async fn columns_render_response_fields() {
+        let result = FuzzResult {
+            payload: "admin".to_string(),
+            status: 200,
+            headers: vec![HttpResponseHeader {
+                name: "X-Request-Id".to_string(),
+                value: "req-${[ secret ]}".to_string(),
+            }],
+            extracted: BTreeMap::from([("token".to_string(), "t-1".to_string())]),
+            ..Default::default()
+        };
+        let variables = column_variables(&result, b"ok");
+
+        let template = "${[ response.header.x-request-id ]} ${[ fuzz.token ]} \
+                        ${[ response.status ]} ${[ response.body ]} ${[ fuzz.payload ]}";
+        let rendered = render_variables(template, &variables).await.unwrap();
+        assert_eq!(rendered, "req-${[ secret ]} t-1 200 ok admin");
+
+        let failed = render_variables("${[ uuid.v4() ]}", &variables).await;
+        assert!(failed.unwrap_err().to_string().contains("uuid.v4()"));
+    }
+}
@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use ts_rs::TS;
+use yaak_models::models::FuzzResult;
+
+/// A payload whose response differs between two runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzPayloadChange {
+    pub payload: String,
+    pub result_id_a: String,
+    pub result_id_b: String,
+    pub status_a: i32,
+    pub status_b: i32,
+    pub content_length_a: i32,
+    pub content_length_b: i32,
+    pub matched_a: bool,
+    pub matched_b: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzRunComparison {
+    pub changed: Vec<FuzzPayloadChange>,
+    /// Payloads sent in the first run only
+    pub only_in_a: Vec<String>,
+    /// Payloads sent in the second run only
+    pub only_in_b: Vec<String>,
+    pub unchanged: i32,
+}
+
+/// Pair the results of two runs by payload and report those whose status,
+/// match state, or size changed. Sizes within `size_tolerance` bytes of each
+/// other count as equal, which absorbs dynamic content such as timestamps.
+/// If a payload appears more than once in a run, its first result is used.
+pub fn compare_runs(a: &[FuzzResult], b: &[FuzzResult], size_tolerance: i32) -> FuzzRunComparison {
+    let mut b_by_payload: HashMap<&str, &FuzzResult> = HashMap::new();
+    for result in b.iter().rev() {
+        b_by_payload.insert(&result.payload, result);
+    }
+
+    let mut comparison = FuzzRunComparison::default();
+    let mut seen_in_a = HashSet::new();
+    for a in a {
+        if !seen_in_a.insert(a.payload.as_str()) {
+            continue;
+        }
+        let Some(b) = b_by_payload.remove(a.payload.as_str()) else {
+            comparison.only_in_a.push(a.payload.clone());
+            continue;
+        };
+
+        let size_changed = (a.content_length - b.content_length).abs() > size_tolerance.max(0);
+        if a.status != b.status || a.matched != b.matched || size_changed {
+            comparison.changed.push(FuzzPayloadChange {
+                payload: a.payload.clone(),
+                result_id_a: a.id.clone(),
+                result_id_b: b.id.clone(),
+                status_a: a.status,
+                status_b: b.status,
+                content_length_a: a.content_length,
+                content_length_b: b.content_length,
+                matched_a: a.matched,
+                matched_b: b.matched,
+            });
+        } else {
+            comparison.unchanged += 1;
+        }
+    }
+
+    // Keep the second run's order for the leftovers
+    comparison.only_in_b = b
+        .iter()
+        .filter(|r| b_by_payload.remove(r.payload.as_str()).is_some())
+        .map(|r| r.payload.clone())
+        .collect();
+
+    comparison
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(payload: &str, status: i32, content_length: i32) -> FuzzResult {
+        FuzzResult {
+            id: format!("fz_{payload}_{status}"),
+            payload: payload.to_string(),
+            status,
+            content_length,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reports_changed_and_missing_payloads() {
+        let before = vec![
+            result("admin", 403, 100),
+            result("login", 200, 500),
+            result("debug", 404, 10),
+            result("old", 404, 10),
+        ];
+        let after = vec![
+            result("admin", 200, 2000),
+            result("login", 200, 503),
+            result("debug", 404, 40),
+            result("new", 200, 10),
+        ];
+
+        let comparison = compare_runs(&before, &after, 5);
+        assert_eq!(
+            comparison.changed.iter().map(|c| c.payload.as_str()).collect::<Vec<_>>(),
+            vec!["admin", "debug"]
+        );
+        assert_eq!(comparison.changed[0].status_a, 403);
+        assert_eq!(comparison.changed[0].status_b, 200);
+        assert_eq!(comparison.unchanged, 1);
+        assert_eq!(comparison.only_in_a, vec!["old"]);
+        assert_eq!(comparison.only_in_b, vec!["new"]);
+    }
+}
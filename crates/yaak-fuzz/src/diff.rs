@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+use yaak_models::models::{FuzzResult, HttpResponseHeader};
+
+/// Bodies whose differing region exceeds this many line pairs are reported as
+/// a wholesale replacement instead of running the quadratic line diff
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub enum FuzzDiffLine {
+    Same { text: String },
+    Removed { text: String },
+    Added { text: String },
+}
+
+/// A header whose value differs between the two results. Missing headers are
+/// `null` on that side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzHeaderDiff {
+    pub name: String,
+    pub a: Option<String>,
+    pub b: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzResultDiff {
+    pub status_a: i32,
+    pub status_b: i32,
+    pub content_length_a: i32,
+    pub content_length_b: i32,
+    /// Only headers that differ, by lowercased name
+    pub headers: Vec<FuzzHeaderDiff>,
+    pub body: Vec<FuzzDiffLine>,
+}
+
+/// Compare two results and their response bodies
+pub fn diff_results(
+    a: &FuzzResult,
+    a_body: &[u8],
+    b: &FuzzResult,
+    b_body: &[u8],
+) -> FuzzResultDiff {
+    FuzzResultDiff {
+        status_a: a.status,
+        status_b: b.status,
+        content_length_a: a.content_length,
+        content_length_b: b.content_length,
+        headers: diff_headers(&a.headers, &b.headers),
+        body: diff_lines(&String::from_utf8_lossy(a_body), &String::from_utf8_lossy(b_body)),
+    }
+}
+
+fn diff_headers(a: &[HttpResponseHeader], b: &[HttpResponseHeader]) -> Vec<FuzzHeaderDiff> {
+    // Repeated headers are joined so e.g. multiple Set-Cookie values compare as one
+    let collect = |headers: &[HttpResponseHeader]| {
+        let mut map: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+        for h in headers {
+            map.entry(h.name.to_lowercase()).or_default().push(&h.value);
+        }
+        map.into_iter().map(|(k, v)| (k, v.join(", "))).collect::<BTreeMap<_, _>>()
+    };
+    let mut a = collect(a);
+    let b = collect(b);
+
+    let mut diffs = Vec::new();
+    for (name, b_value) in b {
+        match a.remove(&name) {
+            Some(a_value) if a_value == b_value => {}
+            a_value => diffs.push(FuzzHeaderDiff { name, a: a_value, b: Some(b_value) }),
+        }
+    }
+    for (name, a_value) in a {
+        diffs.push(FuzzHeaderDiff { name, a: Some(a_value), b: None });
+    }
+    diffs.sort_by(|x, y| x.name.cmp(&y.name));
+    diffs
+}
+
+/// Line-level diff using the longest common subsequence of the region between
+/// the shared prefix and suffix
+pub fn diff_lines(a: &str, b: &str) -> Vec<FuzzDiffLine> {
+    let a: Vec<&str> = a.lines().collect();
+    let b: Vec<&str> = b.lines().collect();
+
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix =
+        a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(x, y)| x == y).count();
+    let a_mid = &a[prefix..a.len() - suffix];
+    let b_mid = &b[prefix..b.len() - suffix];
+
+    let same = |t: &&str| FuzzDiffLine::Same { text: t.to_string() };
+    let removed = |t: &&str| FuzzDiffLine::Removed { text: t.to_string() };
+    let added = |t: &&str| FuzzDiffLine::Added { text: t.to_string() };
+
+    let mut lines: Vec<FuzzDiffLine> = a[..prefix].iter().map(same).collect();
+
+    if a_mid.len().saturating_mul(b_mid.len()) > MAX_DIFF_CELLS {
+        lines.extend(a_mid.iter().map(removed));
+        lines.extend(b_mid.iter().map(added));
+    } else {
+        // lcs[i][j] is the LCS length of a_mid[i..] and b_mid[j..]
+        let width = b_mid.len() + 1;
+        let mut lcs = vec![0u32; (a_mid.len() + 1) * width];
+        for i in (0..a_mid.len()).rev() {
+            for j in (0..b_mid.len()).rev() {
+                lcs[i * width + j] = if a_mid[i] == b_mid[j] {
+                    lcs[(i + 1) * width + j + 1] + 1
+                } else {
+                    lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < a_mid.len() && j < b_mid.len() {
+            if a_mid[i] == b_mid[j] {
+                lines.push(same(&a_mid[i]));
+                i += 1;
+                j += 1;
+            } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+                lines.push(removed(&a_mid[i]));
+                i += 1;
+            } else {
+                lines.push(added(&b_mid[j]));
+                j += 1;
+            }
+        }
+        lines.extend(a_mid[i..].iter().map(removed));
+        lines.extend(b_mid[j..].iter().map(added));
+    }
+
+    lines.extend(a[a.len() - suffix..].iter().map(same));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(name: &str, value: &str) -> HttpResponseHeader {
+        HttpResponseHeader { name: name.to_string(), value: value.to_string() }
+    }
+
+    #[test]
+    fn diffs_changed_lines() {
+        let lines = diff_lines("a\nb\nc\nd", "a\nx\nc\nd\ne");
+        assert_eq!(
+            lines,
+            vec![
+                FuzzDiffLine::Same { text: "a".to_string() },
+                FuzzDiffLine::Removed { text: "b".to_string() },
+                FuzzDiffLine::Added { text: "x".to_string() },
+                FuzzDiffLine::Same { text: "c".to_string() },
+                FuzzDiffLine::Same { text: "d".to_string() },
+                FuzzDiffLine::Added { text: "e".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn diffs_headers_case_insensitively() {
+        let diffs = diff_headers(
+            &[header("Content-Type", "text/html"), header("X-Debug", "1")],
+            &[
+                header("content-type", "text/html"),
+                header("Location", "/admin"),
+            ],
+        );
+        assert_eq!(
+            diffs,
+            vec![
+                FuzzHeaderDiff {
+                    name: "location".to_string(),
+                    a: None,
+                    b: Some("/admin".to_string())
+                },
+                FuzzHeaderDiff { name: "x-debug".to_string(), a: Some("1".to_string()), b: None },
+            ]
+        );
+    }
+}
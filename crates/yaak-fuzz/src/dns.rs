@@ -0,0 +1,89 @@
+//! Hostnames a run resolves to fixed addresses, on top of the workspace's
+//! DNS overrides
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use yaak_http::dns::WILDCARD_HOSTNAME;
+use yaak_models::models::DnsOverride;
+
+/// Check that each of a run's overrides names a host and only valid addresses
+pub(crate) fn validate_dns_overrides(overrides: &[DnsOverride]) -> Result<()> {
+    for o in overrides.iter().filter(|o| o.enabled) {
+        if o.hostname.trim().is_empty() {
+            return Err(GenericError("Enter the hostname to resolve".to_string()));
+        }
+        if o.ipv4.is_empty() && o.ipv6.is_empty() {
+            return Err(GenericError(format!("Enter an address for {}", o.hostname)));
+        }
+        let invalid_ipv4 = o.ipv4.iter().find(|ip| ip.parse::<Ipv4Addr>().is_err());
+        let invalid_ipv6 = o.ipv6.iter().find(|ip| ip.parse::<Ipv6Addr>().is_err());
+        if let Some(ip) = invalid_ipv4.or(invalid_ipv6) {
+            return Err(GenericError(format!("Invalid address {ip} for {}", o.hostname)));
+        }
+    }
+    Ok(())
+}
+
+/// Addresses `overrides` resolve `host` to, picked like the HTTP client's
+/// resolver does: the last override of the host, or else of `*`. Empty
+/// without one.
+pub(crate) fn override_addresses(overrides: &[DnsOverride], host: &str) -> Vec<IpAddr> {
+    let host = host.trim_matches(['[', ']']);
+    let find = |name: &str| {
+        let matching = overrides.iter().rev().filter(|o| o.enabled);
+        matching
+            .filter(|o| o.hostname.eq_ignore_ascii_case(name))
+            .map(addresses)
+            .find(|a| !a.is_empty())
+    };
+    find(host).or_else(|| find(WILDCARD_HOSTNAME)).unwrap_or_default()
+}
+
+/// Part of a cached client's ID that tells a run's overrides apart
+pub(crate) fn overrides_id(overrides: &[DnsOverride]) -> String {
+    let enabled = overrides.iter().filter(|o| o.enabled);
+    let ids = enabled.map(|o| {
+        let addresses = addresses(o).iter().map(IpAddr::to_string).collect::<Vec<_>>();
+        format!("{}={}", o.hostname.to_lowercase(), addresses.join("+"))
+    });
+    let ids = ids.collect::<Vec<_>>();
+    if ids.is_empty() { String::new() } else { format!(".dns.{}", ids.join(",")) }
+}
+
+fn addresses(o: &DnsOverride) -> Vec<IpAddr> {
+    let ipv4 = o.ipv4.iter().filter_map(|ip| ip.parse::<Ipv4Addr>().ok()).map(IpAddr::V4);
+    let ipv6 = o.ipv6.iter().filter_map(|ip| ip.parse::<Ipv6Addr>().ok()).map(IpAddr::V6);
+    ipv4.chain(ipv6).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dns_override(hostname: &str, ipv4: &str) -> DnsOverride {
+        DnsOverride {
+            hostname: hostname.to_string(),
+            ipv4: vec![ipv4.to_string()],
+            ipv6: Vec::new(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn resolves_like_the_client() {
+        let overrides = vec![
+            dns_override("*", "10.0.0.1"),
+            dns_override("api.example.com", "10.0.0.5"),
+            dns_override("API.example.com", "10.0.0.6"),
+        ];
+        let ip = |ip: &str| vec![ip.parse::<IpAddr>().unwrap()];
+        assert_eq!(override_addresses(&overrides, "api.example.com"), ip("10.0.0.6"));
+        assert_eq!(override_addresses(&overrides, "other.example.com"), ip("10.0.0.1"));
+        assert!(override_addresses(&overrides[1..], "other.example.com").is_empty());
+
+        assert!(validate_dns_overrides(&overrides).is_ok());
+        assert!(validate_dns_overrides(&[dns_override("api.example.com", "10.0.0")]).is_err());
+        assert!(validate_dns_overrides(&[dns_override(" ", "10.0.0.5")]).is_err());
+    }
+}
@@ -0,0 +1,232 @@
+//! Encryption at rest for the runs and results of workspaces with encryption
+//! enabled. Stored bodies, headers and extracted values often hold
+//! credentials, and so do parts of a run's config like signing secrets, so
+//! they're encrypted with the workspace key before they're written.
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use serde_json::Value;
+use yaak_crypto::manager::EncryptionManager;
+use yaak_models::db_context::DbContext;
+use yaak_models::models::{FuzzResult, FuzzRun};
+
+/// Marks an encrypted value, like values of the `secure()` template function
+const ENCRYPTED_PREFIX: &str = "YENC_";
+
+/// Config keys of a run that can hold secrets: signing keys, user B's auth
+/// headers, the client certificate's passphrase, and proxy and webhook URLs
+/// with credentials or tokens in them
+const SECRET_CONFIG_KEYS: &[&str] = &["signing", "idor", "clientCertificate", "proxies", "webhook"];
+
+/// Whether runs and results in `workspace_id` should be encrypted
+pub fn encrypts_results(db: &DbContext, workspace_id: &str) -> Result<bool> {
+    Ok(db.get_workspace(workspace_id)?.encryption_key_challenge.is_some())
+}
+
+/// Copy of `result` with its header and extracted values encrypted, for
+/// storage. Header names stay readable.
+pub fn seal_result(
+    encryption_manager: &EncryptionManager,
+    result: &FuzzResult,
+) -> Result<FuzzResult> {
+    let workspace_id = &result.workspace_id;
+    let mut sealed = result.clone();
+    for value in sealed.extracted.values_mut() {
+        *value = seal_value(encryption_manager, workspace_id, value)?;
+    }
+    for header in &mut sealed.request_headers {
+        header.value = seal_value(encryption_manager, workspace_id, &header.value)?;
+    }
+    for header in &mut sealed.headers {
+        header.value = seal_value(encryption_manager, workspace_id, &header.value)?;
+    }
+    Ok(sealed)
+}
+
+/// Decrypt a stored result's header and extracted values. Values stored
+/// before the workspace enabled encryption are returned as they are.
+pub fn open_result(
+    encryption_manager: &EncryptionManager,
+    mut result: FuzzResult,
+) -> Result<FuzzResult> {
+    let workspace_id = result.workspace_id.clone();
+    for value in result.extracted.values_mut() {
+        *value = open_value(encryption_manager, &workspace_id, value)?;
+    }
+    for header in &mut result.request_headers {
+        header.value = open_value(encryption_manager, &workspace_id, &header.value)?;
+    }
+    for header in &mut result.headers {
+        header.value = open_value(encryption_manager, &workspace_id, &header.value)?;
+    }
+    Ok(result)
+}
+
+/// [`open_result`] for each of `results`
+pub fn open_results(
+    encryption_manager: &EncryptionManager,
+    results: Vec<FuzzResult>,
+) -> Result<Vec<FuzzResult>> {
+    results.into_iter().map(|r| open_result(encryption_manager, r)).collect()
+}
+
+/// Copy of `run` with the secret parts of its config encrypted, for storage.
+/// Each is replaced with the encrypted string of its JSON. Parts that are
+/// already encrypted are kept.
+pub fn seal_run(encryption_manager: &EncryptionManager, run: &FuzzRun) -> Result<FuzzRun> {
+    let mut sealed = run.clone();
+    for key in SECRET_CONFIG_KEYS {
+        let value = match sealed.config.get_mut(*key) {
+            None | Some(Value::Null) => continue,
+            Some(Value::String(s)) if s.starts_with(ENCRYPTED_PREFIX) => continue,
+            Some(value) => value,
+        };
+        let json = serde_json::to_string(value)?;
+        *value = Value::String(seal_value(encryption_manager, &run.workspace_id, &json)?);
+    }
+    Ok(sealed)
+}
+
+/// Decrypt the secret parts of a stored run's config. Runs stored before the
+/// workspace enabled encryption are returned as they are.
+pub fn open_run(encryption_manager: &EncryptionManager, mut run: FuzzRun) -> Result<FuzzRun> {
+    for key in SECRET_CONFIG_KEYS {
+        let sealed = match run.config.get(*key) {
+            Some(Value::String(s)) if s.starts_with(ENCRYPTED_PREFIX) => s.clone(),
+            _ => continue,
+        };
+        let json = open_value(encryption_manager, &run.workspace_id, &sealed)?;
+        run.config.insert(key.to_string(), serde_json::from_str(&json)?);
+    }
+    Ok(run)
+}
+
+/// [`open_run`] for each of `runs`
+pub fn open_runs(
+    encryption_manager: &EncryptionManager,
+    runs: Vec<FuzzRun>,
+) -> Result<Vec<FuzzRun>> {
+    runs.into_iter().map(|r| open_run(encryption_manager, r)).collect()
+}
+
+fn seal_value(
+    encryption_manager: &EncryptionManager,
+    workspace_id: &str,
+    value: &str,
+) -> Result<String> {
+    let encrypted = encryption_manager.encrypt(workspace_id, value.as_bytes())?;
+    Ok(format!("{ENCRYPTED_PREFIX}{}", BASE64_STANDARD.encode(encrypted)))
+}
+
+fn open_value(
+    encryption_manager: &EncryptionManager,
+    workspace_id: &str,
+    value: &str,
+) -> Result<String> {
+    let encoded = match value.strip_prefix(ENCRYPTED_PREFIX) {
+        Some(encoded) => encoded,
+        None => return Ok(value.to_string()),
+    };
+    let encrypted = BASE64_STANDARD
+        .decode(encoded)
+        .map_err(|_| GenericError("Stored encrypted value isn't base64".to_string()))?;
+    let decrypted = encryption_manager.decrypt(workspace_id, &encrypted)?;
+    String::from_utf8(decrypted)
+        .map_err(|_| GenericError("Stored encrypted value isn't UTF-8".to_string()))
+}
+
+/// Encryption manager that keeps its master key in memory instead of the OS
+/// keychain, with a key created for `workspace_id`
+#[cfg(test)]
+pub(crate) fn test_encryption_manager(
+    query_manager: yaak_models::query_manager::QueryManager,
+    workspace_id: &str,
+) -> EncryptionManager {
+    keyring::set_default_credential_builder(keyring::mock::default_credential_builder());
+    let encryption_manager = EncryptionManager::new(query_manager, "test");
+    encryption_manager.ensure_workspace_key(workspace_id).unwrap();
+    encryption_manager
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use yaak_models::init_in_memory;
+    use yaak_models::models::{HttpRequestHeader, HttpResponseHeader, Workspace};
+    use yaak_models::util::UpdateSource;
+
+    #[test]
+    fn plain_values_are_opened_unchanged() {
+        let (query_manager, _blob_manager, _rx) = init_in_memory().expect("Failed to init DB");
+        let workspace = query_manager
+            .connect()
+            .upsert_workspace(&Workspace::default(), &UpdateSource::Background)
+            .unwrap();
+        assert!(!encrypts_results(&query_manager.connect(), &workspace.id).unwrap());
+
+        let encryption_manager = EncryptionManager::new(query_manager, "test");
+        let result = FuzzResult {
+            workspace_id: workspace.id,
+            extracted: BTreeMap::from([("token".to_string(), "abc".to_string())]),
+            ..Default::default()
+        };
+        let opened = open_result(&encryption_manager, result.clone()).unwrap();
+        assert_eq!(opened.extracted, result.extracted);
+    }
+
+    #[test]
+    fn sealed_results_and_runs_open_to_the_original() {
+        let (query_manager, _blob_manager, _rx) = init_in_memory().expect("Failed to init DB");
+        let workspace = query_manager
+            .connect()
+            .upsert_workspace(&Workspace::default(), &UpdateSource::Background)
+            .unwrap();
+        let encryption_manager = test_encryption_manager(query_manager.clone(), &workspace.id);
+        assert!(encrypts_results(&query_manager.connect(), &workspace.id).unwrap());
+
+        let result = FuzzResult {
+            workspace_id: workspace.id.clone(),
+            request_headers: vec![HttpResponseHeader {
+                name: "Authorization".to_string(),
+                value: "Bearer abc".to_string(),
+            }],
+            headers: vec![HttpResponseHeader {
+                name: "Set-Cookie".to_string(),
+                value: "session=xyz".to_string(),
+            }],
+            extracted: BTreeMap::from([("token".to_string(), "abc".to_string())]),
+            ..Default::default()
+        };
+        let sealed = seal_result(&encryption_manager, &result).unwrap();
+        assert_eq!(sealed.headers[0].name, "Set-Cookie");
+        assert!(sealed.headers[0].value.starts_with(ENCRYPTED_PREFIX));
+        assert!(sealed.request_headers[0].value.starts_with(ENCRYPTED_PREFIX));
+        let opened = open_result(&encryption_manager, sealed).unwrap();
+        assert_eq!(opened.request_headers, result.request_headers);
+        assert_eq!(opened.headers, result.headers);
+        assert_eq!(opened.extracted, result.extracted);
+
+        let user_b = vec![HttpRequestHeader {
+            name: "Cookie".to_string(),
+            value: "session=b".to_string(),
+            ..Default::default()
+        }];
+        let run = FuzzRun {
+            workspace_id: workspace.id,
+            config: BTreeMap::from([
+                ("idor".to_string(), serde_json::json!({ "userB": user_b })),
+                ("signing".to_string(), Value::Null),
+                ("wordlist".to_string(), serde_json::json!(["admin"])),
+            ]),
+            ..Default::default()
+        };
+        let sealed = seal_run(&encryption_manager, &run).unwrap();
+        assert!(sealed.config["idor"].as_str().unwrap().starts_with(ENCRYPTED_PREFIX));
+        assert_eq!(sealed.config["wordlist"], run.config["wordlist"]);
+        assert_eq!(seal_run(&encryption_manager, &sealed).unwrap().config, sealed.config);
+        assert_eq!(open_run(&encryption_manager, sealed).unwrap().config, run.config);
+    }
+}
@@ -0,0 +1,1195 @@
+use crate::agents::validate_agents;
+use crate::auth_bypass::{AUTH_BYPASS_TAG, strip_auth, validate_auth_bypass};
+use crate::base_urls::{group_by_base_url, rebased_config, schedule, validate_base_urls};
+use crate::boolean_sqli::{
+    BOOLEAN_CONDITION_VALUE, Fingerprint, find_condition, validate_boolean_sqli,
+};
+use crate::byte_payloads::{insert_payload_bytes, validate_payload_encoding};
+use crate::callbacks::{CallbackListener, FuzzInteraction, InteractionTracker, validate_callbacks};
+use crate::canaries::FuzzVulnClass;
+use crate::columns::{apply_columns, validate_columns};
+use crate::dns::validate_dns_overrides;
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::estimate::validate_max_requests;
+use crate::follow_up::{PAYLOAD_VARIABLE, send_follow_up};
+use crate::host_limits::HostLimiter;
+use crate::http2::validate_http2;
+use crate::idor::{IDOR_TAG, as_user_b, validate_idor};
+use crate::markers::payload_request;
+use crate::matchers::{ResponseMatcher, ValueExtractors};
+use crate::metrics::{METRICS_INTERVAL, RunMetrics};
+use crate::open_redirect::OPEN_REDIRECT_TAG;
+use crate::pre_request::PreRequest;
+use crate::proxies::validate_proxies;
+use crate::recursion::{child_config, validate_recursion};
+use crate::reflection::{REFLECTION_VALUE, describe_reflections};
+use crate::rotation::rotate_headers;
+use crate::safe_mode::{check_safe_method, validate_safe_mode};
+use crate::sampling::validate_sample;
+use crate::sender::{FuzzResponse, FuzzSender, SentRequest};
+use crate::session::Session;
+use crate::targets::validate_targets;
+use crate::tls::validate_tls;
+use crate::types::{FuzzCookies, FuzzEvent, FuzzRequest, FuzzSummary};
+use crate::vhost::{unknown_host, validate_vhost};
+use crate::webhook::validate_webhook;
+use futures_util::{StreamExt, future, stream};
+use log::warn;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, watch};
+use tokio::time::interval;
+use tracing::{Instrument, info_span, instrument};
+use yaak_http::cookies::CookieStore;
+use yaak_models::models::{FuzzErrorKind, FuzzResult, HttpRequest, HttpResponseHeader};
+use yaak_models::util::generate_prefixed_id;
+use yaak_templates::escape::escape_template;
+
+/// Check that a run's matchers and extractors compile and its proxy URLs
+/// parse, so a bad config can be reported before any requests are sent.
+pub fn validate_fuzz_request(config: &FuzzRequest) -> Result<()> {
+    ResponseMatcher::new(&config.matchers, config.match_mode)?
+        .with_filters(&config.filters)?
+        .with_verdicts(&config.verdicts)?
+        .detecting_open_redirects(config.open_redirect)
+        .analyzing_reflections(config.reflection_analysis);
+    ValueExtractors::new(&config.extractors)?;
+    Session::new(config.session.as_ref(), &config.request)?;
+    config.pre_request.as_ref().map(PreRequest::new).transpose()?;
+    validate_targets(&config.targets)?;
+    validate_recursion(config)?;
+    validate_base_urls(config)?;
+    validate_vhost(config)?;
+    validate_http2(config)?;
+    validate_tls(config)?;
+    validate_dns_overrides(&config.dns_overrides)?;
+    if let Some(address) = &config.local_address {
+        address
+            .trim()
+            .parse::<IpAddr>()
+            .map_err(|_| GenericError(format!("Invalid local address {address}")))?;
+    }
+    if config.record_responses && config.request.id.is_empty() {
+        return Err(GenericError("Recording responses requires a saved request".to_string()));
+    }
+    match &config.cookies {
+        FuzzCookies::Workspace { cookie_jar_id } if cookie_jar_id.is_empty() => {
+            return Err(GenericError("Choose a cookie jar to share with the run".to_string()));
+        }
+        _ => {}
+    }
+    let logs_in = config.session.as_ref().is_some_and(|s| !s.login.is_empty());
+    if logs_in && config.cookies == FuzzCookies::PerPayload {
+        return Err(GenericError("Logging in again needs a cookie jar for the run".to_string()));
+    }
+    validate_proxies(&config.proxies)?;
+    validate_safe_mode(config)?;
+    validate_payload_encoding(config)?;
+    validate_callbacks(config)?;
+    validate_columns(config)?;
+    validate_boolean_sqli(config)?;
+    validate_auth_bypass(config)?;
+    validate_idor(config)?;
+    validate_webhook(config)?;
+    validate_agents(config)?;
+    validate_sample(config)?;
+    validate_max_requests(config)?;
+    Ok(())
+}
+
+/// Run every payload of the config against the request template, once for
+/// each base URL of a multi-target run, emitting a
+/// `Result` and `Progress` event as each request completes, `Metrics` about
+/// once a second, and a `Finished` event at the end. Stops scheduling new payloads once `cancelled_rx` is set.
+/// Results are tagged with `run_id` but not persisted.
+pub async fn run_fuzz<S: FuzzSender>(
+    run_id: &str,
+    config: &FuzzRequest,
+    sender: &S,
+    events_tx: &mpsc::UnboundedSender<FuzzEvent>,
+    cancelled_rx: watch::Receiver<bool>,
+) -> Result<FuzzSummary> {
+    run_queue(run_id, config, sender, fuzz_queue(config), events_tx, cancelled_rx).await
+}
+
+/// A fresh result for every payload of the config against each base URL
+pub(crate) fn fuzz_queue(config: &FuzzRequest) -> Vec<FuzzResult> {
+    let payloads = config.payloads();
+    let mut queue = Vec::new();
+    if config.base_urls.urls.is_empty() {
+        queue = payload_queue(&payloads, "", 0);
+    }
+    for base_url in &config.base_urls.urls {
+        queue.extend(payload_queue(&payloads, base_url, queue.len()));
+    }
+    queue
+}
+
+/// A fresh result for each payload sent to `base_url`, numbered from
+/// `first_index`
+fn payload_queue(payloads: &[String], base_url: &str, first_index: usize) -> Vec<FuzzResult> {
+    payloads
+        .iter()
+        .enumerate()
+        .map(|(index, payload)| FuzzResult {
+            id: generate_prefixed_id("fz"),
+            payload_index: (first_index + index) as i32,
+            payload: payload.clone(),
+            base_url: base_url.to_string(),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Run the payloads of `results` that failed on the network or timed out
+/// again, emitting events like `run_fuzz`. Payloads blocked by safe mode or the
+/// fuzz scope would fail the same way, so they aren't retried. Retried results
+/// keep their ID and triage state so persisting them replaces the failed ones
+/// in the same run. The summary only covers the retried payloads.
+pub async fn retry_fuzz<S: FuzzSender>(
+    run_id: &str,
+    config: &FuzzRequest,
+    results: &[FuzzResult],
+    sender: &S,
+    events_tx: &mpsc::UnboundedSender<FuzzEvent>,
+    cancelled_rx: watch::Receiver<bool>,
+) -> Result<FuzzSummary> {
+    let queue = results
+        .iter()
+        .filter(|r| matches!(r.error_kind, Some(FuzzErrorKind::Network | FuzzErrorKind::Timeout)))
+        .map(|r| FuzzResult {
+            id: r.id.clone(),
+            created_at: r.created_at,
+            payload_index: r.payload_index,
+            payload: r.payload.clone(),
+            base_url: r.base_url.clone(),
+            starred: r.starred,
+            tags: r.tags.clone(),
+            note: r.note.clone(),
+            ..Default::default()
+        })
+        .collect();
+    run_queue(run_id, config, sender, queue, events_tx, cancelled_rx).await
+}
+
+/// Send each queued payload. Entries only carry the result's identity, payload,
+/// and triage state; everything else is filled in from the response. With
+/// recursion, the paths of matched results are fuzzed next, a level at a
+/// time, growing the total.
+#[instrument(name = "fuzz_run", skip_all, fields(run_id = %run_id, total = queue.len()))]
+pub(crate) async fn run_queue<S: FuzzSender>(
+    run_id: &str,
+    config: &FuzzRequest,
+    sender: &S,
+    queue: Vec<FuzzResult>,
+    events_tx: &mpsc::UnboundedSender<FuzzEvent>,
+    cancelled_rx: watch::Receiver<bool>,
+) -> Result<FuzzSummary> {
+    let mut matcher = ResponseMatcher::new(&config.matchers, config.match_mode)?
+        .with_filters(&config.filters)?
+        .with_verdicts(&config.verdicts)?
+        .detecting_open_redirects(config.open_redirect)
+        .analyzing_reflections(config.reflection_analysis);
+    if config.vhost.is_some() {
+        matcher = matcher.filtering_baseline();
+    }
+    let extractors = ValueExtractors::new(&config.extractors)?;
+    let session = Session::new(config.session.as_ref(), &config.request)?;
+    let pre_request = config.pre_request.as_ref().map(PreRequest::new).transpose()?;
+    let delay = Duration::from_millis(config.delay_ms as u64);
+    let host_limiter = HostLimiter::new(config.host_limits);
+    let started_at = Instant::now();
+    let mut summary = FuzzSummary { total: queue.len(), ..Default::default() };
+    let (callbacks, mut interactions) = match &config.callbacks {
+        Some(callbacks) => {
+            let (listener, rx) = CallbackListener::start(callbacks).await?;
+            (Some(listener), Some(rx))
+        }
+        None => (None, None),
+    };
+    let mut tracker = InteractionTracker::default();
+    let metrics = RunMetrics::new(config.concurrency);
+    let mut metrics_ticker = interval(METRICS_INTERVAL);
+    metrics_ticker.reset();
+
+    // A failed login isn't fatal, it's attempted again once a response shows
+    // that the session expired
+    if let Err(e) = session.start(sender).await {
+        warn!("Failed to log in before fuzz run {run_id}: {e:?}");
+    }
+
+    // Without a baseline, matchers comparing with it never fire. Multi-target
+    // runs compare with the first target's.
+    if matcher.needs_baseline() {
+        let first_base_url = config.base_urls.urls.first().map_or("", String::as_str);
+        let baseline_config = rebased_config(config, first_base_url);
+        match send_baseline(&baseline_config, sender, &session, pre_request.as_ref()).await {
+            Ok(response) => matcher.set_baseline(&response),
+            Err(e) => warn!("Failed to send the baseline for fuzz run {run_id}: {e:?}"),
+        }
+    }
+
+    let max_depth = config.recursion.map_or(0, |r| r.max_depth);
+    let payloads = config.payloads();
+    let mut next_index = config.request_count();
+    let mut level = group_by_base_url(config, queue);
+    for depth in 0..=max_depth {
+        let mut found = Vec::new();
+        let (configs, queues): (Vec<_>, Vec<_>) = level.into_iter().unzip();
+        let entries = schedule(queues, config.base_urls.order);
+        let mut results = stream::iter(entries.into_iter().enumerate())
+            .take_while(|_| future::ready(!*cancelled_rx.borrow()))
+            .then(|(position, entry)| async move {
+                if position > 0 && !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+                entry
+            })
+            .map(|(group, entry)| {
+                let pre_request = pre_request.as_ref();
+                let in_flight = metrics.start();
+                let sent = run_payload(
+                    run_id,
+                    &configs[group],
+                    sender,
+                    &matcher,
+                    &extractors,
+                    &session,
+                    pre_request,
+                    &host_limiter,
+                    callbacks.as_ref(),
+                    entry,
+                );
+                async move {
+                    let _in_flight = in_flight;
+                    sent.await
+                }
+            })
+            .buffer_unordered(config.concurrency.max(1));
+
+        loop {
+            let mut event = tokio::select! {
+                event = results.next() => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+                Some(interaction) = next_interaction(&mut interactions) => {
+                    receive_interaction(&mut tracker, &mut summary, events_tx, interaction);
+                    continue;
+                }
+                _ = metrics_ticker.tick() => {
+                    let _ = events_tx.send(FuzzEvent::Metrics { metrics: metrics.snapshot() });
+                    continue;
+                }
+            };
+            if let FuzzEvent::Result { result, .. } = &mut event {
+                tracker.complete(result);
+                metrics.record(result);
+                summary.completed += 1;
+                if result.matched {
+                    summary.matched += 1;
+                    found.push((result.base_url.clone(), result.url.clone()));
+                }
+                summary.statuses.count(result);
+                if result.error.is_some() {
+                    summary.errors += 1;
+                }
+            }
+            let _ = events_tx.send(event);
+            let _ = events_tx.send(FuzzEvent::Progress {
+                completed: summary.completed,
+                total: summary.total,
+                statuses: summary.statuses,
+            });
+        }
+
+        if depth == max_depth || *cancelled_rx.borrow() {
+            break;
+        }
+        level = found
+            .iter()
+            .map(|(base_url, url)| {
+                let queue = payload_queue(&payloads, base_url, next_index);
+                next_index += queue.len();
+                summary.total += queue.len();
+                (Cow::Owned(child_config(config, url)), queue)
+            })
+            .collect();
+    }
+
+    // Interactions can arrive well after the request that caused them
+    let grace = config.callbacks.as_ref().map_or(0, |c| c.grace_ms);
+    if grace > 0 && !*cancelled_rx.borrow() {
+        let deadline = tokio::time::sleep(Duration::from_millis(grace as u64));
+        tokio::pin!(deadline);
+        loop {
+            tokio::select! {
+                _ = &mut deadline => break,
+                Some(interaction) = next_interaction(&mut interactions) => {
+                    receive_interaction(&mut tracker, &mut summary, events_tx, interaction);
+                }
+                else => break,
+            }
+        }
+    }
+
+    summary.cancelled = *cancelled_rx.borrow();
+    summary.elapsed = duration_to_i32(started_at.elapsed());
+    let _ = events_tx.send(FuzzEvent::Finished { summary: summary.clone() });
+
+    Ok(summary)
+}
+
+/// The next interaction from the run's callback listener, never for runs
+/// without one
+async fn next_interaction(
+    interactions: &mut Option<mpsc::UnboundedReceiver<FuzzInteraction>>,
+) -> Option<FuzzInteraction> {
+    match interactions {
+        Some(rx) => rx.recv().await,
+        None => future::pending().await,
+    }
+}
+
+/// Emit an interaction once its result has completed, counting the result
+/// as matched if it wasn't already
+fn receive_interaction(
+    tracker: &mut InteractionTracker,
+    summary: &mut FuzzSummary,
+    events_tx: &mpsc::UnboundedSender<FuzzEvent>,
+    interaction: FuzzInteraction,
+) {
+    if let Some((interaction, newly_matched)) = tracker.receive(interaction) {
+        if newly_matched {
+            summary.matched += 1;
+        }
+        let _ = events_tx.send(FuzzEvent::Interaction { interaction });
+    }
+}
+
+/// Send the request with an empty payload, which leaves out headers and
+/// parameters that are added per payload. A vhost run's baseline goes to an
+/// unknown host instead, answered by the default vhost.
+async fn send_baseline<S: FuzzSender>(
+    config: &FuzzRequest,
+    sender: &S,
+    session: &Session<'_>,
+    pre_request: Option<&PreRequest<'_>>,
+) -> Result<FuzzResponse> {
+    let cookies = (config.cookies == FuzzCookies::PerPayload).then(CookieStore::new);
+    let (mut variables, _) = session.variables().await;
+    if let Some(pre_request) = pre_request {
+        pre_request.send(sender, &mut variables, cookies.as_ref()).await?;
+    }
+    let payload = if config.vhost.is_some() { unknown_host() } else { String::new() };
+    insert_payload_bytes(config, &payload, &mut variables);
+    sender.send(&payload_request(config, &payload), &variables, cookies.as_ref()).await
+}
+
+#[allow(clippy::too_many_arguments)]
+#[instrument(
+    name = "fuzz_dispatch",
+    skip_all,
+    fields(payload_index = entry.payload_index, base_url = %entry.base_url)
+)]
+async fn run_payload<S: FuzzSender>(
+    run_id: &str,
+    config: &FuzzRequest,
+    sender: &S,
+    matcher: &ResponseMatcher,
+    extractors: &ValueExtractors,
+    session: &Session<'_>,
+    pre_request: Option<&PreRequest<'_>>,
+    host_limiter: &HostLimiter,
+    callbacks: Option<&CallbackListener>,
+    entry: FuzzResult,
+) -> FuzzEvent {
+    let request = payload_request(config, &entry.payload);
+    let request = rotate_headers(config, request, entry.payload_index as usize);
+    let mut result = FuzzResult {
+        model: "fuzz_result".to_string(),
+        workspace_id: config.request.workspace_id.clone(),
+        run_id: run_id.to_string(),
+        method: request.method.clone(),
+        url: request.url.clone(),
+        ..entry
+    };
+    if let Err(e) = check_safe_method(config, &request.method) {
+        result.error_kind = Some(e.kind());
+        result.error = Some(e.to_string());
+        return FuzzEvent::Result {
+            result,
+            body: Vec::new(),
+            url: request.url,
+            request_body: Vec::new(),
+        };
+    }
+
+    let cookies = (config.cookies == FuzzCookies::PerPayload).then(CookieStore::new);
+    let mut body = Vec::new();
+    let mut request_body = Vec::new();
+    let mut url = request.url.clone();
+    let mut original = None;
+    let turn = host_limiter.turn(&request.url).await;
+    let started_at = Instant::now();
+    // Log in and send again while responses show that the session expired.
+    // The template is passed unrendered each time so the sender evaluates its
+    // template functions again for every attempt.
+    let callback_variables = callbacks.map(|c| c.variables(&result.id)).unwrap_or_default();
+    let mut retries = 0;
+    let sent = loop {
+        let (mut variables, generation) = session.variables().await;
+        variables.extend(callback_variables.iter().cloned());
+        variables.insert(PAYLOAD_VARIABLE.to_string(), escape_template(&result.payload));
+        let sent = async {
+            if let Some(pre_request) = pre_request {
+                pre_request.send(sender, &mut variables, cookies.as_ref()).await?;
+            }
+            insert_payload_bytes(config, &result.payload, &mut variables);
+            let span = info_span!("fuzz_send", method = %request.method, url = %request.url);
+            sender.send(&request, &variables, cookies.as_ref()).instrument(span).await
+        }
+        .await;
+        let expired = matches!(&sent, Ok(response) if session.is_expired(response));
+        if !expired || retries >= session.max_retries() {
+            break sent;
+        }
+        retries += 1;
+        if let Err(e) = session.renew(sender, generation).await {
+            warn!("Failed to renew the session during fuzz run {run_id}: {e:?}");
+            break sent;
+        }
+    };
+    drop(turn);
+    match sent {
+        Ok(response) => {
+            url = response.url.clone();
+            original = Some(Fingerprint::of(&response));
+            (body, request_body) = apply_response(&mut result, response, matcher, extractors);
+            apply_verdict_script(sender, config, &mut result, &body).await;
+            apply_columns(sender, config, &mut result, &body).await;
+        }
+        Err(e) => {
+            result.request_size_bytes = unsent_request(&request).size() as i32;
+            result.error_kind = Some(e.kind());
+            result.error = Some(e.to_string());
+            result.matched = e.kind() == FuzzErrorKind::Timeout && matcher.matches_timeout();
+        }
+    }
+    result.elapsed = duration_to_i32(started_at.elapsed());
+
+    if let (Some(boolean), Some(original)) = (&config.boolean_sqli, original) {
+        let index = result.payload_index as usize;
+        let cookies = cookies.as_ref();
+        let send = |payload: String| {
+            let request = rotate_headers(config, payload_request(config, &payload), index);
+            send_variant(sender, session, pre_request, host_limiter, cookies, request)
+        };
+        if let Some(condition) = find_condition(boolean, &result.payload, original, send).await {
+            flag_result(&mut result, FuzzVulnClass::Sqli.tag());
+            result.extracted.insert(BOOLEAN_CONDITION_VALUE.to_string(), condition);
+        }
+    }
+
+    // Probes sent as someone else, without the run's cookies, compared with
+    // the result's own response. Only responses that succeeded are worth
+    // comparing, an endpoint that turns everyone away answers the same either
+    // way.
+    let as_others = [
+        config
+            .auth_bypass
+            .as_ref()
+            .map(|b| (strip_auth(&request, &b.headers), b.size_tolerance, AUTH_BYPASS_TAG)),
+        config.idor.as_ref().map(|i| (as_user_b(&request, i), i.size_tolerance, IDOR_TAG)),
+    ];
+    for (probe, tolerance, tag) in as_others.into_iter().flatten() {
+        let Some(original) = original.filter(Fingerprint::is_success) else {
+            break;
+        };
+        let empty_jar = CookieStore::new();
+        let sent =
+            send_variant(sender, session, pre_request, host_limiter, Some(&empty_jar), probe);
+        if sent.await.is_ok_and(|r| original.resembles(&Fingerprint::of(&r), tolerance)) {
+            flag_result(&mut result, tag);
+        }
+    }
+
+    if let (true, Some(follow_up)) = (result.matched, &config.follow_up) {
+        result.follow_up = Some(send_follow_up(sender, follow_up, &result, cookies.as_ref()).await);
+    }
+
+    FuzzEvent::Result { result, body, url, request_body }
+}
+
+/// Fill in `result` from a response and the request that produced it.
+/// Returns the response body and the request body, which are stored
+/// separately from the result.
+#[instrument(name = "fuzz_match", skip_all)]
+pub(crate) fn apply_response(
+    result: &mut FuzzResult,
+    response: FuzzResponse,
+    matcher: &ResponseMatcher,
+    extractors: &ValueExtractors,
+) -> (Vec<u8>, Vec<u8>) {
+    result.method = response.request.method.clone();
+    result.url = response.request.url.clone();
+    result.request_headers = to_headers(response.request.headers.clone());
+    result.request_size_bytes = response.request.size() as i32;
+    result.status = response.status as i32;
+    result.http_version = response.version.clone();
+    result.content_length = response.body.len() as i32;
+    result.body_hash = hex::encode(Sha256::digest(&response.body));
+    result.matched = matcher.is_match(&response);
+    for verdict in matcher.verdicts(&response) {
+        flag_result(result, verdict.tag());
+    }
+    if matcher.is_open_redirect(&response, &result.payload) {
+        flag_result(result, OPEN_REDIRECT_TAG);
+    }
+    result.extracted = extractors.extract(&response);
+    let reflections = matcher.reflections(&response, &result.payload);
+    if !reflections.is_empty() {
+        if reflections.iter().any(|r| r.exploitable) {
+            flag_result(result, FuzzVulnClass::Xss.tag());
+        }
+        result.extracted.insert(REFLECTION_VALUE.to_string(), describe_reflections(&reflections));
+    }
+    result.headers = to_headers(response.headers);
+    (response.body, response.request.body)
+}
+
+/// Let the run's verdict script judge `result`, merging in what it extracts.
+/// When the script fails, the matchers' verdict stands and the failure is
+/// recorded as the result's error.
+pub(crate) async fn apply_verdict_script<S: FuzzSender>(
+    sender: &S,
+    config: &FuzzRequest,
+    result: &mut FuzzResult,
+    body: &[u8],
+) {
+    let Some(script) = &config.verdict_script else {
+        return;
+    };
+    match sender.run_script(script, result, body).await {
+        Ok(verdict) => {
+            if let Some(matched) = verdict.matched {
+                result.matched = matched;
+            }
+            result.extracted.extend(verdict.extracted);
+        }
+        Err(e) => {
+            result.error_kind = Some(e.kind());
+            result.error = Some(format!("Verdict script failed: {e}"));
+        }
+    }
+}
+
+/// Send a variant of a result's request, for probes that don't make
+/// results of their own
+async fn send_variant<S: FuzzSender>(
+    sender: &S,
+    session: &Session<'_>,
+    pre_request: Option<&PreRequest<'_>>,
+    host_limiter: &HostLimiter,
+    cookies: Option<&CookieStore>,
+    request: HttpRequest,
+) -> Result<FuzzResponse> {
+    let _turn = host_limiter.turn(&request.url).await;
+    let (mut variables, _) = session.variables().await;
+    if let Some(pre_request) = pre_request {
+        pre_request.send(sender, &mut variables, cookies).await?;
+    }
+    sender.send(&request, &variables, cookies).await
+}
+
+/// `request` with its payload injected but not rendered, standing in for the
+/// sent request of a result that failed without one
+fn unsent_request(request: &HttpRequest) -> SentRequest {
+    SentRequest {
+        method: request.method.clone(),
+        url: request.url.clone(),
+        headers: request
+            .headers
+            .iter()
+            .filter(|h| h.enabled && !h.name.is_empty())
+            .map(|h| (h.name.clone(), h.value.clone()))
+            .collect(),
+        body: request.body.get("text").and_then(|t| t.as_str()).unwrap_or_default().into(),
+    }
+}
+
+/// Match `result` and tag it with why
+fn flag_result(result: &mut FuzzResult, tag: &str) {
+    result.matched = true;
+    if !result.tags.iter().any(|t| t == tag) {
+        result.tags.push(tag.to_string());
+    }
+}
+
+fn to_headers(headers: Vec<(String, String)>) -> Vec<HttpResponseHeader> {
+    headers.into_iter().map(|(name, value)| HttpResponseHeader { name, value }).collect()
+}
+
+pub(crate) fn duration_to_i32(duration: Duration) -> i32 {
+    let millis = duration.as_millis();
+    if millis > i32::MAX as u128 { i32::MAX } else { millis as i32 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{
+        FuzzBaseUrlOrder, FuzzBaseUrls, FuzzExtractor, FuzzFollowUp, FuzzMatcher, FuzzPreRequest,
+        FuzzRecursion, FuzzSession, FuzzShuffle, FuzzStatusCounts, FuzzTarget,
+    };
+    use async_trait::async_trait;
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+    use yaak_models::models::HttpRequest;
+    use yaak_plugins::events::RunFuzzScriptResponse;
+
+    /// Responds 200 with a token for `/login/admin`, fails for `/login/broken`,
+    /// and 401 for everything else. A session cookie is set in any jar it's
+    /// given.
+    #[derive(Default)]
+    struct MockSender {
+        sent: Mutex<Vec<(String, BTreeMap<String, String>)>>,
+        cookies_sent: Mutex<Vec<(String, Option<String>)>>,
+        auth_actions: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl FuzzSender for MockSender {
+        async fn send(
+            &self,
+            request: &HttpRequest,
+            variables: &BTreeMap<String, String>,
+            cookies: Option<&CookieStore>,
+        ) -> Result<FuzzResponse> {
+            self.sent.lock().unwrap().push((request.url.clone(), variables.clone()));
+            if let Some(cookies) = cookies {
+                let url = url::Url::parse(&request.url).unwrap();
+                let header = cookies.get_cookie_header(&url);
+                self.cookies_sent.lock().unwrap().push((request.url.clone(), header));
+                cookies.store_cookies_from_response(&url, &["session=s-1; Path=/".to_string()]);
+            }
+            let sent = SentRequest {
+                method: request.method.clone(),
+                url: request.url.clone(),
+                ..Default::default()
+            };
+            self.send_exact(&request.workspace_id, &sent).await
+        }
+
+        async fn send_exact(
+            &self,
+            _workspace_id: &str,
+            request: &SentRequest,
+        ) -> Result<FuzzResponse> {
+            let (status, body) = match request.url.as_str() {
+                "https://example.com/login/admin" => (200, "{\"token\":\"t-1\"}"),
+                "https://example.com/login/broken" => {
+                    return Err(GenericError("connection refused".to_string()));
+                }
+                "https://example.com/login/slow" => {
+                    return Err(yaak_http::error::Error::RequestTimeout(Duration::ZERO).into());
+                }
+                _ => (401, "denied"),
+            };
+            Ok(FuzzResponse {
+                url: request.url.clone(),
+                status,
+                version: "HTTP/1.1".to_string(),
+                headers: Vec::new(),
+                body: body.as_bytes().to_vec(),
+                elapsed: Duration::ZERO,
+                request: request.clone(),
+            })
+        }
+
+        async fn refresh_auth(&self, _request: &HttpRequest, action: &str) -> Result<()> {
+            self.auth_actions.lock().unwrap().push(action.to_string());
+            Ok(())
+        }
+
+        /// Matches responses whose status is the script
+        async fn run_script(
+            &self,
+            script: &str,
+            result: &FuzzResult,
+            body: &[u8],
+        ) -> Result<RunFuzzScriptResponse> {
+            Ok(RunFuzzScriptResponse {
+                matched: Some(result.status.to_string() == script),
+                extracted: [("length".to_string(), body.len().to_string())].into(),
+            })
+        }
+    }
+
+    fn config(wordlist: &[&str]) -> FuzzRequest {
+        FuzzRequest {
+            request: HttpRequest {
+                url: "https://example.com/login/§user§".to_string(),
+                ..Default::default()
+            },
+            wordlist: wordlist.iter().map(|w| w.to_string()).collect(),
+            concurrency: 2,
+            matchers: vec![FuzzMatcher::Status { codes: vec![200] }],
+            extractors: vec![FuzzExtractor::Regex {
+                name: "token".to_string(),
+                pattern: "\"token\":\"([^\"]+)\"".to_string(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    async fn run(config: &FuzzRequest, sender: &MockSender) -> (FuzzSummary, Vec<FuzzResult>) {
+        let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let summary = run_fuzz("fr_test", config, sender, &events_tx, cancel_rx).await.unwrap();
+        drop(events_tx);
+
+        let mut results = Vec::new();
+        while let Some(event) = events_rx.recv().await {
+            if let FuzzEvent::Result { result, .. } = event {
+                results.push(result);
+            }
+        }
+        results.sort_by_key(|r| r.payload_index);
+        (summary, results)
+    }
+
+    #[tokio::test]
+    async fn runs_every_payload() {
+        let sender = MockSender::default();
+        let (summary, results) = run(&config(&["guest", "admin", "broken"]), &sender).await;
+
+        assert_eq!(summary.total, 3);
+        assert_eq!(summary.completed, 3);
+        assert_eq!(summary.matched, 1);
+        assert_eq!(summary.errors, 1);
+        assert_eq!(
+            summary.statuses,
+            FuzzStatusCounts { success: 1, client_error: 1, no_response: 1, ..Default::default() }
+        );
+        assert!(!summary.cancelled);
+
+        assert_eq!(results[0].run_id, "fr_test");
+        assert_eq!(results[0].status, 401);
+        assert_eq!(results[0].http_version, "HTTP/1.1");
+        assert_eq!(results[0].url, "https://example.com/login/guest");
+        assert_eq!(results[0].request_size_bytes, " /login/guest HTTP/1.1\r\n\r\n".len() as i32);
+        assert!(!results[0].matched);
+        assert!(results[1].matched);
+        assert_eq!(results[1].extracted.get("token").map(String::as_str), Some("t-1"));
+        assert_eq!(results[2].error.as_deref(), Some("Fuzz error: connection refused"));
+        assert_eq!(results[2].error_kind, Some(FuzzErrorKind::Other));
+        assert_eq!(results[2].request_size_bytes, " /login/broken HTTP/1.1\r\n\r\n".len() as i32);
+    }
+
+    #[tokio::test]
+    async fn verdict_scripts_override_the_matchers() {
+        let sender = MockSender::default();
+        let mut config = config(&["guest", "admin"]);
+        config.verdict_script = Some("401".to_string());
+
+        let (summary, results) = run(&config, &sender).await;
+        assert_eq!(summary.matched, 1);
+        assert!(results[0].matched);
+        assert!(!results[1].matched);
+        assert_eq!(results[0].extracted.get("length").map(String::as_str), Some("6"));
+        assert_eq!(results[1].extracted.get("token").map(String::as_str), Some("t-1"));
+    }
+
+    #[tokio::test]
+    async fn payloads_are_template_variables() {
+        let sender = MockSender::default();
+        run(&config(&["${[ secret ]}"]), &sender).await;
+
+        let sent = sender.sent.lock().unwrap();
+        let (_, variables) = &sent[0];
+        assert_eq!(variables.get("fuzz.payload").map(String::as_str), Some("\\${[ secret ]}"));
+    }
+
+    #[tokio::test]
+    async fn appends_extensions_to_each_entry() {
+        let sender = MockSender::default();
+        let mut config = config(&["admin", "guest"]);
+        config.extensions = vec![".bak".to_string(), "~".to_string()];
+
+        let (summary, results) = run(&config, &sender).await;
+        assert_eq!(summary.total, 6);
+        let payloads = results.iter().map(|r| r.payload.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            payloads,
+            vec![
+                "admin",
+                "admin.bak",
+                "admin~",
+                "guest",
+                "guest.bak",
+                "guest~"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn blocks_unsafe_method_payloads_in_safe_mode() {
+        let sender = MockSender::default();
+        let mut config = config(&["GET", "DELETE"]);
+        config.targets = vec![FuzzTarget::Method];
+        config.safe_mode = true;
+
+        let (summary, results) = run(&config, &sender).await;
+        assert_eq!(summary.errors, 1);
+        assert_eq!(results[0].error, None);
+        assert!(results[1].error.as_deref().unwrap().contains("Safe mode blocks DELETE"));
+        assert_eq!(sender.sent.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn shuffles_payloads_in_the_same_order_for_a_seed() {
+        let wordlist = (0..20).map(|i| i.to_string()).collect::<Vec<_>>();
+        let mut config = FuzzRequest { wordlist: wordlist.clone(), ..Default::default() };
+        config.shuffle = Some(FuzzShuffle { seed: None });
+        config.pick_seeds();
+        let seed = config.shuffle.unwrap().seed;
+        assert!(seed.is_some());
+
+        let payloads = config.payloads();
+        assert_ne!(payloads, wordlist);
+        assert_eq!(payloads, config.payloads());
+        let mut sorted = payloads.clone();
+        sorted.sort_by_key(|p| p.parse::<u32>().unwrap());
+        assert_eq!(sorted, wordlist);
+    }
+
+    #[tokio::test]
+    async fn runs_against_each_base_url() {
+        let sender = MockSender::default();
+        let mut config = config(&["guest", "admin"]);
+        config.concurrency = 1;
+        config.base_urls = FuzzBaseUrls {
+            urls: vec![
+                "https://example.com".to_string(),
+                "https://eu.example.com".to_string(),
+            ],
+            order: FuzzBaseUrlOrder::Interleaved,
+        };
+
+        let (summary, results) = run(&config, &sender).await;
+        assert_eq!(summary.total, 4);
+        assert_eq!(summary.matched, 1);
+        let admin = results.iter().find(|r| r.matched).unwrap();
+        assert_eq!(admin.base_url, "https://example.com");
+        let sent =
+            sender.sent.lock().unwrap().iter().map(|(url, _)| url.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            sent,
+            vec![
+                "https://example.com/login/guest",
+                "https://eu.example.com/login/guest",
+                "https://example.com/login/admin",
+                "https://eu.example.com/login/admin",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn matches_responses_that_differ_from_baseline() {
+        let sender = MockSender::default();
+        let mut config = config(&["guest", "admin"]);
+        config.matchers = vec![FuzzMatcher::DiffersFromBaseline { tolerance: 0 }];
+
+        let (_, results) = run(&config, &sender).await;
+        assert!(!results[0].matched);
+        assert!(results[1].matched);
+        let sent = sender.sent.lock().unwrap();
+        assert_eq!(sent[0].0, "https://example.com/login/");
+    }
+
+    #[tokio::test]
+    async fn recurses_into_matched_paths() {
+        let sender = MockSender::default();
+        let mut config = config(&["login", "admin"]);
+        config.request.url = "https://example.com/§dir§".to_string();
+        config.matchers = vec![FuzzMatcher::Status { codes: vec![401] }];
+        config.recursion = Some(FuzzRecursion { max_depth: 1 });
+
+        let (summary, results) = run(&config, &sender).await;
+        assert_eq!((summary.total, summary.completed, summary.matched), (6, 6, 5));
+        let mut urls = results[2..].iter().map(|r| r.url.as_str()).collect::<Vec<_>>();
+        urls.sort();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/admin/admin",
+                "https://example.com/admin/login",
+                "https://example.com/login/admin",
+                "https://example.com/login/login",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_timeouts_distinctly() {
+        let sender = MockSender::default();
+        let (summary, results) = run(&config(&["slow"]), &sender).await;
+
+        assert_eq!(summary.errors, 1);
+        assert_eq!(results[0].status, 0);
+        assert_eq!(results[0].error_kind, Some(FuzzErrorKind::Timeout));
+        assert!(!results[0].matched);
+    }
+
+    #[tokio::test]
+    async fn delay_matcher_flags_timeouts() {
+        let sender = MockSender::default();
+        let mut config = config(&["slow", "guest"]);
+        config.matchers = vec![FuzzMatcher::Delayed { min_ms: 5_000 }];
+        let (summary, results) = run(&config, &sender).await;
+
+        assert_eq!(summary.matched, 1);
+        assert!(results.iter().find(|r| r.payload == "slow").unwrap().matched);
+    }
+
+    #[tokio::test]
+    async fn sends_follow_up_only_on_match() {
+        let sender = MockSender::default();
+        let mut config = config(&["guest", "admin"]);
+        config.follow_up = Some(FuzzFollowUp {
+            request: HttpRequest {
+                url: "https://example.com/account".to_string(),
+                ..Default::default()
+            },
+        });
+
+        let (_, results) = run(&config, &sender).await;
+        assert!(results[0].follow_up.is_none());
+        let follow_up = results[1].follow_up.as_ref().unwrap();
+        assert_eq!(follow_up.status, 401);
+        assert_eq!(follow_up.url, "https://example.com/account");
+
+        let sent = sender.sent.lock().unwrap();
+        let (_, variables) = sent.iter().find(|(url, _)| url.ends_with("/account")).unwrap();
+        assert_eq!(variables.get("fuzz.payload").map(String::as_str), Some("admin"));
+        assert_eq!(variables.get("fuzz.token").map(String::as_str), Some("t-1"));
+    }
+
+    #[tokio::test]
+    async fn payload_jar_is_shared_with_follow_up_only() {
+        let sender = MockSender::default();
+        let mut config = config(&["guest", "admin"]);
+        config.concurrency = 1;
+        config.cookies = FuzzCookies::PerPayload;
+        config.follow_up = Some(FuzzFollowUp {
+            request: HttpRequest {
+                url: "https://example.com/account".to_string(),
+                ..Default::default()
+            },
+        });
+        run(&config, &sender).await;
+
+        let cookies_sent = sender.cookies_sent.lock().unwrap();
+        assert_eq!(cookies_sent.len(), 3);
+        assert!(
+            cookies_sent
+                .iter()
+                .filter(|(url, _)| url.contains("/login/"))
+                .all(|(_, c)| c.is_none())
+        );
+        let (_, follow_up_cookies) =
+            cookies_sent.iter().find(|(url, _)| url.ends_with("/account")).unwrap();
+        assert_eq!(follow_up_cookies.as_deref(), Some("session=s-1"));
+    }
+
+    #[tokio::test]
+    async fn logs_in_again_when_session_expires() {
+        let sender = MockSender::default();
+        let mut config = config(&["guest"]);
+        config.session = Some(FuzzSession {
+            expired: vec![FuzzMatcher::Status { codes: vec![401] }],
+            login: vec![HttpRequest {
+                url: "https://example.com/login/admin".to_string(),
+                ..Default::default()
+            }],
+            extractors: config.extractors.clone(),
+            max_retries: 1,
+            ..Default::default()
+        });
+        let (summary, results) = run(&config, &sender).await;
+
+        assert_eq!(summary.completed, 1);
+        assert_eq!(results[0].status, 401);
+        let sent = sender.sent.lock().unwrap();
+        let urls = sent.iter().map(|(url, _)| url.rsplit('/').next().unwrap()).collect::<Vec<_>>();
+        assert_eq!(urls, vec!["admin", "guest", "admin", "guest"]);
+        assert_eq!(sent[1].1.get("fuzz.session.token").map(String::as_str), Some("t-1"));
+    }
+
+    #[tokio::test]
+    async fn refreshes_auth_when_session_expires() {
+        let sender = MockSender::default();
+        let mut config = config(&["guest", "admin"]);
+        config.concurrency = 1;
+        config.session = Some(FuzzSession {
+            expired: vec![FuzzMatcher::Status { codes: vec![401] }],
+            auth_action: Some("Delete Token".to_string()),
+            ..Default::default()
+        });
+        let (summary, _) = run(&config, &sender).await;
+
+        assert_eq!(summary.completed, 2);
+        assert_eq!(*sender.auth_actions.lock().unwrap(), vec!["Delete Token".to_string()]);
+        let sent = sender.sent.lock().unwrap();
+        let urls = sent.iter().map(|(url, _)| url.rsplit('/').next().unwrap()).collect::<Vec<_>>();
+        assert_eq!(urls, vec!["guest", "guest", "admin"]);
+    }
+
+    #[tokio::test]
+    async fn sends_pre_request_for_every_payload() {
+        let sender = MockSender::default();
+        let mut config = config(&["guest", "admin"]);
+        config.pre_request = Some(FuzzPreRequest {
+            request: HttpRequest {
+                url: "https://example.com/login/admin".to_string(),
+                ..Default::default()
+            },
+            extractors: vec![FuzzExtractor::JsonPath {
+                name: "csrf".to_string(),
+                path: "$.token".to_string(),
+            }],
+        });
+        let (summary, _) = run(&config, &sender).await;
+        assert_eq!(summary.errors, 0);
+
+        let sent = sender.sent.lock().unwrap();
+        let payload_requests = sent.iter().filter(|(_, v)| !v.is_empty()).collect::<Vec<_>>();
+        assert_eq!(sent.len(), 4);
+        assert_eq!(payload_requests.len(), 2);
+        assert!(
+            payload_requests
+                .iter()
+                .all(|(_, v)| v.get("fuzz.pre.csrf").map(String::as_str) == Some("t-1"))
+        );
+
+        config.pre_request.as_mut().unwrap().request.url =
+            "https://example.com/login/broken".to_string();
+        let (summary, results) = run(&config, &MockSender::default()).await;
+        assert_eq!(summary.errors, 2);
+        assert!(results[0].error.as_deref().unwrap().starts_with("Pre-request failed"));
+    }
+
+    #[tokio::test]
+    async fn sends_unrendered_template_for_every_request() {
+        let mut config = config(&["guest", "admin"]);
+        config.request.url = "https://example.com/login/§user§?ts=${[ timestamp() ]}".to_string();
+        config.session = Some(FuzzSession {
+            expired: vec![FuzzMatcher::Status { codes: vec![401] }],
+            max_retries: 1,
+            ..Default::default()
+        });
+        let sender = MockSender::default();
+        run(&config, &sender).await;
+
+        // Every payload is sent again after its 401 marks the session expired
+        let mut sent =
+            sender.sent.lock().unwrap().iter().map(|(url, _)| url.clone()).collect::<Vec<_>>();
+        sent.sort();
+        assert_eq!(
+            sent,
+            vec![
+                "https://example.com/login/admin?ts=${[ timestamp() ]}",
+                "https://example.com/login/admin?ts=${[ timestamp() ]}",
+                "https://example.com/login/guest?ts=${[ timestamp() ]}",
+                "https://example.com/login/guest?ts=${[ timestamp() ]}",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn stops_when_cancelled() {
+        let sender = MockSender::default();
+        let (events_tx, _events_rx) = mpsc::unbounded_channel();
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        cancel_tx.send(true).unwrap();
+
+        let summary =
+            run_fuzz("fr_test", &config(&["a", "b", "c"]), &sender, &events_tx, cancel_rx)
+                .await
+                .unwrap();
+        assert_eq!(summary.completed, 0);
+        assert!(summary.cancelled);
+        assert!(sender.sent.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn retries_only_failed_payloads() {
+        let sender = MockSender::default();
+        let previous = vec![
+            FuzzResult {
+                id: "fz_ok".to_string(),
+                payload: "guest".to_string(),
+                status: 401,
+                ..Default::default()
+            },
+            FuzzResult {
+                id: "fz_failed".to_string(),
+                payload_index: 1,
+                payload: "admin".to_string(),
+                error: Some("timed out".to_string()),
+                error_kind: Some(FuzzErrorKind::Timeout),
+                starred: true,
+                ..Default::default()
+            },
+        ];
+        let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let summary =
+            retry_fuzz("fr_test", &config(&[]), &previous, &sender, &events_tx, cancel_rx)
+                .await
+                .unwrap();
+        drop(events_tx);
+
+        assert_eq!((summary.total, summary.completed, summary.errors), (1, 1, 0));
+        let mut results = Vec::new();
+        while let Some(event) = events_rx.recv().await {
+            if let FuzzEvent::Result { result, .. } = event {
+                results.push(result);
+            }
+        }
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "fz_failed");
+        assert_eq!(results[0].payload_index, 1);
+        assert_eq!(results[0].status, 200);
+        assert!(results[0].error.is_none());
+        assert!(results[0].starred);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_blocked_payloads() {
+        let sender = MockSender::default();
+        let previous = vec![FuzzResult {
+            id: "fz_blocked".to_string(),
+            payload: "DELETE".to_string(),
+            error: Some(
+                "Fuzz error: Safe mode blocks DELETE requests. Turn it off to send them."
+                    .to_string(),
+            ),
+            error_kind: Some(FuzzErrorKind::Other),
+            ..Default::default()
+        }];
+        let (events_tx, _events_rx) = mpsc::unbounded_channel();
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let summary =
+            retry_fuzz("fr_test", &config(&[]), &previous, &sender, &events_tx, cancel_rx)
+                .await
+                .unwrap();
+
+        assert_eq!((summary.total, summary.completed), (0, 0));
+        assert!(sender.sent.lock().unwrap().is_empty());
+    }
+}
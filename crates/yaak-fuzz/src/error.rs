@@ -0,0 +1,74 @@
+use serde::{Serialize, Serializer};
+use thiserror::Error;
+use yaak_models::models::FuzzErrorKind;
+use yaak_tls::is_certificate_error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    ModelError(#[from] yaak_models::error::Error),
+
+    #[error(transparent)]
+    TemplateError(#[from] yaak_templates::error::Error),
+
+    #[error(transparent)]
+    HttpError(#[from] yaak_http::error::Error),
+
+    #[error(transparent)]
+    SendError(#[from] yaak::send::SendHttpRequestError),
+
+    #[error(transparent)]
+    CryptoError(#[from] yaak_crypto::error::Error),
+
+    #[error(transparent)]
+    PluginError(#[from] yaak_plugins::error::Error),
+
+    #[error("I/o error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[error("Invalid regex: {0}")]
+    RegexError(#[from] regex::Error),
+
+    #[error("Fuzz error: {0}")]
+    GenericError(String),
+}
+
+impl Error {
+    /// Category recorded with a result that failed with this error
+    pub fn kind(&self) -> FuzzErrorKind {
+        match self {
+            Error::HttpError(
+                yaak_http::error::Error::RequestTimeout(_)
+                | yaak_http::error::Error::BodyReadTimeout,
+            ) => FuzzErrorKind::Timeout,
+            Error::HttpError(yaak_http::error::Error::TlsError(_)) => FuzzErrorKind::Certificate,
+            Error::HttpError(yaak_http::error::Error::Client(e)) if is_certificate_error(e) => {
+                FuzzErrorKind::Certificate
+            }
+            Error::IoError(e) if is_certificate_error(e) => FuzzErrorKind::Certificate,
+            Error::HttpError(yaak_http::error::Error::Client(e))
+                if e.is_connect() || e.is_request() || e.is_body() =>
+            {
+                FuzzErrorKind::Network
+            }
+            Error::HttpError(yaak_http::error::Error::BodyReadError(_)) | Error::IoError(_) => {
+                FuzzErrorKind::Network
+            }
+            _ => FuzzErrorKind::Other,
+        }
+    }
+}
+
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.to_string().as_ref())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
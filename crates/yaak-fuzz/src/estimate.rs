@@ -0,0 +1,89 @@
+//! How many requests a run sends, so a run that's far bigger than intended
+//! is caught before it starts
+
+use crate::boolean_sqli::probe_count;
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::matchers::ResponseMatcher;
+use crate::types::FuzzRequest;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Requests a run sends before recursing. Follow-ups and renewed logins
+/// depend on the responses, so they aren't counted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzEstimate {
+    /// Wordlist entries, each also sent with every extension
+    pub payloads: usize,
+    /// Base URLs every payload is sent to, at least one
+    pub targets: usize,
+    /// Fuzzed requests: every payload for each target
+    pub requests: usize,
+    /// Every request, including boolean, auth bypass and IDOR probes,
+    /// pre-requests, the login and the baseline
+    pub total: usize,
+    /// The run's limit, zero without one
+    pub max_requests: usize,
+    pub exceeds_limit: bool,
+}
+
+pub fn estimate_fuzz(config: &FuzzRequest) -> Result<FuzzEstimate> {
+    let matcher =
+        ResponseMatcher::new(&config.matchers, config.match_mode)?.with_filters(&config.filters)?;
+    let payloads = config.payloads().len();
+    let targets = config.base_urls.urls.len().max(1);
+    let requests = config.request_count();
+    let as_others = requests * (config.auth_bypass.iter().count() + config.idor.iter().count());
+    let probes = requests * config.boolean_sqli.as_ref().map_or(0, probe_count) + as_others;
+    let pre_requests = if config.pre_request.is_some() { requests + probes } else { 0 };
+    let login = config.session.as_ref().map_or(0, |s| s.login.len());
+    let baseline = usize::from(config.vhost.is_some() || matcher.needs_baseline());
+
+    let total = requests + probes + pre_requests + login + baseline;
+    let max_requests = config.max_requests;
+    let exceeds_limit = max_requests > 0 && total > max_requests;
+    Ok(FuzzEstimate { payloads, targets, requests, total, max_requests, exceeds_limit })
+}
+
+/// Refuse to start a run that would send more requests than its limit
+pub(crate) fn validate_max_requests(config: &FuzzRequest) -> Result<()> {
+    let estimate = estimate_fuzz(config)?;
+    if estimate.exceeds_limit {
+        return Err(GenericError(format!(
+            "The run would send {} requests, more than its limit of {}",
+            estimate.total, estimate.max_requests
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FuzzBaseUrls, FuzzPreRequest};
+
+    #[test]
+    fn counts_every_request_before_the_run() {
+        let mut config = FuzzRequest {
+            wordlist: vec!["a".to_string(), "b".to_string()],
+            extensions: vec![".bak".to_string()],
+            base_urls: FuzzBaseUrls {
+                urls: vec!["https://a.test".to_string(), "https://b.test".to_string()],
+                ..Default::default()
+            },
+            pre_request: Some(FuzzPreRequest::default()),
+            ..Default::default()
+        };
+        let estimate = estimate_fuzz(&config).unwrap();
+        assert_eq!((estimate.payloads, estimate.targets, estimate.requests), (4, 2, 8));
+        assert_eq!(estimate.total, 16);
+        assert!(!estimate.exceeds_limit);
+        assert!(validate_max_requests(&config).is_ok());
+
+        config.max_requests = 10;
+        assert!(estimate_fuzz(&config).unwrap().exceeds_limit);
+        assert!(validate_max_requests(&config).is_err());
+    }
+}
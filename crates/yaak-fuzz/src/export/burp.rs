@@ -0,0 +1,157 @@
+use crate::error::Result;
+use crate::export::{FuzzExport, escape_markup, exported_request};
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use std::fmt::Write;
+use url::Url;
+
+/// Render a run as a Burp Suite "saved items" XML document, the format Burp
+/// uses when saving Intruder or Proxy items. Requests and responses are
+/// written as base64-encoded raw HTTP/1.1 messages.
+pub async fn build_burp_xml(export: &FuzzExport, creator_version: &str) -> Result<String> {
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\"?>\n");
+    let _ = writeln!(xml, "<items burpVersion=\"yaak-{}\">", escape_markup(creator_version));
+
+    for (result, body) in &export.results {
+        let request = exported_request(&export.config, &result.payload).await?;
+        let url = Url::parse(&request.url).ok();
+        let host = url.as_ref().and_then(|u| u.host_str()).unwrap_or_default().to_string();
+        let port = url.as_ref().and_then(|u| u.port_or_known_default()).unwrap_or_default();
+        let protocol = url.as_ref().map(|u| u.scheme().to_string()).unwrap_or_default();
+        let path = url
+            .as_ref()
+            .map(|u| match u.query() {
+                Some(q) => format!("{}?{}", u.path(), q),
+                None => u.path().to_string(),
+            })
+            .unwrap_or_else(|| "/".to_string());
+
+        let mut raw_request = format!("{} {} HTTP/1.1\r\n", request.method, path);
+        if !request.headers.iter().any(|(n, _)| n.eq_ignore_ascii_case("host")) {
+            let _ = write!(raw_request, "Host: {host}\r\n");
+        }
+        for (name, value) in &request.headers {
+            let _ = write!(raw_request, "{name}: {value}\r\n");
+        }
+        raw_request.push_str("\r\n");
+        let mut raw_request = raw_request.into_bytes();
+        raw_request.extend(request.body.as_deref().unwrap_or_default());
+
+        let mut raw_response = format!("HTTP/1.1 {}\r\n", result.status);
+        for header in &result.headers {
+            let _ = write!(raw_response, "{}: {}\r\n", header.name, header.value);
+        }
+        raw_response.push_str("\r\n");
+        let mut raw_response = raw_response.into_bytes();
+        raw_response.extend(body);
+
+        let content_type = result
+            .headers
+            .iter()
+            .find(|h| h.name.eq_ignore_ascii_case("content-type"))
+            .map(|h| h.value.as_str())
+            .unwrap_or_default();
+
+        xml.push_str("  <item>\n");
+        let _ = writeln!(
+            xml,
+            "    <time>{}</time>",
+            result.created_at.format("%a %b %d %H:%M:%S UTC %Y")
+        );
+        let _ = writeln!(xml, "    <url>{}</url>", cdata(&request.url));
+        let _ = writeln!(xml, "    <host ip=\"\">{}</host>", escape_markup(&host));
+        let _ = writeln!(xml, "    <port>{port}</port>");
+        let _ = writeln!(xml, "    <protocol>{}</protocol>", escape_markup(&protocol));
+        let _ = writeln!(xml, "    <method>{}</method>", cdata(&request.method));
+        let _ = writeln!(xml, "    <path>{}</path>", cdata(&path));
+        let _ = writeln!(xml, "    <extension>null</extension>");
+        let _ = writeln!(
+            xml,
+            "    <request base64=\"true\">{}</request>",
+            cdata(&BASE64_STANDARD.encode(&raw_request))
+        );
+        let _ = writeln!(xml, "    <status>{}</status>", result.status);
+        let _ = writeln!(xml, "    <responselength>{}</responselength>", raw_response.len());
+        let _ = writeln!(xml, "    <mimetype>{}</mimetype>", burp_mime_type(content_type));
+        let _ = writeln!(
+            xml,
+            "    <response base64=\"true\">{}</response>",
+            cdata(&BASE64_STANDARD.encode(&raw_response))
+        );
+        let _ = writeln!(xml, "    <comment>{}</comment>", cdata(&result.payload));
+        xml.push_str("  </item>\n");
+    }
+
+    xml.push_str("</items>\n");
+    Ok(xml)
+}
+
+/// Burp's coarse MIME categories
+fn burp_mime_type(content_type: &str) -> &'static str {
+    let content_type = content_type.to_lowercase();
+    if content_type.contains("html") {
+        "HTML"
+    } else if content_type.contains("json") {
+        "JSON"
+    } else if content_type.contains("xml") {
+        "XML"
+    } else if content_type.contains("javascript") {
+        "script"
+    } else if content_type.contains("css") {
+        "CSS"
+    } else if content_type.starts_with("image/") {
+        "image"
+    } else if content_type.starts_with("text/") {
+        "text"
+    } else {
+        ""
+    }
+}
+
+fn cdata(text: &str) -> String {
+    format!("<![CDATA[{}]]>", text.replace("]]>", "]]]]><![CDATA[>"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FuzzRequest;
+    use yaak_models::models::{FuzzResult, FuzzRun, HttpRequest};
+
+    #[tokio::test]
+    async fn writes_raw_messages_as_base64() {
+        let export = FuzzExport {
+            run: FuzzRun::default(),
+            config: FuzzRequest {
+                request: HttpRequest {
+                    method: "GET".to_string(),
+                    url: "https://example.com/§p§?q=1".to_string(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            results: vec![(
+                FuzzResult { payload: "admin".to_string(), status: 403, ..Default::default() },
+                b"nope".to_vec(),
+            )],
+        };
+
+        let xml = build_burp_xml(&export, "1.0.0").await.unwrap();
+        assert!(xml.contains("<host ip=\"\">example.com</host>"));
+        assert!(xml.contains("<port>443</port>"));
+        assert!(xml.contains("<status>403</status>"));
+        assert!(xml.contains("<comment><![CDATA[admin]]></comment>"));
+
+        let request =
+            BASE64_STANDARD.encode("GET /admin?q=1 HTTP/1.1\r\nHost: example.com\r\n\r\n");
+        assert!(xml.contains(&request));
+        let response = BASE64_STANDARD.encode("HTTP/1.1 403\r\n\r\nnope");
+        assert!(xml.contains(&response));
+    }
+
+    #[test]
+    fn splits_cdata_terminators() {
+        assert_eq!(cdata("a]]>b"), "<![CDATA[a]]]]><![CDATA[>b]]>");
+    }
+}
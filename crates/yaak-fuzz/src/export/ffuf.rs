@@ -0,0 +1,361 @@
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::export::ExportedRequest;
+use crate::markers::inject_payload;
+use crate::targets::apply_targets;
+use crate::types::{
+    FuzzClientCertificate, FuzzCookies, FuzzHostLimits, FuzzHttp2, FuzzHttpVersion, FuzzMatchMode,
+    FuzzMatcher, FuzzPayloadEncoding, FuzzRedirects, FuzzRequest, FuzzServerName,
+};
+use crate::vhost::set_host_header;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use ts_rs::TS;
+use yaak_http::types::SendableHttpRequest;
+use yaak_models::models::HttpRequest;
+
+/// Keyword ffuf replaces with each wordlist entry
+pub const KEYWORD: &str = "FUZZ";
+
+/// Upper bound used for open-ended size matchers
+const MAX_SIZE: usize = i32::MAX as usize;
+
+/// An ffuf invocation equivalent to a fuzz configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzFfufCommand {
+    pub command: String,
+    /// Parts of the configuration ffuf can't express
+    pub warnings: Vec<String>,
+}
+
+/// The request template with every payload position replaced by `KEYWORD`,
+/// to be rendered before it's passed to `build_ffuf_command`
+pub fn ffuf_template(config: &FuzzRequest) -> HttpRequest {
+    let mut request = inject_payload(&config.request, config.marker.as_deref(), KEYWORD);
+    apply_targets(&mut request, &config.targets, KEYWORD, false);
+    if config.vhost.is_some() {
+        set_host_header(&mut request, KEYWORD);
+    }
+    request
+}
+
+/// Build the ffuf command line for `config` from its rendered template. The
+/// wordlist is expected at `wordlist_path` on the machine running ffuf.
+pub fn build_ffuf_command(
+    config: &FuzzRequest,
+    rendered: SendableHttpRequest,
+    wordlist_path: &str,
+) -> Result<FuzzFfufCommand> {
+    let options = rendered.options.clone();
+    let request = ExportedRequest::from(rendered);
+    let mut warnings = Vec::new();
+
+    let mut args = vec!["ffuf".to_string(), "-u".to_string(), request.url];
+    if request.method != "GET" {
+        args.extend(["-X".to_string(), request.method]);
+    }
+    let user_agents = &config.user_agents.values;
+    for (name, value) in request.headers {
+        if !user_agents.is_empty() && name.eq_ignore_ascii_case("user-agent") {
+            continue;
+        }
+        args.extend(["-H".to_string(), format!("{name}: {value}")]);
+    }
+    if let Some(user_agent) = user_agents.first() {
+        args.extend(["-H".to_string(), format!("User-Agent: {user_agent}")]);
+    }
+    if !config.spoof_headers.names.is_empty() {
+        warnings.push(
+            "ffuf can't change headers per request, spoofed headers are left out".to_string(),
+        );
+    }
+    if user_agents.len() > 1 {
+        warnings.push("ffuf sends a single User-Agent, only the first one is set".to_string());
+    }
+    match request.body {
+        None => return Err(GenericError("ffuf can't send file or multipart bodies".to_string())),
+        Some(body) if body.is_empty() => {}
+        Some(body) => {
+            let body = String::from_utf8(body)
+                .map_err(|_| GenericError("ffuf can't send binary bodies".to_string()))?;
+            args.extend(["-d".to_string(), body]);
+        }
+    }
+
+    args.extend(["-w".to_string(), wordlist_path.to_string()]);
+    let extensions = config.extensions.iter().filter(|e| !e.is_empty());
+    let extensions = extensions.map(String::as_str).collect::<Vec<_>>();
+    if !extensions.is_empty() {
+        args.extend(["-e".to_string(), extensions.join(",")]);
+    }
+    args.extend(["-t".to_string(), config.concurrency.max(1).to_string()]);
+    if config.delay_ms > 0 {
+        args.extend([
+            "-p".to_string(),
+            (config.delay_ms as f64 / 1000.0).to_string(),
+        ]);
+    }
+    if config.validate_certificates == Some(false) {
+        args.push("-k".to_string());
+    }
+    match config.proxies.urls.as_slice() {
+        [] => {}
+        [proxy] => args.extend(["-x".to_string(), proxy.clone()]),
+        [proxy, ..] => {
+            args.extend(["-x".to_string(), proxy.clone()]);
+            warnings.push("ffuf uses a single proxy, only the first one is set".to_string());
+        }
+    }
+    let redirects = config.followed_redirects();
+    if redirects.follow(options.follow_redirects) {
+        args.push("-r".to_string());
+    }
+    if matches!(redirects, FuzzRedirects::Limit { .. } | FuzzRedirects::SameHost) {
+        warnings.push("ffuf follows redirects without a limit or host check".to_string());
+    }
+    let timeout =
+        config.timeouts.total_ms.map(|ms| Duration::from_millis(ms as u64)).or(options.timeout);
+    if let Some(timeout) = timeout {
+        args.extend(["-timeout".to_string(), timeout.as_secs().max(1).to_string()]);
+    }
+    if let Some(recursion) = config.recursion {
+        args.push("-recursion".to_string());
+        args.extend([
+            "-recursion-depth".to_string(),
+            recursion.max_depth.to_string(),
+        ]);
+    }
+    if config.http2.is_some() || config.http_version == FuzzHttpVersion::Http2 {
+        args.push("-http2".to_string());
+    }
+    if config.http2.is_some_and(|http2| http2 != FuzzHttp2::default()) {
+        warnings.push("Padding headers and stream resets are not supported by ffuf".to_string());
+    }
+    if let Some(server_name) = &config.tls.server_name {
+        args.extend(["-sni".to_string(), server_name.clone()]);
+    }
+    let tls = &config.tls;
+    if tls.min_version.is_some() || tls.max_version.is_some() || tls.alpn.is_some() {
+        warnings.push("TLS versions and ALPN protocols are not supported by ffuf".to_string());
+    }
+    match &config.client_certificate {
+        FuzzClientCertificate::Files {
+            crt_file: Some(crt_file),
+            key_file: Some(key_file),
+            pfx_file: None,
+            ..
+        } => {
+            args.extend([
+                "-cc".to_string(),
+                crt_file.clone(),
+                "-ck".to_string(),
+                key_file.clone(),
+            ]);
+        }
+        FuzzClientCertificate::Files { .. } => {
+            warnings.push("ffuf only takes PEM client certificate and key files".to_string());
+        }
+        FuzzClientCertificate::Workspace | FuzzClientCertificate::Off => {}
+    }
+    if config.host_limits != FuzzHostLimits::default() {
+        warnings.push("Per-host limits are not supported by ffuf".to_string());
+    }
+    if config.local_address.is_some() {
+        warnings.push("Local addresses are not supported by ffuf".to_string());
+    }
+    if config.fresh_connections {
+        warnings.push("ffuf always reuses connections".to_string());
+    }
+    if config.timeouts.connect_ms.is_some() || config.timeouts.read_ms.is_some() {
+        warnings.push("Connect and read timeouts are not supported by ffuf".to_string());
+    }
+
+    let all = config.match_mode == FuzzMatchMode::All;
+    args.extend(matcher_args('m', &config.matchers, all, &mut warnings));
+    if all && config.matchers.len() > 1 {
+        args.extend(["-mmode".to_string(), "and".to_string()]);
+    }
+    args.extend(matcher_args('f', &config.filters, false, &mut warnings));
+
+    if !config.extractors.is_empty() || config.follow_up.is_some() {
+        warnings.push("Extractors and follow-up requests are not supported by ffuf".to_string());
+    }
+    if config.shuffle.is_some() {
+        warnings.push("ffuf sends payloads in wordlist order".to_string());
+    }
+    if config.sample.is_some() {
+        warnings.push("ffuf sends every payload, sampling is not supported".to_string());
+    }
+    if config.reflection_analysis {
+        warnings.push("Reflection analysis is not supported by ffuf".to_string());
+    }
+    if config.open_redirect {
+        warnings.push("Open redirect detection is not supported by ffuf".to_string());
+    }
+    if config.callbacks.is_some() {
+        warnings.push("Out-of-band callbacks are not supported by ffuf".to_string());
+    }
+    if config.idor.is_some() {
+        warnings.push("IDOR scanning with a second user is not supported by ffuf".to_string());
+    }
+    if config.auth_bypass.is_some() {
+        warnings.push("Auth bypass diffing is not supported by ffuf".to_string());
+    }
+    if config.boolean_sqli.is_some() {
+        warnings.push("Boolean-based SQL injection pairs are not supported by ffuf".to_string());
+    }
+    if !config.verdicts.is_empty() {
+        warnings.push("Vulnerability signatures are not supported by ffuf".to_string());
+    }
+    if config.payload_encoding == FuzzPayloadEncoding::Base64 {
+        warnings.push("ffuf sends wordlist entries as text, not decoded from base64".to_string());
+    }
+    if config.pre_request.is_some() {
+        warnings.push("Pre-requests are not supported by ffuf".to_string());
+    }
+    if config.session.is_some() {
+        warnings.push("Logging in again is not supported by ffuf".to_string());
+    }
+    if config.cookies != FuzzCookies::Off {
+        warnings.push("Cookie jars are not supported by ffuf".to_string());
+    }
+    if config.signing.is_some() {
+        warnings.push("Request signing is not supported by ffuf".to_string());
+    }
+    if !config.base_urls.urls.is_empty() {
+        warnings
+            .push("ffuf runs against a single target, use one command per base URL".to_string());
+    }
+    if let Some(vhost) = &config.vhost {
+        warnings.push("Filter the default vhost by its size with -fs".to_string());
+        if vhost.server_name != FuzzServerName::Url {
+            warnings.push("TLS server names are not supported by ffuf".to_string());
+        }
+    }
+
+    let command = args.iter().map(|a| shell_quote(a)).collect::<Vec<_>>().join(" ");
+    Ok(FuzzFfufCommand { command, warnings })
+}
+
+/// Flags for one group of matchers, prefixed `-m` or `-f`. ffuf takes a
+/// single value per flag, so codes and sizes are merged into lists and
+/// regexes joined into an alternation, which is only equivalent when the
+/// group is combined with "or".
+fn matcher_args(
+    prefix: char,
+    matchers: &[FuzzMatcher],
+    all: bool,
+    warnings: &mut Vec<String>,
+) -> Vec<String> {
+    let mut status_matchers = 0;
+    let mut codes = Vec::new();
+    let mut sizes = Vec::new();
+    let mut patterns = Vec::new();
+    let mut delays = Vec::new();
+    for matcher in matchers {
+        match matcher {
+            FuzzMatcher::Status { codes: c } => {
+                status_matchers += 1;
+                codes.extend(c.iter().map(|c| c.to_string()));
+            }
+            FuzzMatcher::Size { min, max } => {
+                let (min, max) = (min.unwrap_or(0), max.unwrap_or(MAX_SIZE));
+                sizes.push(if min == max { min.to_string() } else { format!("{min}-{max}") });
+            }
+            FuzzMatcher::Contains { text } => patterns.push(regex::escape(text)),
+            FuzzMatcher::Regex { pattern } => patterns.push(pattern.clone()),
+            FuzzMatcher::Header { name, .. } => {
+                warnings.push(format!("Header matcher for {name} is not supported by ffuf"));
+            }
+            FuzzMatcher::DiffersFromBaseline { .. } => {
+                warnings.push("Baseline comparison is not supported by ffuf".to_string());
+            }
+            FuzzMatcher::Delayed { min_ms } => delays.push(*min_ms),
+        }
+    }
+
+    let mut args = Vec::new();
+    let groups = [("c", codes.join(",")), ("s", sizes.join(","))];
+    for (flag, value) in groups {
+        if !value.is_empty() {
+            args.extend([format!("-{prefix}{flag}"), value]);
+        }
+    }
+    if all && (status_matchers > 1 || sizes.len() > 1 || patterns.len() > 1) {
+        warnings.push(format!("Matchers of the same kind are merged with or by ffuf -{prefix}"));
+    }
+    // ffuf times the first response byte, and doesn't match timeouts
+    if let Some(min_ms) = delays.iter().max() {
+        args.extend([
+            format!("-{prefix}t"),
+            format!(">{}", min_ms.saturating_sub(1)),
+        ]);
+    }
+    match patterns.len() {
+        0 => {}
+        1 => args.extend([format!("-{prefix}r"), patterns.remove(0)]),
+        _ => {
+            let pattern = patterns.iter().map(|p| format!("(?:{p})")).collect::<Vec<_>>();
+            args.extend([format!("-{prefix}r"), pattern.join("|")]);
+        }
+    }
+    args
+}
+
+/// Quote an argument for POSIX shells if it contains anything but safe characters
+fn shell_quote(arg: &str) -> String {
+    let safe = !arg.is_empty()
+        && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:=,@%+".contains(c));
+    if safe { arg.to_string() } else { format!("'{}'", arg.replace('\'', "'\\''")) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::import_command;
+    use crate::types::FuzzProxies;
+
+    #[test]
+    fn builds_command_that_imports_back() {
+        let config = FuzzRequest {
+            concurrency: 8,
+            delay_ms: 250,
+            matchers: vec![
+                FuzzMatcher::Status { codes: vec![200, 302] },
+                FuzzMatcher::Contains { text: "Welcome (admin)".to_string() },
+            ],
+            filters: vec![FuzzMatcher::Size { min: Some(10), max: Some(10) }],
+            validate_certificates: Some(false),
+            proxies: FuzzProxies {
+                urls: vec!["http://127.0.0.1:8080".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let rendered = SendableHttpRequest {
+            url: "https://example.com/login".to_string(),
+            method: "POST".to_string(),
+            headers: vec![("X-Name".to_string(), "it's FUZZ".to_string())],
+            body: Some(yaak_http::types::SendableBody::Bytes("user=FUZZ".into())),
+            options: Default::default(),
+        };
+
+        let ffuf = build_ffuf_command(&config, rendered, "words.txt").unwrap();
+        assert_eq!(
+            ffuf.command,
+            "ffuf -u https://example.com/login -X POST -H 'X-Name: it'\\''s FUZZ' -d user=FUZZ \
+             -w words.txt -t 8 -p 0.25 -k -x http://127.0.0.1:8080 -mc 200,302 -mr 'Welcome \\(admin\\)' -fs 10"
+        );
+        assert!(ffuf.warnings.is_empty());
+
+        let imported = import_command(&ffuf.command).unwrap().config;
+        assert_eq!(imported.request.headers[0].value, "it's §FUZZ§");
+        assert_eq!(imported.matchers[0], config.matchers[0]);
+        assert_eq!(imported.filters, config.filters);
+        assert_eq!(imported.delay_ms, config.delay_ms);
+        assert_eq!(imported.validate_certificates, Some(false));
+        assert_eq!(imported.proxies, config.proxies);
+    }
+}
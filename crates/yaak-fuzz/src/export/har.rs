@@ -0,0 +1,130 @@
+use crate::error::Result;
+use crate::export::{FuzzExport, exported_request, find_header};
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use serde_json::{Value, json};
+
+/// Build a HAR 1.2 log with one entry per exported result
+pub async fn build_har(export: &FuzzExport, creator_version: &str) -> Result<Value> {
+    let mut entries = Vec::with_capacity(export.results.len());
+    for (result, body) in &export.results {
+        let request = exported_request(&export.config, &result.payload).await?;
+
+        let post_data = match &request.body {
+            Some(body) if !body.is_empty() => Some(json!({
+                "mimeType": find_header(&request.headers, "content-type").unwrap_or_default(),
+                "text": String::from_utf8_lossy(body),
+            })),
+            _ => None,
+        };
+        let mut har_request = json!({
+            "method": request.method,
+            "url": request.url,
+            "httpVersion": "HTTP/1.1",
+            "cookies": [],
+            "headers": har_headers(request.headers.iter().map(|(n, v)| (n.as_str(), v.as_str()))),
+            "queryString": [],
+            "headersSize": -1,
+            "bodySize": request.body.as_ref().map(|b| b.len() as i64).unwrap_or(-1),
+        });
+        if let Some(post_data) = post_data {
+            har_request["postData"] = post_data;
+        }
+
+        let response_headers = result.headers.iter().map(|h| (h.name.as_str(), h.value.as_str()));
+        let mime_type = result
+            .headers
+            .iter()
+            .find(|h| h.name.eq_ignore_ascii_case("content-type"))
+            .map(|h| h.value.as_str())
+            .unwrap_or_default();
+        let mut content = json!({ "size": body.len(), "mimeType": mime_type });
+        match std::str::from_utf8(body) {
+            Ok(text) => content["text"] = text.into(),
+            Err(_) => {
+                content["text"] = BASE64_STANDARD.encode(body).into();
+                content["encoding"] = "base64".into();
+            }
+        }
+
+        let mut entry = json!({
+            "startedDateTime": format!("{}Z", result.created_at.format("%Y-%m-%dT%H:%M:%S%.3f")),
+            "time": result.elapsed,
+            "request": har_request,
+            "response": {
+                "status": result.status,
+                "statusText": "",
+                "httpVersion": "HTTP/1.1",
+                "cookies": [],
+                "headers": har_headers(response_headers),
+                "content": content,
+                "redirectURL": "",
+                "headersSize": -1,
+                "bodySize": body.len(),
+            },
+            "cache": {},
+            "timings": { "send": 0, "wait": result.elapsed, "receive": 0 },
+            "comment": format!("Fuzz payload: {}", result.payload),
+        });
+        if let Some(error) = &result.error {
+            entry["response"]["_error"] = error.as_str().into();
+        }
+        entries.push(entry);
+    }
+
+    Ok(json!({
+        "log": {
+            "version": "1.2",
+            "creator": { "name": "Yaak", "version": creator_version },
+            "entries": entries,
+        }
+    }))
+}
+
+fn har_headers<'a>(headers: impl Iterator<Item = (&'a str, &'a str)>) -> Vec<Value> {
+    headers.map(|(name, value)| json!({ "name": name, "value": value })).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FuzzRequest;
+    use yaak_models::models::{FuzzResult, FuzzRun, HttpRequest, HttpResponseHeader};
+
+    #[tokio::test]
+    async fn builds_entry_per_result() {
+        let export = FuzzExport {
+            run: FuzzRun::default(),
+            config: FuzzRequest {
+                request: HttpRequest {
+                    method: "POST".to_string(),
+                    url: "https://example.com/login".to_string(),
+                    body_type: Some("application/json".to_string()),
+                    body: [("text".to_string(), json!("{\"user\":\"§u§\"}"))].into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            results: vec![(
+                FuzzResult {
+                    payload: "admin".to_string(),
+                    status: 200,
+                    headers: vec![HttpResponseHeader {
+                        name: "Content-Type".to_string(),
+                        value: "text/plain".to_string(),
+                    }],
+                    ..Default::default()
+                },
+                b"welcome".to_vec(),
+            )],
+        };
+
+        let har = build_har(&export, "1.0.0").await.unwrap();
+        let entry = &har["log"]["entries"][0];
+        assert_eq!(entry["request"]["method"], "POST");
+        assert_eq!(entry["request"]["postData"]["text"], "{\"user\":\"admin\"}");
+        assert_eq!(entry["response"]["status"], 200);
+        assert_eq!(entry["response"]["content"]["text"], "welcome");
+        assert_eq!(entry["response"]["content"]["mimeType"], "text/plain");
+    }
+}
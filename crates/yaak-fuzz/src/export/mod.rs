@@ -0,0 +1,97 @@
+pub mod burp;
+pub mod ffuf;
+pub mod har;
+pub mod report;
+
+use crate::encryption::{open_result, open_run};
+use crate::error::Result;
+use crate::markers::inject_payload;
+use crate::persist::read_result_body;
+use crate::targets::apply_targets;
+use crate::types::FuzzRequest;
+use yaak_crypto::manager::EncryptionManager;
+use yaak_http::types::{SendableBody, SendableHttpRequest};
+use yaak_models::blob_manager::BlobManager;
+use yaak_models::models::{FuzzResult, FuzzRun};
+use yaak_models::query_manager::QueryManager;
+
+/// A stored run loaded for export, with each result's response body
+pub struct FuzzExport {
+    pub run: FuzzRun,
+    pub config: FuzzRequest,
+    pub results: Vec<(FuzzResult, Vec<u8>)>,
+}
+
+impl FuzzExport {
+    /// Load a run and its results, decrypted. With `flagged_only`, only
+    /// starred results are included.
+    pub fn load(
+        query_manager: &QueryManager,
+        blob_manager: &BlobManager,
+        encryption_manager: &EncryptionManager,
+        run_id: &str,
+        flagged_only: bool,
+    ) -> Result<Self> {
+        let db = query_manager.connect();
+        let run = open_run(encryption_manager, db.get_fuzz_run(run_id)?)?;
+        let config = run_config(&run);
+        let mut results = Vec::new();
+        for result in db.list_fuzz_results(run_id)? {
+            if flagged_only && !result.starred {
+                continue;
+            }
+            let body = read_result_body(blob_manager, encryption_manager, &result)?;
+            results.push((open_result(encryption_manager, result)?, body));
+        }
+        Ok(Self { run, config, results })
+    }
+}
+
+/// The configuration a run was started with, from a run opened with
+/// [`open_run`](crate::encryption::open_run). Runs stored before configs were
+/// recorded yield the default.
+pub fn run_config(run: &FuzzRun) -> FuzzRequest {
+    let config = serde_json::Value::Object(run.config.clone().into_iter().collect());
+    serde_json::from_value(config).unwrap_or_default()
+}
+
+/// Request line, headers, and body for a result, rebuilt from the run's
+/// template with the payload injected. Template tags are left unrendered.
+pub(crate) struct ExportedRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    /// `None` when the body is streamed from a file or multipart form
+    pub body: Option<Vec<u8>>,
+}
+
+pub(crate) async fn exported_request(
+    config: &FuzzRequest,
+    payload: &str,
+) -> Result<ExportedRequest> {
+    let mut request = inject_payload(&config.request, config.marker.as_deref(), payload);
+    apply_targets(&mut request, &config.targets, payload, false);
+    let sendable = SendableHttpRequest::from_http_request(&request, Default::default()).await?;
+    Ok(sendable.into())
+}
+
+impl From<SendableHttpRequest> for ExportedRequest {
+    fn from(sendable: SendableHttpRequest) -> Self {
+        let body = match sendable.body {
+            None => Some(Vec::new()),
+            Some(SendableBody::Bytes(bytes)) => Some(bytes.to_vec()),
+            Some(SendableBody::Stream { .. }) => None,
+        };
+        Self { method: sendable.method, url: sendable.url, headers: sendable.headers, body }
+    }
+}
+
+/// Value of the first header named `name`, compared case-insensitively
+pub(crate) fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+}
+
+/// Escape text for use in XML or HTML content and attribute values
+pub(crate) fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,398 @@
+use crate::export::{FuzzExport, escape_markup};
+use crate::types::{FuzzMatchMode, FuzzMatcher};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use ts_rs::TS;
+use yaak_models::models::{FuzzResult, FuzzRunState};
+
+/// Bodies longer than this are cut off in the report
+const MAX_REPORT_BODY: usize = 16 * 1024;
+
+/// Inline stylesheet so HTML reports need no other files
+const STYLE: &str = "\
+body { font-family: system-ui, sans-serif; color: #222; margin: 2rem auto; max-width: 60rem; }
+table { border-collapse: collapse; margin-bottom: 1rem; }
+th, td { text-align: left; padding: 0.25rem 0.75rem; border-bottom: 1px solid #ddd; }
+pre { background: #f5f5f5; padding: 0.75rem; overflow-x: auto; white-space: pre-wrap; }
+.bar { background: #6b8afd; height: 0.75rem; }
+";
+
+/// Upper bounds (exclusive, in milliseconds) of the response time buckets
+const ELAPSED_BUCKETS: [i32; 5] = [100, 250, 500, 1000, 2500];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub enum FuzzReportFormat {
+    #[default]
+    Html,
+    Markdown,
+}
+
+/// Counts behind the report's charts. Embedded as JSON in HTML reports so
+/// they can be re-plotted.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChartData {
+    /// Result count per status code, with failed requests under `error`
+    statuses: BTreeMap<String, usize>,
+    /// Result count per response time bucket, labelled like `<250ms`
+    elapsed: Vec<(String, usize)>,
+}
+
+impl ChartData {
+    fn new(results: &[(FuzzResult, Vec<u8>)]) -> Self {
+        let mut statuses = BTreeMap::new();
+        let mut elapsed = vec![0; ELAPSED_BUCKETS.len() + 1];
+        for (result, _) in results {
+            let status = match result.error {
+                Some(_) => "error".to_string(),
+                None => result.status.to_string(),
+            };
+            *statuses.entry(status).or_default() += 1;
+            let bucket = ELAPSED_BUCKETS.iter().position(|b| result.elapsed < *b);
+            elapsed[bucket.unwrap_or(ELAPSED_BUCKETS.len())] += 1;
+        }
+
+        let labels = ELAPSED_BUCKETS.iter().map(|b| format!("<{b}ms")).chain([format!(
+            ">={}ms",
+            ELAPSED_BUCKETS[ELAPSED_BUCKETS.len() - 1]
+        )]);
+        Self { statuses, elapsed: labels.zip(elapsed).collect() }
+    }
+}
+
+/// Render a standalone report of a run: its configuration, totals, chart
+/// data, and every starred result with its response body.
+pub fn build_report(export: &FuzzExport, format: FuzzReportFormat) -> String {
+    let run = &export.run;
+    let config = &export.config;
+    let charts = ChartData::new(&export.results);
+    let flagged: Vec<_> = export.results.iter().filter(|(r, _)| r.starred).collect();
+
+    let state = match run.state {
+        FuzzRunState::Running => "Running",
+        FuzzRunState::Finished => "Finished",
+        FuzzRunState::Cancelled => "Cancelled",
+    };
+    let match_mode = match config.match_mode {
+        FuzzMatchMode::Any => "any",
+        FuzzMatchMode::All => "all",
+    };
+    let title = format!("Fuzz report: {} {}", config.request.method, config.request.url);
+    let mut settings = vec![
+        ("Method", config.request.method.clone()),
+        ("URL", config.request.url.clone()),
+        ("Payloads", config.payloads().len().to_string()),
+        ("Concurrency", config.concurrency.max(1).to_string()),
+        ("Delay", format!("{}ms", config.delay_ms)),
+        ("Match mode", match_mode.to_string()),
+    ];
+    if !config.base_urls.urls.is_empty() {
+        settings.insert(2, ("Base URLs", config.base_urls.urls.join(", ")));
+    }
+    let totals = vec![
+        ("Started", run.created_at.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+        ("State", state.to_string()),
+        ("Requests", format!("{} of {}", run.completed, run.total)),
+        ("Matched", run.matched.to_string()),
+        ("Errors", run.errors.to_string()),
+        ("Duration", format!("{}ms", run.elapsed)),
+    ];
+    let matchers: Vec<_> = config.matchers.iter().map(describe_matcher).collect();
+    let filters: Vec<_> = config.filters.iter().map(describe_matcher).collect();
+
+    let report = Report { title, settings, totals, matchers, filters, charts, flagged };
+    match format {
+        FuzzReportFormat::Html => report.html(),
+        FuzzReportFormat::Markdown => report.markdown(),
+    }
+}
+
+struct Report<'a> {
+    title: String,
+    settings: Vec<(&'static str, String)>,
+    totals: Vec<(&'static str, String)>,
+    matchers: Vec<String>,
+    filters: Vec<String>,
+    charts: ChartData,
+    flagged: Vec<&'a (FuzzResult, Vec<u8>)>,
+}
+
+impl Report<'_> {
+    fn html(&self) -> String {
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n",
+            escape_markup(&self.title)
+        );
+        let _ = write!(html, "<style>\n{STYLE}</style>\n</head>\n<body>\n");
+        let _ = writeln!(html, "<h1>{}</h1>", escape_markup(&self.title));
+
+        html.push_str("<h2>Configuration</h2>\n");
+        html_table(&mut html, &self.settings);
+        html_list(&mut html, "Matchers", &self.matchers);
+        html_list(&mut html, "Filters", &self.filters);
+
+        html.push_str("<h2>Summary</h2>\n");
+        html_table(&mut html, &self.totals);
+
+        let statuses: Vec<_> = self.charts.statuses.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+        let elapsed: Vec<_> = self.charts.elapsed.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+        html.push_str("<h2>Status codes</h2>\n");
+        html_bars(&mut html, &statuses);
+        html.push_str("<h2>Response times</h2>\n");
+        html_bars(&mut html, &elapsed);
+        let chart_json = serde_json::to_string(&self.charts).unwrap_or_default();
+        let _ = writeln!(
+            html,
+            "<script type=\"application/json\" id=\"chart-data\">{}</script>",
+            chart_json.replace("</", "<\\/")
+        );
+
+        let _ = writeln!(html, "<h2>Flagged results ({})</h2>", self.flagged.len());
+        if self.flagged.is_empty() {
+            html.push_str("<p>No results were starred.</p>\n");
+        }
+        for (result, body) in &self.flagged {
+            let _ = writeln!(html, "<h3>{}</h3>", escape_markup(&result.payload));
+            html_table(&mut html, &result_details(result));
+            if !result.note.is_empty() {
+                let _ = writeln!(html, "<p>{}</p>", escape_markup(&result.note));
+            }
+            let _ = writeln!(html, "<pre>{}</pre>", escape_markup(&report_body(body)));
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    fn markdown(&self) -> String {
+        let mut md = String::new();
+        let _ = writeln!(md, "# {}\n", self.title);
+
+        md.push_str("## Configuration\n\n");
+        markdown_table(&mut md, ("Setting", "Value"), &self.settings);
+        markdown_list(&mut md, "Matchers", &self.matchers);
+        markdown_list(&mut md, "Filters", &self.filters);
+
+        md.push_str("## Summary\n\n");
+        markdown_table(&mut md, ("Metric", "Value"), &self.totals);
+
+        let statuses: Vec<_> =
+            self.charts.statuses.iter().map(|(k, v)| (k.as_str(), v.to_string())).collect();
+        let elapsed: Vec<_> =
+            self.charts.elapsed.iter().map(|(k, v)| (k.as_str(), v.to_string())).collect();
+        md.push_str("## Status codes\n\n");
+        markdown_table(&mut md, ("Status", "Results"), &statuses);
+        md.push_str("## Response times\n\n");
+        markdown_table(&mut md, ("Time", "Results"), &elapsed);
+
+        let _ = writeln!(md, "## Flagged results ({})\n", self.flagged.len());
+        if self.flagged.is_empty() {
+            md.push_str("No results were starred.\n\n");
+        }
+        for (result, body) in &self.flagged {
+            let _ = writeln!(md, "### `{}`\n", result.payload.replace('`', "'"));
+            markdown_table(&mut md, ("Field", "Value"), &result_details(result));
+            if !result.note.is_empty() {
+                let _ = writeln!(md, "{}\n", result.note);
+            }
+            let body = report_body(body);
+            let fence = "`".repeat(longest_backtick_run(&body).max(2) + 1);
+            let _ = writeln!(md, "{fence}\n{body}\n{fence}\n");
+        }
+        md
+    }
+}
+
+fn result_details(result: &FuzzResult) -> Vec<(&'static str, String)> {
+    let mut details = vec![
+        ("Status", result.status.to_string()),
+        ("Size", format!("{} bytes", result.content_length)),
+        ("Time", format!("{}ms", result.elapsed)),
+    ];
+    if result.request_size_bytes > 0 {
+        details.insert(1, ("Request size", format!("{} bytes", result.request_size_bytes)));
+    }
+    if !result.http_version.is_empty() {
+        details.insert(1, ("Protocol", result.http_version.clone()));
+    }
+    if !result.base_url.is_empty() {
+        details.insert(0, ("Base URL", result.base_url.clone()));
+    }
+    if let Some(error) = &result.error {
+        details.push(("Error", error.clone()));
+    }
+    if !result.tags.is_empty() {
+        details.push(("Tags", result.tags.join(", ")));
+    }
+    for (name, value) in &result.extracted {
+        details.push(("Extracted", format!("{name} = {value}")));
+    }
+    details
+}
+
+fn describe_matcher(matcher: &FuzzMatcher) -> String {
+    match matcher {
+        FuzzMatcher::Status { codes } => {
+            let codes: Vec<_> = codes.iter().map(|c| c.to_string()).collect();
+            format!("Status is {}", codes.join(", "))
+        }
+        FuzzMatcher::Size { min: Some(min), max: Some(max) } if min == max => {
+            format!("Size is {min} bytes")
+        }
+        FuzzMatcher::Size { min: Some(min), max: Some(max) } => {
+            format!("Size is between {min} and {max} bytes")
+        }
+        FuzzMatcher::Size { min: Some(min), max: None } => format!("Size is at least {min} bytes"),
+        FuzzMatcher::Size { min: None, max: Some(max) } => format!("Size is at most {max} bytes"),
+        FuzzMatcher::Size { min: None, max: None } => "Any response".to_string(),
+        FuzzMatcher::Contains { text } => format!("Body contains \"{text}\""),
+        FuzzMatcher::Regex { pattern } => format!("Body matches /{pattern}/"),
+        FuzzMatcher::Header { name, pattern: None } => format!("Header {name} is present"),
+        FuzzMatcher::Header { name, pattern: Some(pattern) } => {
+            format!("Header {name} matches /{pattern}/")
+        }
+        FuzzMatcher::DiffersFromBaseline { tolerance: 0 } => {
+            "Status or size differs from the baseline".to_string()
+        }
+        FuzzMatcher::DiffersFromBaseline { tolerance } => {
+            format!("Status differs from the baseline or size by more than {tolerance} bytes")
+        }
+        FuzzMatcher::Delayed { min_ms } => format!("Response takes at least {min_ms} ms"),
+    }
+}
+
+/// Response body as text, cut off at `MAX_REPORT_BODY` bytes
+fn report_body(body: &[u8]) -> String {
+    let truncated = body.len() > MAX_REPORT_BODY;
+    let mut text = String::from_utf8_lossy(&body[..body.len().min(MAX_REPORT_BODY)]).into_owned();
+    if truncated {
+        let _ = write!(text, "\n… ({} more bytes)", body.len() - MAX_REPORT_BODY);
+    }
+    text
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or_default()
+}
+
+fn html_table(html: &mut String, rows: &[(&str, String)]) {
+    html.push_str("<table>\n");
+    for (name, value) in rows {
+        let _ = writeln!(
+            html,
+            "<tr><th>{}</th><td>{}</td></tr>",
+            escape_markup(name),
+            escape_markup(value)
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+fn html_list(html: &mut String, title: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    let _ = writeln!(html, "<h3>{title}</h3>\n<ul>");
+    for item in items {
+        let _ = writeln!(html, "<li>{}</li>", escape_markup(item));
+    }
+    html.push_str("</ul>\n");
+}
+
+fn html_bars(html: &mut String, rows: &[(&str, usize)]) {
+    let max = rows.iter().map(|(_, v)| *v).max().unwrap_or_default().max(1);
+    html.push_str("<table>\n");
+    for (label, count) in rows {
+        let _ = writeln!(
+            html,
+            "<tr><th>{}</th><td>{count}</td><td style=\"width: 30rem\">\
+             <div class=\"bar\" style=\"width: {}%\"></div></td></tr>",
+            escape_markup(label),
+            count * 100 / max
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+fn markdown_table(md: &mut String, header: (&str, &str), rows: &[(&str, String)]) {
+    let cell = |text: &str| text.replace('|', "\\|").replace('\n', " ");
+    let _ = writeln!(md, "| {} | {} |\n| --- | --- |", header.0, header.1);
+    for (name, value) in rows {
+        let _ = writeln!(md, "| {} | {} |", cell(name), cell(value));
+    }
+    md.push('\n');
+}
+
+fn markdown_list(md: &mut String, title: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    let _ = writeln!(md, "### {title}\n");
+    for item in items {
+        let _ = writeln!(md, "- {item}");
+    }
+    md.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FuzzRequest;
+    use yaak_models::models::{FuzzRun, HttpRequest};
+
+    fn export() -> FuzzExport {
+        let result = |payload: &str, status: i32, elapsed: i32, starred: bool| FuzzResult {
+            payload: payload.to_string(),
+            status,
+            elapsed,
+            starred,
+            ..Default::default()
+        };
+        FuzzExport {
+            run: FuzzRun { total: 3, completed: 3, matched: 1, ..Default::default() },
+            config: FuzzRequest {
+                request: HttpRequest {
+                    method: "GET".to_string(),
+                    url: "https://example.com/§p§".to_string(),
+                    ..Default::default()
+                },
+                matchers: vec![FuzzMatcher::Status { codes: vec![200] }],
+                ..Default::default()
+            },
+            results: vec![
+                (result("admin", 200, 50, true), b"<b>secret</b>".to_vec()),
+                (result("login", 404, 300, false), Vec::new()),
+                (result("debug", 404, 5000, false), Vec::new()),
+            ],
+        }
+    }
+
+    #[test]
+    fn counts_statuses_and_times() {
+        let charts = ChartData::new(&export().results);
+        assert_eq!(charts.statuses.get("200"), Some(&1));
+        assert_eq!(charts.statuses.get("404"), Some(&2));
+        assert_eq!(charts.elapsed[0], ("<100ms".to_string(), 1));
+        assert_eq!(charts.elapsed[2], ("<500ms".to_string(), 1));
+        assert_eq!(charts.elapsed[5], (">=2500ms".to_string(), 1));
+    }
+
+    #[test]
+    fn renders_flagged_results_only() {
+        let html = build_report(&export(), FuzzReportFormat::Html);
+        assert!(html.contains("<h2>Flagged results (1)</h2>"));
+        assert!(html.contains("<pre>&lt;b&gt;secret&lt;/b&gt;</pre>"));
+        assert!(html.contains("<li>Status is 200</li>"));
+        assert!(!html.contains("<h3>login</h3>"));
+
+        let md = build_report(&export(), FuzzReportFormat::Markdown);
+        assert!(md.contains("### `admin`"));
+        assert!(md.contains("```\n<b>secret</b>\n```"));
+        assert!(md.contains("| 404 | 2 |"));
+    }
+}
@@ -0,0 +1,74 @@
+use crate::base_urls::rebased_config;
+use crate::error::Result;
+use crate::export::run_config;
+use crate::markers::payload_request;
+use yaak_models::db_context::DbContext;
+use yaak_models::models::{Folder, FuzzResult, FuzzRun, HttpRequest};
+use yaak_models::util::UpdateSource;
+
+/// Top-level folder that receives requests created from fuzz results
+pub const FINDINGS_FOLDER_NAME: &str = "Fuzz findings";
+
+/// The workspace's findings folder, created on first use
+pub fn findings_folder(
+    db: &DbContext,
+    workspace_id: &str,
+    source: &UpdateSource,
+) -> Result<Folder> {
+    let existing = db
+        .list_folders(workspace_id)?
+        .into_iter()
+        .find(|f| f.folder_id.is_none() && f.name == FINDINGS_FOLDER_NAME);
+    match existing {
+        Some(folder) => Ok(folder),
+        None => Ok(db.upsert_folder(
+            &Folder {
+                workspace_id: workspace_id.to_string(),
+                name: FINDINGS_FOLDER_NAME.to_string(),
+                ..Default::default()
+            },
+            source,
+        )?),
+    }
+}
+
+/// The run's request template with the result's payload injected, named
+/// after the payload and sent to the result's base URL. It still points at
+/// the original folder so it can be rendered with the same environments and
+/// inherited settings. `run` must already be opened.
+pub fn finding_request(run: &FuzzRun, result: &FuzzResult) -> HttpRequest {
+    let config = run_config(run);
+    let template = &config.request;
+    let name = if template.name.is_empty() { &template.url } else { &template.name };
+    HttpRequest {
+        id: String::new(),
+        workspace_id: run.workspace_id.clone(),
+        name: format!("{name} [{}]", result.payload),
+        ..payload_request(&rebased_config(&config, &result.base_url), &result.payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yaak_models::init_in_memory;
+    use yaak_models::models::Workspace;
+
+    #[test]
+    fn reuses_findings_folder() {
+        let (query_manager, _blob_manager, _rx) = init_in_memory().expect("Failed to init DB");
+        let db = query_manager.connect();
+        let source = UpdateSource::Background;
+        let workspace = db
+            .upsert_workspace(
+                &Workspace { name: "Test".to_string(), ..Default::default() },
+                &source,
+            )
+            .unwrap();
+
+        let first = findings_folder(&db, &workspace.id, &source).unwrap();
+        let second = findings_folder(&db, &workspace.id, &source).unwrap();
+        assert_eq!(first.id, second.id);
+        assert_eq!(first.name, FINDINGS_FOLDER_NAME);
+    }
+}
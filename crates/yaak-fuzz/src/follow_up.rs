@@ -0,0 +1,72 @@
+use crate::engine::duration_to_i32;
+use crate::sender::FuzzSender;
+use crate::types::FuzzFollowUp;
+use std::collections::BTreeMap;
+use std::time::Instant;
+use yaak_http::cookies::CookieStore;
+use yaak_models::models::{FuzzFollowUpResult, FuzzResult};
+use yaak_templates::escape::escape_template;
+
+/// Template variable holding the payload of a request, or of the result that
+/// triggered a follow-up
+pub const PAYLOAD_VARIABLE: &str = "fuzz.payload";
+
+/// Prefix for template variables holding extracted values
+pub const VARIABLE_PREFIX: &str = "fuzz.";
+
+/// Template variables available to a follow-up request. Values are escaped so
+/// response content is never evaluated as a template. The payload wins over an
+/// extractor that happens to be named `payload`.
+pub fn follow_up_variables(
+    payload: &str,
+    extracted: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    let mut variables = BTreeMap::new();
+    for (name, value) in extracted {
+        variables.insert(format!("{VARIABLE_PREFIX}{name}"), escape_template(value));
+    }
+    variables.insert(PAYLOAD_VARIABLE.to_string(), escape_template(payload));
+    variables
+}
+
+/// Send the follow-up request for a matching result, with the cookie jar of
+/// its payload if it has one
+pub async fn send_follow_up<S: FuzzSender>(
+    sender: &S,
+    follow_up: &FuzzFollowUp,
+    result: &FuzzResult,
+    cookies: Option<&CookieStore>,
+) -> FuzzFollowUpResult {
+    let variables = follow_up_variables(&result.payload, &result.extracted);
+    let started_at = Instant::now();
+    let mut follow_up_result = FuzzFollowUpResult::default();
+
+    match sender.send(&follow_up.request, &variables, cookies).await {
+        Ok(response) => {
+            follow_up_result.url = response.url;
+            follow_up_result.status = response.status as i32;
+            follow_up_result.content_length = response.body.len() as i32;
+        }
+        Err(e) => follow_up_result.error = Some(e.to_string()),
+    }
+
+    follow_up_result.elapsed = duration_to_i32(started_at.elapsed());
+    follow_up_result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variables_are_prefixed_and_escaped() {
+        let extracted = BTreeMap::from([
+            ("token".to_string(), "abc".to_string()),
+            ("payload".to_string(), "shadowed".to_string()),
+        ]);
+        let variables = follow_up_variables("${[ secret ]}", &extracted);
+
+        assert_eq!(variables.get("fuzz.token").map(String::as_str), Some("abc"));
+        assert_eq!(variables.get("fuzz.payload").map(String::as_str), Some("\\${[ secret ]}"));
+    }
+}
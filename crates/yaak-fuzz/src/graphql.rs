@@ -0,0 +1,201 @@
+//! GraphQL schemas read through introspection, for targeting the arguments
+//! of each field
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::sender::FuzzSender;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+use yaak_models::models::HttpRequest;
+
+/// The parts of the schema needed to list fields and their argument types
+pub const INTROSPECTION_QUERY: &str = "query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    types { name fields { name args { name type { ...TypeRef } } type { ...TypeRef } } }
+  }
+}
+
+fragment TypeRef on __Type {
+  kind name ofType { kind name ofType { kind name ofType { kind name ofType { kind name } } } }
+}";
+
+/// A query or mutation field that takes arguments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzGraphqlField {
+    /// `query` or `mutation`
+    pub operation: String,
+    pub name: String,
+    pub arguments: Vec<FuzzGraphqlArgument>,
+    /// Whether the field returns an object, which needs a selection set
+    pub selectable: bool,
+}
+
+/// An argument of a GraphQL field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzGraphqlArgument {
+    pub name: String,
+    /// Type as written in a query, like `[ID!]!`
+    pub type_name: String,
+}
+
+/// Send the introspection query to the endpoint of the GraphQL `request`,
+/// rendered like a run's requests, and list the schema's fields
+pub async fn introspect<S: FuzzSender>(
+    sender: &S,
+    request: &HttpRequest,
+) -> Result<Vec<FuzzGraphqlField>> {
+    let mut request = request.clone();
+    request.body_type = Some("graphql".to_string());
+    request.body.insert("query".to_string(), json!(INTROSPECTION_QUERY));
+    request.body.insert("variables".to_string(), json!(""));
+    let response = sender.send(&request, &BTreeMap::new(), None).await?;
+    let body: Value = serde_json::from_slice(&response.body)
+        .map_err(|_| GenericError(format!("Introspection returned status {}", response.status)))?;
+    schema_fields(&body)
+}
+
+/// Fields with arguments of the query and mutation types in an introspection
+/// response
+pub fn schema_fields(response: &Value) -> Result<Vec<FuzzGraphqlField>> {
+    let schema = match response.pointer("/data/__schema") {
+        Some(schema) => schema,
+        None => return Err(GenericError("Introspection is disabled on this endpoint".to_string())),
+    };
+    let types = schema["types"].as_array().map(Vec::as_slice).unwrap_or_default();
+
+    let mut fields = Vec::new();
+    for operation in ["query", "mutation"] {
+        let root = schema.pointer(&format!("/{operation}Type/name")).and_then(|n| n.as_str());
+        let root = match root.and_then(|root| types.iter().find(|t| t["name"] == root)) {
+            Some(root) => root,
+            None => continue,
+        };
+        for field in root["fields"].as_array().into_iter().flatten() {
+            let name = field["name"].as_str().unwrap_or_default();
+            let arguments = field["args"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|a| {
+                    Some(FuzzGraphqlArgument {
+                        name: a["name"].as_str()?.to_string(),
+                        type_name: type_name(&a["type"])?,
+                    })
+                })
+                .collect::<Vec<_>>();
+            if name.is_empty() || name.starts_with("__") || arguments.is_empty() {
+                continue;
+            }
+            fields.push(FuzzGraphqlField {
+                operation: operation.to_string(),
+                name: name.to_string(),
+                arguments,
+                selectable: is_selectable(&field["type"]),
+            });
+        }
+    }
+    Ok(fields)
+}
+
+/// `request` with a body calling only `field`, with each argument passed as
+/// a variable of the same name set to null. The variables are the GraphQL
+/// insertion points of the returned request.
+pub fn field_request(request: &HttpRequest, field: &FuzzGraphqlField) -> HttpRequest {
+    let definitions = field.arguments.iter().map(|a| format!("${}: {}", a.name, a.type_name));
+    let arguments = field.arguments.iter().map(|a| format!("{}: ${}", a.name, a.name));
+    let selection = if field.selectable { " { __typename }" } else { "" };
+    let query = format!(
+        "{} ({}) {{\n  {}({}){selection}\n}}",
+        field.operation,
+        definitions.collect::<Vec<_>>().join(", "),
+        field.name,
+        arguments.collect::<Vec<_>>().join(", "),
+    );
+    let variables: Map<String, Value> =
+        field.arguments.iter().map(|a| (a.name.clone(), Value::Null)).collect();
+
+    let mut request = request.clone();
+    request.body_type = Some("graphql".to_string());
+    request.body.insert("query".to_string(), json!(query));
+    request.body.insert(
+        "variables".to_string(),
+        json!(serde_json::to_string_pretty(&variables).unwrap_or_default()),
+    );
+    request
+}
+
+/// A type reference as written in a query
+fn type_name(type_ref: &Value) -> Option<String> {
+    match type_ref["kind"].as_str()? {
+        "NON_NULL" => Some(format!("{}!", type_name(&type_ref["ofType"])?)),
+        "LIST" => Some(format!("[{}]", type_name(&type_ref["ofType"])?)),
+        _ => Some(type_ref["name"].as_str()?.to_string()),
+    }
+}
+
+fn is_selectable(type_ref: &Value) -> bool {
+    match type_ref["kind"].as_str() {
+        Some("NON_NULL" | "LIST") => is_selectable(&type_ref["ofType"]),
+        Some(kind) => matches!(kind, "OBJECT" | "INTERFACE" | "UNION"),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::targets::insertion_points;
+    use crate::types::FuzzTarget;
+
+    #[test]
+    fn lists_fields_and_builds_requests() {
+        let response = json!({"data": {"__schema": {
+            "queryType": {"name": "Query"},
+            "mutationType": null,
+            "types": [{"name": "Query", "fields": [
+                {"name": "ping", "args": [], "type": {"kind": "SCALAR", "name": "String"}},
+                {
+                    "name": "user",
+                    "args": [{"name": "id", "type": {
+                        "kind": "NON_NULL", "name": null,
+                        "ofType": {"kind": "SCALAR", "name": "ID"},
+                    }}],
+                    "type": {"kind": "OBJECT", "name": "User"},
+                },
+            ]}],
+        }}});
+
+        let fields = schema_fields(&response).unwrap();
+        assert_eq!(
+            fields,
+            vec![FuzzGraphqlField {
+                operation: "query".to_string(),
+                name: "user".to_string(),
+                arguments: vec![FuzzGraphqlArgument {
+                    name: "id".to_string(),
+                    type_name: "ID!".to_string(),
+                }],
+                selectable: true,
+            }]
+        );
+
+        let request = field_request(&HttpRequest::default(), &fields[0]);
+        assert_eq!(request.body["query"], "query ($id: ID!) {\n  user(id: $id) { __typename }\n}");
+        let targets = insertion_points(&request).into_iter().map(|p| p.target).collect::<Vec<_>>();
+        assert_eq!(targets, vec![FuzzTarget::GraphqlVariable { path: "$.id".to_string() }]);
+    }
+
+    #[test]
+    fn requires_introspection() {
+        let response = json!({"errors": [{"message": "Introspection is not allowed"}]});
+        assert!(schema_fields(&response).is_err());
+    }
+}
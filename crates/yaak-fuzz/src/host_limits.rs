@@ -0,0 +1,103 @@
+//! Concurrency and pacing applied to each host a run sends to, so requests
+//! to one host don't hold back the others or pile up on it
+
+use crate::types::FuzzHostLimits;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+/// Hands out turns to send to each host
+pub(crate) struct HostLimiter {
+    limits: FuzzHostLimits,
+    hosts: Mutex<HashMap<String, Arc<HostState>>>,
+}
+
+struct HostState {
+    in_flight: Arc<Semaphore>,
+    /// When the next request to the host may start
+    next_start: tokio::sync::Mutex<Instant>,
+}
+
+/// A turn to send to a host, held while the request is in flight
+pub(crate) struct HostTurn {
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl HostLimiter {
+    pub(crate) fn new(limits: FuzzHostLimits) -> Self {
+        Self { limits, hosts: Mutex::new(HashMap::new()) }
+    }
+
+    /// Wait until a request to `url`'s host may start. URLs without a host,
+    /// like ones still holding template tags, share a single turn order.
+    pub(crate) async fn turn(&self, url: &str) -> HostTurn {
+        let FuzzHostLimits { concurrency, delay_ms } = self.limits;
+        if concurrency == 0 && delay_ms == 0 {
+            return HostTurn { _permit: None };
+        }
+        let host = url::Url::parse(url)
+            .ok()
+            .and_then(|u| Some(format!("{}:{}", u.host_str()?, u.port_or_known_default()?)))
+            .unwrap_or_default();
+        let state = self
+            .hosts
+            .lock()
+            .unwrap()
+            .entry(host)
+            .or_insert_with(|| {
+                Arc::new(HostState {
+                    in_flight: Arc::new(Semaphore::new(concurrency.max(1))),
+                    next_start: tokio::sync::Mutex::new(Instant::now()),
+                })
+            })
+            .clone();
+
+        let permit = match concurrency {
+            0 => None,
+            _ => state.in_flight.clone().acquire_owned().await.ok(),
+        };
+        let mut next_start = state.next_start.lock().await;
+        tokio::time::sleep_until(*next_start).await;
+        *next_start = Instant::now() + Duration::from_millis(delay_ms as u64);
+        HostTurn { _permit: permit }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn paces_each_host_separately() {
+        let limiter = HostLimiter::new(FuzzHostLimits { concurrency: 0, delay_ms: 40 });
+        let started_at = Instant::now();
+        limiter.turn("https://a.example.com/1").await;
+        limiter.turn("https://b.example.com/1").await;
+        assert!(started_at.elapsed() < Duration::from_millis(40));
+
+        limiter.turn("https://a.example.com/2").await;
+        assert!(started_at.elapsed() >= Duration::from_millis(40));
+    }
+
+    #[tokio::test]
+    async fn limits_requests_in_flight_per_host() {
+        let limiter = HostLimiter::new(FuzzHostLimits { concurrency: 1, delay_ms: 0 });
+        let first = limiter.turn("https://a.example.com/1").await;
+        let other_host = limiter.turn("https://b.example.com/1");
+        assert!(tokio::time::timeout(Duration::from_millis(20), other_host).await.is_ok());
+
+        let same_host = limiter.turn("https://a.example.com/2");
+        assert!(tokio::time::timeout(Duration::from_millis(20), same_host).await.is_err());
+        drop(first);
+        assert!(
+            tokio::time::timeout(
+                Duration::from_millis(20),
+                limiter.turn("https://a.example.com/3")
+            )
+            .await
+            .is_ok()
+        );
+    }
+}
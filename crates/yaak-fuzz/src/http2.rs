@@ -0,0 +1,218 @@
+//! HTTP/2 sent with h2 directly, for pseudo-header payloads, long header
+//! lists and stream resets that reqwest doesn't expose
+
+use crate::error::Error;
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::raw::{RawConnection, RawTarget, connect, connect_tls, with_timeout};
+use crate::sender::{FuzzResponse, SentRequest};
+use crate::types::{FuzzHttp2, FuzzHttpVersion, FuzzRequest};
+use bytes::Bytes;
+use h2::Reason;
+use h2::client::SendRequest;
+use http::{HeaderName, HeaderValue, Method, Request, Uri};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// Headers h2 rejects, since HTTP/2 has no connection-specific headers
+const CONNECTION_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-connection",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// Check that an HTTP/2 run connects directly and may use HTTP/2
+pub(crate) fn validate_http2(config: &FuzzRequest) -> Result<()> {
+    if config.http2.is_none() {
+        return Ok(());
+    }
+    if !config.proxies.urls.is_empty() {
+        return Err(GenericError("HTTP/2 runs can't be sent through a proxy".to_string()));
+    }
+    if config.http_version == FuzzHttpVersion::Http1 {
+        return Err(GenericError("HTTP/2 runs can't be limited to HTTP/1.1".to_string()));
+    }
+    Ok(())
+}
+
+/// Send `request` over a new HTTP/2 connection, with prior knowledge for
+/// plain HTTP and only offering h2 over TLS
+pub(crate) async fn send_http2(
+    request: SentRequest,
+    options: FuzzHttp2,
+    mut connection: RawConnection,
+) -> Result<FuzzResponse> {
+    let target = RawTarget::parse(&request.url)?;
+    let timeout = connection.timeout;
+    let started_at = Instant::now();
+
+    let response = async {
+        let stream = connect(&target, &connection).await?;
+        let read_timeout = connection.read_timeout;
+        if !target.tls {
+            return exchange(stream, &request, &target, options, read_timeout).await;
+        }
+        connection.tls.alpn_protocols = vec![b"h2".to_vec()];
+        let stream = connect_tls(stream, &target, connection).await?;
+        if stream.get_ref().1.alpn_protocol() != Some(b"h2".as_slice()) {
+            return Err(GenericError("The server doesn't support HTTP/2".to_string()));
+        }
+        exchange(stream, &request, &target, options, read_timeout).await
+    };
+    let (status, headers, body) = with_timeout(timeout, response).await??;
+
+    let elapsed = started_at.elapsed();
+    let version = "HTTP/2".to_string();
+    Ok(FuzzResponse { url: request.url.clone(), status, version, headers, body, elapsed, request })
+}
+
+/// Drive the connection while resetting streams and sending the request
+async fn exchange<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: S,
+    request: &SentRequest,
+    target: &RawTarget,
+    options: FuzzHttp2,
+    read_timeout: Option<Duration>,
+) -> Result<(u16, Vec<(String, String)>, Vec<u8>)> {
+    let (client, connection) = h2::client::handshake(stream).await.map_err(h2_error)?;
+    tokio::select! {
+        response = send(client, request, target, options, read_timeout) => response,
+        Err(e) = connection => Err(h2_error(e)),
+    }
+}
+
+async fn send(
+    mut client: SendRequest<Bytes>,
+    request: &SentRequest,
+    target: &RawTarget,
+    options: FuzzHttp2,
+    read_timeout: Option<Duration>,
+) -> Result<(u16, Vec<(String, String)>, Vec<u8>)> {
+    for _ in 0..options.reset_streams {
+        client = client.ready().await.map_err(h2_error)?;
+        let (_, mut stream) =
+            client.send_request(build_request(request, target, 0)?, false).map_err(h2_error)?;
+        stream.send_reset(Reason::CANCEL);
+    }
+
+    client = client.ready().await.map_err(h2_error)?;
+    let built = build_request(request, target, options.padding_headers)?;
+    let (response, mut stream) =
+        client.send_request(built, request.body.is_empty()).map_err(h2_error)?;
+    if !request.body.is_empty() {
+        stream.send_data(Bytes::from(request.body.clone()), true).map_err(h2_error)?;
+    }
+
+    let response = response.await.map_err(h2_error)?;
+    let status = response.status().as_u16();
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(n, v)| (n.to_string(), String::from_utf8_lossy(v.as_bytes()).into_owned()))
+        .collect();
+    let mut data = response.into_body();
+    let mut body = Vec::new();
+    loop {
+        let chunk = match read_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, data.data())
+                .await
+                .map_err(|_| yaak_http::error::Error::BodyReadTimeout)?,
+            None => data.data().await,
+        };
+        match chunk {
+            None => break,
+            Some(chunk) => {
+                let chunk = chunk.map_err(h2_error)?;
+                let _ = data.flow_control().release_capacity(chunk.len());
+                body.extend_from_slice(&chunk);
+            }
+        }
+    }
+    Ok((status, headers, body))
+}
+
+/// `request` as an h2 request, with pseudo-headers from headers named after
+/// them and `padding` extra headers
+fn build_request(request: &SentRequest, target: &RawTarget, padding: u32) -> Result<Request<()>> {
+    let pseudo = |name: &str| {
+        request.headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    };
+    let default_scheme = if target.tls { "https" } else { "http" };
+    let default_path = &target.path[..target.path.find('#').unwrap_or(target.path.len())];
+    let method = pseudo(":method").unwrap_or(&request.method);
+    let uri = format!(
+        "{}://{}{}",
+        pseudo(":scheme").unwrap_or(default_scheme),
+        pseudo(":authority").unwrap_or(&target.authority),
+        pseudo(":path").unwrap_or(default_path),
+    );
+
+    let mut built = Request::builder()
+        .method(
+            Method::from_bytes(method.as_bytes())
+                .map_err(|_| GenericError(format!("Invalid HTTP/2 method {method}")))?,
+        )
+        .uri(
+            uri.parse::<Uri>()
+                .map_err(|e| GenericError(format!("Invalid HTTP/2 pseudo-headers {uri}: {e}")))?,
+        )
+        .body(())
+        .map_err(|e| GenericError(format!("Invalid HTTP/2 request: {e}")))?;
+
+    let headers = built.headers_mut();
+    for (name, value) in &request.headers {
+        let lower = name.to_ascii_lowercase();
+        if lower.starts_with(':') || CONNECTION_HEADERS.contains(&lower.as_str()) {
+            continue;
+        }
+        let name = HeaderName::from_bytes(lower.as_bytes())
+            .map_err(|_| GenericError(format!("Invalid HTTP/2 header name {name}")))?;
+        let value = HeaderValue::from_bytes(value.as_bytes())
+            .map_err(|_| GenericError(format!("Invalid value for HTTP/2 header {name}")))?;
+        headers.append(name, value);
+    }
+    for index in 0..padding {
+        let name = HeaderName::from_bytes(format!("x-padding-{index}").as_bytes())
+            .map_err(|e| GenericError(e.to_string()))?;
+        headers.append(name, HeaderValue::from_static("x"));
+    }
+    Ok(built)
+}
+
+/// IO errors are kept, so timeouts and certificate errors are categorized
+/// like any other request's
+fn h2_error(e: h2::Error) -> Error {
+    let message = format!("HTTP/2 error: {e}");
+    e.into_io().map_or(GenericError(message), Error::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_pseudo_headers_from_request_headers() {
+        let request = SentRequest {
+            method: "GET".to_string(),
+            url: "https://example.com/a?b=1#frag".to_string(),
+            headers: vec![
+                (":path".to_string(), "/static/..;/admin".to_string()),
+                ("Connection".to_string(), "close".to_string()),
+                ("X-Token".to_string(), "abc".to_string()),
+            ],
+            body: Vec::new(),
+        };
+        let target = RawTarget::parse(&request.url).unwrap();
+        let built = build_request(&request, &target, 2).unwrap();
+
+        assert_eq!(built.uri().to_string(), "https://example.com/static/..;/admin");
+        let names = built.headers().keys().map(|n| n.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["x-token", "x-padding-0", "x-padding-1"]);
+
+        let plain = SentRequest { headers: Vec::new(), ..request };
+        let built = build_request(&plain, &target, 0).unwrap();
+        assert_eq!(built.uri().path_and_query().unwrap().as_str(), "/a?b=1");
+    }
+}
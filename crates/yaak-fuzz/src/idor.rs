@@ -0,0 +1,81 @@
+//! Horizontal privilege escalation: user B's credentials on requests for
+//! user A's objects
+
+use crate::auth_bypass::strip_auth;
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::types::{FuzzIdor, FuzzRequest};
+use yaak_models::models::HttpRequest;
+
+/// Tag given to results that user B gets the same response for
+pub(crate) const IDOR_TAG: &str = "idor";
+
+/// Check that user B's credentials replace all of user A's
+pub(crate) fn validate_idor(config: &FuzzRequest) -> Result<()> {
+    let Some(idor) = &config.idor else {
+        return Ok(());
+    };
+    if !idor.user_b.iter().any(|h| h.enabled && !h.name.trim().is_empty()) {
+        return Err(GenericError("Enter the headers user B is authenticated with".to_string()));
+    }
+    // Every request is signed, which would keep user A's credentials on them
+    if config.signing.is_some() {
+        return Err(GenericError("IDOR scanning can't be combined with signing".to_string()));
+    }
+    Ok(())
+}
+
+/// `request` with user A's credentials replaced by user B's
+pub(crate) fn as_user_b(request: &HttpRequest, idor: &FuzzIdor) -> HttpRequest {
+    let headers = idor.user_b.iter().filter(|h| h.enabled && !h.name.trim().is_empty());
+    let names = headers.clone().map(|h| h.name.trim().to_string()).collect::<Vec<_>>();
+    let mut request = strip_auth(request, &names);
+    request.headers.extend(headers.cloned());
+    request
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yaak_models::models::HttpRequestHeader;
+
+    #[test]
+    fn replaces_user_a_credentials_with_user_b() {
+        let header = |name: &str, value: &str| HttpRequestHeader {
+            enabled: true,
+            name: name.to_string(),
+            value: value.to_string(),
+            id: None,
+        };
+        let request = HttpRequest {
+            url: "https://example.com/invoices/§1§".to_string(),
+            authentication_type: Some("bearer".to_string()),
+            headers: vec![header("X-Session", "user-a"), header("Accept", "*/*")],
+            ..Default::default()
+        };
+        let idor = FuzzIdor {
+            user_b: vec![
+                header("Authorization", "Bearer user-b"),
+                header("X-Session", "user-b"),
+            ],
+            size_tolerance: 0,
+        };
+        let request = as_user_b(&request, &idor);
+
+        assert_eq!(request.authentication_type.as_deref(), Some("none"));
+        let sent = request
+            .headers
+            .iter()
+            .filter(|h| h.enabled)
+            .map(|h| (h.name.as_str(), h.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sent,
+            vec![
+                ("Accept", "*/*"),
+                ("Authorization", "Bearer user-b"),
+                ("X-Session", "user-b")
+            ]
+        );
+    }
+}
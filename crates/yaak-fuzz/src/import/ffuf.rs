@@ -0,0 +1,215 @@
+use super::{
+    FuzzImport, RequestArgs, match_everything, parse_delay_ms, parse_size_matchers,
+    parse_status_codes, split_flag,
+};
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::types::{
+    FuzzClientCertificate, FuzzHttp2, FuzzMatchMode, FuzzMatcher, FuzzProxies, FuzzRequest, FuzzTls,
+};
+
+const DEFAULT_KEYWORD: &str = "FUZZ";
+const DEFAULT_MATCH_CODES: &str = "200-299,301,302,307,401,403,405,500";
+const DEFAULT_THREADS: usize = 40;
+
+/// Options that only affect ffuf's own output and can be dropped silently
+const OUTPUT_SWITCHES: &[&str] = &["c", "s", "v", "json", "noninteractive"];
+
+/// Options that take no value but change what is sent or matched
+const SWITCHES: &[&str] = &["ac", "ach", "ic", "r", "raw", "recursion", "sa", "se", "sf"];
+
+/// Parse ffuf arguments. Only the first wordlist is used. Matchers are
+/// combined with `-mmode` (default `or`) and any filter rejects a result,
+/// mirroring ffuf.
+pub(super) fn parse(args: &[String]) -> Result<FuzzImport> {
+    let mut import = FuzzImport::default();
+    let mut request = RequestArgs::default();
+    let mut keyword = DEFAULT_KEYWORD.to_string();
+    let mut match_codes = None;
+    let mut matchers = Vec::new();
+    let mut filters = Vec::new();
+    let mut match_mode = FuzzMatchMode::Any;
+    let mut concurrency = DEFAULT_THREADS;
+    let mut delay_ms = 0;
+    let mut validate_certificates = None;
+    let mut proxies = Vec::new();
+    let mut extensions = Vec::new();
+    let mut http2 = None;
+    let mut server_name = None;
+    let mut crt_file = None;
+    let mut key_file = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') {
+            import.warnings.push(format!("Ignored argument {arg}"));
+            continue;
+        }
+
+        let (name, inline_value) = split_flag(arg);
+        if name == "k" {
+            validate_certificates = Some(false);
+            continue;
+        }
+        if name == "http2" {
+            http2 = Some(FuzzHttp2::default());
+            continue;
+        }
+        if OUTPUT_SWITCHES.contains(&name) {
+            continue;
+        }
+        if SWITCHES.contains(&name) {
+            import.warnings.push(format!("Ignored option -{name}"));
+            continue;
+        }
+
+        let value = match inline_value {
+            Some(value) => value.to_string(),
+            None => args
+                .next()
+                .ok_or_else(|| GenericError(format!("Missing value for -{name}")))?
+                .clone(),
+        };
+
+        match name {
+            "u" => request.url = Some(value),
+            "X" => request.method = Some(value),
+            "H" => request.headers.push(value),
+            "b" => request.cookie = Some(value),
+            "d" | "data" | "data-ascii" | "data-binary" => request.data = Some(value),
+            "w" if import.wordlist_path.is_some() => {
+                import.warnings.push(format!("Only the first wordlist is used, ignored {value}"));
+            }
+            "w" => {
+                // `path:KEYWORD` sets a custom keyword. Windows drive letters are
+                // left alone since the suffix then contains a path separator.
+                match value.rsplit_once(':') {
+                    Some((path, kw))
+                        if !path.is_empty() && !kw.is_empty() && !kw.contains(['/', '\\']) =>
+                    {
+                        import.wordlist_path = Some(path.to_string());
+                        keyword = kw.to_string();
+                    }
+                    _ => import.wordlist_path = Some(value),
+                }
+            }
+            "mc" => match_codes = Some(value),
+            "ms" => matchers.extend(parse_size_matchers(&value)?),
+            "mr" => matchers.push(FuzzMatcher::Regex { pattern: value }),
+            "fc" => filters.push(FuzzMatcher::Status { codes: parse_status_codes(&value)? }),
+            "fs" => filters.extend(parse_size_matchers(&value)?),
+            "fr" => filters.push(FuzzMatcher::Regex { pattern: value }),
+            "mmode" if value.eq_ignore_ascii_case("and") => match_mode = FuzzMatchMode::All,
+            "mmode" => match_mode = FuzzMatchMode::Any,
+            "fmode" if value.eq_ignore_ascii_case("and") => {
+                import.warnings.push("Filters are always combined with or".to_string());
+            }
+            "t" => {
+                concurrency = value
+                    .parse()
+                    .map_err(|_| GenericError(format!("Invalid thread count {value}")))?;
+            }
+            "p" => delay_ms = parse_delay_ms(&value, &mut import.warnings)?,
+            "x" => proxies.push(value),
+            "sni" => server_name = Some(value),
+            "cc" => crt_file = Some(value),
+            "ck" => key_file = Some(value),
+            "e" => extensions.extend(
+                value.split(',').map(str::trim).filter(|e| !e.is_empty()).map(String::from),
+            ),
+            "mt" | "ft" if value.trim().starts_with('>') => {
+                let min_ms = value.trim()[1..]
+                    .trim()
+                    .parse::<u32>()
+                    .map_err(|_| GenericError(format!("Invalid response time {value}")))?;
+                let delayed = FuzzMatcher::Delayed { min_ms: min_ms.saturating_add(1) };
+                if name == "mt" { matchers.push(delayed) } else { filters.push(delayed) }
+            }
+            "mw" | "ml" | "mt" | "fw" | "fl" | "ft" => {
+                import.warnings.push(format!("Unsupported matcher -{name} {value} was ignored"));
+            }
+            "fmode" | "o" | "of" | "od" => {}
+            _ => import.warnings.push(format!("Ignored option -{name} {value}")),
+        }
+    }
+
+    // Like ffuf, the default status codes only apply when no other matcher is set
+    let match_codes = match match_codes {
+        Some(codes) => Some(codes),
+        None if matchers.is_empty() => Some(DEFAULT_MATCH_CODES.to_string()),
+        None => None,
+    };
+    match match_codes {
+        Some(codes) if codes.eq_ignore_ascii_case("all") => matchers.insert(0, match_everything()),
+        Some(codes) => {
+            matchers.insert(0, FuzzMatcher::Status { codes: parse_status_codes(&codes)? })
+        }
+        None => {}
+    }
+
+    let client_certificate = match (crt_file, key_file) {
+        (None, None) => FuzzClientCertificate::Workspace,
+        (crt_file, key_file) => {
+            FuzzClientCertificate::Files { crt_file, key_file, pfx_file: None, passphrase: None }
+        }
+    };
+    import.config = FuzzRequest {
+        request: request.build(&keyword, &mut import.warnings)?,
+        extensions,
+        concurrency,
+        delay_ms,
+        matchers,
+        match_mode,
+        filters,
+        validate_certificates,
+        proxies: FuzzProxies { urls: proxies, ..Default::default() },
+        http2,
+        tls: FuzzTls { server_name, ..Default::default() },
+        client_certificate,
+        ..Default::default()
+    };
+    Ok(import)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::import::import_command;
+    use crate::types::FuzzMatcher;
+
+    #[test]
+    fn imports_ffuf_command() {
+        let import = import_command(
+            "ffuf -u https://example.com/FUZZ -w /tmp/words.txt -H 'X-Token: abc' \
+             -mc 200,301-302 -fs 42 -t 10 -p 0.5 -e .php,.bak",
+        )
+        .unwrap();
+
+        let config = &import.config;
+        assert_eq!(import.wordlist_path.as_deref(), Some("/tmp/words.txt"));
+        assert_eq!(config.request.method, "GET");
+        assert_eq!(config.request.url, "https://example.com/§FUZZ§");
+        assert_eq!(config.request.headers[0].name, "X-Token");
+        assert_eq!(config.request.headers[0].value, "abc");
+        assert_eq!(config.matchers, vec![FuzzMatcher::Status { codes: vec![200, 301, 302] }]);
+        assert_eq!(config.filters, vec![FuzzMatcher::Size { min: Some(42), max: Some(42) }]);
+        assert_eq!(config.concurrency, 10);
+        assert_eq!(config.delay_ms, 500);
+        assert_eq!(config.extensions, vec![".php", ".bak"]);
+        assert!(import.warnings.is_empty());
+    }
+
+    #[test]
+    fn custom_keyword_and_post_data() {
+        let import = import_command(
+            "ffuf -u https://example.com/login -w users.txt:USER -d 'user=USER&pass=x' \
+             -H 'Content-Type: application/x-www-form-urlencoded'",
+        )
+        .unwrap();
+
+        let request = &import.config.request;
+        assert_eq!(import.wordlist_path.as_deref(), Some("users.txt"));
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.body_type.as_deref(), Some("other"));
+        assert_eq!(request.body.get("text").and_then(|t| t.as_str()), Some("user=§USER§&pass=x"));
+    }
+}
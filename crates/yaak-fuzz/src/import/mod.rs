@@ -0,0 +1,328 @@
+//! Convert command lines of other fuzzers into fuzz configurations
+
+mod ffuf;
+mod wfuzz;
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::markers::{MARKER, escape_markers};
+use crate::types::{FuzzMatcher, FuzzRequest};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use ts_rs::TS;
+use yaak_models::models::{HttpRequest, HttpRequestHeader};
+
+/// A fuzz configuration converted from another tool's command line
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzImport {
+    pub config: FuzzRequest,
+    /// Wordlist file named on the command line. See `FuzzImport::load_wordlist`.
+    pub wordlist_path: Option<String>,
+    /// Options that were ignored or only approximated
+    pub warnings: Vec<String>,
+}
+
+impl FuzzImport {
+    /// Read `wordlist_path` into the config's wordlist, skipping blank lines.
+    /// A file that can't be read is reported as a warning so the rest of the
+    /// import is still usable.
+    pub fn load_wordlist(&mut self) {
+        let Some(path) = &self.wordlist_path else {
+            return;
+        };
+        match fs::read_to_string(path) {
+            Ok(contents) => {
+                self.config.wordlist = contents
+                    .lines()
+                    .filter(|l| !l.trim().is_empty())
+                    .map(|l| l.to_string())
+                    .collect();
+            }
+            Err(e) => self.warnings.push(format!("Failed to read wordlist {path}: {e}")),
+        }
+    }
+}
+
+/// Parse an `ffuf` or `wfuzz` invocation. The tool is picked from the first
+/// word of the command.
+pub fn import_command(command: &str) -> Result<FuzzImport> {
+    let args = split_command_line(command)?;
+    let Some(program) = args.first() else {
+        return Err(GenericError("Command is empty".to_string()));
+    };
+
+    let program = program.rsplit(['/', '\\']).next().unwrap_or(program);
+    match program.trim_end_matches(".exe").trim_end_matches(".py") {
+        "ffuf" => ffuf::parse(&args[1..]),
+        "wfuzz" => wfuzz::parse(&args[1..]),
+        p => Err(GenericError(format!("Unsupported command {p}, expected ffuf or wfuzz"))),
+    }
+}
+
+/// Split a shell command line into words, honoring single and double quotes,
+/// backslash escapes, and line continuations
+fn split_command_line(command: &str) -> Result<Vec<String>> {
+    let unterminated = || GenericError("Unterminated quote in command".to_string());
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next().ok_or_else(unterminated)? {
+                        '\'' => break,
+                        c => current.push(c),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next().ok_or_else(unterminated)? {
+                        '"' => break,
+                        '\\' => match chars.next().ok_or_else(unterminated)? {
+                            c @ ('"' | '\\' | '$' | '`') => current.push(c),
+                            '\n' => {}
+                            c => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                        },
+                        c => current.push(c),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') | None => {}
+                Some(c) => {
+                    in_word = true;
+                    current.push(c);
+                }
+            },
+            c if c.is_whitespace() => {
+                if in_word {
+                    args.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                current.push(c);
+            }
+        }
+    }
+
+    if in_word {
+        args.push(current);
+    }
+    Ok(args)
+}
+
+/// Request options shared by both tools, collected as raw strings because the
+/// keyword may only be known once every argument has been read
+#[derive(Default)]
+struct RequestArgs {
+    url: Option<String>,
+    method: Option<String>,
+    headers: Vec<String>,
+    cookie: Option<String>,
+    data: Option<String>,
+}
+
+impl RequestArgs {
+    /// Build the request template with `keyword` marked as the payload
+    /// position. Data without an explicit method is sent as POST, as both
+    /// tools do.
+    fn build(self, keyword: &str, warnings: &mut Vec<String>) -> Result<HttpRequest> {
+        let Some(url) = self.url else {
+            return Err(GenericError("Command has no target URL".to_string()));
+        };
+
+        let mut headers =
+            self.headers.iter().map(|h| parse_header(h, keyword)).collect::<Result<Vec<_>>>()?;
+        if let Some(cookie) = &self.cookie {
+            headers.push(parse_header(&format!("Cookie: {cookie}"), keyword)?);
+        }
+
+        let has_keyword = url.contains(keyword)
+            || self
+                .headers
+                .iter()
+                .chain(&self.cookie)
+                .chain(&self.data)
+                .any(|v| v.contains(keyword));
+        if !has_keyword {
+            warnings.push(format!("Keyword {keyword} not found, every request will be identical"));
+        }
+
+        let (body_type, body) = match &self.data {
+            Some(data) => text_body(&mark_keyword(data, keyword), &headers),
+            None => (None, BTreeMap::new()),
+        };
+        let method = match (self.method, &self.data) {
+            (Some(method), _) => method.to_uppercase(),
+            (None, Some(_)) => "POST".to_string(),
+            (None, None) => "GET".to_string(),
+        };
+
+        Ok(HttpRequest {
+            method,
+            url: mark_keyword(&url, keyword),
+            headers,
+            body_type,
+            body,
+            ..Default::default()
+        })
+    }
+}
+
+/// Split `--name=value` into its parts. Both tools accept this form alongside
+/// `--name value`.
+fn split_flag(arg: &str) -> (&str, Option<&str>) {
+    let name = arg.trim_start_matches('-');
+    match name.split_once('=') {
+        Some((name, value)) => (name, Some(value)),
+        None => (name, None),
+    }
+}
+
+/// Wrap every occurrence of the tool's keyword in payload markers. Markers
+/// that are already in `text` are escaped.
+fn mark_keyword(text: &str, keyword: &str) -> String {
+    escape_markers(text).replace(keyword, &format!("{MARKER}{keyword}{MARKER}"))
+}
+
+/// Parse a `Name: value` header argument
+fn parse_header(header: &str, keyword: &str) -> Result<HttpRequestHeader> {
+    let Some((name, value)) = header.split_once(':') else {
+        return Err(GenericError(format!("Invalid header {header}")));
+    };
+    Ok(HttpRequestHeader {
+        enabled: true,
+        name: mark_keyword(name.trim(), keyword),
+        value: mark_keyword(value.trim(), keyword),
+        id: None,
+    })
+}
+
+/// Parse a comma-separated list of numbers and inclusive `a-b` ranges
+fn parse_ranges(value: &str) -> Result<Vec<(u64, u64)>> {
+    let invalid = || GenericError(format!("Invalid number list {value}"));
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            let (min, max) = v.split_once('-').unwrap_or((v, v));
+            let min = min.trim().parse().map_err(|_| invalid())?;
+            let max = max.trim().parse().map_err(|_| invalid())?;
+            Ok((min, max))
+        })
+        .collect()
+}
+
+fn parse_status_codes(value: &str) -> Result<Vec<u16>> {
+    let mut codes = Vec::new();
+    for (min, max) in parse_ranges(value)? {
+        if min > max || max > u16::MAX as u64 || max - min > 1000 {
+            return Err(GenericError(format!("Invalid status codes {value}")));
+        }
+        codes.extend(min as u16..=max as u16);
+    }
+    Ok(codes)
+}
+
+fn parse_size_matchers(value: &str) -> Result<Vec<FuzzMatcher>> {
+    Ok(parse_ranges(value)?
+        .into_iter()
+        .map(|(min, max)| FuzzMatcher::Size { min: Some(min as usize), max: Some(max as usize) })
+        .collect())
+}
+
+/// Matcher that fires on every response, for tools whose default is to show
+/// everything that isn't filtered out
+fn match_everything() -> FuzzMatcher {
+    FuzzMatcher::Size { min: None, max: None }
+}
+
+/// Parse a delay in (possibly fractional) seconds. Ranges like `0.1-2.0`
+/// resolve to their lower bound.
+fn parse_delay_ms(value: &str, warnings: &mut Vec<String>) -> Result<u32> {
+    let lower = match value.split_once('-') {
+        Some((lower, _)) => {
+            warnings.push(format!("Random delay {value} was replaced with its minimum"));
+            lower
+        }
+        None => value,
+    };
+    let seconds: f64 =
+        lower.trim().parse().map_err(|_| GenericError(format!("Invalid delay {value}")))?;
+    Ok((seconds * 1000.0).round() as u32)
+}
+
+/// Body type for raw request data, based on the Content-Type header
+fn text_body(
+    data: &str,
+    headers: &[HttpRequestHeader],
+) -> (Option<String>, BTreeMap<String, Value>) {
+    let content_type = headers
+        .iter()
+        .find(|h| h.name.eq_ignore_ascii_case("content-type"))
+        .map(|h| h.value.to_lowercase())
+        .unwrap_or_default();
+    let body_type = if content_type.contains("json") {
+        "application/json"
+    } else if content_type.contains("xml") {
+        "text/xml"
+    } else {
+        "other"
+    };
+    let body = BTreeMap::from([("text".to_string(), Value::String(data.to_string()))]);
+    (Some(body_type.to_string()), body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_quoted_words() {
+        let args = split_command_line(
+            "ffuf -H 'X-Name: a b' -d \"{\\\"q\\\":1}\" \\\n  -u https://x/FUZZ",
+        )
+        .unwrap();
+        assert_eq!(
+            args,
+            vec![
+                "ffuf",
+                "-H",
+                "X-Name: a b",
+                "-d",
+                "{\"q\":1}",
+                "-u",
+                "https://x/FUZZ"
+            ]
+        );
+        assert!(split_command_line("ffuf -u 'https://x").is_err());
+    }
+
+    #[test]
+    fn parses_code_ranges() {
+        assert_eq!(parse_status_codes("200-202, 404").unwrap(), vec![200, 201, 202, 404]);
+        assert!(parse_status_codes("abc").is_err());
+    }
+
+    #[test]
+    fn rejects_other_tools() {
+        assert!(import_command("gobuster dir -u https://x").is_err());
+        assert!(import_command("/usr/bin/ffuf -u https://x/FUZZ -w words.txt").is_ok());
+    }
+}
@@ -0,0 +1,171 @@
+use super::{
+    FuzzImport, RequestArgs, match_everything, parse_delay_ms, parse_ranges, parse_size_matchers,
+    parse_status_codes, split_flag,
+};
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::types::{FuzzMatcher, FuzzRequest};
+
+const KEYWORD: &str = "FUZZ";
+const DEFAULT_THREADS: usize = 10;
+const MAX_RANGE_PAYLOADS: u64 = 1_000_000;
+
+/// Options that take no value
+const SWITCHES: &[&str] = &["c", "v", "Z", "L", "follow", "no-cache", "h", "help"];
+
+/// Parse wfuzz arguments. Only the first payload is used. Responses are
+/// matched unless a hide option rejects them, or, when show options are
+/// present, only if one of those fires.
+pub(super) fn parse(args: &[String]) -> Result<FuzzImport> {
+    let mut import = FuzzImport::default();
+    let mut request = RequestArgs::default();
+    let mut has_payload = false;
+    let mut matchers = Vec::new();
+    let mut filters = Vec::new();
+    let mut concurrency = DEFAULT_THREADS;
+    let mut delay_ms = 0;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') {
+            request.url = Some(arg.clone());
+            continue;
+        }
+
+        let (name, inline_value) = split_flag(arg);
+        if SWITCHES.contains(&name) {
+            if matches!(name, "L" | "follow") {
+                import.warnings.push(format!("Ignored option -{name}"));
+            }
+            continue;
+        }
+
+        let value = match inline_value {
+            Some(value) => value.to_string(),
+            None => args
+                .next()
+                .ok_or_else(|| GenericError(format!("Missing value for -{name}")))?
+                .clone(),
+        };
+
+        match name {
+            "u" => request.url = Some(value),
+            "X" => request.method = Some(value),
+            "H" => request.headers.push(value),
+            "b" => request.cookie = Some(value),
+            "d" => request.data = Some(value),
+            "z" | "w" if has_payload => {
+                import.warnings.push(format!("Only the first payload is used, ignored {value}"));
+            }
+            "w" => {
+                has_payload = true;
+                import.wordlist_path = Some(value);
+            }
+            "z" => {
+                has_payload = true;
+                parse_payload(&value, &mut import)?;
+            }
+            "sc" => matchers.push(FuzzMatcher::Status { codes: parse_status_codes(&value)? }),
+            "sh" => matchers.extend(parse_size_matchers(&value)?),
+            "ss" => matchers.push(FuzzMatcher::Regex { pattern: value }),
+            "hc" => filters.push(FuzzMatcher::Status { codes: parse_status_codes(&value)? }),
+            "hh" => filters.extend(parse_size_matchers(&value)?),
+            "hs" => filters.push(FuzzMatcher::Regex { pattern: value }),
+            "t" => {
+                concurrency = value
+                    .parse()
+                    .map_err(|_| GenericError(format!("Invalid thread count {value}")))?;
+            }
+            "s" => delay_ms = parse_delay_ms(&value, &mut import.warnings)?,
+            "sl" | "sw" | "hl" | "hw" | "filter" | "slice" => {
+                import.warnings.push(format!("Unsupported filter -{name} {value} was ignored"));
+            }
+            "f" | "o" => {}
+            _ => import.warnings.push(format!("Ignored option -{name} {value}")),
+        }
+    }
+
+    if !has_payload {
+        import.warnings.push("Command has no payload".to_string());
+    }
+    if matchers.is_empty() {
+        matchers.push(match_everything());
+    }
+
+    import.config = FuzzRequest {
+        request: request.build(KEYWORD, &mut import.warnings)?,
+        concurrency,
+        delay_ms,
+        matchers,
+        filters,
+        ..Default::default()
+    };
+    Ok(import)
+}
+
+/// Apply a `-z type,value[,encoder]` payload. Files become the wordlist path
+/// while ranges and lists are expanded in place.
+fn parse_payload(spec: &str, import: &mut FuzzImport) -> Result<()> {
+    let mut parts = spec.splitn(3, ',');
+    let kind = parts.next().unwrap_or_default();
+    let value = parts.next().unwrap_or_default();
+    if let Some(encoder) = parts.next() {
+        import.warnings.push(format!("Payload encoder {encoder} was ignored"));
+    }
+
+    match kind {
+        "file" => import.wordlist_path = Some(value.to_string()),
+        "range" => {
+            for (min, max) in parse_ranges(value)? {
+                if max.saturating_sub(min) > MAX_RANGE_PAYLOADS {
+                    return Err(GenericError(format!("Payload range {value} is too large")));
+                }
+                import.config.wordlist.extend((min..=max).map(|n| n.to_string()));
+            }
+        }
+        "list" => import.config.wordlist = value.split('-').map(|v| v.to_string()).collect(),
+        kind => import.warnings.push(format!("Unsupported payload type {kind}")),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::import::import_command;
+    use crate::types::FuzzMatcher;
+
+    #[test]
+    fn imports_wfuzz_command() {
+        let import = import_command(
+            "wfuzz -c -z range,1-3 --hc 404,403 --hh 0 -H 'Cookie: s=1' -t 4 \
+             https://example.com/item/FUZZ",
+        )
+        .unwrap();
+
+        let config = &import.config;
+        assert_eq!(config.wordlist, vec!["1", "2", "3"]);
+        assert_eq!(config.request.url, "https://example.com/item/§FUZZ§");
+        assert_eq!(config.matchers, vec![FuzzMatcher::Size { min: None, max: None }]);
+        assert_eq!(
+            config.filters,
+            vec![
+                FuzzMatcher::Status { codes: vec![404, 403] },
+                FuzzMatcher::Size { min: Some(0), max: Some(0) },
+            ]
+        );
+        assert_eq!(config.concurrency, 4);
+        assert!(import.warnings.is_empty());
+    }
+
+    #[test]
+    fn file_payload_and_show_codes() {
+        let import = import_command(
+            "wfuzz -z file,/tmp/words.txt --sc 200 -d 'q=FUZZ' https://example.com/search",
+        )
+        .unwrap();
+
+        assert_eq!(import.wordlist_path.as_deref(), Some("/tmp/words.txt"));
+        assert_eq!(import.config.request.method, "POST");
+        assert_eq!(import.config.matchers, vec![FuzzMatcher::Status { codes: vec![200] }]);
+    }
+}
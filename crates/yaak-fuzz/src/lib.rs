@@ -0,0 +1,59 @@
+pub mod agents;
+pub mod attacks;
+mod auth_bypass;
+mod base_urls;
+mod boolean_sqli;
+mod byte_payloads;
+pub mod callbacks;
+pub mod canaries;
+mod columns;
+pub mod compare;
+pub mod diff;
+mod dns;
+pub mod encryption;
+mod engine;
+pub mod error;
+pub mod estimate;
+pub mod export;
+pub mod findings;
+pub mod follow_up;
+pub mod graphql;
+mod host_limits;
+mod http2;
+mod idor;
+pub mod import;
+pub mod load;
+pub mod manager;
+pub mod markers;
+pub mod matchers;
+mod metrics;
+mod open_redirect;
+pub mod output;
+pub mod payloads;
+pub mod persist;
+pub mod pre_request;
+pub mod preview;
+pub mod proxies;
+pub mod rate_limit;
+mod raw;
+mod recursion;
+mod reflection;
+pub mod replay;
+mod rotation;
+mod safe_mode;
+mod sampling;
+mod scope;
+pub mod sender;
+pub mod session;
+pub mod signing;
+pub mod smuggling;
+pub mod targets;
+mod tls;
+pub mod types;
+mod vhost;
+pub mod webhook;
+
+pub use engine::{retry_fuzz, run_fuzz, validate_fuzz_request};
+pub use manager::FuzzManager;
+pub use persist::FuzzRunPersister;
+pub use sender::{FuzzResponse, FuzzSender, HttpFuzzSender};
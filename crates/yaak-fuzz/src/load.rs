@@ -0,0 +1,141 @@
+//! Load and soak testing: the template sent at a constant rate for a fixed
+//! time, summarized by latency and error rate
+
+use crate::error::Result;
+use crate::markers::payload_request;
+use crate::rate_limit::send_paced;
+use crate::safe_mode::check_safe_method;
+use crate::sender::{FuzzResponse, FuzzSender};
+use crate::types::FuzzRequest;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzLoadTest {
+    /// Requests started per second. Zero is treated as one.
+    pub rps: u32,
+    /// How long to keep starting requests, in milliseconds
+    pub duration_ms: u32,
+}
+
+/// Response times of the requests that got a response, in milliseconds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzLatency {
+    pub min: u32,
+    pub mean: u32,
+    pub p50: u32,
+    pub p90: u32,
+    pub p99: u32,
+    pub max: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzLoadReport {
+    pub sent: u32,
+    /// Requests that failed without a response, e.g. timeouts
+    pub errors: u32,
+    /// Requests that failed or got a 5xx response, from 0 to 1
+    pub error_rate: f64,
+    /// Responses by status
+    pub statuses: BTreeMap<u16, u32>,
+    /// Requests completed per second, lower than the configured rate when
+    /// the server falls behind
+    pub throughput_rps: f64,
+    pub latency: FuzzLatency,
+    /// How long the test took, in milliseconds
+    pub elapsed: u32,
+}
+
+/// Send `config`'s template, with its first payload, at `load.rps` for
+/// `load.duration_ms`
+pub async fn run_load_test<S: FuzzSender>(
+    sender: &S,
+    config: &FuzzRequest,
+    load: &FuzzLoadTest,
+) -> Result<FuzzLoadReport> {
+    let payload = config.wordlist.first().map_or("", String::as_str);
+    let request = payload_request(config, payload);
+    check_safe_method(config, &request.method)?;
+
+    let rps = load.rps.max(1);
+    let count = (rps as u64 * load.duration_ms as u64 / 1000).clamp(1, u32::MAX as u64) as u32;
+    let interval = Duration::from_secs(1) / rps;
+    let started_at = Instant::now();
+    let sample = |sent: Result<FuzzResponse>| sent.map(|r| (r.status, r.elapsed));
+    let samples = send_paced(sender, &request, count, interval, sample).await;
+    let samples = samples.into_iter().map(|(sample, _)| sample.ok()).collect::<Vec<_>>();
+    Ok(summarize(&samples, started_at.elapsed()))
+}
+
+/// Status and response time of each request, none for ones that failed
+fn summarize(samples: &[Option<(u16, Duration)>], elapsed: Duration) -> FuzzLoadReport {
+    let mut report = FuzzLoadReport {
+        sent: samples.len() as u32,
+        elapsed: elapsed.as_millis().min(u32::MAX as u128) as u32,
+        ..Default::default()
+    };
+    let mut latencies = Vec::with_capacity(samples.len());
+    for sample in samples {
+        match sample {
+            Some((status, latency)) => {
+                *report.statuses.entry(*status).or_default() += 1;
+                latencies.push(latency.as_millis().min(u32::MAX as u128) as u32);
+            }
+            None => report.errors += 1,
+        }
+    }
+
+    let server_errors = report.statuses.range(500..600).map(|(_, n)| n).sum::<u32>();
+    if report.sent > 0 {
+        report.error_rate = (report.errors + server_errors) as f64 / report.sent as f64;
+    }
+    if !elapsed.is_zero() {
+        report.throughput_rps = latencies.len() as f64 / elapsed.as_secs_f64();
+    }
+    latencies.sort_unstable();
+    if let (Some(min), Some(max)) = (latencies.first(), latencies.last()) {
+        let percentile = |p: usize| latencies[(latencies.len() * p).div_ceil(100).max(1) - 1];
+        let total = latencies.iter().map(|l| *l as u64).sum::<u64>();
+        report.latency = FuzzLatency {
+            min: *min,
+            mean: (total / latencies.len() as u64) as u32,
+            p50: percentile(50),
+            p90: percentile(90),
+            p99: percentile(99),
+            max: *max,
+        };
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_latency_and_errors() {
+        let mut samples =
+            (1..=100).map(|ms| Some((200, Duration::from_millis(ms)))).collect::<Vec<_>>();
+        samples[0] = Some((503, Duration::from_millis(1)));
+        samples.push(None);
+        let report = summarize(&samples, Duration::from_secs(2));
+
+        assert_eq!(report.sent, 101);
+        assert_eq!(report.errors, 1);
+        assert_eq!(report.statuses, BTreeMap::from([(200, 99), (503, 1)]));
+        assert!((report.error_rate - 2.0 / 101.0).abs() < 1e-9);
+        assert!((report.throughput_rps - 50.0).abs() < 1e-9);
+        assert_eq!(
+            report.latency,
+            FuzzLatency { min: 1, mean: 50, p50: 50, p90: 90, p99: 99, max: 100 }
+        );
+    }
+}
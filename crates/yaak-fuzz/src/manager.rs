@@ -0,0 +1,58 @@
+use crate::agents::{AgentCoordinator, FuzzAgentServer};
+use crate::error::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{Mutex, watch};
+
+/// Tracks in-progress fuzz runs so they can be cancelled by ID, and the
+/// coordinator worker agents connect to
+#[derive(Clone, Default)]
+pub struct FuzzManager {
+    runs: Arc<Mutex<HashMap<String, watch::Sender<bool>>>>,
+    agents: Arc<Mutex<Option<Arc<AgentCoordinator>>>>,
+}
+
+impl FuzzManager {
+    pub fn new() -> Self {
+        FuzzManager { runs: Default::default(), agents: Default::default() }
+    }
+
+    /// Register a run and return the receiver it should watch for cancellation
+    pub async fn start(&self, run_id: &str) -> watch::Receiver<bool> {
+        let (cancel_tx, cancel_rx) = watch::channel(false);
+        self.runs.lock().await.insert(run_id.to_string(), cancel_tx);
+        cancel_rx
+    }
+
+    /// Forget a run once it has finished
+    pub async fn finish(&self, run_id: &str) {
+        self.runs.lock().await.remove(run_id);
+    }
+
+    /// Signal a run to stop. Returns false if no such run is in progress.
+    pub async fn cancel(&self, run_id: &str) -> bool {
+        match self.runs.lock().await.remove(run_id) {
+            Some(cancel_tx) => cancel_tx.send(true).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Listen for agents on `address`, replacing the coordinator and
+    /// disconnecting the agents of an earlier one
+    pub async fn start_agents(&self, address: &str) -> Result<FuzzAgentServer> {
+        let coordinator = AgentCoordinator::bind(address).await?;
+        let server = coordinator.server();
+        *self.agents.lock().await = Some(Arc::new(coordinator));
+        Ok(server)
+    }
+
+    /// Stop listening for agents. Runs already on them carry on until
+    /// they finish or are cancelled.
+    pub async fn stop_agents(&self) {
+        self.agents.lock().await.take();
+    }
+
+    pub async fn agent_coordinator(&self) -> Option<Arc<AgentCoordinator>> {
+        self.agents.lock().await.clone()
+    }
+}
@@ -0,0 +1,273 @@
+use crate::byte_payloads::BYTES_PLACEHOLDER;
+use crate::targets::apply_targets;
+use crate::types::{FuzzPayloadEncoding, FuzzRequest};
+use crate::vhost::set_host_header;
+use std::borrow::Cow;
+use yaak_models::models::HttpRequest;
+use yaak_templates::escape::escape_template;
+
+/// Delimiter wrapping each payload position in a request template
+pub const MARKER: char = '§';
+
+/// Written before a marker to send it literally instead of starting or
+/// ending a payload position
+pub const MARKER_ESCAPE: char = '\\';
+
+/// Replace every `§…§` span in `text` with `payload`. A trailing unpaired
+/// marker is left untouched and `\§` is replaced with a literal `§`.
+pub fn replace_markers(text: &str, payload: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    // Where the open marker was written to `result`, if a span is open
+    let mut open = None;
+
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            MARKER_ESCAPE if chars.peek() == Some(&MARKER) => {
+                chars.next();
+                result.push(MARKER);
+            }
+            MARKER => match open.take() {
+                Some(start) => {
+                    result.truncate(start);
+                    result.push_str(payload);
+                }
+                None => {
+                    open = Some(result.len());
+                    result.push(MARKER);
+                }
+            },
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// Escape every marker in `text` so it's sent as it is
+pub fn escape_markers(text: &str) -> String {
+    text.replace(MARKER, &format!("{MARKER_ESCAPE}{MARKER}"))
+}
+
+/// Replace every occurrence of `keyword` in `text` with `payload`. A
+/// keyword preceded by `\` is left in place without the backslash.
+pub fn replace_keyword(text: &str, keyword: &str, payload: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(keyword) {
+        match rest[..start].strip_suffix(MARKER_ESCAPE) {
+            Some(before) => {
+                result.push_str(before);
+                result.push_str(keyword);
+            }
+            None => {
+                result.push_str(&rest[..start]);
+                result.push_str(payload);
+            }
+        }
+        rest = &rest[start + keyword.len()..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Keys of form body fields that can hold payload markers
+const FORM_FIELD_KEYS: &[&str] = &["name", "value", "filename", "contentType"];
+
+/// Whether `text` has a payload position, marked by `keyword` or by a `§…§`
+/// span without one
+pub fn has_markers(text: &str, keyword: Option<&str>) -> bool {
+    let replace = |payload| match keyword {
+        Some(keyword) if !keyword.is_empty() => replace_keyword(text, keyword, payload),
+        _ => replace_markers(text, payload),
+    };
+    replace("0") != replace("1")
+}
+
+/// Build the request for a single payload by replacing markers in the URL,
+/// URL parameters, headers, text body, and form fields. With a `keyword`, its occurrences
+/// mark the positions instead of `§…§` spans.
+pub fn inject_payload(request: &HttpRequest, keyword: Option<&str>, payload: &str) -> HttpRequest {
+    let replace = |text: &str| match keyword {
+        Some(keyword) if !keyword.is_empty() => replace_keyword(text, keyword, payload),
+        _ => replace_markers(text, payload),
+    };
+    let mut request = request.clone();
+
+    request.url = replace(&request.url);
+
+    for parameter in request.url_parameters.iter_mut() {
+        parameter.name = replace(&parameter.name);
+        parameter.value = replace(&parameter.value);
+    }
+
+    for header in request.headers.iter_mut() {
+        header.name = replace(&header.name);
+        header.value = replace(&header.value);
+    }
+
+    if let Some(serde_json::Value::String(text)) = request.body.get("text") {
+        let text = replace(text);
+        request.body.insert("text".to_string(), serde_json::Value::String(text));
+    }
+
+    // Form fields are encoded when the body is built, after rendering
+    if let Some(serde_json::Value::Array(fields)) = request.body.get_mut("form") {
+        for field in fields.iter_mut().filter_map(|f| f.as_object_mut()) {
+            for key in FORM_FIELD_KEYS {
+                if let Some(serde_json::Value::String(value)) = field.get_mut(*key) {
+                    *value = replace(value);
+                }
+            }
+        }
+    }
+
+    request
+}
+
+/// The request `config` sends for `payload`, at its markers and targets.
+/// Template tags in the payload are escaped unless the run renders payloads
+/// and the payload doesn't start with its literal prefix. Runs of byte
+/// payloads get a placeholder the sender replaces instead.
+pub fn payload_request(config: &FuzzRequest, payload: &str) -> HttpRequest {
+    let literal_prefix = config.literal_prefix.as_deref().filter(|p| !p.is_empty());
+    let (payload, render) = match config.payload_encoding {
+        FuzzPayloadEncoding::Text => match literal_prefix.and_then(|p| payload.strip_prefix(p)) {
+            Some(literal) => (literal, false),
+            None => (payload, config.render_payloads),
+        },
+        FuzzPayloadEncoding::Base64 => (BYTES_PLACEHOLDER, config.render_payloads),
+    };
+    let template_payload =
+        if render { Cow::Borrowed(payload) } else { Cow::Owned(escape_template(payload)) };
+    let mut request = inject_payload(&config.request, config.marker.as_deref(), &template_payload);
+    apply_targets(&mut request, &config.targets, payload, !render);
+    if config.vhost.is_some() {
+        set_host_header(&mut request, &template_payload);
+    }
+    request
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yaak_models::models::HttpRequestHeader;
+
+    #[test]
+    fn replaces_each_marker_pair() {
+        assert_eq!(replace_markers("/users/§id§", "42"), "/users/42");
+        assert_eq!(replace_markers("§a§-§b§", "x"), "x-x");
+        assert_eq!(replace_markers("no markers", "x"), "no markers");
+    }
+
+    #[test]
+    fn leaves_unpaired_marker() {
+        assert_eq!(replace_markers("§a§ and §tail", "x"), "x and §tail");
+    }
+
+    #[test]
+    fn unescapes_literal_markers() {
+        assert_eq!(replace_markers("\\§ 12 and §a§", "x"), "§ 12 and x");
+        assert_eq!(replace_markers("§a\\§b§", "x"), "x");
+        assert_eq!(replace_markers(&escape_markers("§ 1–3 §"), "x"), "§ 1–3 §");
+    }
+
+    #[test]
+    fn injects_into_url_headers_and_body() {
+        let request = HttpRequest {
+            url: "https://example.com/§path§".to_string(),
+            headers: vec![HttpRequestHeader {
+                enabled: true,
+                name: "X-Test".to_string(),
+                value: "§value§".to_string(),
+                id: None,
+            }],
+            body: [("text".to_string(), serde_json::json!("{\"q\":\"§q§\"}"))].into(),
+            ..Default::default()
+        };
+
+        let injected = inject_payload(&request, None, "admin");
+        assert_eq!(injected.url, "https://example.com/admin");
+        assert_eq!(injected.headers[0].value, "admin");
+        assert_eq!(injected.body["text"], serde_json::json!("{\"q\":\"admin\"}"));
+    }
+
+    #[test]
+    fn injects_into_form_fields() {
+        let request = HttpRequest {
+            body_type: Some("application/x-www-form-urlencoded".to_string()),
+            body: [(
+                "form".to_string(),
+                serde_json::json!([
+                    { "enabled": true, "name": "user", "value": "§user§" },
+                    { "enabled": true, "name": "§field§", "value": "x" },
+                ]),
+            )]
+            .into(),
+            ..Default::default()
+        };
+
+        let injected = inject_payload(&request, None, "a&b");
+        assert_eq!(
+            injected.body["form"],
+            serde_json::json!([
+                { "enabled": true, "name": "user", "value": "a&b" },
+                { "enabled": true, "name": "a&b", "value": "x" },
+            ])
+        );
+    }
+
+    #[test]
+    fn replaces_custom_keyword() {
+        let request = HttpRequest {
+            url: "https://example.com/FUZZ?q=\\FUZZ&r=FUZZ".to_string(),
+            ..Default::default()
+        };
+        let injected = inject_payload(&request, Some("FUZZ"), "admin");
+        assert_eq!(injected.url, "https://example.com/admin?q=FUZZ&r=admin");
+    }
+
+    #[test]
+    fn escapes_template_tags_in_payloads() {
+        let mut config = FuzzRequest {
+            request: HttpRequest {
+                url: "https://example.com/§q§".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let payload = "${[ response.body.path() ]}";
+        assert_eq!(
+            payload_request(&config, payload).url,
+            "https://example.com/\\${[ response.body.path() ]}"
+        );
+
+        config.render_payloads = true;
+        assert_eq!(
+            payload_request(&config, payload).url,
+            "https://example.com/${[ response.body.path() ]}"
+        );
+    }
+
+    #[test]
+    fn sends_prefixed_payloads_literally() {
+        let config = FuzzRequest {
+            request: HttpRequest {
+                url: "https://example.com/§q§".to_string(),
+                ..Default::default()
+            },
+            render_payloads: true,
+            literal_prefix: Some("raw:".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            payload_request(&config, "raw:${[ base_url ]}").url,
+            "https://example.com/\\${[ base_url ]}"
+        );
+        assert_eq!(
+            payload_request(&config, "${[ base_url ]}").url,
+            "https://example.com/${[ base_url ]}"
+        );
+    }
+}
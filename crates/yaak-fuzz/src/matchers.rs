@@ -0,0 +1,487 @@
+use crate::canaries::{FuzzVulnClass, Verdicts};
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::open_redirect::redirects_to_payload;
+use crate::reflection::{Reflection, reflections};
+use crate::sender::FuzzResponse;
+use crate::types::{FuzzExtractor, FuzzMatchMode, FuzzMatcher};
+use regex::Regex;
+use serde_json::Value;
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+enum CompiledMatcher {
+    Status(Vec<u16>),
+    Size {
+        min: Option<usize>,
+        max: Option<usize>,
+    },
+    Contains(String),
+    Regex(Regex),
+    Header {
+        name: String,
+        pattern: Option<Regex>,
+    },
+    DiffersFromBaseline {
+        tolerance: usize,
+    },
+    Delayed(Duration),
+}
+
+/// The parts of the baseline response that later responses are compared with
+struct Baseline {
+    status: u16,
+    size: usize,
+}
+
+impl CompiledMatcher {
+    fn compile(matcher: &FuzzMatcher) -> Result<Self> {
+        Ok(match matcher {
+            FuzzMatcher::Status { codes } => CompiledMatcher::Status(codes.clone()),
+            FuzzMatcher::Size { min, max } => CompiledMatcher::Size { min: *min, max: *max },
+            FuzzMatcher::Contains { text } => CompiledMatcher::Contains(text.clone()),
+            FuzzMatcher::Regex { pattern } => CompiledMatcher::Regex(Regex::new(pattern)?),
+            FuzzMatcher::Header { name, pattern } => CompiledMatcher::Header {
+                name: name.clone(),
+                pattern: pattern.as_deref().map(Regex::new).transpose()?,
+            },
+            FuzzMatcher::DiffersFromBaseline { tolerance } => {
+                CompiledMatcher::DiffersFromBaseline { tolerance: *tolerance }
+            }
+            FuzzMatcher::Delayed { min_ms } => {
+                CompiledMatcher::Delayed(Duration::from_millis(*min_ms as u64))
+            }
+        })
+    }
+
+    fn check(&self, response: &FuzzResponse, body: &str, baseline: Option<&Baseline>) -> bool {
+        match self {
+            CompiledMatcher::Status(codes) => codes.contains(&response.status),
+            CompiledMatcher::Size { min, max } => {
+                let size = response.body.len();
+                min.is_none_or(|min| size >= min) && max.is_none_or(|max| size <= max)
+            }
+            CompiledMatcher::Contains(text) => body.contains(text.as_str()),
+            CompiledMatcher::Regex(regex) => regex.is_match(body),
+            CompiledMatcher::Header { name, pattern } => match response.header(name) {
+                None => false,
+                Some(value) => pattern.as_ref().is_none_or(|p| p.is_match(value)),
+            },
+            CompiledMatcher::DiffersFromBaseline { tolerance } => baseline.is_some_and(|b| {
+                b.status != response.status || b.size.abs_diff(response.body.len()) > *tolerance
+            }),
+            CompiledMatcher::Delayed(min) => response.elapsed >= *min,
+        }
+    }
+}
+
+/// Matchers compiled once per run and evaluated against every response
+pub struct ResponseMatcher {
+    matchers: Vec<CompiledMatcher>,
+    mode: FuzzMatchMode,
+    filters: Vec<CompiledMatcher>,
+    baseline: Option<Baseline>,
+    filters_baseline: bool,
+    verdicts: Verdicts,
+    open_redirects: bool,
+    reflections: bool,
+}
+
+impl ResponseMatcher {
+    pub fn new(matchers: &[FuzzMatcher], mode: FuzzMatchMode) -> Result<Self> {
+        let matchers = matchers.iter().map(CompiledMatcher::compile).collect::<Result<_>>()?;
+        Ok(Self {
+            matchers,
+            mode,
+            filters: Vec::new(),
+            baseline: None,
+            filters_baseline: false,
+            verdicts: Verdicts::default(),
+            open_redirects: false,
+            reflections: false,
+        })
+    }
+
+    /// Reject responses that any of `filters` fires on, regardless of the matchers
+    pub fn with_filters(mut self, filters: &[FuzzMatcher]) -> Result<Self> {
+        self.filters = filters.iter().map(CompiledMatcher::compile).collect::<Result<_>>()?;
+        Ok(self)
+    }
+
+    /// Flag responses showing the signature of any of `classes`, which
+    /// match regardless of the matchers and filters
+    pub fn with_verdicts(mut self, classes: &[FuzzVulnClass]) -> Result<Self> {
+        self.verdicts = Verdicts::new(classes)?;
+        Ok(self)
+    }
+
+    /// Flag responses that redirect to the host in their payload, when
+    /// `enabled`
+    pub fn detecting_open_redirects(mut self, enabled: bool) -> Self {
+        self.open_redirects = enabled;
+        self
+    }
+
+    /// Find the HTML contexts payloads reflect in, when `enabled`
+    pub fn analyzing_reflections(mut self, enabled: bool) -> Self {
+        self.reflections = enabled;
+        self
+    }
+
+    /// Also reject responses with the baseline's status and size
+    pub fn filtering_baseline(mut self) -> Self {
+        self.filters_baseline = true;
+        self
+    }
+
+    /// Whether any matcher or filter compares responses with a baseline
+    pub fn needs_baseline(&self) -> bool {
+        let compares =
+            |m: &CompiledMatcher| matches!(m, CompiledMatcher::DiffersFromBaseline { .. });
+        self.filters_baseline || self.matchers.iter().chain(&self.filters).any(compares)
+    }
+
+    /// Compare later responses with `response`
+    pub fn set_baseline(&mut self, response: &FuzzResponse) {
+        self.baseline = Some(Baseline { status: response.status, size: response.body.len() });
+    }
+
+    /// Whether a request that timed out matches, which a delay matcher
+    /// decides on its own when any matcher is enough
+    pub fn matches_timeout(&self) -> bool {
+        let delayed = |m: &CompiledMatcher| matches!(m, CompiledMatcher::Delayed(_));
+        let decides = self.mode == FuzzMatchMode::Any || self.matchers.len() == 1;
+        decides && self.matchers.iter().any(delayed) && !self.filters.iter().any(delayed)
+    }
+
+    /// Vulnerability classes whose signature the response shows
+    pub fn verdicts(&self, response: &FuzzResponse) -> Vec<FuzzVulnClass> {
+        self.verdicts.classify(response)
+    }
+
+    /// Whether the response is an open redirect to the host in `payload`
+    pub fn is_open_redirect(&self, response: &FuzzResponse, payload: &str) -> bool {
+        self.open_redirects && redirects_to_payload(response, payload)
+    }
+
+    /// Where `payload` reflects in the response, and whether it breaks out
+    pub(crate) fn reflections(&self, response: &FuzzResponse, payload: &str) -> Vec<Reflection> {
+        if !self.reflections {
+            return Vec::new();
+        }
+        reflections(response, payload)
+    }
+
+    /// Whether the response matches. Always false when no matchers are configured.
+    pub fn is_match(&self, response: &FuzzResponse) -> bool {
+        if self.matchers.is_empty() {
+            return false;
+        }
+
+        let body = response.body_text();
+        let check =
+            |matcher: &CompiledMatcher| matcher.check(response, &body, self.baseline.as_ref());
+
+        let matched = match self.mode {
+            FuzzMatchMode::Any => self.matchers.iter().any(check),
+            FuzzMatchMode::All => self.matchers.iter().all(check),
+        };
+        let is_baseline = self.filters_baseline
+            && self
+                .baseline
+                .as_ref()
+                .is_some_and(|b| b.status == response.status && b.size == response.body.len());
+        matched && !self.filters.iter().any(check) && !is_baseline
+    }
+}
+
+enum CompiledExtractor {
+    Regex {
+        name: String,
+        regex: Regex,
+    },
+    Header {
+        name: String,
+        header: String,
+    },
+    JsonPath {
+        name: String,
+        path: Vec<PathSegment>,
+    },
+}
+
+#[derive(Clone)]
+pub(crate) enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parse the subset of JSONPath that addresses a single value: `$` followed
+/// by `.key`, `['key']`, or `[index]` segments
+pub(crate) fn parse_json_path(path: &str) -> Result<Vec<PathSegment>> {
+    let invalid = || GenericError(format!("Invalid JSONPath {path}"));
+    let mut rest = path.trim().strip_prefix('$').ok_or_else(invalid)?;
+    let mut segments = Vec::new();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            let end = after.find(['.', '[']).unwrap_or(after.len());
+            if end == 0 {
+                return Err(invalid());
+            }
+            segments.push(PathSegment::Key(after[..end].to_string()));
+            rest = &after[end..];
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(invalid)?;
+            let inner = after[..end].trim();
+            let quoted = inner
+                .strip_prefix('\'')
+                .and_then(|k| k.strip_suffix('\''))
+                .or_else(|| inner.strip_prefix('"').and_then(|k| k.strip_suffix('"')));
+            segments.push(match quoted {
+                Some(key) => PathSegment::Key(key.to_string()),
+                None => PathSegment::Index(inner.parse().map_err(|_| invalid())?),
+            });
+            rest = &after[end + 1..];
+        } else {
+            return Err(invalid());
+        }
+    }
+    Ok(segments)
+}
+
+pub(crate) fn select<'a>(value: &'a Value, path: &[PathSegment]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |value, segment| match segment {
+        PathSegment::Key(key) => value.get(key),
+        PathSegment::Index(index) => value.get(index),
+    })
+}
+
+pub(crate) fn select_mut<'a>(value: &'a mut Value, path: &[PathSegment]) -> Option<&'a mut Value> {
+    path.iter().try_fold(value, |value, segment| match segment {
+        PathSegment::Key(key) => value.get_mut(key),
+        PathSegment::Index(index) => value.get_mut(index),
+    })
+}
+
+/// Extractors compiled once per run
+pub struct ValueExtractors {
+    extractors: Vec<CompiledExtractor>,
+}
+
+impl ValueExtractors {
+    pub fn new(extractors: &[FuzzExtractor]) -> Result<Self> {
+        let mut compiled = Vec::with_capacity(extractors.len());
+        for extractor in extractors {
+            compiled.push(match extractor {
+                FuzzExtractor::Regex { name, pattern } => {
+                    CompiledExtractor::Regex { name: name.clone(), regex: Regex::new(pattern)? }
+                }
+                FuzzExtractor::Header { name, header } => {
+                    CompiledExtractor::Header { name: name.clone(), header: header.clone() }
+                }
+                FuzzExtractor::JsonPath { name, path } => {
+                    CompiledExtractor::JsonPath { name: name.clone(), path: parse_json_path(path)? }
+                }
+            });
+        }
+        Ok(Self { extractors: compiled })
+    }
+
+    /// Extract every value found in the response. Extractors that find nothing are omitted.
+    pub fn extract(&self, response: &FuzzResponse) -> BTreeMap<String, String> {
+        let mut values = BTreeMap::new();
+        if self.extractors.is_empty() {
+            return values;
+        }
+
+        let body = response.body_text();
+        let json = OnceCell::new();
+        for extractor in &self.extractors {
+            match extractor {
+                CompiledExtractor::Regex { name, regex } => {
+                    let value = regex.captures(&body).and_then(|c| c.get(1).or_else(|| c.get(0)));
+                    if let Some(value) = value {
+                        values.insert(name.clone(), value.as_str().to_string());
+                    }
+                }
+                CompiledExtractor::Header { name, header } => {
+                    if let Some(value) = response.header(header) {
+                        values.insert(name.clone(), value.to_string());
+                    }
+                }
+                CompiledExtractor::JsonPath { name, path } => {
+                    let json = json.get_or_init(|| serde_json::from_str::<Value>(&body).ok());
+                    match json.as_ref().and_then(|json| select(json, path)) {
+                        Some(Value::String(value)) => {
+                            values.insert(name.clone(), value.clone());
+                        }
+                        Some(value) => {
+                            values.insert(name.clone(), value.to_string());
+                        }
+                        None => {}
+                    }
+                }
+            }
+        }
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(status: u16, body: &str) -> FuzzResponse {
+        FuzzResponse {
+            status,
+            headers: vec![("Set-Cookie".to_string(), "session=abc123; Path=/".to_string())],
+            body: body.as_bytes().to_vec(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn empty_matchers_never_match() {
+        let matcher = ResponseMatcher::new(&[], FuzzMatchMode::Any).unwrap();
+        assert!(!matcher.is_match(&response(200, "ok")));
+    }
+
+    #[test]
+    fn match_modes() {
+        let matchers = vec![
+            FuzzMatcher::Status { codes: vec![200] },
+            FuzzMatcher::Contains { text: "Welcome".to_string() },
+        ];
+        let any = ResponseMatcher::new(&matchers, FuzzMatchMode::Any).unwrap();
+        let all = ResponseMatcher::new(&matchers, FuzzMatchMode::All).unwrap();
+
+        assert!(any.is_match(&response(200, "Invalid password")));
+        assert!(!all.is_match(&response(200, "Invalid password")));
+        assert!(all.is_match(&response(200, "Welcome back")));
+        assert!(!any.is_match(&response(401, "Invalid password")));
+    }
+
+    #[test]
+    fn size_and_header_matchers() {
+        let size = ResponseMatcher::new(
+            &[FuzzMatcher::Size { min: Some(3), max: Some(5) }],
+            FuzzMatchMode::Any,
+        )
+        .unwrap();
+        assert!(size.is_match(&response(200, "abcd")));
+        assert!(!size.is_match(&response(200, "abcdef")));
+
+        let header = ResponseMatcher::new(
+            &[FuzzMatcher::Header {
+                name: "set-cookie".to_string(),
+                pattern: Some("^session=".to_string()),
+            }],
+            FuzzMatchMode::Any,
+        )
+        .unwrap();
+        assert!(header.is_match(&response(200, "")));
+    }
+
+    #[test]
+    fn filters_reject_matches() {
+        let matcher =
+            ResponseMatcher::new(&[FuzzMatcher::Status { codes: vec![200] }], FuzzMatchMode::Any)
+                .unwrap()
+                .with_filters(&[FuzzMatcher::Size { min: Some(4), max: Some(4) }])
+                .unwrap();
+        assert!(matcher.is_match(&response(200, "abc")));
+        assert!(!matcher.is_match(&response(200, "abcd")));
+    }
+
+    #[test]
+    fn delay_matcher() {
+        let matcher = ResponseMatcher::new(
+            &[
+                FuzzMatcher::Delayed { min_ms: 100 },
+                FuzzMatcher::Status { codes: vec![200] },
+            ],
+            FuzzMatchMode::All,
+        )
+        .unwrap();
+        let slow = FuzzResponse { elapsed: Duration::from_millis(150), ..response(200, "") };
+        assert!(matcher.is_match(&slow));
+        assert!(!matcher.is_match(&response(200, "")));
+        assert!(!matcher.matches_timeout());
+    }
+
+    #[test]
+    fn compares_with_baseline() {
+        let matchers = [FuzzMatcher::DiffersFromBaseline { tolerance: 2 }];
+        let mut matcher = ResponseMatcher::new(&matchers, FuzzMatchMode::Any).unwrap();
+        assert!(matcher.needs_baseline());
+        assert!(!matcher.is_match(&response(500, "error")));
+
+        matcher.set_baseline(&response(200, "Not found"));
+        assert!(!matcher.is_match(&response(200, "Not found!")));
+        assert!(matcher.is_match(&response(200, "Not found, try id")));
+        assert!(matcher.is_match(&response(500, "Not found")));
+    }
+
+    #[test]
+    fn filters_baseline_response() {
+        let matchers = [FuzzMatcher::Status { codes: vec![200] }];
+        let mut matcher =
+            ResponseMatcher::new(&matchers, FuzzMatchMode::Any).unwrap().filtering_baseline();
+        assert!(matcher.needs_baseline());
+        assert!(matcher.is_match(&response(200, "Welcome")));
+
+        matcher.set_baseline(&response(200, "Welcome"));
+        assert!(!matcher.is_match(&response(200, "Welcome")));
+        assert!(matcher.is_match(&response(200, "Intranet")));
+    }
+
+    #[test]
+    fn invalid_regex_is_an_error() {
+        let result = ResponseMatcher::new(
+            &[FuzzMatcher::Regex { pattern: "(".to_string() }],
+            FuzzMatchMode::Any,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn extracts_regex_group_and_header() {
+        let extractors = ValueExtractors::new(&[
+            FuzzExtractor::Regex {
+                name: "token".to_string(),
+                pattern: "\"token\":\"([^\"]+)\"".to_string(),
+            },
+            FuzzExtractor::Header { name: "cookie".to_string(), header: "set-cookie".to_string() },
+            FuzzExtractor::Regex { name: "missing".to_string(), pattern: "nope".to_string() },
+        ])
+        .unwrap();
+
+        let values = extractors.extract(&response(200, "{\"token\":\"t-1\"}"));
+        assert_eq!(values.get("token").map(String::as_str), Some("t-1"));
+        assert_eq!(values.get("cookie").map(String::as_str), Some("session=abc123; Path=/"));
+        assert!(!values.contains_key("missing"));
+    }
+
+    #[test]
+    fn extracts_json_path() {
+        let extractors = ValueExtractors::new(&[
+            FuzzExtractor::JsonPath { name: "csrf".to_string(), path: "$.data.csrf".to_string() },
+            FuzzExtractor::JsonPath {
+                name: "id".to_string(),
+                path: "$.items[1]['user id']".to_string(),
+            },
+            FuzzExtractor::JsonPath { name: "missing".to_string(), path: "$.nope".to_string() },
+        ])
+        .unwrap();
+
+        let body = r#"{"data":{"csrf":"c-1"},"items":[{},{"user id":7}]}"#;
+        let values = extractors.extract(&response(200, body));
+        assert_eq!(values.get("csrf").map(String::as_str), Some("c-1"));
+        assert_eq!(values.get("id").map(String::as_str), Some("7"));
+        assert!(!values.contains_key("missing"));
+
+        let invalid = FuzzExtractor::JsonPath { name: "x".to_string(), path: "data".to_string() };
+        assert!(ValueExtractors::new(&[invalid]).is_err());
+    }
+}
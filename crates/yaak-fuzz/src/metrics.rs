@@ -0,0 +1,102 @@
+//! Live metrics of a running run, sent about once a second so the UI can
+//! plot throughput and errors while the attack is underway
+
+use crate::types::FuzzMetrics;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use yaak_models::models::FuzzResult;
+
+/// How often a run sends its metrics
+pub(crate) const METRICS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Completed requests older than this don't count towards the rates
+const WINDOW: Duration = Duration::from_secs(10);
+
+/// Tracks the requests of a run that are in flight and the ones that
+/// completed recently
+pub(crate) struct RunMetrics {
+    concurrency: usize,
+    started_at: Instant,
+    in_flight: AtomicUsize,
+    /// When each recent request completed and whether it failed
+    completed: Mutex<VecDeque<(Instant, bool)>>,
+}
+
+/// Counts a request as in flight until it's dropped
+pub(crate) struct InFlight<'a>(&'a AtomicUsize);
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl RunMetrics {
+    pub(crate) fn new(concurrency: usize) -> Self {
+        Self {
+            concurrency: concurrency.max(1),
+            started_at: Instant::now(),
+            in_flight: AtomicUsize::new(0),
+            completed: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub(crate) fn start(&self) -> InFlight<'_> {
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        InFlight(&self.in_flight)
+    }
+
+    pub(crate) fn record(&self, result: &FuzzResult) {
+        let now = Instant::now();
+        let mut completed = self.completed.lock().unwrap();
+        completed.push_back((now, result.error.is_some()));
+        forget_old(&mut completed, now);
+    }
+
+    pub(crate) fn snapshot(&self) -> FuzzMetrics {
+        let now = Instant::now();
+        let mut completed = self.completed.lock().unwrap();
+        forget_old(&mut completed, now);
+        let window = now.duration_since(self.started_at).min(WINDOW).as_secs_f64();
+        let errors = completed.iter().filter(|(_, failed)| *failed).count();
+        let in_flight = self.in_flight.load(Ordering::Relaxed);
+        FuzzMetrics {
+            requests_per_second: if window > 0.0 { completed.len() as f64 / window } else { 0.0 },
+            in_flight,
+            utilization: in_flight as f64 / self.concurrency as f64,
+            error_rate: match completed.len() {
+                0 => 0.0,
+                total => errors as f64 / total as f64,
+            },
+        }
+    }
+}
+
+fn forget_old(completed: &mut VecDeque<(Instant, bool)>, now: Instant) {
+    while completed.front().is_some_and(|(at, _)| now.duration_since(*at) > WINDOW) {
+        completed.pop_front();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_requests_in_flight_and_failures() {
+        let metrics = RunMetrics::new(4);
+        let first = metrics.start();
+        let _second = metrics.start();
+        drop(first);
+        for error in [None, None, None, Some("timed out".to_string())] {
+            metrics.record(&FuzzResult { error, ..Default::default() });
+        }
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.in_flight, 1);
+        assert_eq!(snapshot.utilization, 0.25);
+        assert_eq!(snapshot.error_rate, 0.25);
+    }
+}
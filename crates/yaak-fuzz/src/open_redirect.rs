@@ -0,0 +1,68 @@
+//! Open redirects: a payload in a redirect parameter that the `Location`
+//! header sends the browser on to
+
+use crate::sender::FuzzResponse;
+use url::Url;
+
+/// Tag given to results that redirect to the payload's host
+pub(crate) const OPEN_REDIRECT_TAG: &str = "open_redirect";
+
+/// Stands in for the target when resolving a payload like a browser would,
+/// so payloads that stay on it can be told apart
+const PLACEHOLDER_ORIGIN: &str = "https://placeholder.invalid/";
+
+/// Whether `response` redirects to the host `payload` names. The
+/// `Location` header is resolved against the response's URL the way a
+/// browser would, so tricks like `//evil.test`, `/\evil.test` or
+/// `https://target@evil.test` count.
+pub(crate) fn redirects_to_payload(response: &FuzzResponse, payload: &str) -> bool {
+    if !(300..400).contains(&response.status) {
+        return false;
+    }
+    let (Some(location), Some(payload_host)) = (response.header("location"), payload_host(payload))
+    else {
+        return false;
+    };
+    let target = Url::parse(&response.url).and_then(|base| base.join(location.trim()));
+    target.ok().and_then(|url| url.host_str().map(str::to_string)).is_some_and(|host| {
+        host.trim_end_matches('.').eq_ignore_ascii_case(payload_host.trim_end_matches('.'))
+    })
+}
+
+/// The host a browser would go to for `payload`, or the payload itself when
+/// it's a bare hostname like `evil.test`
+fn payload_host(payload: &str) -> Option<String> {
+    let payload = payload.trim();
+    let resolved = Url::parse(PLACEHOLDER_ORIGIN).and_then(|base| base.join(payload)).ok()?;
+    match resolved.host_str() {
+        Some("placeholder.invalid") => {
+            let bare = payload.contains('.') && !payload.contains(['/', '\\', '?', '#', ' ']);
+            bare.then(|| payload.to_ascii_lowercase())
+        }
+        Some(host) => Some(host.to_string()),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_redirects_to_the_payload_host() {
+        let response = |status: u16, location: &str| FuzzResponse {
+            url: "https://target.test/login?next=x".to_string(),
+            status,
+            headers: vec![("Location".to_string(), location.to_string())],
+            ..Default::default()
+        };
+        assert!(redirects_to_payload(&response(302, "https://evil.test/"), "https://evil.test"));
+        assert!(redirects_to_payload(&response(301, "//evil.test"), "//evil.test"));
+        assert!(redirects_to_payload(&response(302, "/\\evil.test"), "/\\evil.test"));
+        assert!(redirects_to_payload(&response(302, "https://target.test@evil.test"), "evil.test"));
+
+        assert!(!redirects_to_payload(&response(302, "/evil.test"), "/evil.test"));
+        assert!(!redirects_to_payload(&response(200, "https://evil.test/"), "https://evil.test"));
+        assert!(!redirects_to_payload(&response(302, "https://target.test/"), "evil.test"));
+    }
+}
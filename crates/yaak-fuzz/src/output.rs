@@ -0,0 +1,68 @@
+use crate::error::Result;
+use crate::types::FuzzEvent;
+use std::fs::File;
+use std::io::{Write, stdout};
+use std::path::Path;
+
+/// Writes each result to a file as one JSON object per line as the run
+/// progresses. Every line is written in full as soon as its result arrives,
+/// so the file stays usable with tools like `jq` even if the app crashes.
+pub struct JsonlWriter {
+    file: Box<dyn Write + Send>,
+}
+
+impl JsonlWriter {
+    /// Create the file, replacing any existing one
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self { file: Box::new(File::create(path)?) })
+    }
+
+    /// Write to standard output instead of a file, to pipe a headless run
+    pub fn stdout() -> Self {
+        Self { file: Box::new(stdout()) }
+    }
+
+    /// Append the result carried by `event`. Other events are ignored.
+    pub fn write(&mut self, event: &FuzzEvent) -> Result<()> {
+        if let FuzzEvent::Result { result, .. } = event {
+            let mut line = serde_json::to_string(result)?;
+            line.push('\n');
+            self.file.write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FuzzSummary;
+    use yaak_models::models::FuzzResult;
+
+    #[test]
+    fn writes_one_line_per_result() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("results.jsonl");
+        let mut writer = JsonlWriter::create(&path).unwrap();
+
+        for payload in ["a", "b"] {
+            let result = FuzzResult { payload: payload.to_string(), ..Default::default() };
+            writer
+                .write(&FuzzEvent::Result {
+                    result,
+                    body: Vec::new(),
+                    url: String::new(),
+                    request_body: Vec::new(),
+                })
+                .unwrap();
+        }
+        writer.write(&FuzzEvent::Finished { summary: FuzzSummary::default() }).unwrap();
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let payloads = contents
+            .lines()
+            .map(|l| serde_json::from_str::<FuzzResult>(l).unwrap().payload)
+            .collect::<Vec<_>>();
+        assert_eq!(payloads, vec!["a", "b"]);
+    }
+}
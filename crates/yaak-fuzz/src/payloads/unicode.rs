@@ -0,0 +1,113 @@
+/// Characters that normalize, case-fold or decompose to ASCII that filters
+/// look for, or to a different string than they appear to be
+const CONFUSABLES: &[&str] = &[
+    // Fullwidth forms, which NFKC turns into their ASCII counterparts
+    "\u{FF1C}script\u{FF1E}",
+    "\u{FF0E}\u{FF0E}\u{FF0F}",
+    "\u{FF07} or 1=1--",
+    "\u{FF02}",
+    "\u{FF45}\u{FF58}\u{FF41}\u{FF4D}\u{FF50}\u{FF4C}\u{FF45}",
+    // Small and other compatibility forms
+    "\u{FE64}script\u{FE65}",
+    "\u{2025}/",
+    "\u{FB01}le",
+    "\u{2460}",
+    // Mathematical bold "admin", astral characters that NFKC maps to ASCII
+    "\u{1D41A}\u{1D41D}\u{1D426}\u{1D422}\u{1D427}",
+    // Case mappings that change length or land on ASCII
+    "\u{212A}",
+    "\u{017F}",
+    "\u{0131}",
+    "\u{0130}",
+    "\u{00DF}",
+    // Precomposed and decomposed forms of the same text
+    "\u{00E9}",
+    "e\u{0301}",
+    "\u{212B}",
+    "A\u{030A}",
+    // Homoglyphs that don't normalize, like Cyrillic "а" in "admin"
+    "\u{0430}dmin",
+];
+
+/// Overlong and otherwise invalid UTF-8 for `/`, `../`, `<`, `'` and NUL,
+/// plus an encoded surrogate, percent-encoded since payloads are text
+const OVERLONG_ENCODINGS: &[&str] = &[
+    "%c0%af",
+    "%e0%80%af",
+    "%f0%80%80%af",
+    "%c0%ae%c0%ae%c0%af",
+    "%c0%bc",
+    "%c0%a7",
+    "%c0%80",
+    "%ed%a0%80",
+];
+
+/// Invisible characters, alone and inside a word filters might match
+const ZERO_WIDTH: &[&str] = &[
+    "ad\u{200B}min",
+    "ad\u{200C}min",
+    "ad\u{200D}min",
+    "ad\u{2060}min",
+    "ad\u{00AD}min",
+    "\u{FEFF}admin",
+    "\u{180E}",
+    "\u{200B}",
+];
+
+/// Bidirectional overrides and isolates, which make text display in a
+/// different order than it's stored
+const BIDI_CONTROLS: &[&str] = &[
+    "\u{202E}gpj.exe",
+    "\u{202E}",
+    "\u{202D}",
+    "\u{2066}",
+    "\u{2067}",
+    "\u{2068}",
+    "\u{2069}",
+    "\u{200F}",
+    "\u{061C}",
+];
+
+/// Characters outside the Basic Multilingual Plane, which take surrogate
+/// pairs in UTF-16 and four bytes in UTF-8
+const ASTRAL: &[&str] = &[
+    "\u{1F600}",
+    "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}",
+    "\u{20731}",
+    "\u{1D11E}",
+    "\u{E0041}",
+    "\u{10FFFF}",
+];
+
+/// Unicode edge cases for probing canonicalization bugs: normalization
+/// confusables, overlong encodings, zero-width characters, bidirectional
+/// overrides and astral-plane characters
+pub(super) fn unicode_edge_cases() -> Vec<String> {
+    [
+        CONFUSABLES,
+        OVERLONG_ENCODINGS,
+        ZERO_WIDTH,
+        BIDI_CONTROLS,
+        ASTRAL,
+    ]
+    .concat()
+    .into_iter()
+    .map(str::to_string)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn covers_every_category_without_duplicates() {
+        let payloads = unicode_edge_cases();
+        assert_eq!(payloads.iter().collect::<HashSet<_>>().len(), payloads.len());
+        assert!(payloads.iter().any(|p| p == "%c0%ae%c0%ae%c0%af"));
+        assert!(payloads.iter().any(|p| p.contains('\u{200B}')));
+        assert!(payloads.iter().any(|p| p.starts_with('\u{202E}')));
+        assert!(payloads.iter().any(|p| p.chars().any(|c| c as u32 > 0xFFFF)));
+    }
+}
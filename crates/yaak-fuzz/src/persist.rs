@@ -0,0 +1,726 @@
+use crate::callbacks::record_interaction;
+use crate::encryption::{encrypts_results, open_result, seal_result, seal_run};
+use crate::error::Result;
+use crate::types::{FuzzEvent, FuzzRequest, FuzzStatusCounts};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use yaak_crypto::manager::EncryptionManager;
+use yaak_models::blob_manager::{BlobManager, BodyChunk};
+use yaak_models::db_context::DbContext;
+use yaak_models::models::{FuzzResult, FuzzRun, FuzzRunState, HttpResponse, HttpResponseState};
+use yaak_models::query_manager::QueryManager;
+use yaak_models::util::{UpdateSource, generate_prefixed_id};
+
+/// Writes a run and its results to the database as events arrive, so results
+/// and their triage state survive a reload. Response bodies go to the blob
+/// database.
+pub struct FuzzRunPersister {
+    query_manager: QueryManager,
+    blob_manager: BlobManager,
+    run: FuzzRun,
+    update_source: UpdateSource,
+    response_dir: Option<PathBuf>,
+    /// Set when the run's workspace encrypts, to encrypt bodies, headers,
+    /// extracted values and the run's secrets before they're stored
+    encryption_manager: Option<Arc<EncryptionManager>>,
+    /// Results replace earlier ones and the summary only covers retried payloads
+    retrying: bool,
+}
+
+impl FuzzRunPersister {
+    /// Create the run record in the `Running` state. When the run's workspace
+    /// has encryption enabled, everything stored is encrypted with its key,
+    /// starting with the run's secrets.
+    pub fn start(
+        query_manager: QueryManager,
+        blob_manager: BlobManager,
+        encryption_manager: Arc<EncryptionManager>,
+        config: &FuzzRequest,
+        update_source: UpdateSource,
+    ) -> Result<Self> {
+        let request = &config.request;
+        let encryption_manager = workspace_encryption(
+            &query_manager.connect(),
+            &request.workspace_id,
+            encryption_manager,
+        )?;
+        let run = FuzzRun {
+            workspace_id: request.workspace_id.clone(),
+            request_id: if request.id.is_empty() { None } else { Some(request.id.clone()) },
+            config: match serde_json::to_value(config)? {
+                serde_json::Value::Object(config) => config.into_iter().collect(),
+                _ => Default::default(),
+            },
+            total: config.request_count() as i32,
+            ..Default::default()
+        };
+        let run = match &encryption_manager {
+            Some(encryption_manager) => seal_run(encryption_manager, &run)?,
+            None => run,
+        };
+        let run = query_manager.connect().upsert_fuzz_run(&run, &update_source)?;
+        Ok(Self {
+            query_manager,
+            blob_manager,
+            run,
+            update_source,
+            response_dir: None,
+            encryption_manager,
+            retrying: false,
+        })
+    }
+
+    /// Reopen a finished run to retry its failed payloads, moving it back to
+    /// the `Running` state. Counts are recomputed from the run's results once
+    /// it finishes.
+    pub fn retry(
+        query_manager: QueryManager,
+        blob_manager: BlobManager,
+        encryption_manager: Arc<EncryptionManager>,
+        run_id: &str,
+        update_source: UpdateSource,
+    ) -> Result<Self> {
+        let run = query_manager.connect().get_fuzz_run(run_id)?;
+        let encryption_manager =
+            workspace_encryption(&query_manager.connect(), &run.workspace_id, encryption_manager)?;
+        // Runs stored before the workspace enabled encryption get sealed now
+        let run = match &encryption_manager {
+            Some(encryption_manager) => seal_run(encryption_manager, &run)?,
+            None => run,
+        };
+        let run = query_manager
+            .connect()
+            .upsert_fuzz_run(&FuzzRun { state: FuzzRunState::Running, ..run }, &update_source)?;
+        Ok(Self {
+            query_manager,
+            blob_manager,
+            run,
+            update_source,
+            response_dir: None,
+            encryption_manager,
+            retrying: true,
+        })
+    }
+
+    /// Also record each result as an `HttpResponse` of the run's source
+    /// request, writing bodies to `response_dir` like regular responses. Has
+    /// no effect for runs without a saved source request.
+    pub fn with_response_history(mut self, response_dir: PathBuf) -> Self {
+        self.response_dir = Some(response_dir);
+        self
+    }
+
+    pub fn run_id(&self) -> &str {
+        &self.run.id
+    }
+
+    /// Persist a result, or the final summary once the run finishes. Progress
+    /// events are not written since they are implied by the stored results.
+    /// Interactions flag the result they belong to.
+    /// A result recorded in the response history gets its `response_id` set.
+    pub fn record(&mut self, event: &mut FuzzEvent) -> Result<()> {
+        let db = self.query_manager.connect();
+        match event {
+            FuzzEvent::Result { result, body, url, request_body } => {
+                if let (Some(dir), Some(request_id)) = (&self.response_dir, &self.run.request_id) {
+                    let response = self.record_response(&db, dir, request_id, result, body, url)?;
+                    result.response_id = Some(response.id);
+                }
+                // The result sent on keeps its plain values
+                self.upsert_result(&db, result)?;
+                if self.retrying {
+                    self.delete_bodies(&result.id)?;
+                }
+                self.store_shared_body(result, body)?;
+                self.store_body(result, &result_request_body_id(&result.id), request_body)?;
+            }
+            FuzzEvent::Progress { .. } | FuzzEvent::Metrics { .. } => {}
+            FuzzEvent::Interaction { interaction } => {
+                let mut result = db.get_fuzz_result(&interaction.result_id)?;
+                if let Some(encryption_manager) = &self.encryption_manager {
+                    result = open_result(encryption_manager, result)?;
+                }
+                record_interaction(&mut result, interaction);
+                self.upsert_result(&db, &result)?;
+            }
+            FuzzEvent::Finished { summary } => {
+                let state = if summary.cancelled {
+                    FuzzRunState::Cancelled
+                } else {
+                    FuzzRunState::Finished
+                };
+                let run = if self.retrying {
+                    // Retried results replaced the failed ones, which may
+                    // already have matched, so count the stored results
+                    let results = db.list_fuzz_results(&self.run.id)?;
+                    let mut statuses = FuzzStatusCounts::default();
+                    results.iter().for_each(|r| statuses.count(r));
+                    with_statuses(
+                        FuzzRun {
+                            state,
+                            completed: results.len() as i32,
+                            matched: results.iter().filter(|r| r.matched).count() as i32,
+                            errors: results.iter().filter(|r| r.error.is_some()).count() as i32,
+                            elapsed: self.run.elapsed.saturating_add(summary.elapsed),
+                            ..self.run.clone()
+                        },
+                        &statuses,
+                    )
+                } else {
+                    with_statuses(
+                        FuzzRun {
+                            state,
+                            total: summary.total as i32,
+                            completed: summary.completed as i32,
+                            matched: summary.matched as i32,
+                            errors: summary.errors as i32,
+                            elapsed: summary.elapsed,
+                            ..self.run.clone()
+                        },
+                        &summary.statuses,
+                    )
+                };
+                self.run = db.upsert_fuzz_run(&run, &self.update_source)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete the bodies stored for a result that's being replaced. Shared
+    /// bodies stay, since other results can point at them.
+    fn delete_bodies(&self, result_id: &str) -> Result<()> {
+        let db = self.blob_manager.connect();
+        for blob_id in [result_body_id(result_id), result_request_body_id(result_id)] {
+            db.delete_chunks(&encrypted_blob_id(&blob_id))?;
+            db.delete_chunks(&blob_id)?;
+        }
+        Ok(())
+    }
+
+    /// Store a result, sealed when the workspace encrypts
+    fn upsert_result(&self, db: &DbContext, result: &FuzzResult) -> Result<()> {
+        match &self.encryption_manager {
+            Some(encryption_manager) => db.upsert_fuzz_result(
+                &seal_result(encryption_manager, result)?,
+                &self.update_source,
+            )?,
+            None => db.upsert_fuzz_result(result, &self.update_source)?,
+        };
+        Ok(())
+    }
+
+    /// Write a non-empty body to the blob database, encrypted under its own
+    /// blob ID when the workspace encrypts
+    fn store_body(&self, result: &FuzzResult, blob_id: &str, body: &[u8]) -> Result<()> {
+        if body.is_empty() {
+            return Ok(());
+        }
+        let chunk = match &self.encryption_manager {
+            Some(encryption_manager) => BodyChunk::new(
+                encrypted_blob_id(blob_id),
+                0,
+                encryption_manager.encrypt(&result.workspace_id, body)?,
+            ),
+            None => BodyChunk::new(blob_id, 0, body.to_vec()),
+        };
+        self.blob_manager.connect().insert_chunk(&chunk)?;
+        Ok(())
+    }
+
+    /// Write a response body once per workspace, under its hash, so results
+    /// with identical responses share it
+    fn store_shared_body(&self, result: &FuzzResult, body: &[u8]) -> Result<()> {
+        let blob_id = shared_body_id(result);
+        let stored_id = match &self.encryption_manager {
+            Some(_) => encrypted_blob_id(&blob_id),
+            None => blob_id.clone(),
+        };
+        if self.blob_manager.connect().body_exists(&stored_id)? {
+            return Ok(());
+        }
+        self.store_body(result, &blob_id, body)
+    }
+
+    fn record_response(
+        &self,
+        db: &DbContext,
+        response_dir: &Path,
+        request_id: &str,
+        result: &FuzzResult,
+        body: &[u8],
+        url: &str,
+    ) -> Result<HttpResponse> {
+        let id = generate_prefixed_id("rs");
+        let body_path = match result.error {
+            Some(_) => None,
+            None => {
+                fs::create_dir_all(response_dir)?;
+                let body_path = response_dir.join(&id);
+                fs::write(&body_path, body)?;
+                Some(body_path.to_string_lossy().to_string())
+            }
+        };
+        Ok(db.upsert_http_response(
+            &HttpResponse {
+                id,
+                workspace_id: result.workspace_id.clone(),
+                request_id: request_id.to_string(),
+                body_path,
+                content_length: Some(result.content_length),
+                elapsed: result.elapsed,
+                error: result.error.clone(),
+                headers: result.headers.clone(),
+                request_headers: result.request_headers.clone(),
+                status: result.status,
+                state: HttpResponseState::Closed,
+                url: url.to_string(),
+                ..Default::default()
+            },
+            &self.update_source,
+            &self.blob_manager,
+        )?)
+    }
+}
+
+/// Copy of `run` with its counts by status class set to `statuses`
+fn with_statuses(run: FuzzRun, statuses: &FuzzStatusCounts) -> FuzzRun {
+    FuzzRun {
+        status_success: statuses.success as i32,
+        status_redirect: statuses.redirect as i32,
+        status_client_error: statuses.client_error as i32,
+        status_server_error: statuses.server_error as i32,
+        status_no_response: statuses.no_response as i32,
+        ..run
+    }
+}
+
+/// The encryption manager to store a run with, when its workspace encrypts
+fn workspace_encryption(
+    db: &DbContext,
+    workspace_id: &str,
+    encryption_manager: Arc<EncryptionManager>,
+) -> Result<Option<Arc<EncryptionManager>>> {
+    Ok(encrypts_results(db, workspace_id)?.then_some(encryption_manager))
+}
+
+/// Blob ID under which a result's response body was stored before bodies
+/// were shared between results
+pub fn result_body_id(result_id: &str) -> String {
+    format!("{result_id}.response")
+}
+
+/// Blob ID under which the response body shared by every result of the
+/// workspace with the same body hash is stored
+pub fn shared_body_id(result: &FuzzResult) -> String {
+    format!("{}.fuzz_body.{}", result.workspace_id, result.body_hash)
+}
+
+/// Read a stored response body. Results without a body return an empty one.
+pub fn read_result_body(
+    blob_manager: &BlobManager,
+    encryption_manager: &EncryptionManager,
+    result: &FuzzResult,
+) -> Result<Vec<u8>> {
+    let body = read_body(blob_manager, encryption_manager, result, &shared_body_id(result))?;
+    if !body.is_empty() {
+        return Ok(body);
+    }
+    read_body(blob_manager, encryption_manager, result, &result_body_id(&result.id))
+}
+
+/// Blob ID under which the body of a result's request is stored
+pub fn result_request_body_id(result_id: &str) -> String {
+    format!("{result_id}.request")
+}
+
+/// Read the stored body of a result's request. Requests without a body
+/// return an empty one.
+pub fn read_result_request_body(
+    blob_manager: &BlobManager,
+    encryption_manager: &EncryptionManager,
+    result: &FuzzResult,
+) -> Result<Vec<u8>> {
+    read_body(blob_manager, encryption_manager, result, &result_request_body_id(&result.id))
+}
+
+/// Blob ID under which the encrypted form of a body is stored, so bodies
+/// stored before the workspace enabled encryption stay readable
+fn encrypted_blob_id(blob_id: &str) -> String {
+    format!("{blob_id}.encrypted")
+}
+
+fn read_body(
+    blob_manager: &BlobManager,
+    encryption_manager: &EncryptionManager,
+    result: &FuzzResult,
+    blob_id: &str,
+) -> Result<Vec<u8>> {
+    let db = blob_manager.connect();
+    let encrypted = db.get_chunks(&encrypted_blob_id(blob_id))?;
+    if !encrypted.is_empty() {
+        let encrypted: Vec<u8> = encrypted.into_iter().flat_map(|c| c.data).collect();
+        return Ok(encryption_manager.decrypt(&result.workspace_id, &encrypted)?);
+    }
+    Ok(db.get_chunks(blob_id)?.into_iter().flat_map(|c| c.data).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encryption::{open_run, test_encryption_manager};
+    use crate::types::{FuzzHmacSigning, FuzzSigning, FuzzSummary};
+    use std::collections::BTreeMap;
+    use yaak_models::init_in_memory;
+    use yaak_models::models::{HttpRequest, HttpResponseHeader, Workspace};
+
+    #[test]
+    fn records_results_in_response_history() {
+        let (query_manager, blob_manager, _rx) = init_in_memory().expect("Failed to init DB");
+        let source = UpdateSource::Background;
+        let request = {
+            let db = query_manager.connect();
+            let workspace = db.upsert_workspace(&Workspace::default(), &source).unwrap();
+            db.upsert_http_request(
+                &HttpRequest { workspace_id: workspace.id, ..Default::default() },
+                &source,
+            )
+            .unwrap()
+        };
+        let config = FuzzRequest {
+            request: request.clone(),
+            wordlist: vec!["admin".to_string()],
+            record_responses: true,
+            ..Default::default()
+        };
+
+        let response_dir = tempfile::tempdir().unwrap();
+        let encryption_manager = Arc::new(EncryptionManager::new(query_manager.clone(), "test"));
+        let mut persister = FuzzRunPersister::start(
+            query_manager.clone(),
+            blob_manager,
+            encryption_manager,
+            &config,
+            source,
+        )
+        .unwrap()
+        .with_response_history(response_dir.path().to_path_buf());
+        let mut event = FuzzEvent::Result {
+            result: FuzzResult {
+                workspace_id: request.workspace_id.clone(),
+                run_id: persister.run_id().to_string(),
+                payload: "admin".to_string(),
+                status: 200,
+                ..Default::default()
+            },
+            body: b"hello".to_vec(),
+            url: "https://example.com/admin".to_string(),
+            request_body: Vec::new(),
+        };
+        persister.record(&mut event).unwrap();
+
+        let FuzzEvent::Result { result, .. } = event else {
+            unreachable!()
+        };
+        let response =
+            query_manager.connect().get_http_response(&result.response_id.unwrap()).unwrap();
+        assert_eq!(response.request_id, request.id);
+        assert_eq!(response.url, "https://example.com/admin");
+        assert_eq!(fs::read(response.body_path.unwrap()).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn identical_bodies_are_stored_once() {
+        let (query_manager, blob_manager, _rx) = init_in_memory().expect("Failed to init DB");
+        let source = UpdateSource::Background;
+        let workspace =
+            query_manager.connect().upsert_workspace(&Workspace::default(), &source).unwrap();
+        let config = FuzzRequest {
+            request: HttpRequest { workspace_id: workspace.id.clone(), ..Default::default() },
+            wordlist: vec!["a".to_string(), "b".to_string()],
+            ..Default::default()
+        };
+        let encryption_manager = Arc::new(EncryptionManager::new(query_manager.clone(), "test"));
+        let mut persister = FuzzRunPersister::start(
+            query_manager.clone(),
+            blob_manager.clone(),
+            encryption_manager.clone(),
+            &config,
+            source,
+        )
+        .unwrap();
+        let result = FuzzResult {
+            workspace_id: workspace.id.clone(),
+            run_id: persister.run_id().to_string(),
+            body_hash: "abc".to_string(),
+            ..Default::default()
+        };
+        for payload in ["a", "b"] {
+            let mut event = FuzzEvent::Result {
+                result: FuzzResult { payload: payload.to_string(), ..result.clone() },
+                body: b"not found".to_vec(),
+                url: String::new(),
+                request_body: Vec::new(),
+            };
+            persister.record(&mut event).unwrap();
+        }
+
+        let chunks = blob_manager.connect().get_chunks(&shared_body_id(&result)).unwrap();
+        assert_eq!(chunks.len(), 1);
+        for result in query_manager.connect().list_fuzz_results(persister.run_id()).unwrap() {
+            let body = read_result_body(&blob_manager, &encryption_manager, &result).unwrap();
+            assert_eq!(body, b"not found");
+        }
+    }
+
+    #[test]
+    fn encrypted_workspaces_store_no_plaintext() {
+        let (query_manager, blob_manager, _rx) = init_in_memory().expect("Failed to init DB");
+        let source = UpdateSource::Background;
+        let workspace =
+            query_manager.connect().upsert_workspace(&Workspace::default(), &source).unwrap();
+        let encryption_manager =
+            Arc::new(test_encryption_manager(query_manager.clone(), &workspace.id));
+        let config = FuzzRequest {
+            request: HttpRequest { workspace_id: workspace.id.clone(), ..Default::default() },
+            wordlist: vec!["admin".to_string()],
+            signing: Some(FuzzSigning::HmacBody(FuzzHmacSigning {
+                secret: "signing-secret".to_string(),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        let mut persister = FuzzRunPersister::start(
+            query_manager.clone(),
+            blob_manager.clone(),
+            encryption_manager.clone(),
+            &config,
+            source,
+        )
+        .unwrap();
+        let result = FuzzResult {
+            workspace_id: workspace.id.clone(),
+            run_id: persister.run_id().to_string(),
+            body_hash: "abc".to_string(),
+            request_headers: vec![HttpResponseHeader {
+                name: "Authorization".to_string(),
+                value: "Bearer request-secret".to_string(),
+            }],
+            headers: vec![HttpResponseHeader {
+                name: "Set-Cookie".to_string(),
+                value: "session=cookie-secret".to_string(),
+            }],
+            extracted: BTreeMap::from([("token".to_string(), "extracted-secret".to_string())]),
+            ..Default::default()
+        };
+        let mut event = FuzzEvent::Result {
+            result: result.clone(),
+            body: b"body-secret".to_vec(),
+            url: String::new(),
+            request_body: Vec::new(),
+        };
+        persister.record(&mut event).unwrap();
+
+        let db = query_manager.connect();
+        let run = db.get_fuzz_run(persister.run_id()).unwrap();
+        let stored = db.list_fuzz_results(persister.run_id()).unwrap();
+        let rows = serde_json::to_string(&(&run, &stored)).unwrap();
+        let chunks = blob_manager
+            .connect()
+            .get_chunks(&encrypted_blob_id(&shared_body_id(&result)))
+            .unwrap();
+        let body = String::from_utf8_lossy(&chunks[0].data).to_string();
+        for secret in [
+            "signing-secret",
+            "request-secret",
+            "cookie-secret",
+            "extracted-secret",
+        ] {
+            assert!(!rows.contains(secret), "{secret} was stored in plain text");
+        }
+        assert!(!body.contains("body-secret"));
+
+        let opened = open_result(&encryption_manager, stored[0].clone()).unwrap();
+        assert_eq!(opened.headers, result.headers);
+        let run = open_run(&encryption_manager, run).unwrap();
+        assert_eq!(crate::export::run_config(&run).signing, config.signing);
+    }
+
+    /// Result event for `payload`, under an ID of its own so a retry replaces it
+    fn payload_event(
+        workspace_id: &str,
+        run_id: &str,
+        payload: &str,
+        result: FuzzResult,
+    ) -> FuzzEvent {
+        FuzzEvent::Result {
+            result: FuzzResult {
+                id: format!("fz_{payload}"),
+                workspace_id: workspace_id.to_string(),
+                run_id: run_id.to_string(),
+                payload: payload.to_string(),
+                ..result
+            },
+            body: Vec::new(),
+            url: String::new(),
+            request_body: Vec::new(),
+        }
+    }
+
+    fn finished(completed: usize, matched: usize, errors: usize) -> FuzzEvent {
+        FuzzEvent::Finished {
+            summary: FuzzSummary {
+                total: completed,
+                completed,
+                matched,
+                errors,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn finished_runs_store_status_counts() {
+        let (query_manager, blob_manager, _rx) = init_in_memory().expect("Failed to init DB");
+        let workspace = query_manager
+            .connect()
+            .upsert_workspace(&Workspace::default(), &UpdateSource::Background)
+            .unwrap();
+        let config = FuzzRequest {
+            request: HttpRequest { workspace_id: workspace.id, ..Default::default() },
+            ..Default::default()
+        };
+        let mut persister = FuzzRunPersister::start(
+            query_manager.clone(),
+            blob_manager,
+            Arc::new(EncryptionManager::new(query_manager.clone(), "test")),
+            &config,
+            UpdateSource::Background,
+        )
+        .unwrap();
+        let statuses =
+            FuzzStatusCounts { success: 3, client_error: 2, no_response: 1, ..Default::default() };
+        persister
+            .record(&mut FuzzEvent::Finished {
+                summary: FuzzSummary { completed: 6, statuses, ..Default::default() },
+            })
+            .unwrap();
+
+        let run = query_manager.connect().get_fuzz_run(persister.run_id()).unwrap();
+        let counts = (run.status_success, run.status_client_error, run.status_no_response);
+        assert_eq!(counts, (3, 2, 1));
+        assert_eq!((run.status_redirect, run.status_server_error), (0, 0));
+    }
+
+    /// Record a run of `a`, which succeeds, and `b`, which times out, then
+    /// retry `b`. Returns the run once the retry finishes.
+    fn retry_timeout(
+        timed_out: FuzzResult,
+        retried: FuzzResult,
+    ) -> (QueryManager, BlobManager, FuzzRun) {
+        let (query_manager, blob_manager, _rx) = init_in_memory().expect("Failed to init DB");
+        let source = UpdateSource::Background;
+        let workspace =
+            query_manager.connect().upsert_workspace(&Workspace::default(), &source).unwrap();
+        let config = FuzzRequest {
+            request: HttpRequest { workspace_id: workspace.id.clone(), ..Default::default() },
+            wordlist: vec!["a".to_string(), "b".to_string()],
+            ..Default::default()
+        };
+
+        let encryption_manager = Arc::new(EncryptionManager::new(query_manager.clone(), "test"));
+        let mut persister = FuzzRunPersister::start(
+            query_manager.clone(),
+            blob_manager.clone(),
+            encryption_manager.clone(),
+            &config,
+            source.clone(),
+        )
+        .unwrap();
+        let run_id = persister.run_id().to_string();
+        let succeeded = FuzzResult { status: 200, matched: true, ..Default::default() };
+        let timed_out = FuzzResult { error: Some("timed out".to_string()), ..timed_out };
+        let matched = 1 + timed_out.matched as usize;
+        persister.record(&mut payload_event(&workspace.id, &run_id, "a", succeeded)).unwrap();
+        persister.record(&mut payload_event(&workspace.id, &run_id, "b", timed_out)).unwrap();
+        persister.record(&mut finished(2, matched, 1)).unwrap();
+
+        let mut persister = FuzzRunPersister::retry(
+            query_manager.clone(),
+            blob_manager.clone(),
+            encryption_manager,
+            &run_id,
+            source,
+        )
+        .unwrap();
+        let retried_matched = retried.matched as usize;
+        persister.record(&mut payload_event(&workspace.id, &run_id, "b", retried)).unwrap();
+        persister.record(&mut finished(1, retried_matched, 0)).unwrap();
+
+        let run = query_manager.connect().get_fuzz_run(&run_id).unwrap();
+        (query_manager, blob_manager, run)
+    }
+
+    #[test]
+    fn retry_replaces_failed_results() {
+        let retried = FuzzResult { status: 200, matched: true, ..Default::default() };
+        let (query_manager, _blob_manager, run) = retry_timeout(Default::default(), retried);
+
+        assert_eq!((run.completed, run.matched, run.errors), (2, 2, 0));
+        assert_eq!((run.status_success, run.status_no_response), (2, 0));
+        let results = query_manager.connect().list_fuzz_results(&run.id).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.status == 200));
+    }
+
+    #[test]
+    fn retrying_a_matched_timeout_counts_it_once() {
+        // A timeout matcher matched the timeout, and the retry's response doesn't
+        let timed_out = FuzzResult { matched: true, ..Default::default() };
+        let retried = FuzzResult { status: 200, ..Default::default() };
+        let (_query_manager, _blob_manager, run) = retry_timeout(timed_out, retried);
+
+        assert_eq!((run.completed, run.matched, run.errors), (2, 1, 0));
+    }
+
+    #[test]
+    fn retry_keeps_bodies_of_similarly_named_results() {
+        let (query_manager, blob_manager, _rx) = init_in_memory().expect("Failed to init DB");
+        let workspace = query_manager
+            .connect()
+            .upsert_workspace(&Workspace::default(), &UpdateSource::Background)
+            .unwrap();
+        let config = FuzzRequest {
+            request: HttpRequest { workspace_id: workspace.id.clone(), ..Default::default() },
+            ..Default::default()
+        };
+        let encryption_manager = Arc::new(EncryptionManager::new(query_manager.clone(), "test"));
+        let persister = FuzzRunPersister::start(
+            query_manager.clone(),
+            blob_manager.clone(),
+            encryption_manager.clone(),
+            &config,
+            UpdateSource::Background,
+        )
+        .unwrap();
+        let run_id = persister.run_id().to_string();
+        // `_` matches any character in a LIKE pattern
+        blob_manager
+            .connect()
+            .insert_chunk(&BodyChunk::new("fzXb.request", 0, b"kept".to_vec()))
+            .unwrap();
+
+        let mut persister = FuzzRunPersister::retry(
+            query_manager.clone(),
+            blob_manager.clone(),
+            encryption_manager,
+            &run_id,
+            UpdateSource::Background,
+        )
+        .unwrap();
+        let retried = FuzzResult { status: 200, ..Default::default() };
+        persister.record(&mut payload_event(&workspace.id, &run_id, "b", retried)).unwrap();
+
+        assert_eq!(blob_manager.connect().get_chunks("fzXb.request").unwrap().len(), 1);
+    }
+}
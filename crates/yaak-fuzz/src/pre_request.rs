@@ -0,0 +1,48 @@
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::matchers::ValueExtractors;
+use crate::sender::FuzzSender;
+use crate::types::FuzzPreRequest;
+use std::collections::BTreeMap;
+use yaak_http::cookies::CookieStore;
+use yaak_templates::escape::escape_template;
+
+/// Prefix for template variables holding values extracted by the pre-request
+pub const PRE_REQUEST_VARIABLE_PREFIX: &str = "fuzz.pre.";
+
+/// A run's pre-request with its extractors compiled
+pub(crate) struct PreRequest<'a> {
+    config: &'a FuzzPreRequest,
+    extractors: ValueExtractors,
+}
+
+impl<'a> PreRequest<'a> {
+    pub fn new(config: &'a FuzzPreRequest) -> Result<Self> {
+        Ok(Self { config, extractors: ValueExtractors::new(&config.extractors)? })
+    }
+
+    /// Send the pre-request and add the values it extracts to `variables`.
+    /// Values are escaped so response content is never evaluated as a
+    /// template.
+    pub async fn send<S: FuzzSender>(
+        &self,
+        sender: &S,
+        variables: &mut BTreeMap<String, String>,
+        cookies: Option<&CookieStore>,
+    ) -> Result<()> {
+        let response = sender
+            .send(&self.config.request, variables, cookies)
+            .await
+            .map_err(|e| GenericError(format!("Pre-request failed: {e}")))?;
+        let mut values = self.extractors.extract(&response);
+        for extractor in &self.config.extractors {
+            let name = extractor.name();
+            let value = values
+                .remove(name)
+                .ok_or_else(|| GenericError(format!("Pre-request found no value for {name}")))?;
+            variables
+                .insert(format!("{PRE_REQUEST_VARIABLE_PREFIX}{name}"), escape_template(&value));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use crate::base_urls::{group_by_base_url, schedule};
+use crate::byte_payloads::insert_payload_bytes;
+use crate::engine::fuzz_queue;
+use crate::markers::payload_request;
+use crate::rotation::rotate_headers;
+use crate::safe_mode::check_safe_method;
+use crate::sender::HttpFuzzSender;
+use crate::types::FuzzRequest;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+use yaak_models::models::{FuzzResult, HttpRequest, HttpResponseHeader};
+
+/// A payload's request rendered as the run would send it, without sending it
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzPreview {
+    pub payload_index: i32,
+    pub payload: String,
+    pub base_url: String,
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<HttpResponseHeader>,
+    /// Decoded as UTF-8, with invalid bytes replaced
+    pub body: String,
+    /// Why the request couldn't be rendered or wouldn't be sent
+    pub error: Option<String>,
+}
+
+/// The first `limit` requests of a run, in the order they would be sent.
+/// Values extracted by login and pre-requests aren't available since nothing
+/// is sent, so templates using them fail to render. Recursion isn't followed.
+pub async fn preview_fuzz(
+    config: &FuzzRequest,
+    sender: &HttpFuzzSender<'_>,
+    limit: usize,
+) -> Vec<FuzzPreview> {
+    let mut previews = Vec::new();
+    for (entry, request) in preview_requests(config, limit) {
+        let mut preview = FuzzPreview {
+            payload_index: entry.payload_index,
+            payload: entry.payload,
+            base_url: entry.base_url,
+            method: request.method.clone(),
+            url: request.url.clone(),
+            ..Default::default()
+        };
+        let sent = match check_safe_method(config, &request.method) {
+            Ok(()) => {
+                let mut variables = BTreeMap::new();
+                insert_payload_bytes(config, &preview.payload, &mut variables);
+                sender.preview(&request, &variables).await
+            }
+            Err(e) => Err(e),
+        };
+        match sent {
+            Ok(sent) => {
+                preview.method = sent.method;
+                preview.url = sent.url;
+                preview.headers = sent
+                    .headers
+                    .into_iter()
+                    .map(|(name, value)| HttpResponseHeader { name, value })
+                    .collect();
+                preview.body = String::from_utf8_lossy(&sent.body).into_owned();
+            }
+            Err(e) => preview.error = Some(e.to_string()),
+        }
+        previews.push(preview);
+    }
+    previews
+}
+
+/// The first `limit` payloads of a run with the request templates they're
+/// sent with
+fn preview_requests(config: &FuzzRequest, limit: usize) -> Vec<(FuzzResult, HttpRequest)> {
+    let (configs, queues): (Vec<_>, Vec<_>) =
+        group_by_base_url(config, fuzz_queue(config)).into_iter().unzip();
+    let entries = schedule(queues, config.base_urls.order).into_iter().take(limit);
+    entries
+        .map(|(group, entry)| {
+            let request = payload_request(&configs[group], &entry.payload);
+            let request = rotate_headers(&configs[group], request, entry.payload_index as usize);
+            (entry, request)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FuzzBaseUrlOrder, FuzzBaseUrls};
+
+    #[test]
+    fn previews_the_first_requests_in_send_order() {
+        let config = FuzzRequest {
+            request: HttpRequest {
+                url: "https://example.com/§path§".to_string(),
+                ..Default::default()
+            },
+            wordlist: vec!["admin".to_string(), "login".to_string(), "api".to_string()],
+            base_urls: FuzzBaseUrls {
+                urls: vec!["https://a.test".to_string(), "https://b.test".to_string()],
+                order: FuzzBaseUrlOrder::Interleaved,
+            },
+            ..Default::default()
+        };
+
+        let requests = preview_requests(&config, 3);
+        let urls = requests.iter().map(|(_, r)| r.url.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            urls,
+            vec![
+                "https://a.test/admin",
+                "https://b.test/admin",
+                "https://a.test/login"
+            ]
+        );
+        assert_eq!(requests[1].0.base_url, "https://b.test");
+        assert_eq!(requests[1].0.payload_index, 3);
+    }
+}
@@ -0,0 +1,76 @@
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::types::{FuzzProxies, FuzzProxyRotation};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use yaak::send::proxy_setting_from_settings;
+use yaak_http::client::HttpConnectionProxySetting;
+use yaak_models::models::ProxySetting;
+
+/// Hands out a run's proxies, one per request
+pub struct ProxyRotator {
+    urls: Vec<String>,
+    rotation: FuzzProxyRotation,
+    next: AtomicUsize,
+}
+
+impl ProxyRotator {
+    pub fn new(proxies: &FuzzProxies) -> Self {
+        Self { urls: proxies.urls.clone(), rotation: proxies.rotation, next: AtomicUsize::new(0) }
+    }
+
+    /// Index and setting of the proxy for the next request, or `None` when
+    /// the run has no proxies of its own
+    pub fn next(&self) -> Option<(usize, HttpConnectionProxySetting)> {
+        if self.urls.is_empty() {
+            return None;
+        }
+        let index = match self.rotation {
+            FuzzProxyRotation::RoundRobin => {
+                self.next.fetch_add(1, Ordering::Relaxed) % self.urls.len()
+            }
+            FuzzProxyRotation::Random => rand::random_range(0..self.urls.len()),
+        };
+        let url = self.urls[index].clone();
+        // Credentials in the URL are picked up by the client
+        let setting = proxy_setting_from_settings(Some(ProxySetting::Enabled {
+            http: url.clone(),
+            https: url,
+            auth: None,
+            bypass: String::new(),
+            disabled: false,
+        }));
+        Some((index, setting))
+    }
+}
+
+/// Check that every proxy URL parses
+pub fn validate_proxies(proxies: &FuzzProxies) -> Result<()> {
+    for proxy in &proxies.urls {
+        if url::Url::parse(proxy).is_err() {
+            return Err(GenericError(format!("Invalid proxy URL {proxy}")));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_robin_cycles_through_proxies() {
+        let rotator = ProxyRotator::new(&FuzzProxies {
+            urls: vec!["http://a:8080".to_string(), "http://b:8080".to_string()],
+            rotation: FuzzProxyRotation::RoundRobin,
+        });
+        let picked = (0..3).map(|_| rotator.next().unwrap().0).collect::<Vec<_>>();
+        assert_eq!(picked, vec![0, 1, 0]);
+        assert!(ProxyRotator::new(&FuzzProxies::default()).next().is_none());
+    }
+
+    #[test]
+    fn rejects_invalid_proxy_urls() {
+        let proxies = FuzzProxies { urls: vec!["not a url".to_string()], ..Default::default() };
+        assert!(validate_proxies(&proxies).is_err());
+    }
+}
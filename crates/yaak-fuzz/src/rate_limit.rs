@@ -0,0 +1,285 @@
+//! Rate limit discovery: the template sent at a rising rate until the
+//! endpoint starts turning requests away, then watched until it recovers
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::markers::payload_request;
+use crate::safe_mode::check_safe_method;
+use crate::sender::{FuzzResponse, FuzzSender};
+use crate::types::FuzzRequest;
+use futures_util::{StreamExt, stream};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tokio::time::Instant;
+use ts_rs::TS;
+use yaak_models::models::{HttpRequest, HttpResponseHeader};
+
+/// How long to wait for blocking to end when the probe doesn't say
+const DEFAULT_RESET_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Time between requests checking whether blocking has ended
+const RESET_POLL: Duration = Duration::from_millis(250);
+
+/// Requests a paced run waits on at once
+const MAX_IN_FLIGHT: usize = 1024;
+
+/// How a rate limit discovery ramps up. Each step doubles the rate of the
+/// one before, up to `max_rps`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzRateLimitProbe {
+    /// Requests per second of the first step. Zero is treated as one.
+    pub start_rps: u32,
+    /// Requests per second of the last step
+    pub max_rps: u32,
+    /// How long each step lasts, in milliseconds. Zero is treated as a
+    /// second.
+    pub step_ms: u32,
+    /// Statuses that show requests are being turned away, or 429 when empty
+    pub blocked_statuses: Vec<u16>,
+    /// How long to wait for blocking to end, in milliseconds. Zero is
+    /// treated as a minute.
+    pub reset_timeout_ms: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzRateLimitStep {
+    pub rps: u32,
+    pub sent: u32,
+    pub blocked: u32,
+    pub errors: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzRateLimitReport {
+    pub steps: Vec<FuzzRateLimitStep>,
+    /// Fastest step that wasn't turned away, in requests per second
+    pub threshold_rps: Option<u32>,
+    /// Step the endpoint started turning requests away at, none when it
+    /// never did
+    pub blocked_rps: Option<u32>,
+    /// Requests sent back to back, once blocking ended, before being turned
+    /// away again. None when blocking didn't end, or when `max_rps` requests
+    /// got through.
+    pub burst: Option<u32>,
+    /// How long blocking lasted, in milliseconds
+    pub reset_ms: Option<u32>,
+    /// `Retry-After` and rate limit headers of the first response that was
+    /// turned away
+    pub headers: Vec<HttpResponseHeader>,
+}
+
+/// Ramp up requests of `config`'s template, with its first payload, until
+/// the endpoint turns them away, then measure how long blocking lasts and
+/// how big a burst gets through once it ends
+pub async fn discover_rate_limit<S: FuzzSender>(
+    sender: &S,
+    config: &FuzzRequest,
+    probe: &FuzzRateLimitProbe,
+) -> Result<FuzzRateLimitReport> {
+    let start_rps = probe.start_rps.max(1);
+    if probe.max_rps < start_rps {
+        return Err(GenericError("The maximum rate has to be at least the first".to_string()));
+    }
+    let payload = config.wordlist.first().map_or("", String::as_str);
+    let request = payload_request(config, payload);
+    check_safe_method(config, &request.method)?;
+    let blocks = |response: &FuzzResponse| {
+        if probe.blocked_statuses.is_empty() {
+            response.status == 429
+        } else {
+            probe.blocked_statuses.contains(&response.status)
+        }
+    };
+    let step = Duration::from_millis(if probe.step_ms == 0 { 1000 } else { probe.step_ms.into() });
+
+    let mut report = FuzzRateLimitReport::default();
+    let mut rps = start_rps;
+    let blocked_at = loop {
+        let (result, blocked_at) = send_step(sender, &request, rps, step, &blocks).await;
+        report.steps.push(result);
+        if let Some((at, headers)) = blocked_at {
+            report.blocked_rps = Some(rps);
+            report.headers = headers;
+            break Some(at);
+        }
+        report.threshold_rps = Some(rps);
+        if rps == probe.max_rps {
+            break None;
+        }
+        rps = rps.saturating_mul(2).min(probe.max_rps);
+    };
+    let Some(blocked_at) = blocked_at else {
+        return Ok(report);
+    };
+
+    let reset_timeout = match probe.reset_timeout_ms {
+        0 => DEFAULT_RESET_TIMEOUT,
+        ms => Duration::from_millis(ms.into()),
+    };
+    loop {
+        if blocked_at.elapsed() > reset_timeout {
+            return Ok(report);
+        }
+        tokio::time::sleep(RESET_POLL).await;
+        if let Ok(response) = send(sender, &request).await {
+            if !blocks(&response) {
+                report.reset_ms =
+                    Some(blocked_at.elapsed().as_millis().min(u32::MAX as u128) as u32);
+                break;
+            }
+        }
+    }
+
+    for sent in 0..probe.max_rps {
+        if send(sender, &request).await.is_ok_and(|r| blocks(&r)) {
+            report.burst = Some(sent);
+            break;
+        }
+    }
+    Ok(report)
+}
+
+async fn send<S: FuzzSender>(sender: &S, request: &HttpRequest) -> Result<FuzzResponse> {
+    sender.send(request, &BTreeMap::new(), None).await
+}
+
+/// Send `request` `count` times, `interval` apart, keeping what `sample`
+/// takes from each response and when it arrived. Responses are dropped as
+/// they arrive, so long runs don't hold on to their bodies. At most
+/// `MAX_IN_FLIGHT` requests wait for a response, and the rate drops below
+/// one per `interval` when more would.
+pub(crate) async fn send_paced<S: FuzzSender, T>(
+    sender: &S,
+    request: &HttpRequest,
+    count: u32,
+    interval: Duration,
+    sample: impl Fn(Result<FuzzResponse>) -> T,
+) -> Vec<(T, Instant)> {
+    let sample = &sample;
+    let started_at = Instant::now();
+    stream::iter(0..count)
+        .then(|i| tokio::time::sleep_until(started_at + interval * i))
+        .map(|_| async move { (sample(send(sender, request).await), Instant::now()) })
+        .buffer_unordered(MAX_IN_FLIGHT)
+        .collect()
+        .await
+}
+
+/// Send `request` at `rps` for `step`, evenly spaced. Returns when the first
+/// response that was turned away arrived, with its rate limit headers.
+async fn send_step<S: FuzzSender>(
+    sender: &S,
+    request: &HttpRequest,
+    rps: u32,
+    step: Duration,
+    blocks: &impl Fn(&FuzzResponse) -> bool,
+) -> (FuzzRateLimitStep, Option<(Instant, Vec<HttpResponseHeader>)>) {
+    let count = (rps as u128 * step.as_millis() / 1000).max(1) as u32;
+    let interval = Duration::from_secs(1) / rps;
+    let headers_if_blocked =
+        |sent: Result<FuzzResponse>| sent.map(|r| blocks(&r).then(|| rate_limit_headers(&r)));
+    let responses = send_paced(sender, request, count, interval, headers_if_blocked).await;
+
+    let mut result = FuzzRateLimitStep { rps, sent: count, ..Default::default() };
+    let mut first_blocked: Option<(Instant, Vec<HttpResponseHeader>)> = None;
+    for (response, at) in responses {
+        match response {
+            Ok(Some(headers)) => {
+                result.blocked += 1;
+                if first_blocked.as_ref().is_none_or(|(first, _)| at < *first) {
+                    first_blocked = Some((at, headers));
+                }
+            }
+            Ok(None) => {}
+            Err(_) => result.errors += 1,
+        }
+    }
+    (result, first_blocked)
+}
+
+fn rate_limit_headers(response: &FuzzResponse) -> Vec<HttpResponseHeader> {
+    let shown = |name: &str| {
+        let name = name.to_ascii_lowercase();
+        name == "retry-after" || name.starts_with("ratelimit") || name.starts_with("x-ratelimit")
+    };
+    response
+        .headers
+        .iter()
+        .filter(|(name, _)| shown(name))
+        .map(|(name, value)| HttpResponseHeader { name: name.clone(), value: value.clone() })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sender::SentRequest;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+    use yaak_http::cookies::CookieStore;
+
+    /// Lets 6 requests through in any 100ms, turning the rest away
+    #[derive(Default)]
+    struct LimitedSender {
+        passed: Mutex<Vec<Instant>>,
+    }
+
+    #[async_trait]
+    impl FuzzSender for LimitedSender {
+        async fn send(
+            &self,
+            _request: &HttpRequest,
+            _variables: &BTreeMap<String, String>,
+            _cookies: Option<&CookieStore>,
+        ) -> Result<FuzzResponse> {
+            let mut passed = self.passed.lock().unwrap();
+            passed.retain(|at| at.elapsed() < Duration::from_millis(100));
+            let status = if passed.len() >= 6 { 429 } else { 200 };
+            if status == 200 {
+                passed.push(Instant::now());
+            }
+            let headers = vec![("Retry-After".to_string(), "1".to_string())];
+            Ok(FuzzResponse { status, headers, ..Default::default() })
+        }
+
+        async fn send_exact(&self, _: &str, _: &SentRequest) -> Result<FuzzResponse> {
+            unimplemented!()
+        }
+
+        async fn refresh_auth(&self, _: &HttpRequest, _: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn finds_the_rate_that_gets_blocked() {
+        let probe =
+            FuzzRateLimitProbe { start_rps: 10, max_rps: 160, step_ms: 200, ..Default::default() };
+        let report =
+            discover_rate_limit(&LimitedSender::default(), &FuzzRequest::default(), &probe)
+                .await
+                .unwrap();
+
+        assert_eq!(report.threshold_rps, Some(40));
+        assert_eq!(report.blocked_rps, Some(80));
+        assert_eq!(report.steps.iter().map(|s| s.sent).collect::<Vec<_>>(), vec![2, 4, 8, 16]);
+        assert!(report.reset_ms.is_some());
+        // The request that saw blocking end is part of the burst's window
+        assert_eq!(report.burst, Some(5));
+        assert_eq!(report.headers[0].name, "Retry-After");
+
+        let probe = FuzzRateLimitProbe { start_rps: 10, max_rps: 5, ..Default::default() };
+        assert!(
+            discover_rate_limit(&LimitedSender::default(), &FuzzRequest::default(), &probe)
+                .await
+                .is_err()
+        );
+    }
+}
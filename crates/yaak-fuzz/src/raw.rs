@@ -0,0 +1,401 @@
+//! HTTP/1.1 written straight to the socket, for requests reqwest would
+//! reject or normalize, like CRLF injection payloads, illegal characters or
+//! conflicting framing headers
+
+use crate::byte_payloads::splice_bytes;
+use crate::dns::override_addresses;
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::sender::{FuzzResponse, SentRequest};
+use rustls::ClientConfig;
+use rustls::pki_types::ServerName;
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpSocket, TcpStream, lookup_host};
+use tokio_rustls::TlsConnector;
+use tokio_rustls::client::TlsStream;
+use yaak_models::models::DnsOverride;
+
+/// Most response headers parsed before the response is rejected
+const MAX_HEADERS: usize = 256;
+
+/// Characters allowed in methods and header names besides letters and digits
+const TOKEN_SYMBOLS: &[u8] = b"!#$%&'*+-.^_`|~";
+
+/// Path segments the URL parser resolves away
+const DOT_SEGMENTS: &[&str] = &[".", "..", "%2e", "%2e%2e", ".%2e", "%2e."];
+
+/// Status, HTTP version, headers and body of a response read from the socket
+type RawResponse = (u16, String, Vec<(String, String)>, Vec<u8>);
+
+/// How a raw request connects
+pub(crate) struct RawConnection {
+    pub tls: ClientConfig,
+    /// Sent in the TLS handshake instead of the URL's host
+    pub server_name: Option<String>,
+    /// Looked up before resolving the URL's host
+    pub dns_overrides: Vec<DnsOverride>,
+    /// Source address of the connection
+    pub local_address: Option<IpAddr>,
+    pub connect_timeout: Option<Duration>,
+    pub read_timeout: Option<Duration>,
+    pub timeout: Option<Duration>,
+}
+
+/// Whether reqwest would reject or rewrite `request`: a method or header
+/// name that isn't a token, control characters in a header value or the
+/// request target, backslashes or dot segments in the target, or
+/// `Content-Length` headers that are repeated, combined with
+/// `Transfer-Encoding`, or don't match the body. Repeated headers with others
+/// in between are sent raw too, so their order is kept.
+pub(crate) fn needs_raw(request: &SentRequest) -> bool {
+    let is_token = |s: &str| {
+        !s.is_empty() && s.bytes().all(|b| b.is_ascii_alphanumeric() || TOKEN_SYMBOLS.contains(&b))
+    };
+    let is_control = |b: u8| (b < b' ' && b != b'\t') || b == 0x7f;
+    if !is_token(&request.method) {
+        return true;
+    }
+
+    let target = RawTarget::request_target(&request.url);
+    if target.bytes().any(|b| is_control(b) || b == b'\t' || b == b'\\') {
+        return true;
+    }
+    let path = &target[..target.find(['?', '#']).unwrap_or(target.len())];
+    if path.split('/').any(|s| DOT_SEGMENTS.contains(&s.to_ascii_lowercase().as_str())) {
+        return true;
+    }
+
+    if request.headers.iter().any(|(n, v)| !is_token(n) || v.bytes().any(is_control)) {
+        return true;
+    }
+    // reqwest sends repeated headers next to each other
+    let names = request.headers.iter().map(|(n, _)| n.to_ascii_lowercase()).collect::<Vec<_>>();
+    if (1..names.len()).any(|i| names[i] != names[i - 1] && names[..i - 1].contains(&names[i])) {
+        return true;
+    }
+    let lengths = request.headers.iter().filter(|(n, _)| n.eq_ignore_ascii_case("content-length"));
+    let chunked = request.headers.iter().any(|(n, _)| n.eq_ignore_ascii_case("transfer-encoding"));
+    match lengths.collect::<Vec<_>>().as_slice() {
+        [] => false,
+        [(_, length)] => chunked || length.trim().parse::<usize>().ok() != Some(request.body.len()),
+        _ => true,
+    }
+}
+
+/// Send `request` over a new connection exactly as it is, without following
+/// redirects or adding cookies, with `payload_bytes` written at the byte
+/// payload placeholders in its request line and headers. Bodies are returned
+/// as received, without decompressing them.
+pub(crate) async fn send_raw(
+    request: SentRequest,
+    connection: RawConnection,
+    payload_bytes: Option<&[u8]>,
+) -> Result<FuzzResponse> {
+    let target = RawTarget::parse(&request.url)?;
+    let mut bytes = serialize_request(&request, &target);
+    if let Some(payload_bytes) = payload_bytes {
+        bytes = splice_bytes(&bytes, payload_bytes);
+    }
+    let head_only = request.method.eq_ignore_ascii_case("HEAD");
+    let timeout = connection.timeout;
+    let started_at = Instant::now();
+
+    let response = async move {
+        let mut stream = connect(&target, &connection).await?;
+        if !target.tls {
+            return exchange(&mut stream, &bytes, head_only, connection.read_timeout).await;
+        }
+        let read_timeout = connection.read_timeout;
+        let mut stream = connect_tls(stream, &target, connection).await?;
+        exchange(&mut stream, &bytes, head_only, read_timeout).await
+    };
+    let (status, version, headers, body) = with_timeout(timeout, response).await??;
+
+    let elapsed = started_at.elapsed();
+    Ok(FuzzResponse { url: request.url.clone(), status, version, headers, body, elapsed, request })
+}
+
+/// Open a TCP connection to `target`, at an overridden address if it has one
+/// and from the connection's local address if it's set
+pub(crate) async fn connect(target: &RawTarget, connection: &RawConnection) -> Result<TcpStream> {
+    let host = target.host.trim_matches(['[', ']']);
+    let addresses = override_addresses(&connection.dns_overrides, host);
+    let addresses = addresses.into_iter().map(|ip| SocketAddr::new(ip, target.port));
+    let mut addresses = addresses.collect::<Vec<_>>();
+    let timeout = connection.connect_timeout;
+
+    let local_address = match connection.local_address {
+        Some(local_address) => local_address,
+        None if addresses.is_empty() => {
+            return Ok(with_timeout(timeout, TcpStream::connect((host, target.port))).await??);
+        }
+        None => return Ok(with_timeout(timeout, TcpStream::connect(addresses.as_slice())).await??),
+    };
+    if addresses.is_empty() {
+        addresses = lookup_host((host, target.port)).await?.collect();
+    }
+    // A socket bound to an address of one family only reaches that family
+    let address = addresses.into_iter().find(|a| a.is_ipv4() == local_address.is_ipv4());
+    let family = if local_address.is_ipv4() { "IPv4" } else { "IPv6" };
+    let address = address.ok_or_else(|| GenericError(format!("{host} has no {family} address")))?;
+    let socket = if local_address.is_ipv4() { TcpSocket::new_v4()? } else { TcpSocket::new_v6()? };
+    socket.bind(SocketAddr::new(local_address, 0))?;
+    Ok(with_timeout(timeout, socket.connect(address)).await??)
+}
+
+/// Start TLS on `stream`, sending the connection's server name or the
+/// target's host
+pub(crate) async fn connect_tls(
+    stream: TcpStream,
+    target: &RawTarget,
+    connection: RawConnection,
+) -> Result<TlsStream<TcpStream>> {
+    let name = connection.server_name.as_deref().unwrap_or(&target.host);
+    let server_name = ServerName::try_from(name.trim_matches(['[', ']']).to_string())
+        .map_err(|_| GenericError(format!("Invalid TLS server name {name}")))?;
+    let connector = TlsConnector::from(Arc::new(connection.tls));
+    let handshake = connector.connect(server_name, stream);
+    Ok(with_timeout(connection.connect_timeout, handshake).await??)
+}
+
+/// Where a raw request goes, split from its URL without normalizing it
+pub(crate) struct RawTarget {
+    pub host: String,
+    pub port: u16,
+    pub tls: bool,
+    pub authority: String,
+    /// Path and query as written in the URL
+    pub path: String,
+}
+
+impl RawTarget {
+    pub(crate) fn parse(url: &str) -> Result<Self> {
+        let invalid = || GenericError(format!("Invalid URL {url}"));
+        let (scheme, rest) = url.split_once("://").ok_or_else(invalid)?;
+        let (tls, default_port) = match scheme.to_ascii_lowercase().as_str() {
+            "https" => (true, 443),
+            "http" => (false, 80),
+            _ => return Err(invalid()),
+        };
+        let authority = &rest[..rest.find(['/', '?', '#']).unwrap_or(rest.len())];
+        let authority = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+        let port_start = match authority.rfind(']') {
+            Some(bracket) => authority[bracket..].find(':').map(|i| bracket + i),
+            None => authority.rfind(':'),
+        };
+        let (host, port) = match port_start {
+            Some(i) => (&authority[..i], authority[i + 1..].parse().map_err(|_| invalid())?),
+            None => (authority, default_port),
+        };
+        if host.is_empty() {
+            return Err(invalid());
+        }
+
+        let path = Self::request_target(url);
+        let path = if path.starts_with('/') { path.to_string() } else { format!("/{path}") };
+        Ok(Self { host: host.to_string(), port, tls, authority: authority.to_string(), path })
+    }
+
+    /// Everything after the authority of `url`
+    pub(crate) fn request_target(url: &str) -> &str {
+        let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+        &rest[rest.find(['/', '?', '#']).unwrap_or(rest.len())..]
+    }
+}
+
+/// The bytes of `request`: the request line, `Host` unless it's set, the
+/// headers in their order, `Content-Length` for a body without framing
+/// headers, and `Connection: close` unless it's set
+fn serialize_request(request: &SentRequest, target: &RawTarget) -> Vec<u8> {
+    let has = |name: &str| request.headers.iter().any(|(n, _)| n.trim().eq_ignore_ascii_case(name));
+    let mut head = format!("{} {} HTTP/1.1\r\n", request.method, target.path);
+    if !has("host") {
+        head.push_str(&format!("Host: {}\r\n", target.authority));
+    }
+    for (name, value) in &request.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    if !request.body.is_empty() && !has("content-length") && !has("transfer-encoding") {
+        head.push_str(&format!("Content-Length: {}\r\n", request.body.len()));
+    }
+    if !has("connection") {
+        head.push_str("Connection: close\r\n");
+    }
+    head.push_str("\r\n");
+
+    let mut bytes = head.into_bytes();
+    bytes.extend_from_slice(&request.body);
+    bytes
+}
+
+/// Write the request and read until the response is complete
+async fn exchange<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    bytes: &[u8],
+    head_only: bool,
+    read_timeout: Option<Duration>,
+) -> Result<RawResponse> {
+    stream.write_all(bytes).await?;
+    stream.flush().await?;
+
+    let mut received = Vec::new();
+    let mut chunk = vec![0; 16 * 1024];
+    loop {
+        let read = match read_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, stream.read(&mut chunk))
+                .await
+                .map_err(|_| yaak_http::error::Error::BodyReadTimeout)?,
+            None => stream.read(&mut chunk).await,
+        };
+        // Servers often close TLS connections without a close_notify
+        let read = match read {
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => 0,
+            read => read?,
+        };
+        received.extend_from_slice(&chunk[..read]);
+        if let Some(response) = parse_response(&received, head_only, read == 0)? {
+            return Ok(response);
+        }
+        if read == 0 {
+            return Err(GenericError("Connection closed before the response was complete".into()));
+        }
+    }
+}
+
+/// The response in `received`, once it's complete. Without framing headers
+/// the body lasts until the connection is closed, at `eof`.
+fn parse_response(received: &[u8], head_only: bool, eof: bool) -> Result<Option<RawResponse>> {
+    let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+    let mut response = httparse::Response::new(&mut headers);
+    let head_len = match response.parse(received) {
+        Ok(httparse::Status::Complete(len)) => len,
+        Ok(httparse::Status::Partial) => return Ok(None),
+        Err(e) => return Err(GenericError(format!("Invalid response: {e}"))),
+    };
+    let status = response.code.unwrap_or_default();
+    let version = format!("HTTP/1.{}", response.version.unwrap_or(1));
+    // Interim responses come before the final one
+    if (100..200).contains(&status) && status != 101 {
+        return parse_response(&received[head_len..], head_only, eof);
+    }
+
+    let parsed = response
+        .headers
+        .iter()
+        .map(|h| (h.name.to_string(), String::from_utf8_lossy(h.value).into_owned()))
+        .collect::<Vec<_>>();
+    let header = |name: &str| {
+        parsed.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    };
+    let content = &received[head_len..];
+    let body = if head_only || matches!(status, 101 | 204 | 304) {
+        Some(Vec::new())
+    } else if header("transfer-encoding")
+        .is_some_and(|t| t.to_ascii_lowercase().contains("chunked"))
+    {
+        decode_chunked(content)
+    } else if let Some(length) = header("content-length").and_then(|l| l.trim().parse().ok()) {
+        content.get(..length).map(<[u8]>::to_vec)
+    } else if eof {
+        Some(content.to_vec())
+    } else {
+        None
+    };
+    Ok(body.map(|body| (status, version, parsed, body)))
+}
+
+/// The body of a chunked message, once its last chunk has arrived
+fn decode_chunked(mut data: &[u8]) -> Option<Vec<u8>> {
+    let mut body = Vec::new();
+    loop {
+        let line_end = data.windows(2).position(|w| w == b"\r\n")?;
+        let line = std::str::from_utf8(&data[..line_end]).ok()?;
+        let size = usize::from_str_radix(line.split(';').next()?.trim(), 16).ok()?;
+        data = &data[line_end + 2..];
+        if size == 0 {
+            return Some(body);
+        }
+        body.extend_from_slice(data.get(..size)?);
+        data = data.get(size + 2..)?;
+    }
+}
+
+pub(crate) async fn with_timeout<F: Future>(
+    timeout: Option<Duration>,
+    future: F,
+) -> Result<F::Output> {
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, future)
+            .await
+            .map_err(|_| yaak_http::error::Error::RequestTimeout(timeout).into()),
+        None => Ok(future.await),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(url: &str, headers: &[(&str, &str)], body: &str) -> SentRequest {
+        SentRequest {
+            method: "POST".to_string(),
+            url: url.to_string(),
+            headers: headers.iter().map(|(n, v)| (n.to_string(), v.to_string())).collect(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn detects_malformed_requests() {
+        let url = "https://example.com/login";
+        assert!(!needs_raw(&request(url, &[("Content-Length", "4")], "user")));
+        assert!(needs_raw(&request(url, &[("X-Test", "a\r\nInjected: 1")], "")));
+        assert!(needs_raw(&request(url, &[("Content-Length ", "4")], "user")));
+        assert!(needs_raw(&request(
+            url,
+            &[("Content-Length", "4"), ("Content-Length", "0")],
+            "user"
+        )));
+        assert!(needs_raw(&request(url, &[("Content-Length", "9")], "user")));
+        assert!(needs_raw(&request("https://example.com/a/../admin", &[], "")));
+        assert!(!needs_raw(&request(url, &[("Host", "a"), ("host", "b")], "")));
+        assert!(needs_raw(&request(url, &[("Host", "a"), ("X-A", "1"), ("Host", "b")], "")));
+        assert!(needs_raw(&request("https://example.com/a%0d%0a\r\nX: 1", &[], "")));
+        assert!(!needs_raw(&request("https://example.com/?q=' or 1=1", &[], "")));
+    }
+
+    #[test]
+    fn serializes_headers_as_written() {
+        let request =
+            request("http://example.com:8080/login?next=/", &[("X-A", "1"), ("x-a", "2")], "user");
+        let target = RawTarget::parse(&request.url).unwrap();
+        assert_eq!((target.host.as_str(), target.port, target.tls), ("example.com", 8080, false));
+        assert_eq!(
+            String::from_utf8(serialize_request(&request, &target)).unwrap(),
+            "POST /login?next=/ HTTP/1.1\r\nHost: example.com:8080\r\nX-A: 1\r\nx-a: 2\r\n\
+             Content-Length: 4\r\nConnection: close\r\n\r\nuser"
+        );
+    }
+
+    #[test]
+    fn parses_responses() {
+        let chunked = b"HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 200 OK\r\n\
+            Transfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n2\r\nde\r\n0\r\n\r\n";
+        let (status, version, headers, body) =
+            parse_response(chunked, false, false).unwrap().unwrap();
+        assert_eq!((status, body.as_slice()), (200, b"abcde".as_slice()));
+        assert_eq!(version, "HTTP/1.1");
+        assert_eq!(headers[0].0, "Transfer-Encoding");
+
+        let partial = b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nabc";
+        assert!(parse_response(partial, false, false).unwrap().is_none());
+        let unframed = b"HTTP/1.0 404 Not Found\r\n\r\nmissing";
+        assert!(parse_response(unframed, false, false).unwrap().is_none());
+        let (_, version, _, body) = parse_response(unframed, false, true).unwrap().unwrap();
+        assert_eq!((version.as_str(), body.as_slice()), ("HTTP/1.0", b"missing".as_slice()));
+    }
+}
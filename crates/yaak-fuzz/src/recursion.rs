@@ -0,0 +1,73 @@
+//! Descending into paths found by a content discovery run
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::markers::{MARKER, escape_markers};
+use crate::types::FuzzRequest;
+use yaak_templates::escape::escape_template;
+
+/// Check that a run with recursion fuzzes the last segment of its URL path
+pub(crate) fn validate_recursion(config: &FuzzRequest) -> Result<()> {
+    if config.recursion.is_none() {
+        return Ok(());
+    }
+    let url = &config.request.url;
+    let path = &url[..url.find(['?', '#']).unwrap_or(url.len())];
+    let last_segment = path.rsplit('/').next().unwrap_or_default();
+    let marked = match config.marker.as_deref() {
+        Some(keyword) if !keyword.is_empty() => last_segment.contains(keyword),
+        _ => last_segment.contains(MARKER),
+    };
+    if marked {
+        Ok(())
+    } else {
+        Err(GenericError("Recursion needs a payload position in the last path segment".to_string()))
+    }
+}
+
+/// `config` fuzzing the path below `found`, the URL a matched result was
+/// sent to. Query parameters are already part of `found`, so the parameter
+/// list is cleared.
+pub(crate) fn child_config(config: &FuzzRequest, found: &str) -> FuzzRequest {
+    let (path, rest) = found.split_at(found.find(['?', '#']).unwrap_or(found.len()));
+    let escape = |text: &str| escape_template(&escape_markers(text));
+    let position = match config.marker.as_deref() {
+        Some(keyword) if !keyword.is_empty() => keyword.to_string(),
+        _ => format!("{MARKER}{MARKER}"),
+    };
+    let url = format!("{}/{position}{}", escape(path.trim_end_matches('/')), escape(rest));
+
+    let mut config = config.clone();
+    config.request.url = url;
+    config.request.url_parameters.clear();
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FuzzRecursion;
+    use yaak_models::models::HttpRequest;
+
+    fn config(url: &str) -> FuzzRequest {
+        FuzzRequest {
+            request: HttpRequest { url: url.to_string(), ..Default::default() },
+            recursion: Some(FuzzRecursion { max_depth: 2 }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn requires_marked_last_segment() {
+        assert!(validate_recursion(&config("${[ base ]}/files/§dir§?v=1")).is_ok());
+        assert!(validate_recursion(&config("https://example.com/§dir§/index.php")).is_err());
+    }
+
+    #[test]
+    fn fuzzes_below_found_path() {
+        let child =
+            child_config(&config("https://example.com/§dir§"), "https://example.com/admin/?v=1");
+        assert_eq!(child.request.url, "https://example.com/admin/§§?v=1");
+        assert!(validate_recursion(&child).is_ok());
+    }
+}
@@ -0,0 +1,290 @@
+//! Where a payload reflects in an HTML response, and whether it can break
+//! out of that context there. Only exact reflections are found, so encoded
+//! ones count as safe.
+
+use crate::sender::FuzzResponse;
+
+/// Extracted value holding the contexts a result's payload reflects in
+pub(crate) const REFLECTION_VALUE: &str = "reflection";
+
+/// Reflections of a payload that are analyzed
+const MAX_REFLECTIONS: usize = 10;
+
+/// Attributes whose value is loaded as a URL, so a `javascript:` URL runs
+const URL_ATTRIBUTES: &[&str] = &["href", "src", "action", "formaction", "data", "srcdoc"];
+
+/// Elements whose content is text up to their closing tag
+const RAW_TEXT_ELEMENTS: &[&str] = &["style", "textarea", "title", "noscript", "xmp"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ReflectionContext {
+    /// Between tags
+    Text,
+    /// Inside a tag, where attributes go
+    Tag,
+    Attribute {
+        name: String,
+        quote: Option<u8>,
+    },
+    Comment,
+    /// Script code, or a string in it
+    Script {
+        quote: Option<u8>,
+    },
+    /// Content of an element like `<title>` that can't hold tags
+    RawText {
+        element: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Reflection {
+    pub context: ReflectionContext,
+    pub exploitable: bool,
+}
+
+impl Reflection {
+    fn describe(&self) -> String {
+        let quoted = |quote: Option<u8>| match quote {
+            Some(b'"') => "double-quoted",
+            Some(b'\'') => "single-quoted",
+            Some(_) => "backtick-quoted",
+            None => "unquoted",
+        };
+        let context = match &self.context {
+            ReflectionContext::Text => "HTML text".to_string(),
+            ReflectionContext::Tag => "tag".to_string(),
+            ReflectionContext::Attribute { name, quote } => {
+                format!("{} attribute {name}", quoted(*quote))
+            }
+            ReflectionContext::Comment => "comment".to_string(),
+            ReflectionContext::Script { quote: None } => "script".to_string(),
+            ReflectionContext::Script { quote } => format!("{} script string", quoted(*quote)),
+            ReflectionContext::RawText { element } => format!("<{element}> text"),
+        };
+        let verdict = if self.exploitable { "exploitable" } else { "contained" };
+        format!("{context} ({verdict})")
+    }
+}
+
+/// The contexts `payload` reflects in, for HTML responses
+pub(crate) fn reflections(response: &FuzzResponse, payload: &str) -> Vec<Reflection> {
+    let html = response.header("content-type").is_none_or(|t| t.contains("html"));
+    if payload.is_empty() || !html {
+        return Vec::new();
+    }
+    let body = response.body_text();
+    body.match_indices(payload)
+        .take(MAX_REFLECTIONS)
+        .map(|(at, _)| {
+            let context = context_at(body.as_bytes(), at);
+            let exploitable = breaks_out(&context, payload);
+            Reflection { context, exploitable }
+        })
+        .collect()
+}
+
+/// Every reflection, like `HTML text (exploitable); comment (contained)`
+pub(crate) fn describe_reflections(reflections: &[Reflection]) -> String {
+    reflections.iter().map(Reflection::describe).collect::<Vec<_>>().join("; ")
+}
+
+#[derive(Clone)]
+enum State {
+    Text,
+    /// In a tag named `element`, empty for closing tags
+    Tag {
+        element: String,
+    },
+    Value {
+        element: String,
+        name: String,
+        quote: Option<u8>,
+    },
+    Comment,
+    Script {
+        quote: Option<u8>,
+    },
+    RawText {
+        element: String,
+    },
+}
+
+/// The context at byte `end` of `html`, found by tokenizing up to it
+fn context_at(html: &[u8], end: usize) -> ReflectionContext {
+    let starts = |at: usize, prefix: &str| {
+        html.get(at..at + prefix.len()).is_some_and(|s| s.eq_ignore_ascii_case(prefix.as_bytes()))
+    };
+    let word = |at: usize, stop: &dyn Fn(u8) -> bool| {
+        let len = html[at..end].iter().take_while(|b| !stop(**b)).count();
+        (String::from_utf8_lossy(&html[at..at + len]).to_ascii_lowercase(), at + len)
+    };
+
+    let mut state = State::Text;
+    let mut i = 0;
+    while i < end {
+        let b = html[i];
+        match &mut state {
+            State::Text if starts(i, "<!--") => {
+                state = State::Comment;
+                i += 4;
+            }
+            State::Text if b == b'<' && html.get(i + 1).is_some_and(u8::is_ascii_alphabetic) => {
+                let (element, next) = word(i + 1, &|b| !b.is_ascii_alphanumeric());
+                state = State::Tag { element };
+                i = next;
+            }
+            State::Text if starts(i, "</") => {
+                state = State::Tag { element: String::new() };
+                i += 2;
+            }
+            State::Tag { element } if b == b'>' => {
+                state = match element.as_str() {
+                    "script" => State::Script { quote: None },
+                    e if RAW_TEXT_ELEMENTS.contains(&e) => State::RawText { element: e.into() },
+                    _ => State::Text,
+                };
+                i += 1;
+            }
+            State::Tag { element } if !b.is_ascii_whitespace() && b != b'/' => {
+                let element = std::mem::take(element);
+                let (name, mut next) = word(i, &|b| b.is_ascii_whitespace() || b"=>/".contains(&b));
+                while next < end && html[next].is_ascii_whitespace() {
+                    next += 1;
+                }
+                if next < end && html[next] == b'=' {
+                    next += 1;
+                    while next < end && html[next].is_ascii_whitespace() {
+                        next += 1;
+                    }
+                    let quote = html.get(next).copied().filter(|q| *q == b'"' || *q == b'\'');
+                    next += usize::from(quote.is_some());
+                    state = State::Value { element, name, quote };
+                } else {
+                    state = State::Tag { element };
+                }
+                i = next.max(i + 1);
+            }
+            State::Value { element, quote: Some(quote), .. } if b == *quote => {
+                state = State::Tag { element: std::mem::take(element) };
+                i += 1;
+            }
+            State::Value { element, quote: None, .. } if b.is_ascii_whitespace() || b == b'>' => {
+                state = State::Tag { element: std::mem::take(element) };
+            }
+            State::Comment if starts(i, "-->") => {
+                state = State::Text;
+                i += 3;
+            }
+            State::Script { quote: None } if starts(i, "</script") => {
+                state = State::Tag { element: String::new() };
+                i += 8;
+            }
+            State::Script { quote: None } if b"\"'`".contains(&b) => {
+                state = State::Script { quote: Some(b) };
+                i += 1;
+            }
+            State::Script { quote: Some(_) } if b == b'\\' => i += 2,
+            State::Script { quote: Some(quote) } if b == *quote => {
+                state = State::Script { quote: None };
+                i += 1;
+            }
+            State::RawText { element } if starts(i, &format!("</{element}")) => {
+                i += 2 + element.len();
+                state = State::Tag { element: String::new() };
+            }
+            _ => i += 1,
+        }
+    }
+
+    match state {
+        State::Text => ReflectionContext::Text,
+        State::Tag { .. } => ReflectionContext::Tag,
+        State::Value { name, quote, .. } => ReflectionContext::Attribute { name, quote },
+        State::Comment => ReflectionContext::Comment,
+        State::Script { quote } => ReflectionContext::Script { quote },
+        State::RawText { element } => ReflectionContext::RawText { element },
+    }
+}
+
+/// Whether `payload`, reflected as it is in `context`, can start markup or
+/// script of its own
+fn breaks_out(context: &ReflectionContext, payload: &str) -> bool {
+    let lower = payload.to_ascii_lowercase();
+    let opens_tag =
+        payload.as_bytes().windows(2).any(|w| w[0] == b'<' && w[1].is_ascii_alphabetic());
+    match context {
+        ReflectionContext::Text => opens_tag,
+        ReflectionContext::Tag => payload.contains(['=', '>']),
+        ReflectionContext::Attribute { name, quote } => {
+            let escapes = match quote {
+                Some(quote) => payload.as_bytes().contains(quote),
+                None => payload.contains(|c: char| c.is_ascii_whitespace() || c == '>'),
+            };
+            let runs_script = name.starts_with("on")
+                || (URL_ATTRIBUTES.contains(&name.as_str())
+                    && lower.trim_start().starts_with("javascript:"));
+            escapes || runs_script
+        }
+        ReflectionContext::Comment => lower.contains("-->") || lower.contains("--!>"),
+        ReflectionContext::Script { quote: None } => true,
+        ReflectionContext::Script { quote: Some(quote) } => {
+            payload.as_bytes().contains(quote) || lower.contains("</script")
+        }
+        ReflectionContext::RawText { element } => lower.contains(&format!("</{element}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_context_of_each_reflection() {
+        let response = |body: &str| FuzzResponse {
+            headers: vec![("Content-Type".to_string(), "text/html".to_string())],
+            body: body.into(),
+            ..Default::default()
+        };
+        let contexts = |body: &str, payload: &str| {
+            reflections(&response(body), payload)
+                .into_iter()
+                .map(|r| (r.context, r.exploitable))
+                .collect::<Vec<_>>()
+        };
+
+        let payload = "x\"><svg>";
+        assert_eq!(
+            contexts(&format!("<p>{payload}</p><input value=\"{payload}\">"), payload),
+            vec![
+                (ReflectionContext::Text, true),
+                (ReflectionContext::Attribute { name: "value".into(), quote: Some(b'"') }, true),
+            ]
+        );
+        assert_eq!(
+            contexts("<input value='a\"b'>", "a\"b"),
+            vec![(
+                ReflectionContext::Attribute { name: "value".into(), quote: Some(b'\'') },
+                false
+            )]
+        );
+        assert_eq!(
+            contexts("<script>var q = \"it's\";</script>", "it's"),
+            vec![(ReflectionContext::Script { quote: Some(b'"') }, false)]
+        );
+        assert_eq!(
+            contexts("<title>a</title> <!-- a --> <div onclick=\"a\">", "a"),
+            vec![
+                (ReflectionContext::RawText { element: "title".into() }, false),
+                (ReflectionContext::Comment, false),
+                (ReflectionContext::Attribute { name: "onclick".into(), quote: Some(b'"') }, true),
+            ]
+        );
+        let json = FuzzResponse {
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: b"<svg>".to_vec(),
+            ..Default::default()
+        };
+        assert!(reflections(&json, "<svg>").is_empty());
+    }
+}
@@ -0,0 +1,169 @@
+use crate::columns::apply_columns;
+use crate::diff::{FuzzResultDiff, diff_results};
+use crate::engine::{apply_response, apply_verdict_script, duration_to_i32};
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::matchers::{ResponseMatcher, ValueExtractors};
+use crate::safe_mode::check_safe_method;
+use crate::sender::{FuzzSender, SentRequest};
+use crate::types::FuzzRequest;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use ts_rs::TS;
+use yaak_models::models::FuzzResult;
+
+/// A result's stored request sent again, compared with the original response
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzReplay {
+    /// Evaluated with the run's matchers and extractors, but not persisted
+    pub result: FuzzResult,
+    pub diff: FuzzResultDiff,
+}
+
+/// The request a result sent, given its stored body. Results recorded before
+/// requests were stored can't be replayed.
+pub fn stored_request(result: &FuzzResult, body: Vec<u8>) -> Result<SentRequest> {
+    if result.url.is_empty() {
+        return Err(GenericError("Result has no stored request to replay".to_string()));
+    }
+    Ok(SentRequest {
+        method: result.method.clone(),
+        url: result.url.clone(),
+        headers: result.request_headers.iter().map(|h| (h.name.clone(), h.value.clone())).collect(),
+        body,
+    })
+}
+
+/// Send `request` again exactly as it was stored for `original`, instead of
+/// rendering the template again, so values from template functions like
+/// timestamps or random IDs stay the same. Follow-up requests are not sent.
+pub async fn replay_result<S: FuzzSender>(
+    sender: &S,
+    config: &FuzzRequest,
+    original: &FuzzResult,
+    original_body: &[u8],
+    request: &SentRequest,
+) -> Result<FuzzReplay> {
+    check_safe_method(config, &request.method)?;
+    let matcher = ResponseMatcher::new(&config.matchers, config.match_mode)?
+        .with_filters(&config.filters)?
+        .with_verdicts(&config.verdicts)?
+        .detecting_open_redirects(config.open_redirect)
+        .analyzing_reflections(config.reflection_analysis);
+    let extractors = ValueExtractors::new(&config.extractors)?;
+    let mut result = FuzzResult {
+        model: "fuzz_result".to_string(),
+        workspace_id: original.workspace_id.clone(),
+        run_id: original.run_id.clone(),
+        payload_index: original.payload_index,
+        payload: original.payload.clone(),
+        base_url: original.base_url.clone(),
+        method: request.method.clone(),
+        url: request.url.clone(),
+        ..Default::default()
+    };
+
+    let mut body = Vec::new();
+    let started_at = Instant::now();
+    match sender.send_exact(&original.workspace_id, request).await {
+        Ok(response) => {
+            (body, _) = apply_response(&mut result, response, &matcher, &extractors);
+            apply_verdict_script(sender, config, &mut result, &body).await;
+            apply_columns(sender, config, &mut result, &body).await;
+        }
+        Err(e) => {
+            result.error_kind = Some(e.kind());
+            result.error = Some(e.to_string());
+        }
+    }
+    result.elapsed = duration_to_i32(started_at.elapsed());
+
+    let diff = diff_results(original, original_body, &result, &body);
+    Ok(FuzzReplay { result, diff })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sender::FuzzResponse;
+    use crate::types::FuzzMatcher;
+    use async_trait::async_trait;
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+    use yaak_http::cookies::CookieStore;
+    use yaak_models::models::{HttpRequest, HttpResponseHeader};
+
+    /// Echoes the request body and records what it was asked to send exactly
+    #[derive(Default)]
+    struct EchoSender {
+        sent: Mutex<Vec<SentRequest>>,
+    }
+
+    #[async_trait]
+    impl FuzzSender for EchoSender {
+        async fn send(
+            &self,
+            _request: &HttpRequest,
+            _variables: &BTreeMap<String, String>,
+            _cookies: Option<&CookieStore>,
+        ) -> Result<FuzzResponse> {
+            Err(GenericError("templates must not be rendered".to_string()))
+        }
+
+        async fn send_exact(
+            &self,
+            _workspace_id: &str,
+            request: &SentRequest,
+        ) -> Result<FuzzResponse> {
+            self.sent.lock().unwrap().push(request.clone());
+            Ok(FuzzResponse {
+                url: request.url.clone(),
+                status: 200,
+                body: request.body.clone(),
+                request: request.clone(),
+                ..Default::default()
+            })
+        }
+
+        async fn refresh_auth(&self, _request: &HttpRequest, _action: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn replays_stored_request() {
+        let original = FuzzResult {
+            payload: "admin".to_string(),
+            method: "POST".to_string(),
+            url: "https://example.com/login".to_string(),
+            request_headers: vec![HttpResponseHeader {
+                name: "X-Nonce".to_string(),
+                value: "1700000000".to_string(),
+            }],
+            status: 401,
+            ..Default::default()
+        };
+        let request = stored_request(&original, b"user=admin&ts=1700000000".to_vec()).unwrap();
+        let config = FuzzRequest {
+            matchers: vec![FuzzMatcher::Status { codes: vec![200] }],
+            ..Default::default()
+        };
+
+        let sender = EchoSender::default();
+        let replay = replay_result(&sender, &config, &original, b"denied", &request).await.unwrap();
+
+        let sent = sender.sent.lock().unwrap();
+        assert_eq!(sent[0].headers, vec![("X-Nonce".to_string(), "1700000000".to_string())]);
+        assert_eq!(sent[0].body, b"user=admin&ts=1700000000");
+        assert!(replay.result.matched);
+        assert_eq!(replay.result.payload, "admin");
+        assert_eq!((replay.diff.status_a, replay.diff.status_b), (401, 200));
+    }
+
+    #[test]
+    fn requires_stored_request() {
+        assert!(stored_request(&FuzzResult::default(), Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,131 @@
+//! Header values a run changes from one request to the next, so its traffic
+//! doesn't share a fingerprint
+
+use crate::types::{FuzzProxyRotation, FuzzRequest};
+use std::net::Ipv4Addr;
+use yaak_models::models::{HttpRequest, HttpRequestHeader};
+
+/// `request` with the run's rotating headers set for the request at `index`
+pub(crate) fn rotate_headers(
+    config: &FuzzRequest,
+    mut request: HttpRequest,
+    index: usize,
+) -> HttpRequest {
+    let user_agents = &config.user_agents;
+    if let Some(user_agent) = pick(&user_agents.values, user_agents.rotation, index) {
+        set_header(&mut request, "User-Agent", user_agent);
+    }
+
+    // Every spoofed header carries the same address, like a single proxy
+    // would send
+    let spoof = &config.spoof_headers;
+    if !spoof.names.is_empty() {
+        let address = match pick(&spoof.values, spoof.rotation, index) {
+            Some(address) => address.to_string(),
+            None => random_address().to_string(),
+        };
+        for name in &spoof.names {
+            set_header(&mut request, name, &address);
+        }
+    }
+    request
+}
+
+/// A random unicast IPv4 address
+fn random_address() -> Ipv4Addr {
+    let [_, b, c, d] = rand::random::<[u8; 4]>();
+    Ipv4Addr::new(rand::random_range(1..224), b, c, d)
+}
+
+/// The value for the request at `index`, or `None` without any values
+fn pick(values: &[String], rotation: FuzzProxyRotation, index: usize) -> Option<&str> {
+    if values.is_empty() {
+        return None;
+    }
+    let index = match rotation {
+        FuzzProxyRotation::RoundRobin => index % values.len(),
+        FuzzProxyRotation::Random => rand::random_range(0..values.len()),
+    };
+    Some(&values[index])
+}
+
+/// Replace every `name` header of `request`, including disabled ones, so
+/// the value also wins over inherited headers
+fn set_header(request: &mut HttpRequest, name: &str, value: &str) {
+    request.headers.retain(|h| !h.name.eq_ignore_ascii_case(name));
+    request.headers.push(HttpRequestHeader {
+        enabled: true,
+        name: name.to_string(),
+        value: value.to_string(),
+        id: None,
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FuzzSpoofHeaders, FuzzUserAgents};
+
+    #[test]
+    fn rotates_user_agents() {
+        let config = FuzzRequest {
+            request: HttpRequest {
+                headers: vec![HttpRequestHeader {
+                    enabled: true,
+                    name: "user-agent".to_string(),
+                    value: "yaak".to_string(),
+                    id: None,
+                }],
+                ..Default::default()
+            },
+            user_agents: FuzzUserAgents {
+                values: vec!["curl/8.0".to_string(), "Mozilla/5.0".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let user_agents = (0..3)
+            .map(|i| rotate_headers(&config, config.request.clone(), i).headers)
+            .map(|headers| (headers.len(), headers[0].value.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            user_agents,
+            vec![
+                (1, "curl/8.0".to_string()),
+                (1, "Mozilla/5.0".to_string()),
+                (1, "curl/8.0".to_string())
+            ]
+        );
+
+        let unchanged = FuzzRequest { user_agents: FuzzUserAgents::default(), ..config };
+        let request = rotate_headers(&unchanged, unchanged.request.clone(), 0);
+        assert_eq!(request.headers[0].value, "yaak");
+    }
+
+    #[test]
+    fn spoofs_one_address_per_request() {
+        let names = vec!["X-Forwarded-For".to_string(), "X-Real-IP".to_string()];
+        let config = FuzzRequest {
+            spoof_headers: FuzzSpoofHeaders {
+                names: names.clone(),
+                values: vec!["127.0.0.1".to_string(), "10.0.0.1".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let request = rotate_headers(&config, HttpRequest::default(), 1);
+        let headers = request.headers.iter().map(|h| (h.name.as_str(), h.value.as_str()));
+        assert_eq!(
+            headers.collect::<Vec<_>>(),
+            vec![("X-Forwarded-For", "10.0.0.1"), ("X-Real-IP", "10.0.0.1")]
+        );
+
+        let random = FuzzRequest {
+            spoof_headers: FuzzSpoofHeaders { names, ..Default::default() },
+            ..Default::default()
+        };
+        let request = rotate_headers(&random, HttpRequest::default(), 0);
+        assert!(request.headers[0].value.parse::<Ipv4Addr>().is_ok());
+        assert_eq!(request.headers[0].value, request.headers[1].value);
+    }
+}
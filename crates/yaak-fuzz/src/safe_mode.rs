@@ -0,0 +1,66 @@
+//! Runs that only send methods without side effects, for targets like
+//! production where a stray POST or DELETE could change data
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::types::{FuzzRequest, FuzzTarget};
+
+/// Methods that only read, per RFC 9110
+const SAFE_METHODS: &[&str] = &["GET", "HEAD", "OPTIONS", "TRACE"];
+
+/// Check the methods a safe mode run sends regardless of its payloads. The
+/// fuzzed request's is only checked here when it isn't a payload position.
+pub(crate) fn validate_safe_mode(config: &FuzzRequest) -> Result<()> {
+    if !config.safe_mode {
+        return Ok(());
+    }
+    if !config.targets.contains(&FuzzTarget::Method) {
+        check_safe_method(config, &config.request.method)?;
+    }
+    if let Some(pre_request) = &config.pre_request {
+        check_safe_method(config, &pre_request.request.method)?;
+    }
+    if let Some(follow_up) = &config.follow_up {
+        check_safe_method(config, &follow_up.request.method)?;
+    }
+    Ok(())
+}
+
+/// Refuse to send `method` during a safe mode run unless it's a safe method
+pub(crate) fn check_safe_method(config: &FuzzRequest, method: &str) -> Result<()> {
+    let method = method.trim();
+    if !config.safe_mode || SAFE_METHODS.iter().any(|m| m.eq_ignore_ascii_case(method)) {
+        return Ok(());
+    }
+    Err(GenericError(format!("Safe mode blocks {method} requests. Turn it off to send them.")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FuzzFollowUp;
+    use yaak_models::models::HttpRequest;
+
+    #[test]
+    fn blocks_methods_with_side_effects() {
+        let request = |method: &str| HttpRequest {
+            method: method.to_string(),
+            url: "https://example.com/".to_string(),
+            ..Default::default()
+        };
+        let config = FuzzRequest { request: request("get"), safe_mode: true, ..Default::default() };
+        assert!(validate_safe_mode(&config).is_ok());
+        assert!(check_safe_method(&config, "DELETE").is_err());
+
+        let posts = FuzzRequest { request: request("POST"), ..config.clone() };
+        assert!(validate_safe_mode(&posts).is_err());
+        assert!(validate_safe_mode(&FuzzRequest { safe_mode: false, ..posts.clone() }).is_ok());
+
+        // Method payloads are checked as each request is sent
+        let fuzzed = FuzzRequest { targets: vec![FuzzTarget::Method], ..posts.clone() };
+        assert!(validate_safe_mode(&fuzzed).is_ok());
+
+        let follow_up = Some(FuzzFollowUp { request: request("PUT") });
+        assert!(validate_safe_mode(&FuzzRequest { follow_up, ..config }).is_err());
+    }
+}
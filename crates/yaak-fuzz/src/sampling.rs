@@ -0,0 +1,83 @@
+//! A subset of a large payload space, sent as a smoke test before the full
+//! run
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::types::{FuzzRequest, FuzzSample};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// Check that a run's sample keeps at least one payload
+pub(crate) fn validate_sample(config: &FuzzRequest) -> Result<()> {
+    match config.sample {
+        None => Ok(()),
+        Some(FuzzSample::Percent { percent, .. }) if !(percent > 0.0 && percent <= 100.0) => {
+            Err(GenericError(format!("Sample percentage {percent} isn't between 0 and 100")))
+        }
+        Some(
+            FuzzSample::First { count: 0 }
+            | FuzzSample::Last { count: 0 }
+            | FuzzSample::Random { count: 0, .. },
+        ) => Err(GenericError("Sample at least one payload".to_string())),
+        Some(_) => Ok(()),
+    }
+}
+
+/// The payloads `sample` keeps, in their original order
+pub(crate) fn sample_payloads(mut payloads: Vec<String>, sample: FuzzSample) -> Vec<String> {
+    let len = payloads.len();
+    let (count, seed) = match sample {
+        FuzzSample::First { count } => {
+            payloads.truncate(count);
+            return payloads;
+        }
+        FuzzSample::Last { count } => {
+            payloads.drain(..len.saturating_sub(count));
+            return payloads;
+        }
+        FuzzSample::Percent { percent, seed } => {
+            ((len as f64 * percent / 100.0).ceil() as usize, seed)
+        }
+        FuzzSample::Random { count, seed } => (count, seed),
+    };
+    let count = count.min(len);
+    let indexes = match seed {
+        Some(seed) => rand::seq::index::sample(&mut StdRng::seed_from_u64(seed.into()), len, count),
+        None => rand::seq::index::sample(&mut rand::rng(), len, count),
+    };
+
+    let mut keep = vec![false; len];
+    for index in indexes {
+        keep[index] = true;
+    }
+    let mut keep = keep.into_iter();
+    payloads.retain(|_| keep.next().unwrap_or(false));
+    payloads
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_a_subset_in_order() {
+        let payloads = (0..10).map(|i| i.to_string()).collect::<Vec<_>>();
+        let sample = |s| sample_payloads(payloads.clone(), s);
+        assert_eq!(sample(FuzzSample::First { count: 2 }), vec!["0", "1"]);
+        assert_eq!(sample(FuzzSample::Last { count: 2 }), vec!["8", "9"]);
+        assert_eq!(sample(FuzzSample::Last { count: 20 }).len(), 10);
+
+        let random = sample(FuzzSample::Random { count: 4, seed: Some(7) });
+        assert_eq!(random.len(), 4);
+        assert_eq!(random, sample(FuzzSample::Random { count: 4, seed: Some(7) }));
+        assert!(random.windows(2).all(|w| w[0].parse::<u32>().unwrap() < w[1].parse().unwrap()));
+        assert_eq!(sample(FuzzSample::Percent { percent: 25.0, seed: Some(1) }).len(), 3);
+
+        let config = |sample| FuzzRequest { sample: Some(sample), ..Default::default() };
+        assert!(validate_sample(&config(FuzzSample::Percent { percent: 0.5, seed: None })).is_ok());
+        assert!(
+            validate_sample(&config(FuzzSample::Percent { percent: 0.0, seed: None })).is_err()
+        );
+        assert!(validate_sample(&config(FuzzSample::First { count: 0 })).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+//! The hosts and networks a workspace's fuzz runs may send requests to, so
+//! payloads and redirects can't move a run to another target
+
+use crate::dns::override_addresses;
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use std::net::IpAddr;
+use tokio::net::lookup_host;
+use url::{Host, Url};
+use yaak_models::models::DnsOverride;
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Scope {
+    /// Lowercase hostnames, with a leading `*.` for any subdomain
+    hosts: Vec<String>,
+    networks: Vec<(IpAddr, u8)>,
+}
+
+impl Scope {
+    /// Parse a workspace's entries: hostnames, `*.example.com` for the
+    /// subdomains of a domain, and addresses or CIDR networks
+    pub(crate) fn parse(entries: &[String]) -> Result<Scope> {
+        let mut scope = Scope::default();
+        for entry in entries.iter().map(|e| e.trim()).filter(|e| !e.is_empty()) {
+            let (address, prefix) = match entry.split_once('/') {
+                Some((address, prefix)) => (address, Some(prefix)),
+                None => (entry, None),
+            };
+            let Ok(address) = address.trim_matches(['[', ']']).parse::<IpAddr>() else {
+                if prefix.is_some() {
+                    return Err(GenericError(format!("Invalid network {entry} in the fuzz scope")));
+                }
+                scope.hosts.push(entry.trim_end_matches('.').to_lowercase());
+                continue;
+            };
+            let max = if address.is_ipv4() { 32 } else { 128 };
+            let prefix = match prefix {
+                None => max,
+                Some(prefix) => {
+                    prefix.parse::<u8>().ok().filter(|p| *p <= max).ok_or_else(|| {
+                        GenericError(format!("Invalid network {entry} in the fuzz scope"))
+                    })?
+                }
+            };
+            scope.networks.push((address, prefix));
+        }
+        Ok(scope)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.hosts.is_empty() && self.networks.is_empty()
+    }
+
+    /// Whether `url`'s host is in scope, resolving names with `overrides`
+    /// only. Any URL is when the scope is empty.
+    pub(crate) fn allows_url(&self, url: &str, overrides: &[DnsOverride]) -> bool {
+        match self.url_host(url) {
+            Ok(None) => true,
+            Ok(Some(Host::Domain(name))) => {
+                self.allows_addresses(&override_addresses(overrides, &name))
+            }
+            Ok(Some(_)) | Err(_) => false,
+        }
+    }
+
+    /// `url`'s host, unless it's an address or name in scope
+    fn url_host(&self, url: &str) -> Result<Option<Host<String>>> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let url = Url::parse(url).map_err(|e| GenericError(format!("Invalid URL {url}: {e}")))?;
+        let host = url.host().map(|h| h.to_owned());
+        let allowed = match &host {
+            None => false,
+            Some(Host::Domain(name)) => self.allows_name(name),
+            Some(Host::Ipv4(ip)) => self.allows_addresses(&[IpAddr::V4(*ip)]),
+            Some(Host::Ipv6(ip)) => self.allows_addresses(&[IpAddr::V6(*ip)]),
+        };
+        if allowed { Ok(None) } else { Ok(host) }
+    }
+
+    fn allows_name(&self, name: &str) -> bool {
+        let name = name.trim_end_matches('.').to_lowercase();
+        self.hosts.iter().any(|host| match host.strip_prefix("*.") {
+            Some(domain) => name.strip_suffix(domain).is_some_and(|sub| sub.ends_with('.')),
+            None => *host == name,
+        })
+    }
+
+    /// Whether any address was given and each is in one of the networks
+    fn allows_addresses(&self, addresses: &[IpAddr]) -> bool {
+        !addresses.is_empty()
+            && addresses.iter().all(|address| {
+                self.networks
+                    .iter()
+                    .any(|(network, prefix)| in_network(*address, *network, *prefix))
+            })
+    }
+}
+
+/// Refuse to send to `url` unless its host is in scope. Names outside the
+/// scope's hostnames are resolved like the client would, and are in scope
+/// when every address they resolve to is.
+pub(crate) async fn check_scope(scope: &Scope, url: &str, overrides: &[DnsOverride]) -> Result<()> {
+    let Some(host) = scope.url_host(url)? else {
+        return Ok(());
+    };
+    let in_scope = match &host {
+        Host::Domain(name) if !scope.networks.is_empty() => {
+            let mut addresses = override_addresses(overrides, name);
+            if addresses.is_empty() {
+                let port = Url::parse(url).ok().and_then(|u| u.port_or_known_default());
+                let resolved = lookup_host((name.as_str(), port.unwrap_or(80))).await?;
+                addresses = resolved.map(|a| a.ip()).collect();
+            }
+            scope.allows_addresses(&addresses)
+        }
+        _ => false,
+    };
+    if in_scope {
+        return Ok(());
+    }
+    Err(GenericError(format!("{host} is outside the workspace's fuzz scope")))
+}
+
+fn in_network(address: IpAddr, network: IpAddr, prefix: u8) -> bool {
+    match (address, network) {
+        (IpAddr::V4(address), IpAddr::V4(network)) => {
+            let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+            u32::from(address) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(address), IpAddr::V6(network)) => {
+            let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+            u128::from(address) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn only_sends_to_hosts_in_scope() {
+        let names = Scope::parse(&["api.example.com".to_string(), "*.internal.test".to_string()]);
+        let names = names.unwrap();
+        assert!(check_scope(&names, "https://API.example.com/a", &[]).await.is_ok());
+        assert!(check_scope(&names, "https://db.internal.test/", &[]).await.is_ok());
+        assert!(check_scope(&names, "https://internal.test/", &[]).await.is_err());
+        assert!(check_scope(&names, "https://example.com/", &[]).await.is_err());
+
+        let networks = Scope::parse(&["10.0.0.0/8".to_string(), "::1".to_string()]).unwrap();
+        assert!(check_scope(&networks, "http://10.1.2.3:8080/", &[]).await.is_ok());
+        assert!(check_scope(&networks, "http://11.0.0.1/", &[]).await.is_err());
+        assert!(check_scope(&networks, "http://[::1]/", &[]).await.is_ok());
+
+        let overrides = vec![DnsOverride {
+            hostname: "target.test".to_string(),
+            ipv4: vec!["10.0.0.5".to_string()],
+            ipv6: Vec::new(),
+            enabled: true,
+        }];
+        assert!(check_scope(&networks, "https://target.test/", &overrides).await.is_ok());
+        assert!(networks.allows_url("https://target.test/", &overrides));
+        assert!(!networks.allows_url("https://target.test/", &[]));
+
+        assert!(Scope::parse(&[]).unwrap().allows_url("https://anywhere.test/", &[]));
+        assert!(Scope::parse(&["10.0.0.0/33".to_string()]).is_err());
+        assert!(Scope::parse(&["example.com/8".to_string()]).is_err());
+    }
+}
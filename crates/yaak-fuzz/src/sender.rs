@@ -0,0 +1,1113 @@
+use crate::byte_payloads::{bytes_in_head, payload_bytes, show_bytes, splice_bytes};
+use crate::columns::render_variables;
+use crate::dns::{override_addresses, overrides_id};
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::http2::send_http2;
+use crate::proxies::ProxyRotator;
+use crate::raw::{RawConnection, RawTarget, needs_raw, send_raw};
+use crate::scope::{Scope, check_scope};
+use crate::signing::sign_request;
+use crate::tls::{certificate_id, client_certificate, tls_version};
+use crate::types::{
+    FuzzClientCertificate, FuzzCookies, FuzzHttp2, FuzzHttpVersion, FuzzProxies, FuzzRedirects,
+    FuzzRequest, FuzzSigning, FuzzTimeouts, FuzzTls, FuzzVhost,
+};
+use crate::vhost::{server_name, server_name_url};
+use async_trait::async_trait;
+use chrono::Utc;
+use opentelemetry::global;
+use opentelemetry::propagation::TextMapPropagator;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::AsyncReadExt;
+use tokio::net::lookup_host;
+use tokio::sync::{mpsc, watch};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use yaak::render::render_http_request;
+use yaak::send::{
+    HttpSendRuntimeConfig, PrepareSendableRequest, apply_plugin_authentication,
+    render_sendable_request, resolve_http_send_runtime_config, send_with_connection_manager,
+};
+use yaak_crypto::manager::EncryptionManager;
+use yaak_http::client::{HttpConnectionOptions, HttpVersionPolicy};
+use yaak_http::cookies::CookieStore;
+use yaak_http::dns::WILDCARD_HOSTNAME;
+use yaak_http::manager::HttpConnectionManager;
+use yaak_http::sender::ReqwestSender;
+use yaak_http::transaction::HttpTransaction;
+use yaak_http::types::{SendableBody, SendableHttpRequest};
+use yaak_models::db_context::DbContext;
+use yaak_models::models::{
+    CookieJar, DnsOverride, Environment, EnvironmentVariable, FuzzResult, HttpRequest,
+    HttpRequestHeader,
+};
+use yaak_models::queries::dedupe_headers;
+use yaak_models::query_manager::QueryManager;
+use yaak_models::render::make_vars_hashmap;
+use yaak_models::util::UpdateSource;
+use yaak_plugins::events::{
+    HttpHeader, JsonPrimitive, PluginContext, PrepareFuzzRequestRequest, RenderPurpose,
+    RunFuzzScriptRequest, RunFuzzScriptResponse,
+};
+use yaak_plugins::manager::PluginManager;
+use yaak_plugins::template_callback::PluginTemplateCallback;
+use yaak_templates::{RenderOptions, parse_and_render, render_json_value_raw};
+use yaak_tls::get_tls_config_for_versions;
+
+/// Redirects followed unless the run sets a limit, matching `HttpTransaction::new`
+const MAX_REDIRECTS: usize = 10;
+
+/// Fully-read response for a single fuzz request
+#[derive(Debug, Clone, Default)]
+pub struct FuzzResponse {
+    /// Final URL (after redirects)
+    pub url: String,
+    pub status: u16,
+    /// HTTP version the response came over, e.g. `HTTP/1.1` or `HTTP/2`
+    pub version: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// Time from sending the request until the response arrived
+    pub elapsed: Duration,
+    /// The request that produced this response
+    pub request: SentRequest,
+}
+
+/// A request exactly as it was sent, after rendering and payload injection
+#[derive(Debug, Clone, Default)]
+pub struct SentRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl SentRequest {
+    /// Size in bytes of the request as an HTTP/1.1 message: the request line,
+    /// the headers and the body. Headers the client adds on its own, like
+    /// `Host`, aren't counted.
+    pub fn size(&self) -> usize {
+        let target = RawTarget::request_target(&self.url);
+        let target = target.split('#').next().unwrap_or_default();
+        let target =
+            if target.starts_with('/') { target.to_string() } else { format!("/{target}") };
+        let request_line = format!("{} {target} HTTP/1.1\r\n", self.method);
+        let headers: usize = self.headers.iter().map(|(n, v)| format!("{n}: {v}\r\n").len()).sum();
+        request_line.len() + headers + "\r\n".len() + self.body.len()
+    }
+}
+
+impl FuzzResponse {
+    /// First header with the given name, compared case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+
+    pub fn body_text(&self) -> Cow<'_, str> {
+        String::from_utf8_lossy(&self.body)
+    }
+}
+
+/// Sends a single request on behalf of the engine. Abstracted so the engine
+/// can be exercised without a network.
+#[async_trait]
+pub trait FuzzSender: Send + Sync {
+    /// Render and send `request`. Entries in `variables` take precedence over
+    /// environment variables with the same name. `cookies` replaces the
+    /// sender's own cookie jar, e.g. with one that's scoped to a payload.
+    /// Implementations must render on every call, without caching template
+    /// function results, so anti-replay values like nonces stay unique.
+    async fn send(
+        &self,
+        request: &HttpRequest,
+        variables: &BTreeMap<String, String>,
+        cookies: Option<&CookieStore>,
+    ) -> Result<FuzzResponse>;
+
+    /// Send an already rendered request as-is, using the connection settings
+    /// of `workspace_id`
+    async fn send_exact(&self, workspace_id: &str, request: &SentRequest) -> Result<FuzzResponse>;
+
+    /// Run the auth plugin `action` of `request`'s authentication, e.g. to
+    /// discard an expired token so the next request gets a new one
+    async fn refresh_auth(&self, request: &HttpRequest, action: &str) -> Result<()>;
+
+    /// Run a verdict script on a result's response
+    async fn run_script(
+        &self,
+        _script: &str,
+        _result: &FuzzResult,
+        _body: &[u8],
+    ) -> Result<RunFuzzScriptResponse> {
+        Err(GenericError("Verdict scripts aren't supported here".to_string()))
+    }
+
+    /// Render a result column's template with `variables`
+    async fn render_template(
+        &self,
+        template: &str,
+        variables: &HashMap<String, String>,
+    ) -> Result<String> {
+        render_variables(template, variables).await
+    }
+}
+
+/// What a request inherits from its folders and workspace. It doesn't depend
+/// on the payload, so it's resolved once per run for each folder instead of
+/// for every request.
+struct Inherited {
+    environment_chain: Vec<Environment>,
+    authentication: (Option<String>, BTreeMap<String, serde_json::Value>, String),
+    headers: Vec<HttpRequestHeader>,
+    runtime_config: HttpSendRuntimeConfig,
+}
+
+/// Sendable request hook of a fuzz send. Byte payloads go in before auth, so
+/// schemes that sign the body sign the bytes that are sent.
+struct PayloadBytesAuthentication<'a> {
+    bytes: Option<Vec<u8>>,
+    plugin_manager: &'a PluginManager,
+    plugin_context: &'a PluginContext,
+}
+
+#[async_trait]
+impl PrepareSendableRequest for PayloadBytesAuthentication<'_> {
+    async fn prepare_sendable_request(
+        &self,
+        rendered_request: &HttpRequest,
+        auth_context_id: &str,
+        sendable_request: &mut SendableHttpRequest,
+    ) -> std::result::Result<(), String> {
+        if let (Some(bytes), Some(SendableBody::Bytes(body))) =
+            (&self.bytes, &mut sendable_request.body)
+        {
+            *body = splice_bytes(body, bytes).into();
+        }
+        apply_plugin_authentication(
+            sendable_request,
+            rendered_request,
+            auth_context_id,
+            self.plugin_manager,
+            self.plugin_context,
+        )
+        .await
+    }
+}
+
+/// Sends fuzz requests through yaak-http, using the workspace's environments,
+/// inherited auth and headers, and connection settings.
+pub struct HttpFuzzSender<'a> {
+    query_manager: QueryManager,
+    connection_manager: &'a HttpConnectionManager,
+    plugin_manager: Arc<PluginManager>,
+    plugin_context: PluginContext,
+    template_callback: PluginTemplateCallback,
+    environment_id: Option<String>,
+    environment_overrides: BTreeMap<String, String>,
+    cancelled_rx: watch::Receiver<bool>,
+    timeouts: FuzzTimeouts,
+    redirects: FuzzRedirects,
+    validate_certificates: Option<bool>,
+    proxies: ProxyRotator,
+    cookie_jar: Option<CookieJar>,
+    cookie_store: Option<CookieStore>,
+    signing: Option<FuzzSigning>,
+    prepare_with_plugins: bool,
+    vhost: Option<FuzzVhost>,
+    http2: Option<FuzzHttp2>,
+    http_version: FuzzHttpVersion,
+    fresh_connections: bool,
+    tls: FuzzTls,
+    client_certificate: FuzzClientCertificate,
+    dns_overrides: Vec<DnsOverride>,
+    local_address: Option<IpAddr>,
+    /// Keyed by workspace and folder ID
+    inherited: Mutex<HashMap<(String, Option<String>), Arc<Inherited>>>,
+}
+
+impl<'a> HttpFuzzSender<'a> {
+    pub fn new(
+        query_manager: QueryManager,
+        connection_manager: &'a HttpConnectionManager,
+        plugin_manager: Arc<PluginManager>,
+        encryption_manager: Arc<EncryptionManager>,
+        plugin_context: &PluginContext,
+        environment_id: Option<String>,
+        cancelled_rx: watch::Receiver<bool>,
+    ) -> Self {
+        let template_callback = PluginTemplateCallback::new(
+            plugin_manager.clone(),
+            encryption_manager,
+            plugin_context,
+            RenderPurpose::Send,
+        );
+        Self {
+            query_manager,
+            connection_manager,
+            plugin_manager,
+            plugin_context: plugin_context.clone(),
+            template_callback,
+            environment_id,
+            environment_overrides: BTreeMap::new(),
+            cancelled_rx,
+            timeouts: FuzzTimeouts::default(),
+            redirects: FuzzRedirects::default(),
+            validate_certificates: None,
+            proxies: ProxyRotator::new(&FuzzProxies::default()),
+            cookie_jar: None,
+            cookie_store: None,
+            signing: None,
+            prepare_with_plugins: false,
+            vhost: None,
+            http2: None,
+            http_version: FuzzHttpVersion::default(),
+            fresh_connections: false,
+            tls: FuzzTls::default(),
+            client_certificate: FuzzClientCertificate::default(),
+            dns_overrides: Vec::new(),
+            local_address: None,
+            inherited: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Apply every connection setting of `config`, as a run sends with them
+    pub fn with_config(self, config: &FuzzRequest) -> Result<Self> {
+        self.with_environment_overrides(config.environment_overrides.clone())
+            .with_timeouts(config.timeouts)
+            .with_redirects(config.followed_redirects())
+            .with_validate_certificates(config.validate_certificates)
+            .with_proxies(&config.proxies)
+            .with_signing(config.signing.clone())
+            .with_plugin_preparation(config.prepare_with_plugins)
+            .with_vhost(config.vhost.clone())
+            .with_http2(config.http2)
+            .with_http_version(config.http_version)
+            .with_fresh_connections(config.fresh_connections)
+            .with_tls(config.tls.clone())
+            .with_client_certificate(config.client_certificate.clone())
+            .with_dns_overrides(config.dns_overrides.clone())
+            .with_local_address(config.local_address.as_deref())
+            .with_cookies(&config.cookies)
+    }
+
+    /// Set variables over the resolved environments without changing them
+    pub fn with_environment_overrides(
+        mut self,
+        environment_overrides: BTreeMap<String, String>,
+    ) -> Self {
+        self.environment_overrides = environment_overrides;
+        self
+    }
+
+    /// Apply a run's timeouts instead of only the workspace's request timeout
+    pub fn with_timeouts(mut self, timeouts: FuzzTimeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Apply a run's redirect policy instead of the workspace's setting
+    pub fn with_redirects(mut self, redirects: FuzzRedirects) -> Self {
+        self.redirects = redirects;
+        self
+    }
+
+    /// Validate certificates or not regardless of the workspace's setting
+    pub fn with_validate_certificates(mut self, validate_certificates: Option<bool>) -> Self {
+        self.validate_certificates = validate_certificates;
+        self
+    }
+
+    /// Rotate through a run's proxies instead of the workspace's proxy setting
+    pub fn with_proxies(mut self, proxies: &FuzzProxies) -> Self {
+        self.proxies = ProxyRotator::new(proxies);
+        self
+    }
+
+    /// Send cookies from a run's jar. Per-payload jars are passed to `send`
+    /// by the engine instead.
+    pub fn with_cookies(mut self, cookies: &FuzzCookies) -> Result<Self> {
+        match cookies {
+            FuzzCookies::Off | FuzzCookies::PerPayload => {}
+            FuzzCookies::Workspace { cookie_jar_id } => {
+                let cookie_jar = self.query_manager.connect().get_cookie_jar(cookie_jar_id)?;
+                self.cookie_store = Some(CookieStore::from_cookies(cookie_jar.cookies.clone()));
+                self.cookie_jar = Some(cookie_jar);
+            }
+            FuzzCookies::PerRun => self.cookie_store = Some(CookieStore::new()),
+        }
+        Ok(self)
+    }
+
+    /// Sign every rendered request, after payloads are injected
+    pub fn with_signing(mut self, signing: Option<FuzzSigning>) -> Self {
+        self.signing = signing;
+        self
+    }
+
+    /// Let plugins change every request right before it's sent
+    pub fn with_plugin_preparation(mut self, prepare_with_plugins: bool) -> Self {
+        self.prepare_with_plugins = prepare_with_plugins;
+        self
+    }
+
+    /// Send a vhost run's TLS server name, connecting to the URL's address
+    /// whatever the name is
+    pub fn with_vhost(mut self, vhost: Option<FuzzVhost>) -> Self {
+        self.vhost = vhost;
+        self
+    }
+
+    /// Send every request over HTTP/2 with a run's h2 options
+    pub fn with_http2(mut self, http2: Option<FuzzHttp2>) -> Self {
+        self.http2 = http2;
+        self
+    }
+
+    /// Limit which HTTP versions the run's client may use
+    pub fn with_http_version(mut self, http_version: FuzzHttpVersion) -> Self {
+        self.http_version = http_version;
+        self
+    }
+
+    /// Open a new connection for every request
+    pub fn with_fresh_connections(mut self, fresh_connections: bool) -> Self {
+        self.fresh_connections = fresh_connections;
+        self
+    }
+
+    /// Limit TLS versions, replace the ALPN protocols or send another server
+    /// name, as a run sets them
+    pub fn with_tls(mut self, tls: FuzzTls) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Present a run's client certificate instead of the workspace's
+    pub fn with_client_certificate(mut self, client_certificate: FuzzClientCertificate) -> Self {
+        self.client_certificate = client_certificate;
+        self
+    }
+
+    /// Resolve hostnames to a run's addresses before the workspace's overrides
+    pub fn with_dns_overrides(mut self, dns_overrides: Vec<DnsOverride>) -> Self {
+        self.dns_overrides = dns_overrides;
+        self
+    }
+
+    /// Send from a run's local address. Addresses that don't parse are left
+    /// to validation to report.
+    pub fn with_local_address(mut self, local_address: Option<&str>) -> Self {
+        self.local_address = local_address.and_then(|a| a.trim().parse().ok());
+        self
+    }
+
+    /// Save the cookies received during the run to the shared workspace jar,
+    /// if there is one
+    pub fn save_cookies(&self) -> Result<()> {
+        if let (Some(cookie_jar), Some(cookie_store)) = (&self.cookie_jar, &self.cookie_store) {
+            let cookie_jar =
+                CookieJar { cookies: cookie_store.get_all_cookies(), ..cookie_jar.clone() };
+            self.query_manager
+                .connect()
+                .upsert_cookie_jar(&cookie_jar, &UpdateSource::Background)?;
+        }
+        Ok(())
+    }
+}
+
+impl HttpFuzzSender<'_> {
+    /// Render `request` the same way `send` does, without sending it
+    pub async fn render(
+        &self,
+        request: &HttpRequest,
+        variables: &BTreeMap<String, String>,
+    ) -> Result<SendableHttpRequest> {
+        Ok(self.prepare(request, variables).await?.0)
+    }
+
+    /// The request `send` would send for `request`, rendered and signed but
+    /// without cookies from the jar, which are only added while sending
+    pub async fn preview(
+        &self,
+        request: &HttpRequest,
+        variables: &BTreeMap<String, String>,
+    ) -> Result<SentRequest> {
+        let (mut request, _, signing) = self.prepare(request, variables).await?;
+        if self.prepare_with_plugins {
+            request = self.plugin_prepared(request).await?;
+        }
+        let mut sent = SentRequest {
+            method: request.method,
+            url: request.url,
+            headers: request.headers,
+            body: read_body(request.body).await?,
+        };
+        if let Some(bytes) = payload_bytes(variables)? {
+            sent.body = splice_bytes(&sent.body, &bytes);
+            show_bytes(&mut sent, &bytes);
+        }
+        if let Some(signing) = &signing {
+            sign_request(signing, &mut sent, Utc::now())?;
+        }
+        Ok(sent)
+    }
+
+    /// Render `request` into one that works without its original folders:
+    /// inherited auth and folder headers are copied in and template tags are
+    /// replaced with their values. Workspace headers are left out since any
+    /// request in the workspace inherits them.
+    pub async fn render_standalone(
+        &self,
+        request: &HttpRequest,
+        variables: &BTreeMap<String, String>,
+    ) -> Result<HttpRequest> {
+        let (request, mut environment_chain) = {
+            let db = self.query_manager.connect();
+            let environment_chain = self.environment_chain(&db, request)?;
+            let (authentication_type, authentication, _) =
+                db.resolve_auth_for_http_request(request)?;
+            let workspace = db.get_workspace(&request.workspace_id)?;
+            let workspace_headers = db.resolve_headers_for_workspace(&workspace);
+            let headers = db
+                .resolve_headers_for_http_request(request)?
+                .into_iter()
+                .filter(|h| !workspace_headers.contains(h))
+                .collect();
+
+            let mut request = request.clone();
+            request.authentication_type = authentication_type;
+            request.authentication = authentication;
+            request.headers = headers;
+            (request, environment_chain)
+        };
+
+        if !variables.is_empty() {
+            environment_chain.insert(0, variables_environment(variables));
+        }
+
+        Ok(render_http_request(
+            &request,
+            environment_chain,
+            &self.template_callback,
+            &RenderOptions::throw(),
+        )
+        .await?)
+    }
+
+    /// Environments `request` is rendered with, most specific first, with the
+    /// run's overrides in front
+    fn environment_chain(&self, db: &DbContext, request: &HttpRequest) -> Result<Vec<Environment>> {
+        let mut environment_chain = db.resolve_environments(
+            &request.workspace_id,
+            request.folder_id.as_deref(),
+            self.environment_id.as_deref(),
+        )?;
+        if !self.environment_overrides.is_empty() {
+            environment_chain.insert(0, variables_environment(&self.environment_overrides));
+        }
+        Ok(environment_chain)
+    }
+
+    /// Environments, auth, headers and connection settings `request` inherits,
+    /// resolved on first use. Changes made to them during a run only apply to
+    /// the next run.
+    fn inherited(&self, request: &HttpRequest) -> Result<Arc<Inherited>> {
+        let key = (request.workspace_id.clone(), request.folder_id.clone());
+        if let Some(inherited) = self.inherited.lock().unwrap().get(&key) {
+            return Ok(inherited.clone());
+        }
+
+        // A request without auth or headers of its own resolves to its parents'
+        let parent = HttpRequest {
+            workspace_id: request.workspace_id.clone(),
+            folder_id: request.folder_id.clone(),
+            ..Default::default()
+        };
+        let (environment_chain, authentication, headers) = {
+            let db = self.query_manager.connect();
+            let environment_chain = self.environment_chain(&db, request)?;
+            let authentication = db.resolve_auth_for_http_request(&parent)?;
+            let headers = db.resolve_headers_for_http_request(&parent)?;
+            (environment_chain, authentication, headers)
+        };
+        let runtime_config =
+            resolve_http_send_runtime_config(&self.query_manager, &request.workspace_id)?;
+
+        let inherited =
+            Arc::new(Inherited { environment_chain, authentication, headers, runtime_config });
+        self.inherited.lock().unwrap().insert(key, inherited.clone());
+        Ok(inherited)
+    }
+
+    /// Apply what `request` inherits and render it. Only rendering and auth
+    /// plugins run for every request, the rest is cached per run.
+    async fn prepare(
+        &self,
+        request: &HttpRequest,
+        variables: &BTreeMap<String, String>,
+    ) -> Result<(SendableHttpRequest, HttpSendRuntimeConfig, Option<FuzzSigning>)> {
+        let inherited = self.inherited(request)?;
+        let (authentication_type, authentication, auth_context_id) =
+            match request.authentication_type {
+                Some(_) => (
+                    request.authentication_type.clone(),
+                    request.authentication.clone(),
+                    request.id.clone(),
+                ),
+                None => inherited.authentication.clone(),
+            };
+        let mut request = request.clone();
+        request.authentication_type = authentication_type;
+        request.authentication = authentication;
+        request.headers = merge_headers(&inherited.headers, &request.headers);
+        let runtime_config = inherited.runtime_config.clone();
+        let mut environment_chain = inherited.environment_chain.clone();
+
+        if !variables.is_empty() {
+            environment_chain.insert(0, variables_environment(variables));
+        }
+
+        // Rendered with the request so secrets can come from the environment
+        let signing = match &self.signing {
+            None => None,
+            Some(signing) => {
+                let vars = make_vars_hashmap(environment_chain.clone());
+                let value = render_json_value_raw(
+                    serde_json::to_value(signing)?,
+                    &vars,
+                    &self.template_callback,
+                    &RenderOptions::throw(),
+                )
+                .await?;
+                Some(serde_json::from_value(value)?)
+            }
+        };
+        let hook = PayloadBytesAuthentication {
+            bytes: payload_bytes(variables)?,
+            plugin_manager: &self.plugin_manager,
+            plugin_context: &self.plugin_context,
+        };
+        let (_, sendable_request) = render_sendable_request(
+            &request,
+            environment_chain,
+            &self.template_callback,
+            runtime_config.send_options.clone(),
+            &auth_context_id,
+            Some(&hook),
+        )
+        .await?;
+
+        Ok((sendable_request, runtime_config, signing))
+    }
+
+    /// Let plugins change `request` right before it's sent
+    async fn plugin_prepared(
+        &self,
+        mut request: SendableHttpRequest,
+    ) -> Result<SendableHttpRequest> {
+        let body = match &request.body {
+            Some(SendableBody::Bytes(bytes)) => String::from_utf8(bytes.to_vec()).ok(),
+            _ => None,
+        };
+        let prepared = self
+            .plugin_manager
+            .prepare_fuzz_request(
+                &self.plugin_context,
+                PrepareFuzzRequestRequest {
+                    method: request.method.clone(),
+                    url: request.url.clone(),
+                    headers: request
+                        .headers
+                        .iter()
+                        .map(|(name, value)| HttpHeader {
+                            name: name.clone(),
+                            value: value.clone(),
+                        })
+                        .collect(),
+                    body,
+                },
+            )
+            .await?;
+        request.url = prepared.url;
+        request.headers = prepared.headers.into_iter().map(|h| (h.name, h.value)).collect();
+        if let Some(body) = prepared.body {
+            request.body = Some(SendableBody::Bytes(body.into_bytes().into()));
+        }
+        Ok(request)
+    }
+
+    /// Send `request` through the connection manager. Bodies are fully read
+    /// beforehand so the exact bytes can be stored with the result. Cookies
+    /// from the jar are added while sending, so they aren't part of the
+    /// stored request. The request is signed last, as it will be sent.
+    /// Malformed requests and HTTP/2 runs are written to the socket instead,
+    /// without a proxy, redirects or cookies. Requests and redirects outside
+    /// the workspace's fuzz scope aren't sent. Byte payloads replace their
+    /// placeholders in the body, and requests with them in the request line
+    /// or headers are written to the socket too. When traces are exported,
+    /// requests carry the `traceparent` of the span they're sent under.
+    async fn execute(
+        &self,
+        mut request: SendableHttpRequest,
+        runtime_config: HttpSendRuntimeConfig,
+        signing: Option<&FuzzSigning>,
+        cookies: Option<&CookieStore>,
+        payload_bytes: Option<&[u8]>,
+    ) -> Result<FuzzResponse> {
+        let FuzzTimeouts { connect_ms, read_ms, total_ms } = self.timeouts;
+        let validate_certificates =
+            self.validate_certificates.unwrap_or(runtime_config.validate_certificates);
+        if let Some(total_ms) = total_ms {
+            request.options.timeout = Some(Duration::from_millis(total_ms as u64));
+        }
+        request.options.follow_redirects = self.redirects.follow(request.options.follow_redirects);
+
+        let mut sent = SentRequest {
+            method: request.method,
+            url: request.url,
+            headers: request.headers,
+            body: read_body(request.body).await?,
+        };
+        if let Some(bytes) = payload_bytes {
+            sent.body = splice_bytes(&sent.body, bytes);
+        }
+        let head_bytes = payload_bytes.filter(|_| bytes_in_head(&sent));
+        for (name, value) in trace_headers() {
+            if !sent.headers.iter().any(|(n, _)| n.eq_ignore_ascii_case(&name)) {
+                sent.headers.push((name, value));
+            }
+        }
+        if let Some(signing) = signing {
+            sign_request(signing, &mut sent, Utc::now())?;
+        }
+        let server_name = match &self.tls.server_name {
+            Some(name) => Some(name.trim()),
+            None => self.vhost.as_ref().and_then(|v| server_name(v, &sent.headers)),
+        };
+        let min_tls_version = self.tls.min_version.map(tls_version);
+        let max_tls_version = self.tls.max_version.map(tls_version);
+        let client_certificate = client_certificate(
+            &self.client_certificate,
+            &sent.url,
+            &runtime_config.client_certificates,
+        );
+
+        // The run's overrides come last, so they win over the workspace's
+        let mut dns_overrides = runtime_config.dns_overrides;
+        dns_overrides.extend(self.dns_overrides.iter().cloned());
+        let dns_id = overrides_id(&self.dns_overrides);
+        let scope = Scope::parse(&runtime_config.fuzz_scope)?;
+        check_scope(&scope, &sent.url, &dns_overrides).await?;
+        let scope_overrides = dns_overrides.clone();
+        let local_id = self.local_address.map(|a| format!(".from.{a}")).unwrap_or_default();
+
+        let raw_connection = || -> Result<RawConnection> {
+            let mut tls = get_tls_config_for_versions(
+                validate_certificates,
+                false,
+                client_certificate.clone(),
+                min_tls_version,
+                max_tls_version,
+            )
+            .map_err(yaak_http::error::Error::from)?;
+            if let Some(protocols) = &self.tls.alpn {
+                tls.alpn_protocols = protocols.iter().map(|p| p.as_bytes().to_vec()).collect();
+            }
+            Ok(RawConnection {
+                tls,
+                server_name: server_name.map(str::to_string),
+                dns_overrides: dns_overrides.clone(),
+                local_address: self.local_address,
+                connect_timeout: connect_ms.map(|ms| Duration::from_millis(ms as u64)),
+                read_timeout: read_ms.map(|ms| Duration::from_millis(ms as u64)),
+                timeout: request.options.timeout,
+            })
+        };
+        if let Some(http2) = self.http2 {
+            if head_bytes.is_some() {
+                return Err(GenericError(
+                    "Byte payloads in the URL or headers can't be sent over HTTP/2".to_string(),
+                ));
+            }
+            let connection = raw_connection()?;
+            return send_http2(sent, http2, connection).await;
+        }
+        // reqwest rejects or rewrites malformed requests, so those are written
+        // to the socket as they are
+        if needs_raw(&sent) || head_bytes.is_some() {
+            let connection = raw_connection()?;
+            let mut response = send_raw(sent, connection, head_bytes).await?;
+            if let Some(bytes) = head_bytes {
+                show_bytes(&mut response.request, bytes);
+                response.url = response.request.url.clone();
+            }
+            return Ok(response);
+        }
+
+        // Another server name is sent by putting it in the URL and resolving
+        // every name to the URL's original address
+        let mut connect_url = None;
+        let mut resolved_id = String::new();
+        let renamed = match server_name {
+            Some(server_name) => server_name_url(&sent.url, server_name)?,
+            None => None,
+        };
+        if let Some((url, origin)) = renamed {
+            let host = origin.rsplit_once(':').map_or(origin.as_str(), |(host, _)| host);
+            let mut addresses = override_addresses(&dns_overrides, host);
+            if addresses.is_empty() {
+                addresses = lookup_host(&origin).await?.map(|a| a.ip()).collect();
+            }
+            let (ipv4, ipv6): (Vec<_>, Vec<_>) = addresses.iter().partition(|ip| ip.is_ipv4());
+            dns_overrides = vec![DnsOverride {
+                hostname: WILDCARD_HOSTNAME.to_string(),
+                ipv4: ipv4.iter().map(|ip| ip.to_string()).collect(),
+                ipv6: ipv6.iter().map(|ip| ip.to_string()).collect(),
+                enabled: true,
+            }];
+            let first = addresses.first().map(|ip| ip.to_string()).unwrap_or_default();
+            resolved_id = format!(".sni.{first}");
+            connect_url = Some(url);
+        }
+
+        let certificate_id = certificate_id(client_certificate.as_ref());
+        let (proxy_id, proxy) = match self.proxies.next() {
+            Some((index, proxy)) => (format!(".p{index}"), proxy),
+            None => (String::new(), runtime_config.proxy),
+        };
+        let options = HttpConnectionOptions {
+            // Clients are cached by ID, so settings applied to the client are part of it
+            id: format!(
+                "fuzz.{}.{validate_certificates}.{connect_ms:?}.{read_ms:?}.{:?}.{}.{:?}{proxy_id}{resolved_id}{certificate_id}{dns_id}{local_id}",
+                self.plugin_context.id, self.http_version, self.fresh_connections, self.tls
+            ),
+            validate_certificates,
+            proxy,
+            client_certificate,
+            dns_overrides,
+            connect_timeout: connect_ms.map(|ms| Duration::from_millis(ms as u64)),
+            read_timeout: read_ms.map(|ms| Duration::from_millis(ms as u64)),
+            http_version: match self.http_version {
+                FuzzHttpVersion::Auto => HttpVersionPolicy::Auto,
+                FuzzHttpVersion::Http1 => HttpVersionPolicy::Http1Only,
+                FuzzHttpVersion::Http2 => HttpVersionPolicy::Http2,
+            },
+            reuse_connections: !self.fresh_connections,
+            min_tls_version,
+            max_tls_version,
+            alpn_protocols: self.tls.alpn.clone(),
+            local_address: self.local_address,
+        };
+
+        let sendable_request = SendableHttpRequest {
+            url: connect_url.unwrap_or_else(|| sent.url.clone()),
+            method: sent.method.clone(),
+            headers: sent.headers.clone(),
+            body: if sent.body.is_empty() {
+                None
+            } else {
+                Some(SendableBody::Bytes(sent.body.clone().into()))
+            },
+            options: request.options,
+        };
+
+        // Per-request timing events aren't surfaced for fuzz traffic
+        let (event_tx, _) = mpsc::channel(1);
+        let cookie_store = cookies.or(self.cookie_store.as_ref()).cloned();
+        let redirects = self.redirects;
+        let transaction = move |sender: ReqwestSender| {
+            let transaction = match redirects {
+                FuzzRedirects::Limit { max } => {
+                    HttpTransaction::with_options(sender, max, cookie_store)
+                }
+                FuzzRedirects::SameHost => {
+                    HttpTransaction::with_options(sender, MAX_REDIRECTS, cookie_store)
+                        .same_host_redirects()
+                }
+                FuzzRedirects::Workspace | FuzzRedirects::Off => {
+                    HttpTransaction::with_options(sender, MAX_REDIRECTS, cookie_store)
+                }
+            };
+            // Redirects out of scope are returned instead of followed
+            if scope.is_empty() {
+                transaction
+            } else {
+                transaction
+                    .filter_redirects(Box::new(move |url| scope.allows_url(url, &scope_overrides)))
+            }
+        };
+        let started_at = Instant::now();
+        let response = send_with_connection_manager(
+            self.connection_manager,
+            &options,
+            transaction,
+            sendable_request,
+            self.cancelled_rx.clone(),
+            event_tx,
+        )
+        .await?;
+
+        let url = response.url.clone();
+        let status = response.status;
+        let version = response.version.clone().unwrap_or_default();
+        let headers = response.headers.clone();
+        let elapsed = started_at.elapsed();
+        let (body, _) = response.bytes().await?;
+
+        Ok(FuzzResponse { url, status, version, headers, body, elapsed, request: sent })
+    }
+}
+
+#[async_trait]
+impl FuzzSender for HttpFuzzSender<'_> {
+    async fn send(
+        &self,
+        request: &HttpRequest,
+        variables: &BTreeMap<String, String>,
+        cookies: Option<&CookieStore>,
+    ) -> Result<FuzzResponse> {
+        let (mut sendable_request, runtime_config, signing) =
+            self.prepare(request, variables).await?;
+        if self.prepare_with_plugins {
+            sendable_request = self.plugin_prepared(sendable_request).await?;
+        }
+        let bytes = payload_bytes(variables)?;
+        self.execute(sendable_request, runtime_config, signing.as_ref(), cookies, bytes.as_deref())
+            .await
+    }
+
+    async fn send_exact(&self, workspace_id: &str, request: &SentRequest) -> Result<FuzzResponse> {
+        let runtime_config = resolve_http_send_runtime_config(&self.query_manager, workspace_id)?;
+        let sendable_request = SendableHttpRequest {
+            url: request.url.clone(),
+            method: request.method.clone(),
+            headers: request.headers.clone(),
+            body: Some(SendableBody::Bytes(request.body.clone().into())),
+            options: runtime_config.send_options.clone(),
+        };
+        // Stored requests are already signed and sent as they are
+        self.execute(sendable_request, runtime_config, None, None, None).await
+    }
+
+    async fn run_script(
+        &self,
+        script: &str,
+        result: &FuzzResult,
+        body: &[u8],
+    ) -> Result<RunFuzzScriptResponse> {
+        let req = RunFuzzScriptRequest {
+            script: script.to_string(),
+            payload: result.payload.clone(),
+            url: result.url.clone(),
+            status: result.status,
+            headers: result
+                .headers
+                .iter()
+                .map(|h| HttpHeader { name: h.name.clone(), value: h.value.clone() })
+                .collect(),
+            body: String::from_utf8_lossy(body).to_string(),
+        };
+        Ok(self.plugin_manager.run_fuzz_script(&self.plugin_context, req).await?)
+    }
+
+    async fn render_template(
+        &self,
+        template: &str,
+        variables: &HashMap<String, String>,
+    ) -> Result<String> {
+        let opt = RenderOptions::throw();
+        Ok(parse_and_render(template, variables, &self.template_callback, &opt).await?)
+    }
+
+    async fn refresh_auth(&self, request: &HttpRequest, action: &str) -> Result<()> {
+        let (request, auth_context_id, environment_chain) = {
+            let db = self.query_manager.connect();
+            let environment_chain = self.environment_chain(&db, request)?;
+            let (authentication_type, authentication, auth_context_id) =
+                db.resolve_auth_for_http_request(request)?;
+
+            // Only the auth values are rendered since the rest of the template
+            // may need variables of a payload
+            let request = HttpRequest {
+                workspace_id: request.workspace_id.clone(),
+                folder_id: request.folder_id.clone(),
+                authentication_type,
+                authentication,
+                ..Default::default()
+            };
+            (request, auth_context_id, environment_chain)
+        };
+        let auth_name = match request.authentication_type.as_deref() {
+            None | Some("none") => {
+                return Err(GenericError("Request has no authentication to renew".to_string()));
+            }
+            Some(auth_name) => auth_name.to_string(),
+        };
+
+        let rendered_request = render_http_request(
+            &request,
+            environment_chain,
+            &self.template_callback,
+            &RenderOptions::throw(),
+        )
+        .await?;
+        let values: HashMap<String, JsonPrimitive> =
+            serde_json::from_value(serde_json::to_value(&rendered_request.authentication)?)?;
+
+        let plugin_error = |e: yaak_plugins::error::Error| GenericError(e.to_string());
+        let config = self
+            .plugin_manager
+            .get_http_authentication_config(
+                &self.plugin_context,
+                &auth_name,
+                values.clone(),
+                &auth_context_id,
+            )
+            .await
+            .map_err(plugin_error)?;
+        let index =
+            config.actions.unwrap_or_default().iter().position(|a| a.label == action).ok_or_else(
+                || GenericError(format!("Authentication {auth_name} has no {action} action")),
+            )?;
+        self.plugin_manager
+            .call_http_authentication_action(
+                &self.plugin_context,
+                &auth_name,
+                index as i32,
+                values,
+                &auth_context_id,
+            )
+            .await
+            .map_err(plugin_error)
+    }
+}
+
+/// Inherited headers, without the ones the request sets itself, followed by
+/// the request's own in the order they were written. Fuzz requests repeat
+/// headers on purpose, so those aren't deduplicated.
+fn merge_headers(
+    inherited: &[HttpRequestHeader],
+    own: &[HttpRequestHeader],
+) -> Vec<HttpRequestHeader> {
+    let mut headers = dedupe_headers(inherited.to_vec());
+    headers.retain(|h| !own.iter().any(|o| o.name.eq_ignore_ascii_case(&h.name)));
+    headers.extend(own.iter().cloned());
+    headers
+}
+
+/// Bodies are fully read so the exact bytes can be stored with the result
+async fn read_body(body: Option<SendableBody>) -> Result<Vec<u8>> {
+    Ok(match body {
+        None => Vec::new(),
+        Some(SendableBody::Bytes(bytes)) => bytes.to_vec(),
+        Some(SendableBody::Stream { mut data, .. }) => {
+            let mut body = Vec::new();
+            data.read_to_end(&mut body).await?;
+            body
+        }
+    })
+}
+
+/// Trace context headers of the current span, so servers can join their
+/// traces to the run's. Empty unless a subscriber exports traces.
+fn trace_headers() -> HashMap<String, String> {
+    let context = tracing::Span::current().context();
+    let mut headers = HashMap::new();
+    global::get_text_map_propagator(|propagator| propagator.inject_context(&context, &mut headers));
+    headers
+}
+
+fn variables_environment(variables: &BTreeMap<String, String>) -> Environment {
+    Environment {
+        name: "Fuzz".to_string(),
+        variables: variables
+            .iter()
+            .map(|(name, value)| EnvironmentVariable {
+                enabled: true,
+                name: name.clone(),
+                value: value.clone(),
+                id: None,
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(name: &str, value: &str) -> HttpRequestHeader {
+        HttpRequestHeader {
+            enabled: true,
+            name: name.to_string(),
+            value: value.to_string(),
+            id: None,
+        }
+    }
+
+    #[test]
+    fn sizes_requests_as_http1_messages() {
+        let request = SentRequest {
+            method: "POST".to_string(),
+            url: "https://example.com:8443/login?next=%2F#form".to_string(),
+            headers: vec![("Content-Type".to_string(), "text/plain".to_string())],
+            body: b"user=admin".to_vec(),
+        };
+        let message = "POST /login?next=%2F HTTP/1.1\r\nContent-Type: text/plain\r\n\r\nuser=admin";
+        assert_eq!(request.size(), message.len());
+
+        let request = SentRequest { url: "https://example.com".to_string(), ..request };
+        let message = "POST / HTTP/1.1\r\nContent-Type: text/plain\r\n\r\nuser=admin";
+        assert_eq!(request.size(), message.len());
+    }
+
+    #[test]
+    fn keeps_repeated_request_headers() {
+        let inherited = [
+            header("Accept", "*/*"),
+            header("host", "folder.example.com"),
+        ];
+        let own = [
+            header("Host", "a.example.com"),
+            header("X-A", "1"),
+            header("Host", "b"),
+        ];
+        let merged = merge_headers(&inherited, &own);
+        let merged = merged.iter().map(|h| (h.name.as_str(), h.value.as_str()));
+        assert_eq!(
+            merged.collect::<Vec<_>>(),
+            vec![
+                ("Accept", "*/*"),
+                ("Host", "a.example.com"),
+                ("X-A", "1"),
+                ("Host", "b")
+            ]
+        );
+    }
+
+    #[test]
+    fn trace_headers_carry_the_current_span() {
+        use opentelemetry::trace::TracerProvider as _;
+        use opentelemetry_sdk::propagation::TraceContextPropagator;
+        use opentelemetry_sdk::trace::TracerProvider;
+        use tracing_subscriber::layer::SubscriberExt;
+
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let tracer = TracerProvider::builder().build().tracer("test");
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+        tracing::subscriber::with_default(subscriber, || {
+            assert!(trace_headers().is_empty());
+
+            let span = tracing::info_span!("fuzz_send");
+            let _entered = span.enter();
+            let traceparent = trace_headers().remove("traceparent").unwrap();
+            assert!(traceparent.starts_with("00-"), "{traceparent}");
+        });
+    }
+}
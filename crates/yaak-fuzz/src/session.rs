@@ -0,0 +1,106 @@
+use crate::error::Result;
+use crate::matchers::{ResponseMatcher, ValueExtractors};
+use crate::sender::{FuzzResponse, FuzzSender};
+use crate::types::{FuzzMatchMode, FuzzSession};
+use std::collections::BTreeMap;
+use tokio::sync::RwLock;
+use yaak_models::models::HttpRequest;
+use yaak_templates::escape::escape_template;
+
+/// Prefix for template variables holding values extracted from login responses
+pub const SESSION_VARIABLE_PREFIX: &str = "fuzz.session.";
+
+/// A run's login session, shared by every request in flight
+pub(crate) struct Session<'a> {
+    config: Option<&'a FuzzSession>,
+    /// Template whose auth is renewed by `auth_action`
+    request: &'a HttpRequest,
+    expired: ResponseMatcher,
+    extractors: ValueExtractors,
+    state: RwLock<SessionState>,
+}
+
+#[derive(Default)]
+struct SessionState {
+    variables: BTreeMap<String, String>,
+    /// Bumped on every login, so requests that failed with the same expired
+    /// session only log in once
+    generation: usize,
+}
+
+impl<'a> Session<'a> {
+    pub fn new(config: Option<&'a FuzzSession>, request: &'a HttpRequest) -> Result<Self> {
+        let (expired, extractors) = match config {
+            Some(config) => (config.expired.as_slice(), config.extractors.as_slice()),
+            None => (&[][..], &[][..]),
+        };
+        Ok(Self {
+            config,
+            request,
+            expired: ResponseMatcher::new(expired, FuzzMatchMode::Any)?,
+            extractors: ValueExtractors::new(extractors)?,
+            state: RwLock::default(),
+        })
+    }
+
+    /// How often a payload is sent again after logging in
+    pub fn max_retries(&self) -> usize {
+        self.config.map_or(0, |c| c.max_retries.max(1))
+    }
+
+    pub fn is_expired(&self, response: &FuzzResponse) -> bool {
+        self.expired.is_match(response)
+    }
+
+    /// Template variables of the current session and the login they came
+    /// from. Waits while a login is in progress.
+    pub async fn variables(&self) -> (BTreeMap<String, String>, usize) {
+        let state = self.state.read().await;
+        (state.variables.clone(), state.generation)
+    }
+
+    /// Log in before the first payload
+    pub async fn start<S: FuzzSender>(&self, sender: &S) -> Result<()> {
+        let Some(config) = self.config else {
+            return Ok(());
+        };
+        let mut state = self.state.write().await;
+        login(config, &self.extractors, sender, &mut state).await
+    }
+
+    /// Renew the auth token and log in again, unless the session was already
+    /// renewed since `generation`. Other requests wait until this finishes.
+    pub async fn renew<S: FuzzSender>(&self, sender: &S, generation: usize) -> Result<()> {
+        let Some(config) = self.config else {
+            return Ok(());
+        };
+        let mut state = self.state.write().await;
+        if state.generation != generation {
+            return Ok(());
+        }
+
+        if let Some(action) = &config.auth_action {
+            sender.refresh_auth(self.request, action).await?;
+        }
+        login(config, &self.extractors, sender, &mut state).await
+    }
+}
+
+/// Send the login requests in order. Values are escaped so response content
+/// is never evaluated as a template.
+async fn login<S: FuzzSender>(
+    config: &FuzzSession,
+    extractors: &ValueExtractors,
+    sender: &S,
+    state: &mut SessionState,
+) -> Result<()> {
+    for request in &config.login {
+        let response = sender.send(request, &state.variables, None).await?;
+        for (name, value) in extractors.extract(&response) {
+            let name = format!("{SESSION_VARIABLE_PREFIX}{name}");
+            state.variables.insert(name, escape_template(&value));
+        }
+    }
+    state.generation += 1;
+    Ok(())
+}
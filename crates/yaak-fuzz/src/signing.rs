@@ -0,0 +1,181 @@
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::sender::SentRequest;
+use crate::types::{FuzzAwsSigning, FuzzHmacEncoding, FuzzHmacSigning, FuzzSigning};
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+/// Add the signature for `request` as it will be sent, replacing any
+/// signature headers it already has
+pub fn sign_request(
+    signing: &FuzzSigning,
+    request: &mut SentRequest,
+    now: DateTime<Utc>,
+) -> Result<()> {
+    match signing {
+        FuzzSigning::AwsSigV4(aws) => sign_aws(aws, request, now),
+        FuzzSigning::HmacBody(config) => {
+            sign_hmac(config, request);
+            Ok(())
+        }
+    }
+}
+
+fn sign_hmac(config: &FuzzHmacSigning, request: &mut SentRequest) {
+    let signature = hmac_sha256(config.secret.as_bytes(), &request.body);
+    let signature = match config.encoding {
+        FuzzHmacEncoding::Hex => hex::encode(signature),
+        FuzzHmacEncoding::Base64 => BASE64_STANDARD.encode(signature),
+    };
+    set_header(request, &config.header, format!("{}{signature}", config.prefix));
+}
+
+fn sign_aws(aws: &FuzzAwsSigning, request: &mut SentRequest, now: DateTime<Utc>) -> Result<()> {
+    let url = Url::parse(&request.url)
+        .map_err(|e| GenericError(format!("Can't sign invalid URL {}: {e}", request.url)))?;
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{host}:{port}"),
+        (Some(host), None) => host.to_string(),
+        (None, _) => return Err(GenericError(format!("Can't sign URL without host {url}"))),
+    };
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = hex::encode(Sha256::digest(&request.body));
+
+    set_header(request, "x-amz-date", amz_date.clone());
+    // S3 requires the payload hash header, other services only sign it
+    if aws.service == "s3" {
+        set_header(request, "x-amz-content-sha256", payload_hash.clone());
+    }
+    if let Some(token) = aws.session_token.as_ref().filter(|t| !t.is_empty()) {
+        set_header(request, "x-amz-security-token", token.clone());
+    }
+
+    let mut headers = vec![("host".to_string(), host)];
+    for (name, value) in &request.headers {
+        let name = name.to_lowercase();
+        if name == "content-type" || name.starts_with("x-amz-") {
+            headers.push((name, value.trim().to_string()));
+        }
+    }
+    headers.sort();
+    let signed_headers = headers.iter().map(|(n, _)| n.as_str()).collect::<Vec<_>>().join(";");
+    let canonical_headers = headers.iter().map(|(n, v)| format!("{n}:{v}\n")).collect::<String>();
+
+    let mut query =
+        url.query_pairs().map(|(k, v)| (aws_encode(&k), aws_encode(&v))).collect::<Vec<_>>();
+    query.sort();
+    let canonical_query =
+        query.iter().map(|(k, v)| format!("{k}={v}")).collect::<Vec<_>>().join("&");
+
+    let canonical_request = format!(
+        "{}\n{}\n{canonical_query}\n{canonical_headers}\n{signed_headers}\n{payload_hash}",
+        request.method.to_uppercase(),
+        url.path(),
+    );
+    let scope = format!("{date}/{}/{}/aws4_request", aws.region, aws.service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let key = [date.as_str(), &aws.region, &aws.service, "aws4_request"]
+        .iter()
+        .fold(format!("AWS4{}", aws.secret_access_key).into_bytes(), |key, part| {
+            hmac_sha256(&key, part.as_bytes())
+        });
+    let signature = hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()));
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+        aws.access_key_id
+    );
+    set_header(request, "authorization", authorization);
+    Ok(())
+}
+
+/// Percent-encode everything but unreserved characters, as SigV4 expects
+fn aws_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn set_header(request: &mut SentRequest, name: &str, value: String) {
+    request.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+    request.headers.push((name.to_string(), value));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn header<'a>(request: &'a SentRequest, name: &str) -> &'a str {
+        request.headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).unwrap().1.as_str()
+    }
+
+    #[test]
+    fn signs_aws_test_suite_request() {
+        // "get-vanilla" from the AWS Signature Version 4 test suite
+        let signing = FuzzSigning::AwsSigV4(FuzzAwsSigning {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            region: "us-east-1".to_string(),
+            service: "service".to_string(),
+            ..Default::default()
+        });
+        let mut request = SentRequest {
+            method: "GET".to_string(),
+            url: "https://example.amazonaws.com/".to_string(),
+            ..Default::default()
+        };
+        let now = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+        sign_request(&signing, &mut request, now).unwrap();
+
+        assert_eq!(header(&request, "x-amz-date"), "20150830T123600Z");
+        assert_eq!(
+            header(&request, "authorization"),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, \
+             SignedHeaders=host;x-amz-date, \
+             Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+    }
+
+    #[test]
+    fn signs_body_with_hmac() {
+        let signing = FuzzSigning::HmacBody(FuzzHmacSigning {
+            header: "X-Signature".to_string(),
+            secret: "key".to_string(),
+            prefix: "sha256=".to_string(),
+            ..Default::default()
+        });
+        let mut request = SentRequest {
+            headers: vec![("x-signature".to_string(), "stale".to_string())],
+            body: b"The quick brown fox jumps over the lazy dog".to_vec(),
+            ..Default::default()
+        };
+        sign_request(&signing, &mut request, Utc::now()).unwrap();
+
+        assert_eq!(request.headers.len(), 1);
+        assert_eq!(
+            header(&request, "X-Signature"),
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+}
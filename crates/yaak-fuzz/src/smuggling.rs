@@ -0,0 +1,136 @@
+//! Request smuggling probes. Front and back ends that disagree on where a
+//! body ends leave the back end waiting for bytes that never come, so a
+//! probe that hangs points at a desync.
+
+use crate::markers::{MARKER, escape_markers};
+use crate::types::{FuzzMatcher, FuzzRequest, FuzzTimeouts};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use ts_rs::TS;
+use yaak_models::models::{HttpRequest, HttpRequestHeader};
+
+/// Ways of writing `chunked` that some servers accept and others ignore,
+/// each sent as the probe's `Transfer-Encoding`
+pub const TRANSFER_ENCODINGS: &[&str] = &[
+    "chunked",
+    " chunked",
+    "chunked ",
+    "\tchunked",
+    "Chunked",
+    "CHUNKED",
+    "\"chunked\"",
+    "chunked, identity",
+    "identity, chunked",
+    "x-chunked",
+    "chunk",
+];
+
+/// Probes still waiting after this long are flagged. Servers answer the
+/// probe itself immediately when both ends agree.
+const DELAY_MS: u32 = 5_000;
+
+/// Probes are given up on after this long, which still flags them
+const PROBE_TIMEOUT_MS: u32 = 10_000;
+
+/// Which end is expected to use which header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub enum FuzzSmugglingTechnique {
+    /// The front end uses `Content-Length`, the back end `Transfer-Encoding`
+    ClTe,
+    /// The front end uses `Transfer-Encoding`, the back end `Content-Length`
+    TeCl,
+}
+
+/// A run sending `technique`'s probe to `request`'s URL, with each of
+/// `TRANSFER_ENCODINGS` as a payload. The conflicting headers make the
+/// sender write the probes to the socket as they are. Probes go one at a
+/// time so they don't slow each other down, and ones that are delayed or
+/// time out match.
+pub fn smuggling_config(request: &HttpRequest, technique: FuzzSmugglingTechnique) -> FuzzRequest {
+    let (content_length, body) = match technique {
+        // The front end forwards "1\r\nZ", so the back end waits for the
+        // rest of the chunk
+        FuzzSmugglingTechnique::ClTe => ("4", "1\r\nZ\r\nQ"),
+        // The front end forwards the last chunk only, so the back end waits
+        // for the sixth byte
+        FuzzSmugglingTechnique::TeCl => ("6", "0\r\n\r\nX"),
+    };
+    let new_header = |name: &str, value: String| HttpRequestHeader {
+        enabled: true,
+        name: name.to_string(),
+        value,
+        id: None,
+    };
+
+    let mut request = request.clone();
+    request.method = "POST".to_string();
+    request.url = escape_markers(&request.url);
+    for parameter in request.url_parameters.iter_mut() {
+        parameter.name = escape_markers(&parameter.name);
+        parameter.value = escape_markers(&parameter.value);
+    }
+    request.headers.retain(|h| {
+        !["content-length", "transfer-encoding"].iter().any(|n| h.name.eq_ignore_ascii_case(n))
+    });
+    for header in request.headers.iter_mut() {
+        header.name = escape_markers(&header.name);
+        header.value = escape_markers(&header.value);
+    }
+    request.headers.push(new_header("Content-Length", content_length.to_string()));
+    request.headers.push(new_header("Transfer-Encoding", format!("{MARKER}chunked{MARKER}")));
+    request.body_type = Some("text/plain".to_string());
+    request.body.clear();
+    request.body.insert("text".to_string(), json!(body));
+
+    FuzzRequest {
+        request,
+        wordlist: TRANSFER_ENCODINGS.iter().map(|e| e.to_string()).collect(),
+        concurrency: 1,
+        timeouts: FuzzTimeouts { total_ms: Some(PROBE_TIMEOUT_MS), ..Default::default() },
+        matchers: vec![FuzzMatcher::Delayed { min_ms: DELAY_MS }],
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markers::payload_request;
+    use crate::raw::needs_raw;
+    use crate::sender::SentRequest;
+
+    #[test]
+    fn probes_go_through_raw_sender() {
+        let template = HttpRequest {
+            url: "https://example.com/§x§".to_string(),
+            headers: vec![HttpRequestHeader {
+                enabled: true,
+                name: "content-length".to_string(),
+                value: "0".to_string(),
+                id: None,
+            }],
+            ..Default::default()
+        };
+        let config = smuggling_config(&template, FuzzSmugglingTechnique::TeCl);
+        let request = payload_request(&config, " chunked");
+        assert_eq!(request.url, "https://example.com/§x§");
+
+        let headers = request.headers.iter().map(|h| (h.name.clone(), h.value.clone()));
+        let sent = SentRequest {
+            method: request.method.clone(),
+            url: request.url.clone(),
+            headers: headers.collect(),
+            body: b"0\r\n\r\nX".to_vec(),
+        };
+        assert_eq!(
+            sent.headers,
+            vec![
+                ("Content-Length".to_string(), "6".to_string()),
+                ("Transfer-Encoding".to_string(), " chunked".to_string()),
+            ]
+        );
+        assert!(needs_raw(&sent));
+    }
+}
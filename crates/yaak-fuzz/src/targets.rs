@@ -0,0 +1,614 @@
+//! Payload positions picked by the structure of a request instead of markers
+
+use crate::error::Result;
+use crate::matchers::{PathSegment, parse_json_path, select_mut};
+use crate::types::{FuzzFormPart, FuzzParameterLocation, FuzzTarget};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::borrow::Cow;
+use std::path::Path;
+use ts_rs::TS;
+use url::form_urlencoded;
+use yaak_models::models::{HttpRequest, HttpRequestHeader, HttpUrlParameter};
+use yaak_templates::escape::escape_template;
+use yaak_templates::strip_json_comments::strip_json_comments;
+
+/// Body type and body field holding JSON text that targets can address
+const JSON_BODY: (&str, &str) = ("application/json", "text");
+const GRAPHQL_VARIABLES: (&str, &str) = ("graphql", "variables");
+
+/// A place in a request that a run can target, with its current value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_fuzz.ts")]
+pub struct FuzzInsertionPoint {
+    pub target: FuzzTarget,
+    pub value: String,
+}
+
+/// Every query parameter of `request`, from its URL and its parameter list,
+/// followed by its form fields and the leaves of its JSON body, in order.
+/// Path parameters like `:id` are left out. Multipart file fields are listed
+/// by filename, content type and content instead of value.
+pub fn insertion_points(request: &HttpRequest) -> Vec<FuzzInsertionPoint> {
+    let url_parameters = query_pairs(&request.url).map(|(name, value)| (name, value.into_owned()));
+    let list_parameters = request
+        .url_parameters
+        .iter()
+        .filter(|p| p.enabled && !p.name.is_empty() && !p.name.starts_with(':'))
+        .map(|p| (Cow::Owned(p.name.clone()), p.value.clone()));
+
+    let query_points = url_parameters.chain(list_parameters).map(|(name, value)| {
+        FuzzInsertionPoint { target: FuzzTarget::QueryParameter { name: name.into_owned() }, value }
+    });
+    let form_points = form_fields(request).flat_map(|field| {
+        let name = field_str(field, "name");
+        let parts = if field_str(field, "file").is_empty() {
+            vec![(FuzzFormPart::Value, field_str(field, "value"))]
+        } else {
+            vec![
+                (FuzzFormPart::Filename, field_str(field, "filename")),
+                (FuzzFormPart::ContentType, field_str(field, "contentType")),
+                (FuzzFormPart::Content, field_str(field, "file")),
+            ]
+        };
+        parts.into_iter().map(move |(part, value)| FuzzInsertionPoint {
+            target: FuzzTarget::FormField { name: name.to_string(), part },
+            value: value.to_string(),
+        })
+    });
+
+    let mut json_points = Vec::new();
+    if let Some(body) = json_text(request, JSON_BODY) {
+        json_leaves(&body, "$".to_string(), &mut |path, value| {
+            json_points.push(FuzzInsertionPoint { target: FuzzTarget::JsonPath { path }, value })
+        });
+    }
+    if let Some(variables) = json_text(request, GRAPHQL_VARIABLES) {
+        json_leaves(&variables, "$".to_string(), &mut |path, value| {
+            let target = FuzzTarget::GraphqlVariable { path };
+            json_points.push(FuzzInsertionPoint { target, value })
+        });
+    }
+
+    let mut points: Vec<FuzzInsertionPoint> = Vec::new();
+    for point in query_points.chain(form_points).chain(json_points) {
+        if !points.iter().any(|p| p.target == point.target) {
+            points.push(point);
+        }
+    }
+    points
+}
+
+/// Check that every JSONPath target parses
+pub fn validate_targets(targets: &[FuzzTarget]) -> Result<()> {
+    for target in targets {
+        match target {
+            FuzzTarget::JsonPath { path } | FuzzTarget::GraphqlVariable { path } => {
+                parse_json_path(path)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Put `payload` at every target in `request`. Values that are rendered as
+/// templates get the payload with its tags escaped when `escape` is set.
+/// Values in the URL are percent-encoded and the method isn't rendered, so
+/// their tags never are.
+pub fn apply_targets(
+    request: &mut HttpRequest,
+    targets: &[FuzzTarget],
+    payload: &str,
+    escape: bool,
+) {
+    let template_payload = if escape { escape_template(payload) } else { payload.to_string() };
+    for target in targets {
+        match target {
+            FuzzTarget::Method => request.method = payload.to_string(),
+            FuzzTarget::HeaderName { value } if !payload.is_empty() => {
+                request.headers.push(HttpRequestHeader {
+                    enabled: true,
+                    name: template_payload.clone(),
+                    value: value.clone(),
+                    id: None,
+                });
+            }
+            FuzzTarget::HeaderName { .. } => {}
+            FuzzTarget::AddedParameter { location: FuzzParameterLocation::Query, value }
+                if !payload.is_empty() =>
+            {
+                request.url_parameters.push(HttpUrlParameter {
+                    enabled: true,
+                    name: template_payload.clone(),
+                    value: value.clone(),
+                    id: None,
+                });
+            }
+            FuzzTarget::AddedParameter { .. } => {}
+            FuzzTarget::QueryParameter { name } => {
+                request.url = replace_query_value(&request.url, name, payload);
+                for parameter in
+                    request.url_parameters.iter_mut().filter(|p| p.enabled && p.name == *name)
+                {
+                    parameter.value = template_payload.clone();
+                }
+            }
+            FuzzTarget::FormField { name, part } => {
+                let fields = match request.body.get_mut("form") {
+                    Some(Value::Array(fields)) => fields,
+                    _ => continue,
+                };
+                let fields = fields.iter_mut().filter_map(|f| f.as_object_mut());
+                for field in fields.filter(|f| is_enabled(f) && field_str(f, "name") == name) {
+                    set_form_part(field, *part, &template_payload);
+                }
+            }
+            FuzzTarget::JsonPath { .. } | FuzzTarget::GraphqlVariable { .. } => {}
+        }
+    }
+
+    // JSON targets are replaced together so the text is only serialized once
+    let (mut body_paths, mut variable_paths, mut added) = (Vec::new(), Vec::new(), Vec::new());
+    for target in targets {
+        match target {
+            FuzzTarget::JsonPath { path } => body_paths.extend(parse_json_path(path).ok()),
+            FuzzTarget::GraphqlVariable { path } => {
+                variable_paths.extend(parse_json_path(path).ok())
+            }
+            FuzzTarget::AddedParameter { location: FuzzParameterLocation::JsonBody, value }
+                if !payload.is_empty() =>
+            {
+                added.push(value.as_str())
+            }
+            _ => {}
+        }
+    }
+    let fields = [
+        (JSON_BODY, body_paths, added),
+        (GRAPHQL_VARIABLES, variable_paths, Vec::new()),
+    ];
+    for (field, paths, added) in fields {
+        if paths.is_empty() && added.is_empty() {
+            continue;
+        }
+        let text = json_text(request, field)
+            .and_then(|v| replace_json_values(v, &paths, &added, payload, escape));
+        if let Some(text) = text {
+            request.body.insert(field.1.to_string(), Value::String(text));
+        }
+    }
+}
+
+/// Set the value at `target` to `value` as written, e.g. to carry a marker
+/// over from another request. Unlike [`apply_targets`], values in the URL
+/// aren't percent-encoded.
+pub(crate) fn set_target_value(request: &mut HttpRequest, target: &FuzzTarget, value: &str) {
+    match target {
+        FuzzTarget::QueryParameter { name } => {
+            request.url = replace_query_raw(&request.url, name, value);
+            for parameter in
+                request.url_parameters.iter_mut().filter(|p| p.enabled && p.name == *name)
+            {
+                parameter.value = value.to_string();
+            }
+        }
+        _ => apply_targets(request, std::slice::from_ref(target), value, false),
+    }
+}
+
+/// Serialize `body` with the values at `paths` replaced by `payload`, and a
+/// top-level key named by `payload` for each of the `added` values. The
+/// payload is escaped after it's encoded as a JSON string, since a tag
+/// escape inside a string would be doubled by the encoding and no longer
+/// escape anything.
+fn replace_json_values(
+    body: Value,
+    paths: &[Vec<PathSegment>],
+    added: &[&str],
+    payload: &str,
+    escape: bool,
+) -> Option<String> {
+    let literal = serde_json::to_string(payload).ok()?;
+    let literal = if escape { escape_template(&literal) } else { literal };
+    serialize_with_placeholder(body, &literal, |body, placeholder| {
+        let mut replaced = replace_paths(body, paths, placeholder);
+        if let Value::Object(map) = body {
+            for value in added {
+                map.insert(placeholder.to_string(), json!(value));
+                replaced = true;
+            }
+        }
+        replaced
+    })
+}
+
+/// Serialize `body` with the values at `paths` replaced by `raw`, which is
+/// written as it is, so it can be text no JSON value serializes to. `None`
+/// if none of the paths exist.
+pub(crate) fn serialize_with_raw(
+    body: Value,
+    paths: &[Vec<PathSegment>],
+    raw: &str,
+) -> Option<String> {
+    serialize_with_placeholder(body, raw, |body, placeholder| {
+        replace_paths(body, paths, placeholder)
+    })
+}
+
+/// Serialize `body` after `edit` puts a placeholder string where `raw` goes,
+/// as a key or a value. The placeholder can't be in the body yet, so every
+/// occurrence of it is replaced once the body is serialized. `None` if
+/// `edit` returns false because it changed nothing.
+fn serialize_with_placeholder(
+    mut body: Value,
+    raw: &str,
+    edit: impl FnOnce(&mut Value, &str) -> bool,
+) -> Option<String> {
+    let existing = serde_json::to_string_pretty(&body).ok()?;
+    let mut placeholder = "\0".to_string();
+    while existing.contains(&serde_json::to_string(&placeholder).ok()?) {
+        placeholder.push('\0');
+    }
+    if !edit(&mut body, &placeholder) {
+        return None;
+    }
+
+    let text = serde_json::to_string_pretty(&body).ok()?;
+    Some(text.replace(&serde_json::to_string(&placeholder).ok()?, raw))
+}
+
+/// Set the values at `paths` to `text`. Whether any of them exist.
+fn replace_paths(body: &mut Value, paths: &[Vec<PathSegment>], text: &str) -> bool {
+    let mut replaced = false;
+    for path in paths {
+        if let Some(value) = select_mut(body, path) {
+            *value = Value::String(text.to_string());
+            replaced = true;
+        }
+    }
+    replaced
+}
+
+fn set_form_part(field: &mut Map<String, Value>, part: FuzzFormPart, payload: &str) {
+    let key = match part {
+        FuzzFormPart::Value => "value",
+        FuzzFormPart::Filename => "filename",
+        FuzzFormPart::ContentType => "contentType",
+        FuzzFormPart::Content => {
+            // Files are read from disk as they are, so the file becomes a
+            // text field carrying the payload under the file's name
+            let file = field_str(field, "file").to_string();
+            if !file.is_empty() {
+                if field_str(field, "filename").is_empty() {
+                    let filename = Path::new(&file).file_name().and_then(|n| n.to_str());
+                    field.insert("filename".to_string(), json!(filename.unwrap_or("file")));
+                }
+                field.insert("file".to_string(), json!(""));
+            }
+            "value"
+        }
+    };
+    field.insert(key.to_string(), json!(payload));
+}
+
+/// Each enabled, named field of a form body. Fields named by a template tag
+/// are listed as written.
+fn form_fields(request: &HttpRequest) -> impl Iterator<Item = &Map<String, Value>> {
+    let is_form = matches!(
+        request.body_type.as_deref(),
+        Some("application/x-www-form-urlencoded" | "multipart/form-data")
+    );
+    let fields = match request.body.get("form") {
+        Some(Value::Array(fields)) if is_form => fields.as_slice(),
+        _ => &[],
+    };
+    fields
+        .iter()
+        .filter_map(|f| f.as_object())
+        .filter(|f| is_enabled(f) && !field_str(f, "name").is_empty())
+}
+
+/// Parsed body text, without comments, from the body field `field.1` of
+/// requests with body type `field.0`. Text that only parses once its template
+/// tags are rendered, like `{"id": ${[ id ]}}`, has no structure to target.
+fn json_text(request: &HttpRequest, field: (&str, &str)) -> Option<Value> {
+    if request.body_type.as_deref() != Some(field.0) {
+        return None;
+    }
+    let text = request.body.get(field.1).and_then(|t| t.as_str())?;
+    serde_json::from_str(&strip_json_comments(text)).ok()
+}
+
+/// Call `leaf` with a JSONPath for every scalar under `value` and its value
+/// as text
+fn json_leaves(value: &Value, path: String, leaf: &mut impl FnMut(String, String)) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let simple = !key.is_empty()
+                    && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                    && !key.starts_with(|c: char| c.is_ascii_digit());
+                let path = if simple {
+                    format!("{path}.{key}")
+                } else if !key.contains('\'') {
+                    format!("{path}['{key}']")
+                } else if !key.contains('"') {
+                    format!("{path}[\"{key}\"]")
+                } else {
+                    continue;
+                };
+                json_leaves(value, path, leaf);
+            }
+        }
+        Value::Array(values) => {
+            for (index, value) in values.iter().enumerate() {
+                json_leaves(value, format!("{path}[{index}]"), leaf);
+            }
+        }
+        scalar => {
+            let value = match scalar {
+                Value::String(text) => text.clone(),
+                _ => scalar.to_string(),
+            };
+            leaf(path, value);
+        }
+    }
+}
+
+fn field_str<'a>(field: &'a Map<String, Value>, key: &str) -> &'a str {
+    field.get(key).and_then(|v| v.as_str()).unwrap_or_default()
+}
+
+/// Form fields are enabled unless they say otherwise, matching how bodies
+/// are built
+fn is_enabled(field: &Map<String, Value>) -> bool {
+    field.get("enabled").and_then(|e| e.as_bool()).unwrap_or(true)
+}
+
+/// Decoded name and value of each pair in the query string of `url`
+fn query_pairs(url: &str) -> impl Iterator<Item = (Cow<'_, str>, Cow<'_, str>)> {
+    let query = split_query(url).1.unwrap_or_default();
+    form_urlencoded::parse(query.as_bytes())
+}
+
+/// `url` split into everything before the query string, the query string,
+/// and the fragment including its `#`
+fn split_query(url: &str) -> (&str, Option<&str>, &str) {
+    let (rest, fragment) = match url.find('#') {
+        Some(i) => url.split_at(i),
+        None => (url, ""),
+    };
+    match rest.split_once('?') {
+        Some((base, query)) => (base, Some(query), fragment),
+        None => (rest, None, fragment),
+    }
+}
+
+/// Set the value of every `name` parameter in the query string of `url`,
+/// leaving the other pairs exactly as written
+fn replace_query_value(url: &str, name: &str, value: &str) -> String {
+    let encoded = form_urlencoded::byte_serialize(value.as_bytes()).collect::<String>();
+    replace_query_raw(url, name, &encoded)
+}
+
+/// [`replace_query_value`] with `encoded` written as is
+fn replace_query_raw(url: &str, name: &str, encoded: &str) -> String {
+    let (base, Some(query), fragment) = split_query(url) else {
+        return url.to_string();
+    };
+    let pairs = query
+        .split('&')
+        .map(|pair| {
+            let raw_name = pair.split_once('=').map_or(pair, |(n, _)| n);
+            let matches =
+                form_urlencoded::parse(raw_name.as_bytes()).next().is_some_and(|(n, _)| n == name);
+            if matches { format!("{raw_name}={encoded}") } else { pair.to_string() }
+        })
+        .collect::<Vec<_>>();
+    format!("{base}?{}{fragment}", pairs.join("&"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yaak_templates::escape::unescape_template;
+
+    fn request() -> HttpRequest {
+        HttpRequest {
+            url: "${[ base_url ]}/search?q=shoes&page=2&q=boots#results".to_string(),
+            url_parameters: vec![
+                HttpUrlParameter {
+                    enabled: true,
+                    name: "sort".to_string(),
+                    value: "asc".to_string(),
+                    id: None,
+                },
+                HttpUrlParameter {
+                    enabled: true,
+                    name: ":id".to_string(),
+                    value: "1".to_string(),
+                    id: None,
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn lists_query_parameters() {
+        let point = |name: &str, value: &str| FuzzInsertionPoint {
+            target: FuzzTarget::QueryParameter { name: name.to_string() },
+            value: value.to_string(),
+        };
+        assert_eq!(
+            insertion_points(&request()),
+            vec![
+                point("q", "shoes"),
+                point("page", "2"),
+                point("sort", "asc")
+            ]
+        );
+    }
+
+    #[test]
+    fn replaces_parameters_by_name() {
+        let mut request = request();
+        let targets = [
+            FuzzTarget::QueryParameter { name: "q".to_string() },
+            FuzzTarget::QueryParameter { name: "sort".to_string() },
+        ];
+        apply_targets(&mut request, &targets, "a b&${[ x ]}", true);
+
+        assert_eq!(
+            request.url,
+            "${[ base_url ]}/search?q=a+b%26%24%7B%5B+x+%5D%7D&page=2&q=a+b%26%24%7B%5B+x+%5D%7D#results"
+        );
+        assert_eq!(request.url_parameters[0].value, "a b&\\${[ x ]}");
+        assert_eq!(request.url_parameters[1].value, "1");
+    }
+
+    #[test]
+    fn replaces_method() {
+        let mut request = request();
+        apply_targets(&mut request, &[FuzzTarget::Method], "PROPFIND", true);
+        assert_eq!(request.method, "PROPFIND");
+    }
+
+    #[test]
+    fn adds_header_named_by_payload() {
+        let target = FuzzTarget::HeaderName { value: "127.0.0.1".to_string() };
+        let mut added = request();
+        apply_targets(&mut added, &[target.clone()], "X-Forwarded-For", true);
+        let header = added.headers.last().unwrap();
+        assert_eq!((header.name.as_str(), header.value.as_str()), ("X-Forwarded-For", "127.0.0.1"));
+
+        let mut baseline = request();
+        apply_targets(&mut baseline, &[target], "", true);
+        assert_eq!(baseline.headers, request().headers);
+    }
+
+    #[test]
+    fn adds_parameters_named_by_payload() {
+        let mut request = HttpRequest {
+            body_type: Some("application/json".to_string()),
+            body: [("text".to_string(), json!(r#"{"id": 7}"#))].into(),
+            ..Default::default()
+        };
+        let targets = [
+            FuzzTarget::AddedParameter {
+                location: FuzzParameterLocation::Query,
+                value: "c4n4ry".to_string(),
+            },
+            FuzzTarget::AddedParameter {
+                location: FuzzParameterLocation::JsonBody,
+                value: "c4n4ry".to_string(),
+            },
+        ];
+        apply_targets(&mut request, &targets, "debug", true);
+
+        assert_eq!(request.url_parameters[0].name, "debug");
+        assert_eq!(request.url_parameters[0].value, "c4n4ry");
+        let body: Value = serde_json::from_str(request.body["text"].as_str().unwrap()).unwrap();
+        assert_eq!(body, json!({"id": 7, "debug": "c4n4ry"}));
+    }
+
+    #[test]
+    fn lists_and_replaces_form_fields() {
+        let mut request = HttpRequest {
+            body_type: Some("application/x-www-form-urlencoded".to_string()),
+            body: [(
+                "form".to_string(),
+                serde_json::json!([
+                    { "name": "user", "value": "admin" },
+                    { "enabled": false, "name": "debug", "value": "1" },
+                ]),
+            )]
+            .into(),
+            ..Default::default()
+        };
+        let target = FuzzTarget::FormField { name: "user".to_string(), part: FuzzFormPart::Value };
+        assert_eq!(
+            insertion_points(&request),
+            vec![FuzzInsertionPoint { target: target.clone(), value: "admin".to_string() }]
+        );
+
+        apply_targets(&mut request, &[target], "a=b&c", true);
+        assert_eq!(request.body["form"][0]["value"], "a=b&c");
+    }
+
+    #[test]
+    fn replaces_multipart_file_content() {
+        let mut request = HttpRequest {
+            body_type: Some("multipart/form-data".to_string()),
+            body: [(
+                "form".to_string(),
+                serde_json::json!([{ "name": "avatar", "file": "/tmp/avatar.png" }]),
+            )]
+            .into(),
+            ..Default::default()
+        };
+        let target = |part| FuzzTarget::FormField { name: "avatar".to_string(), part };
+        assert_eq!(
+            insertion_points(&request).into_iter().map(|p| p.target).collect::<Vec<_>>(),
+            vec![
+                target(FuzzFormPart::Filename),
+                target(FuzzFormPart::ContentType),
+                target(FuzzFormPart::Content),
+            ]
+        );
+
+        apply_targets(&mut request, &[target(FuzzFormPart::Content)], "<?php ?>", true);
+        assert_eq!(
+            request.body["form"][0],
+            serde_json::json!({
+                "name": "avatar",
+                "file": "",
+                "filename": "avatar.png",
+                "value": "<?php ?>",
+            })
+        );
+    }
+
+    #[test]
+    fn lists_and_replaces_json_paths() {
+        let mut request = HttpRequest {
+            body_type: Some("application/json".to_string()),
+            body: [(
+                "text".to_string(),
+                Value::String(
+                    r#"{
+                        // Sign up form
+                        "user": {"email": "a@example.com", "tags": ["x"]},
+                        "max-age": 10
+                    }"#
+                    .to_string(),
+                ),
+            )]
+            .into(),
+            ..Default::default()
+        };
+        let target = |path: &str| FuzzTarget::JsonPath { path: path.to_string() };
+        assert_eq!(
+            insertion_points(&request),
+            vec![
+                FuzzInsertionPoint {
+                    target: target("$.user.email"),
+                    value: "a@example.com".to_string(),
+                },
+                FuzzInsertionPoint { target: target("$.user.tags[0]"), value: "x".to_string() },
+                FuzzInsertionPoint { target: target("$['max-age']"), value: "10".to_string() },
+            ]
+        );
+
+        let targets = [target("$.user.email"), target("$.missing")];
+        apply_targets(&mut request, &targets, "\"${[ x ]}", true);
+        let text = request.body["text"].as_str().unwrap();
+        assert!(text.contains(r#""email": "\"\${[ x ]}""#));
+        let body: Value = serde_json::from_str(&unescape_template(text)).unwrap();
+        assert_eq!(body["user"]["email"], "\"${[ x ]}");
+        assert_eq!(body["max-age"], 10);
+        assert!(validate_targets(&[target("user.email")]).is_err());
+    }
+}
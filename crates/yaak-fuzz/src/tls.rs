@@ -0,0 +1,138 @@
+//! TLS versions, ALPN protocols, server names and client certificates set
+//! by a run
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::types::{FuzzClientCertificate, FuzzRequest, FuzzTls, FuzzTlsVersion};
+use std::path::Path;
+use yaak_models::models::ClientCertificate;
+use yaak_tls::{ClientCertificateConfig, TlsVersion, find_client_certificate};
+
+/// Check that a run's TLS settings leave something to offer and its client
+/// certificate files exist
+pub(crate) fn validate_tls(config: &FuzzRequest) -> Result<()> {
+    validate_client_certificate(&config.client_certificate)?;
+    let FuzzTls { min_version, max_version, alpn, server_name } = &config.tls;
+    let versions = min_version.zip(*max_version);
+    if versions.is_some_and(|(min, max)| tls_version(min) > tls_version(max)) {
+        return Err(GenericError("The minimum TLS version is above the maximum".to_string()));
+    }
+    if alpn.as_ref().is_some_and(|a| a.iter().any(|p| p.trim().is_empty())) {
+        return Err(GenericError("ALPN protocols can't be empty".to_string()));
+    }
+    match server_name {
+        None => Ok(()),
+        Some(name) if name.trim().is_empty() => {
+            Err(GenericError("Enter the TLS server name to send".to_string()))
+        }
+        // The proxy resolves the server name itself, reaching the wrong host
+        Some(_) if !config.proxies.urls.is_empty() => Err(GenericError(
+            "A TLS server name can't be set when sending through a proxy".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+fn validate_client_certificate(certificate: &FuzzClientCertificate) -> Result<()> {
+    let FuzzClientCertificate::Files { crt_file, key_file, pfx_file, .. } = certificate else {
+        return Ok(());
+    };
+    let given = |file: &Option<String>| file.as_deref().filter(|f| !f.trim().is_empty());
+    let files = match (given(crt_file), given(key_file), given(pfx_file)) {
+        (_, _, Some(pfx_file)) => vec![pfx_file],
+        (Some(crt_file), Some(key_file), None) => vec![crt_file, key_file],
+        _ => {
+            return Err(GenericError(
+                "Choose a PKCS#12 file or both a certificate and a key file".to_string(),
+            ));
+        }
+    };
+    match files.into_iter().find(|f| !Path::new(f).is_file()) {
+        Some(missing) => Err(GenericError(format!("Client certificate file {missing} not found"))),
+        None => Ok(()),
+    }
+}
+
+/// The client certificate `certificate` presents for a request to `url`
+pub(crate) fn client_certificate(
+    certificate: &FuzzClientCertificate,
+    url: &str,
+    workspace_certificates: &[ClientCertificate],
+) -> Option<ClientCertificateConfig> {
+    match certificate {
+        FuzzClientCertificate::Workspace => find_client_certificate(url, workspace_certificates),
+        FuzzClientCertificate::Off => None,
+        FuzzClientCertificate::Files { crt_file, key_file, pfx_file, passphrase } => {
+            Some(ClientCertificateConfig {
+                crt_file: crt_file.clone(),
+                key_file: key_file.clone(),
+                pfx_file: pfx_file.clone(),
+                passphrase: passphrase.clone(),
+            })
+        }
+    }
+}
+
+/// Part of a cached client's ID that tells certificates apart, without the
+/// passphrase
+pub(crate) fn certificate_id(certificate: Option<&ClientCertificateConfig>) -> String {
+    match certificate {
+        None => String::new(),
+        Some(c) => format!(".cert.{:?}.{:?}.{:?}", c.crt_file, c.key_file, c.pfx_file),
+    }
+}
+
+pub(crate) fn tls_version(version: FuzzTlsVersion) -> TlsVersion {
+    match version {
+        FuzzTlsVersion::Tls12 => TlsVersion::Tls12,
+        FuzzTlsVersion::Tls13 => TlsVersion::Tls13,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FuzzProxies;
+
+    #[test]
+    fn rejects_unusable_tls_settings() {
+        let limited = |min, max| FuzzRequest {
+            tls: FuzzTls { min_version: Some(min), max_version: Some(max), ..Default::default() },
+            ..Default::default()
+        };
+        assert!(validate_tls(&limited(FuzzTlsVersion::Tls12, FuzzTlsVersion::Tls12)).is_ok());
+        assert!(validate_tls(&limited(FuzzTlsVersion::Tls13, FuzzTlsVersion::Tls12)).is_err());
+
+        let proxied = FuzzRequest {
+            tls: FuzzTls {
+                server_name: Some("internal.example.com".to_string()),
+                ..Default::default()
+            },
+            proxies: FuzzProxies {
+                urls: vec!["http://127.0.0.1:8080".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(validate_tls(&proxied).is_err());
+        assert!(validate_tls(&FuzzRequest { proxies: FuzzProxies::default(), ..proxied }).is_ok());
+    }
+
+    #[test]
+    fn checks_client_certificate_files() {
+        let files = |crt_file: Option<&str>, key_file: Option<&str>| FuzzClientCertificate::Files {
+            crt_file: crt_file.map(str::to_string),
+            key_file: key_file.map(str::to_string),
+            pfx_file: None,
+            passphrase: None,
+        };
+        let manifest = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+        assert!(validate_client_certificate(&files(Some(manifest), Some(manifest))).is_ok());
+        assert!(validate_client_certificate(&files(Some(manifest), None)).is_err());
+        assert!(validate_client_certificate(&files(Some(manifest), Some("missing.key"))).is_err());
+
+        let certificate = client_certificate(&files(Some("a.crt"), Some("a.key")), "", &[]);
+        assert_eq!(certificate.and_then(|c| c.key_file).as_deref(), Some("a.key"));
+        assert!(client_certificate(&FuzzClientCertificate::Off, "https://a.test", &[]).is_none());
+    }
+}
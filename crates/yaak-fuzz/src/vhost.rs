@@ -0,0 +1,133 @@
+//! Virtual host discovery, with the `Host` header and the TLS server name
+//! decoupled from the address requests are sent to
+
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::types::{FuzzRequest, FuzzServerName, FuzzVhost};
+use url::Url;
+use yaak_models::models::{HttpRequest, HttpRequestHeader};
+use yaak_models::util::generate_id;
+
+/// Check that a vhost run's server name can be sent
+pub(crate) fn validate_vhost(config: &FuzzRequest) -> Result<()> {
+    match config.vhost.as_ref().map(|v| &v.server_name) {
+        None | Some(FuzzServerName::Url) => Ok(()),
+        Some(FuzzServerName::Fixed { name }) if name.trim().is_empty() => {
+            Err(GenericError("Enter the TLS server name to send".to_string()))
+        }
+        // The proxy resolves the server name itself, reaching the wrong host
+        Some(_) if !config.proxies.urls.is_empty() => Err(GenericError(
+            "A TLS server name can't be set when sending through a proxy".to_string(),
+        )),
+        Some(_) => Ok(()),
+    }
+}
+
+/// Send `request` to its URL with `host` in the `Host` header, replacing any
+/// `Host` header it has
+pub(crate) fn set_host_header(request: &mut HttpRequest, host: &str) {
+    request.headers.retain(|h| !h.name.eq_ignore_ascii_case("host"));
+    request.headers.push(HttpRequestHeader {
+        enabled: true,
+        name: "Host".to_string(),
+        value: host.to_string(),
+        id: None,
+    });
+}
+
+/// A host no server is configured for, answered by the default vhost
+pub(crate) fn unknown_host() -> String {
+    format!("{}.invalid", generate_id().to_lowercase())
+}
+
+/// The TLS server name `vhost` sends for a request with these headers, when
+/// it isn't the URL's host
+pub(crate) fn server_name<'a>(
+    vhost: &'a FuzzVhost,
+    headers: &'a [(String, String)],
+) -> Option<&'a str> {
+    match &vhost.server_name {
+        FuzzServerName::Url => None,
+        FuzzServerName::Payload => {
+            let host = headers.iter().find(|(name, _)| name.eq_ignore_ascii_case("host"));
+            host.map(|(_, value)| value.trim()).filter(|v| !v.is_empty())
+        }
+        FuzzServerName::Fixed { name } => Some(name.trim()),
+    }
+}
+
+/// `url` with its host replaced by `server_name`, so the name is sent in the
+/// TLS handshake, along with the original `host:port` the connection has to
+/// reach instead. None for plain HTTP and URLs already on that host.
+pub(crate) fn server_name_url(url: &str, server_name: &str) -> Result<Option<(String, String)>> {
+    let mut url = Url::parse(url).map_err(|e| GenericError(format!("Invalid URL {url}: {e}")))?;
+    let (host, port) = match (url.host_str(), url.port_or_known_default()) {
+        (Some(host), Some(port)) => (host.to_string(), port),
+        _ => return Ok(None),
+    };
+    if url.scheme() != "https" || host.eq_ignore_ascii_case(server_name) {
+        return Ok(None);
+    }
+    url.set_host(Some(server_name))
+        .map_err(|e| GenericError(format!("Invalid TLS server name {server_name}: {e}")))?;
+    Ok(Some((url.to_string(), format!("{host}:{port}"))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markers::payload_request;
+    use crate::types::FuzzProxies;
+
+    #[test]
+    fn sets_host_header_per_payload() {
+        let config = FuzzRequest {
+            request: HttpRequest {
+                url: "https://10.0.0.5/".to_string(),
+                headers: vec![HttpRequestHeader {
+                    name: "host".to_string(),
+                    value: "www.example.com".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            vhost: Some(FuzzVhost::default()),
+            ..Default::default()
+        };
+        let request = payload_request(&config, "dev.example.com");
+        assert_eq!(request.url, "https://10.0.0.5/");
+        let headers = request.headers.iter().map(|h| (h.name.as_str(), h.value.as_str()));
+        assert_eq!(headers.collect::<Vec<_>>(), vec![("Host", "dev.example.com")]);
+    }
+
+    #[test]
+    fn replaces_host_for_server_name() {
+        let (url, origin) =
+            server_name_url("https://10.0.0.5/admin?x=1", "intranet.example.com").unwrap().unwrap();
+        assert_eq!(url, "https://intranet.example.com/admin?x=1");
+        assert_eq!(origin, "10.0.0.5:443");
+        assert!(server_name_url("http://10.0.0.5/", "intranet.example.com").unwrap().is_none());
+        assert!(server_name_url("https://example.com/", "EXAMPLE.com").unwrap().is_none());
+    }
+
+    #[test]
+    fn server_name_follows_host_header() {
+        let vhost = FuzzVhost { server_name: FuzzServerName::Payload };
+        let headers = vec![("host".to_string(), "dev.example.com".to_string())];
+        assert_eq!(server_name(&vhost, &headers), Some("dev.example.com"));
+        assert_eq!(server_name(&FuzzVhost::default(), &headers), None);
+    }
+
+    #[test]
+    fn rejects_server_name_through_proxy() {
+        let config = FuzzRequest {
+            vhost: Some(FuzzVhost { server_name: FuzzServerName::Payload }),
+            proxies: FuzzProxies {
+                urls: vec!["http://127.0.0.1:8080".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(validate_vhost(&config).is_err());
+    }
+}
@@ -0,0 +1,132 @@
+//! Webhook notifications: a JSON summary posted when a result matches and
+//! when the run finishes, so unattended runs can ping a chat or a pipeline
+
+use crate::callbacks::FuzzInteractionKind;
+use crate::error::Error::GenericError;
+use crate::error::Result;
+use crate::types::{FuzzEvent, FuzzRequest, FuzzWebhook};
+use serde_json::{Value, json};
+use url::Url;
+
+/// Check that a run's webhook URL parses
+pub(crate) fn validate_webhook(config: &FuzzRequest) -> Result<()> {
+    let Some(webhook) = &config.webhook else {
+        return Ok(());
+    };
+    let url = Url::parse(webhook.url.trim())
+        .map_err(|_| GenericError(format!("Invalid webhook URL {}", webhook.url)))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(GenericError("Webhook URLs have to be http or https".to_string()));
+    }
+    Ok(())
+}
+
+/// Turns a run's events into the messages its webhook receives
+pub struct WebhookNotifier {
+    run_id: String,
+    max_matches: u32,
+    posted_matches: u32,
+}
+
+impl WebhookNotifier {
+    pub fn new(run_id: &str, webhook: &FuzzWebhook) -> Self {
+        Self { run_id: run_id.to_string(), max_matches: webhook.max_matches, posted_matches: 0 }
+    }
+
+    /// Body to post for `event`, none for unmatched results, progress, and
+    /// matches past `max_matches`
+    pub fn message(&mut self, event: &FuzzEvent) -> Option<Value> {
+        let mut message = match event {
+            FuzzEvent::Result { result, .. } if result.matched => {
+                self.take_match()?;
+                let text = format!(
+                    "Fuzz run matched `{}`: {} {} {} ({} bytes)",
+                    result.payload, result.status, result.method, result.url, result.content_length
+                );
+                // Request headers are left out, they often carry credentials
+                let result = json!({
+                    "id": result.id,
+                    "payload": result.payload,
+                    "method": result.method,
+                    "url": result.url,
+                    "status": result.status,
+                    "contentLength": result.content_length,
+                    "elapsed": result.elapsed,
+                    "tags": result.tags,
+                    "extracted": result.extracted,
+                });
+                json!({ "event": "match", "text": text, "result": result })
+            }
+            FuzzEvent::Interaction { interaction } => {
+                self.take_match()?;
+                let kind = match interaction.kind {
+                    FuzzInteractionKind::Http => "an HTTP",
+                    FuzzInteractionKind::Dns => "a DNS",
+                };
+                let text = format!(
+                    "Fuzz run got {kind} callback from {} for result {}: {}",
+                    interaction.remote_address, interaction.result_id, interaction.details
+                );
+                json!({ "event": "interaction", "text": text, "interaction": interaction })
+            }
+            FuzzEvent::Finished { summary } => {
+                let state = if summary.cancelled { "cancelled" } else { "finished" };
+                let text = format!("Fuzz run {state}: {}", summary.describe());
+                json!({ "event": "finished", "text": text, "summary": summary })
+            }
+            FuzzEvent::Result { .. } | FuzzEvent::Progress { .. } | FuzzEvent::Metrics { .. } => {
+                return None;
+            }
+        };
+        message["runId"] = Value::String(self.run_id.clone());
+        Some(message)
+    }
+
+    /// Count a match towards `max_matches`, none once it's reached
+    fn take_match(&mut self) -> Option<()> {
+        if self.max_matches > 0 && self.posted_matches >= self.max_matches {
+            return None;
+        }
+        self.posted_matches += 1;
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FuzzSummary;
+    use yaak_models::models::FuzzResult;
+
+    #[test]
+    fn posts_matches_up_to_the_limit_and_the_summary() {
+        let webhook = FuzzWebhook { url: "https://hooks.example.com".to_string(), max_matches: 1 };
+        let mut notifier = WebhookNotifier::new("fr_test", &webhook);
+        let result = |payload: &str, matched: bool| FuzzEvent::Result {
+            result: FuzzResult {
+                payload: payload.to_string(),
+                status: 200,
+                matched,
+                ..Default::default()
+            },
+            body: Vec::new(),
+            url: String::new(),
+            request_body: Vec::new(),
+        };
+
+        assert_eq!(notifier.message(&result("miss", false)), None);
+        let first = notifier.message(&result("admin", true)).unwrap();
+        assert_eq!(first["event"], "match");
+        assert_eq!(first["runId"], "fr_test");
+        assert_eq!(first["result"]["payload"], "admin");
+        assert_eq!(notifier.message(&result("backup", true)), None);
+
+        let summary =
+            FuzzSummary { completed: 10, matched: 2, elapsed: 1500, ..Default::default() };
+        let last = notifier.message(&FuzzEvent::Finished { summary }).unwrap();
+        assert_eq!(
+            last["text"],
+            "Fuzz run finished: 2 matched and 0 failed of 10 requests in 1.5s"
+        );
+    }
+}
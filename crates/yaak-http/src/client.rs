@@ -2,9 +2,11 @@ use crate::dns::LocalhostResolver;
 use crate::error::Result;
 use log::{debug, info, warn};
 use reqwest::{Client, Proxy, redirect};
+use std::net::IpAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use yaak_models::models::DnsOverride;
-use yaak_tls::{ClientCertificateConfig, get_tls_config};
+use yaak_tls::{ClientCertificateConfig, TlsVersion, get_tls_config_for_versions};
 
 #[derive(Clone)]
 pub struct HttpConnectionProxySettingAuth {
@@ -24,6 +26,17 @@ pub enum HttpConnectionProxySetting {
     },
 }
 
+/// Which HTTP versions a client may use
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HttpVersionPolicy {
+    /// HTTP/2 when the server offers it over TLS, HTTP/1.1 otherwise
+    #[default]
+    Auto,
+    Http1Only,
+    /// HTTP/2 only, with prior knowledge for plain HTTP
+    Http2,
+}
+
 #[derive(Clone)]
 pub struct HttpConnectionOptions {
     pub id: String,
@@ -31,6 +44,21 @@ pub struct HttpConnectionOptions {
     pub proxy: HttpConnectionProxySetting,
     pub client_certificate: Option<ClientCertificateConfig>,
     pub dns_overrides: Vec<DnsOverride>,
+    /// Limit on establishing a connection, including the TLS handshake
+    pub connect_timeout: Option<Duration>,
+    /// Limit on each read from the connection, reset after every successful read
+    pub read_timeout: Option<Duration>,
+    pub http_version: HttpVersionPolicy,
+    /// Keep idle connections open for later requests. Without it every
+    /// request opens a new connection, which also resolves DNS each time.
+    pub reuse_connections: bool,
+    pub min_tls_version: Option<TlsVersion>,
+    pub max_tls_version: Option<TlsVersion>,
+    /// Protocols offered over ALPN instead of the ones `http_version` implies
+    pub alpn_protocols: Option<Vec<String>>,
+    /// Source address connections are made from, e.g. to leave through one
+    /// interface of several
+    pub local_address: Option<IpAddr>,
 }
 
 impl HttpConnectionOptions {
@@ -46,14 +74,46 @@ impl HttpConnectionOptions {
             .no_brotli()
             .no_deflate()
             .referer(false)
-            .tls_info(true)
+            .tls_info(true);
+
+        if !self.reuse_connections {
             // Disable connection pooling to ensure DNS resolution happens on each request
             // This is needed so we can emit DNS timing events for each request
-            .pool_max_idle_per_host(0);
+            client = client.pool_max_idle_per_host(0);
+        }
 
-        // Configure TLS with optional client certificate
-        let config =
-            get_tls_config(self.validate_certificates, true, self.client_certificate.clone())?;
+        if let Some(timeout) = self.connect_timeout {
+            client = client.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.read_timeout {
+            client = client.read_timeout(timeout);
+        }
+        if let Some(address) = self.local_address {
+            client = client.local_address(address);
+        }
+
+        match self.http_version {
+            HttpVersionPolicy::Auto => {}
+            HttpVersionPolicy::Http1Only => client = client.http1_only(),
+            HttpVersionPolicy::Http2 => client = client.http2_prior_knowledge(),
+        }
+
+        // Configure TLS with optional client certificate. HTTP/1.1-only
+        // clients don't offer h2, and HTTP/2-only clients offer nothing else.
+        let with_alpn = self.http_version != HttpVersionPolicy::Http1Only;
+        let mut config = get_tls_config_for_versions(
+            self.validate_certificates,
+            with_alpn,
+            self.client_certificate.clone(),
+            self.min_tls_version,
+            self.max_tls_version,
+        )?;
+        if self.http_version == HttpVersionPolicy::Http2 {
+            config.alpn_protocols = vec![b"h2".to_vec()];
+        }
+        if let Some(protocols) = &self.alpn_protocols {
+            config.alpn_protocols = protocols.iter().map(|p| p.as_bytes().to_vec()).collect();
+        }
         client = client.use_preconfigured_tls(config);
 
         // Configure DNS resolver - keep a reference to configure per-request
@@ -74,9 +134,10 @@ impl HttpConnectionOptions {
         }
 
         info!(
-            "Building new HTTP client validate_certificates={} client_cert={}",
+            "Building new HTTP client validate_certificates={} client_cert={} http_version={:?}",
             self.validate_certificates,
-            self.client_certificate.is_some()
+            self.client_certificate.is_some(),
+            self.http_version
         );
 
         Ok((client.build()?, resolver))
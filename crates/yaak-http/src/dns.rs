@@ -13,6 +13,9 @@ use tokio::sync::{RwLock, mpsc};
 use tower_service::Service;
 use yaak_models::models::DnsOverride;
 
+/// Override hostname that matches every name without an override of its own
+pub const WILDCARD_HOSTNAME: &str = "*";
+
 /// Stores resolved addresses for a hostname override
 #[derive(Clone)]
 pub struct ResolvedOverride {
@@ -76,7 +79,7 @@ impl Resolve for LocalhostResolver {
         info!("DNS resolve called for: {}", host);
 
         // Check for DNS override first
-        if let Some(resolved) = overrides.get(&host) {
+        if let Some(resolved) = overrides.get(&host).or_else(|| overrides.get(WILDCARD_HOSTNAME)) {
             log::debug!("DNS override found for: {}", host);
             let hostname = host.clone();
             let mut addrs: Vec<SocketAddr> = Vec::new();
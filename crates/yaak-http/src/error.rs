@@ -23,6 +23,9 @@ pub enum Error {
 
     #[error("Failed to read response body: {0}")]
     BodyReadError(String),
+
+    #[error("Timed out reading response body")]
+    BodyReadTimeout,
 }
 
 impl Serialize for Error {
@@ -332,6 +332,9 @@ impl HttpResponse {
                     decompressed.extend_from_slice(&buf[..n]);
                     bytes_read += n as u64;
                 }
+                Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {
+                    return Err(Error::BodyReadTimeout);
+                }
                 Err(e) => {
                     return Err(Error::BodyReadError(e.to_string()));
                 }
@@ -557,9 +560,16 @@ impl HttpSender for ReqwestSender {
         let byte_stream = response.bytes_stream();
 
         // Convert the stream to an AsyncRead
-        let stream_reader = StreamReader::new(
-            byte_stream.map(|result| result.map_err(|e| std::io::Error::other(e))),
-        );
+        let stream_reader = StreamReader::new(byte_stream.map(|result| {
+            result.map_err(|e| {
+                // Keep timeouts recognizable once wrapped in an io error
+                if e.is_timeout() {
+                    std::io::Error::new(std::io::ErrorKind::TimedOut, e)
+                } else {
+                    std::io::Error::other(e)
+                }
+            })
+        }));
 
         // Wrap the stream with tracking to emit chunk received events via the same channel
         let tracking_reader = TrackingRead::new(stream_reader, event_tx);
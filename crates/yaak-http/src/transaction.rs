@@ -12,22 +12,45 @@ pub struct HttpTransaction<S: HttpSender> {
     sender: S,
     max_redirects: usize,
     cookie_store: Option<CookieStore>,
+    same_host_redirects: bool,
+    redirect_filter: Option<RedirectFilter>,
 }
 
+/// Decides from its URL whether a redirect may be followed
+pub type RedirectFilter = Box<dyn Fn(&str) -> bool + Send + Sync>;
+
 impl<S: HttpSender> HttpTransaction<S> {
     /// Create a new transaction with default settings
     pub fn new(sender: S) -> Self {
-        Self { sender, max_redirects: 10, cookie_store: None }
+        Self {
+            sender,
+            max_redirects: 10,
+            cookie_store: None,
+            same_host_redirects: false,
+            redirect_filter: None,
+        }
     }
 
     /// Create a new transaction with custom max redirects
     pub fn with_max_redirects(sender: S, max_redirects: usize) -> Self {
-        Self { sender, max_redirects, cookie_store: None }
+        Self {
+            sender,
+            max_redirects,
+            cookie_store: None,
+            same_host_redirects: false,
+            redirect_filter: None,
+        }
     }
 
     /// Create a new transaction with a cookie store
     pub fn with_cookie_store(sender: S, cookie_store: CookieStore) -> Self {
-        Self { sender, max_redirects: 10, cookie_store: Some(cookie_store) }
+        Self {
+            sender,
+            max_redirects: 10,
+            cookie_store: Some(cookie_store),
+            same_host_redirects: false,
+            redirect_filter: None,
+        }
     }
 
     /// Create a new transaction with custom max redirects and a cookie store
@@ -36,7 +59,27 @@ impl<S: HttpSender> HttpTransaction<S> {
         max_redirects: usize,
         cookie_store: Option<CookieStore>,
     ) -> Self {
-        Self { sender, max_redirects, cookie_store }
+        Self {
+            sender,
+            max_redirects,
+            cookie_store,
+            same_host_redirects: false,
+            redirect_filter: None,
+        }
+    }
+
+    /// Only follow redirects that stay on the original request's host and
+    /// port. A redirect anywhere else is returned as-is.
+    pub fn same_host_redirects(mut self) -> Self {
+        self.same_host_redirects = true;
+        self
+    }
+
+    /// Only follow redirects whose URL `filter` allows. Any other redirect
+    /// is returned as-is.
+    pub fn filter_redirects(mut self, filter: RedirectFilter) -> Self {
+        self.redirect_filter = Some(filter);
+        self
     }
 
     /// Execute the request with cancellation support.
@@ -49,6 +92,7 @@ impl<S: HttpSender> HttpTransaction<S> {
         event_tx: mpsc::Sender<HttpResponseEvent>,
     ) -> Result<HttpResponse> {
         let mut redirect_count = 0;
+        let original_host = Self::host_and_port(&request.url);
         let mut current_url = request.url;
         let mut current_method = request.method;
         let mut current_headers = request.headers;
@@ -164,6 +208,15 @@ impl<S: HttpSender> HttpTransaction<S> {
                     )
                 })?;
 
+            let next_url = Self::resolve_location(&current_url, location)?;
+            if self.same_host_redirects && Self::host_and_port(&next_url) != original_host {
+                // Leaving the original host - return the redirect response as-is
+                return Ok(response);
+            }
+            if self.redirect_filter.as_ref().is_some_and(|allows| !allows(&next_url)) {
+                return Ok(response);
+            }
+
             // Also get status before draining
             let status = response.status;
 
@@ -173,19 +226,7 @@ impl<S: HttpSender> HttpTransaction<S> {
             response.drain().await?;
 
             // Update the request URL
-            let previous_url = current_url.clone();
-            current_url = if location.starts_with("http://") || location.starts_with("https://") {
-                // Absolute URL
-                location
-            } else if location.starts_with('/') {
-                // Absolute path - need to extract base URL from current request
-                let base_url = Self::extract_base_url(&current_url)?;
-                format!("{}{}", base_url, location)
-            } else {
-                // Relative path - need to resolve relative to current path
-                let base_path = Self::extract_base_path(&current_url)?;
-                format!("{}/{}", base_path, location)
-            };
+            let previous_url = std::mem::replace(&mut current_url, next_url);
 
             // Determine redirect behavior based on status code and method
             let behavior = if status == 303 {
@@ -260,13 +301,7 @@ impl<S: HttpSender> HttpTransaction<S> {
         next_url: &str,
     ) -> Vec<String> {
         let mut dropped_headers = Vec::new();
-        let previous_host = Url::parse(previous_url).ok().and_then(|u| {
-            u.host_str().map(|h| format!("{}:{}", h, u.port_or_known_default().unwrap_or(0)))
-        });
-        let next_host = Url::parse(next_url).ok().and_then(|u| {
-            u.host_str().map(|h| format!("{}:{}", h, u.port_or_known_default().unwrap_or(0)))
-        });
-        if previous_host != next_host {
+        if Self::host_and_port(previous_url) != Self::host_and_port(next_url) {
             headers.retain(|h| {
                 let name_lower = h.0.to_lowercase();
                 let should_drop = name_lower == "authorization"
@@ -289,6 +324,29 @@ impl<S: HttpSender> HttpTransaction<S> {
         }
     }
 
+    /// `host:port` of a URL, used to tell whether a redirect changes hosts
+    fn host_and_port(url: &str) -> Option<String> {
+        Url::parse(url).ok().and_then(|u| {
+            u.host_str().map(|h| format!("{}:{}", h, u.port_or_known_default().unwrap_or(0)))
+        })
+    }
+
+    /// Resolve a Location header against the URL that returned it
+    fn resolve_location(current_url: &str, location: String) -> Result<String> {
+        Ok(if location.starts_with("http://") || location.starts_with("https://") {
+            // Absolute URL
+            location
+        } else if location.starts_with('/') {
+            // Absolute path - need to extract base URL from current request
+            let base_url = Self::extract_base_url(current_url)?;
+            format!("{}{}", base_url, location)
+        } else {
+            // Relative path - need to resolve relative to current path
+            let base_path = Self::extract_base_path(current_url)?;
+            format!("{}/{}", base_path, location)
+        })
+    }
+
     /// Check if a status code indicates a redirect
     fn is_redirect(status: u16) -> bool {
         matches!(status, 301 | 302 | 303 | 307 | 308)
@@ -495,6 +553,85 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_transaction_same_host_redirects() {
+        let responses = vec![
+            MockResponse {
+                status: 302,
+                headers: vec![("Location".to_string(), "/next".to_string())],
+                body: vec![],
+            },
+            MockResponse {
+                status: 302,
+                headers: vec![("Location".to_string(), "https://login.example.org/".to_string())],
+                body: vec![],
+            },
+        ];
+
+        let sender = MockSender::new(responses);
+        let captured = sender.captured_requests.clone();
+        let transaction = HttpTransaction::new(sender).same_host_redirects();
+
+        let request = SendableHttpRequest {
+            url: "https://example.com/start".to_string(),
+            method: "GET".to_string(),
+            options: crate::types::SendableHttpRequestOptions {
+                follow_redirects: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let (_tx, rx) = tokio::sync::watch::channel(false);
+        let (event_tx, _event_rx) = mpsc::channel(100);
+        let result = transaction.execute_with_cancellation(request, rx, event_tx).await.unwrap();
+        assert_eq!(result.status, 302);
+
+        let captured = captured.lock().await;
+        assert_eq!(captured.len(), 2);
+        assert_eq!(captured[1].url, "https://example.com/next");
+    }
+
+    #[tokio::test]
+    async fn test_transaction_filter_redirects() {
+        let responses = vec![
+            MockResponse {
+                status: 302,
+                headers: vec![("Location".to_string(), "https://api.example.com/".to_string())],
+                body: vec![],
+            },
+            MockResponse {
+                status: 302,
+                headers: vec![("Location".to_string(), "https://evil.test/".to_string())],
+                body: vec![],
+            },
+        ];
+
+        let sender = MockSender::new(responses);
+        let captured = sender.captured_requests.clone();
+        let transaction = HttpTransaction::new(sender)
+            .filter_redirects(Box::new(|url| url.contains("example.com")));
+
+        let request = SendableHttpRequest {
+            url: "https://example.com/start".to_string(),
+            method: "GET".to_string(),
+            options: crate::types::SendableHttpRequestOptions {
+                follow_redirects: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let (_tx, rx) = tokio::sync::watch::channel(false);
+        let (event_tx, _event_rx) = mpsc::channel(100);
+        let result = transaction.execute_with_cancellation(request, rx, event_tx).await.unwrap();
+        assert_eq!(result.status, 302);
+
+        let captured = captured.lock().await;
+        assert_eq!(captured.len(), 2);
+        assert_eq!(captured[1].url, "https://api.example.com/");
+    }
+
     #[test]
     fn test_is_redirect() {
         assert!(HttpTransaction::<MockSender>::is_redirect(301));
@@ -382,14 +382,20 @@ async fn build_multipart_body(
         let content_type = get_str(p, "contentType");
 
         if file_path.is_empty() {
-            // Text field
+            // Text field, sent as file content when it has a filename
+            let filename = get_str(p, "filename");
+            let disposition = if filename.is_empty() {
+                format!("form-data; name=\"{}\"", name)
+            } else {
+                format!("form-data; name=\"{}\"; filename=\"{}\"", name, filename)
+            };
             let header = if !content_type.is_empty() {
                 format!(
-                    "Content-Disposition: form-data; name=\"{}\"\r\nContent-Type: {}\r\n\r\n{}",
-                    name, content_type, value
+                    "Content-Disposition: {}\r\nContent-Type: {}\r\n\r\n{}",
+                    disposition, content_type, value
                 )
             } else {
-                format!("Content-Disposition: form-data; name=\"{}\"\r\n\r\n{}", name, value)
+                format!("Content-Disposition: {}\r\n\r\n{}", disposition, value)
             };
             let header_bytes = header.into_bytes();
             total_size += header_bytes.len();
@@ -951,6 +957,33 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_multipart_body_text_field_with_filename() -> Result<()> {
+        let mut body = BTreeMap::new();
+        body.insert(
+            "form".to_string(),
+            json!([
+                { "enabled": true, "name": "upload", "value": "<?php ?>", "file": "", "filename": "shell.php", "contentType": "image/png" },
+            ]),
+        );
+
+        let (result, _) = build_multipart_body(&body, &vec![]).await?;
+        match result {
+            Some(SendableBodyWithMeta::Stream { data: mut stream, .. }) => {
+                let mut buf = Vec::new();
+                use tokio::io::AsyncReadExt;
+                stream.read_to_end(&mut buf).await.expect("Failed to read stream");
+                assert_eq!(
+                    String::from_utf8_lossy(&buf),
+                    "--------YaakFormBoundary\r\nContent-Disposition: form-data; name=\"upload\"; filename=\"shell.php\"\r\nContent-Type: image/png\r\n\r\n<?php ?>\r\n--------YaakFormBoundary--\r\n",
+                );
+            }
+            _ => panic!("Expected Some(SendableBody::Stream)"),
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_multipart_body_with_file() -> Result<()> {
         let mut body = BTreeMap::new();
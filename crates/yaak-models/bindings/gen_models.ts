@@ -1,6 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type AnyModel = CookieJar | Environment | Folder | GraphQlIntrospection | GrpcConnection | GrpcEvent | GrpcRequest | HttpRequest | HttpResponse | HttpResponseEvent | KeyValue | Plugin | Settings | SyncState | WebsocketConnection | WebsocketEvent | WebsocketRequest | Workspace | WorkspaceMeta;
+export type AnyModel = CookieJar | Environment | Folder | FuzzAttack | FuzzResult | FuzzRun | GraphQlIntrospection | GrpcConnection | GrpcEvent | GrpcRequest | HttpRequest | HttpResponse | HttpResponseEvent | KeyValue | Plugin | Settings | SyncState | WebsocketConnection | WebsocketEvent | WebsocketRequest | Workspace | WorkspaceMeta;
 
 export type ClientCertificate = { host: string, port: number | null, crtFile: string | null, keyFile: string | null, pfxFile: string | null, passphrase: string | null, enabled?: boolean, };
 
@@ -22,7 +22,40 @@ export type Environment = { model: "environment", id: string, workspaceId: strin
 
 export type EnvironmentVariable = { enabled?: boolean, name: string, value: string, id?: string, };
 
-export type Folder = { model: "folder", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, name: string, sortPriority: number, };
+export type Folder = { model: "folder", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, name: string, sortPriority: number, 
+/**
+ * Fuzz options, like `concurrency`, `delayMs`, `proxies` and `matchers`,
+ * that attacks on requests in the folder start with. Subfolders override
+ * them option by option.
+ */
+fuzzDefaults: Record<string, any>, };
+
+export type FuzzAttack = { model: "fuzz_attack", id: string, createdAt: string, updatedAt: string, workspaceId: string, requestId: string | null, name: string, description: string, config: Record<string, any>, };
+
+/**
+ * Why a fuzz request failed without a response
+ */
+export type FuzzErrorKind = "timeout" | "network" | "certificate" | "other";
+
+export type FuzzFollowUpResult = { url: string, status: number, elapsed: number, contentLength: number, error: string | null, };
+
+export type FuzzResult = { model: "fuzz_result", id: string, createdAt: string, updatedAt: string, workspaceId: string, runId: string, payloadIndex: number, payload: string, baseUrl: string, method: string, url: string, requestHeaders: Array<HttpResponseHeader>, requestSizeBytes: number, status: number, httpVersion: string, elapsed: number, contentLength: number, bodyHash: string, headers: Array<HttpResponseHeader>, error: string | null, errorKind: FuzzErrorKind | null, matched: boolean, extracted: { [key in string]?: string }, followUp: FuzzFollowUpResult | null, responseId: string | null, starred: boolean, tags: Array<string>, note: string, };
+
+export type FuzzResultFilter = { statuses: Array<number>, minContentLength: number | null, maxContentLength: number | null, matched: boolean | null, starred: boolean | null, hasError: boolean | null, tag: string | null, payload: string | null, bodyHash: string | null, };
+
+export type FuzzResultGroup = { status: number, contentLength: number, bodyHash: string, count: number, payload: string, };
+
+export type FuzzResultPage = { offset: number, limit: number, };
+
+export type FuzzResultQueryResult = { results: Array<FuzzResult>, total: number, };
+
+export type FuzzResultSort = { field: FuzzResultSortField, descending: boolean, };
+
+export type FuzzResultSortField = "payload_index" | "status" | "content_length" | "elapsed";
+
+export type FuzzRun = { model: "fuzz_run", id: string, createdAt: string, updatedAt: string, workspaceId: string, requestId: string | null, config: Record<string, any>, state: FuzzRunState, total: number, completed: number, matched: number, errors: number, elapsed: number, statusSuccess: number, statusRedirect: number, statusClientError: number, statusServerError: number, statusNoResponse: number, };
+
+export type FuzzRunState = "running" | "finished" | "cancelled";
 
 export type GraphQlIntrospection = { model: "graphql_introspection", id: string, createdAt: string, updatedAt: string, workspaceId: string, requestId: string, content: string | null, };
 
@@ -77,7 +110,7 @@ export type ProxySetting = { "type": "enabled", http: string, https: string, aut
 
 export type ProxySettingAuth = { user: string, password: string, };
 
-export type Settings = { model: "settings", id: string, createdAt: string, updatedAt: string, appearance: string, clientCertificates: Array<ClientCertificate>, coloredMethods: boolean, editorFont: string | null, editorFontSize: number, editorKeymap: EditorKeymap, editorSoftWrap: boolean, hideWindowControls: boolean, useNativeTitlebar: boolean, interfaceFont: string | null, interfaceFontSize: number, interfaceScale: number, openWorkspaceNewWindow: boolean | null, proxy: ProxySetting | null, themeDark: string, themeLight: string, updateChannel: string, hideLicenseBadge: boolean, autoupdate: boolean, autoDownloadUpdates: boolean, checkNotifications: boolean, hotkeys: { [key in string]?: Array<string> }, };
+export type Settings = { model: "settings", id: string, createdAt: string, updatedAt: string, appearance: string, clientCertificates: Array<ClientCertificate>, coloredMethods: boolean, editorFont: string | null, editorFontSize: number, editorKeymap: EditorKeymap, editorSoftWrap: boolean, hideWindowControls: boolean, useNativeTitlebar: boolean, interfaceFont: string | null, interfaceFontSize: number, interfaceScale: number, openWorkspaceNewWindow: boolean | null, proxy: ProxySetting | null, themeDark: string, themeLight: string, updateChannel: string, hideLicenseBadge: boolean, autoupdate: boolean, autoDownloadUpdates: boolean, checkNotifications: boolean, hotkeys: { [key in string]?: Array<string> }, otlpEndpoint: string | null, };
 
 export type SyncState = { model: "sync_state", id: string, workspaceId: string, createdAt: string, updatedAt: string, flushedAt: string, modelId: string, checksum: string, relPath: string, syncDir: string, };
 
@@ -95,6 +128,11 @@ export type WebsocketMessageType = "text" | "binary";
 
 export type WebsocketRequest = { model: "websocket_request", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, message: string, name: string, sortPriority: number, url: string, urlParameters: Array<HttpUrlParameter>, };
 
-export type Workspace = { model: "workspace", id: string, createdAt: string, updatedAt: string, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, name: string, encryptionKeyChallenge: string | null, settingValidateCertificates: boolean, settingFollowRedirects: boolean, settingRequestTimeout: number, settingDnsOverrides: Array<DnsOverride>, };
+export type Workspace = { model: "workspace", id: string, createdAt: string, updatedAt: string, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, name: string, encryptionKeyChallenge: string | null, settingValidateCertificates: boolean, settingFollowRedirects: boolean, settingRequestTimeout: number, settingDnsOverrides: Array<DnsOverride>, 
+/**
+ * Hosts, `*.` subdomain wildcards and CIDR networks fuzz runs may send
+ * requests to. Empty allows any host.
+ */
+settingFuzzScope: Array<string>, };
 
 export type WorkspaceMeta = { model: "workspace_meta", id: string, workspaceId: string, createdAt: string, updatedAt: string, encryptionKey: EncryptedKey | null, settingSyncDir: string | null, };
@@ -5,6 +5,9 @@ export function newStoreData(): ModelStoreData {
     cookie_jar: {},
     environment: {},
     folder: {},
+    fuzz_attack: {},
+    fuzz_result: {},
+    fuzz_run: {},
     graphql_introspection: {},
     grpc_connection: {},
     grpc_event: {},
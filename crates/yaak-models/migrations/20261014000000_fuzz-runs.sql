@@ -0,0 +1,46 @@
+CREATE TABLE fuzz_runs
+(
+    id           TEXT                                                    NOT NULL
+        PRIMARY KEY,
+    model        TEXT     DEFAULT 'fuzz_run'                             NOT NULL,
+    workspace_id TEXT                                                    NOT NULL
+        REFERENCES workspaces
+            ON DELETE CASCADE,
+    request_id   TEXT                                                    NULL,
+    created_at   DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    updated_at   DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    state        TEXT     DEFAULT 'running'                              NOT NULL,
+    total        INTEGER  DEFAULT 0                                      NOT NULL,
+    completed    INTEGER  DEFAULT 0                                      NOT NULL,
+    matched      INTEGER  DEFAULT 0                                      NOT NULL,
+    errors       INTEGER  DEFAULT 0                                      NOT NULL,
+    elapsed      INTEGER  DEFAULT 0                                      NOT NULL
+);
+
+CREATE TABLE fuzz_results
+(
+    id             TEXT                                                    NOT NULL
+        PRIMARY KEY,
+    model          TEXT     DEFAULT 'fuzz_result'                          NOT NULL,
+    workspace_id   TEXT                                                    NOT NULL
+        REFERENCES workspaces
+            ON DELETE CASCADE,
+    run_id         TEXT                                                    NOT NULL
+        REFERENCES fuzz_runs
+            ON DELETE CASCADE,
+    created_at     DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    updated_at     DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    payload_index  INTEGER                                                 NOT NULL,
+    payload        TEXT                                                    NOT NULL,
+    status         INTEGER  DEFAULT 0                                      NOT NULL,
+    elapsed        INTEGER  DEFAULT 0                                      NOT NULL,
+    content_length INTEGER  DEFAULT 0                                      NOT NULL,
+    error          TEXT                                                    NULL,
+    matched        BOOLEAN  DEFAULT FALSE                                  NOT NULL,
+    extracted      TEXT     DEFAULT '{}'                                   NOT NULL,
+    follow_up      TEXT                                                    NULL,
+    starred        BOOLEAN  DEFAULT FALSE                                  NOT NULL,
+    tags           TEXT     DEFAULT '[]'                                   NOT NULL
+);
+
+CREATE INDEX idx_fuzz_results_run_id ON fuzz_results (run_id);
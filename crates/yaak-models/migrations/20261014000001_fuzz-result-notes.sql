@@ -0,0 +1,2 @@
+ALTER TABLE fuzz_results
+    ADD COLUMN note TEXT DEFAULT '' NOT NULL;
@@ -0,0 +1,5 @@
+ALTER TABLE fuzz_results
+    ADD COLUMN body_hash TEXT DEFAULT '' NOT NULL;
+
+CREATE INDEX idx_fuzz_results_run_id_group
+    ON fuzz_results (run_id, status, content_length, body_hash);
@@ -0,0 +1,2 @@
+ALTER TABLE fuzz_results
+    ADD COLUMN headers TEXT DEFAULT '[]' NOT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE fuzz_runs
+    ADD COLUMN config TEXT DEFAULT '{}' NOT NULL;
@@ -0,0 +1,6 @@
+ALTER TABLE fuzz_results
+    ADD COLUMN method TEXT NOT NULL DEFAULT '';
+ALTER TABLE fuzz_results
+    ADD COLUMN url TEXT NOT NULL DEFAULT '';
+ALTER TABLE fuzz_results
+    ADD COLUMN request_headers TEXT NOT NULL DEFAULT '[]';
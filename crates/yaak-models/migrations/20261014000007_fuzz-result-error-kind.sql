@@ -0,0 +1,2 @@
+ALTER TABLE fuzz_results
+    ADD COLUMN error_kind TEXT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE fuzz_results
+    ADD COLUMN base_url TEXT NOT NULL DEFAULT '';
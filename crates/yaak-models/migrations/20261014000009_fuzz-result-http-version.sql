@@ -0,0 +1,2 @@
+ALTER TABLE fuzz_results
+    ADD COLUMN http_version TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,2 @@
+-- Hosts and networks fuzz runs of a workspace may send requests to
+ALTER TABLE workspaces ADD COLUMN setting_fuzz_scope TEXT DEFAULT '[]' NOT NULL;
@@ -0,0 +1,17 @@
+CREATE TABLE fuzz_attacks
+(
+    id           TEXT                                                    NOT NULL
+        PRIMARY KEY,
+    model        TEXT     DEFAULT 'fuzz_attack'                          NOT NULL,
+    workspace_id TEXT                                                    NOT NULL
+        REFERENCES workspaces
+            ON DELETE CASCADE,
+    request_id   TEXT                                                    NULL,
+    created_at   DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    updated_at   DATETIME DEFAULT (STRFTIME('%Y-%m-%d %H:%M:%f', 'NOW')) NOT NULL,
+    name         TEXT     DEFAULT ''                                     NOT NULL,
+    description  TEXT     DEFAULT ''                                     NOT NULL,
+    config       TEXT     DEFAULT '{}'                                   NOT NULL
+);
+
+CREATE INDEX idx_fuzz_attacks_workspace_id ON fuzz_attacks (workspace_id);
@@ -0,0 +1,2 @@
+-- Fuzz options that attacks on requests inside a folder start with
+ALTER TABLE folders ADD COLUMN fuzz_defaults TEXT DEFAULT '{}' NOT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE fuzz_results
+    ADD COLUMN request_content_length INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,10 @@
+ALTER TABLE fuzz_runs
+    ADD COLUMN status_success INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE fuzz_runs
+    ADD COLUMN status_redirect INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE fuzz_runs
+    ADD COLUMN status_client_error INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE fuzz_runs
+    ADD COLUMN status_server_error INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE fuzz_runs
+    ADD COLUMN status_no_response INTEGER NOT NULL DEFAULT 0;
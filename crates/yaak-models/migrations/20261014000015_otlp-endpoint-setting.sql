@@ -0,0 +1,3 @@
+-- Endpoint traces are exported to over OTLP, read when the app starts
+ALTER TABLE settings
+    ADD COLUMN otlp_endpoint TEXT;
@@ -0,0 +1,2 @@
+ALTER TABLE fuzz_results
+    RENAME COLUMN request_content_length TO request_size_bytes;
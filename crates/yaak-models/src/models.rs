@@ -164,6 +164,8 @@ pub struct Settings {
     pub auto_download_updates: bool,
     pub check_notifications: bool,
     pub hotkeys: HashMap<String, Vec<String>>,
+    // Traces are exported over OTLP/HTTP to this endpoint when set, from the next launch
+    pub otlp_endpoint: Option<String>,
 }
 
 impl UpsertModelInfo for Settings {
@@ -223,6 +225,7 @@ impl UpsertModelInfo for Settings {
             (CheckNotifications, self.check_notifications.into()),
             (Proxy, proxy.into()),
             (Hotkeys, hotkeys.into()),
+            (OtlpEndpoint, self.otlp_endpoint.into()),
         ])
     }
 
@@ -251,6 +254,7 @@ impl UpsertModelInfo for Settings {
             SettingsIden::ColoredMethods,
             SettingsIden::CheckNotifications,
             SettingsIden::Hotkeys,
+            SettingsIden::OtlpEndpoint,
         ]
     }
 
@@ -289,6 +293,7 @@ impl UpsertModelInfo for Settings {
             colored_methods: row.get("colored_methods")?,
             check_notifications: row.get("check_notifications")?,
             hotkeys: serde_json::from_str(&hotkeys).unwrap_or_default(),
+            otlp_endpoint: row.get("otlp_endpoint")?,
         })
     }
 }
@@ -320,6 +325,10 @@ pub struct Workspace {
     pub setting_request_timeout: i32,
     #[serde(default)]
     pub setting_dns_overrides: Vec<DnsOverride>,
+    /// Hosts, `*.` subdomain wildcards and CIDR networks fuzz runs may send
+    /// requests to. Empty allows any host.
+    #[serde(default)]
+    pub setting_fuzz_scope: Vec<String>,
 }
 
 impl UpsertModelInfo for Workspace {
@@ -361,6 +370,7 @@ impl UpsertModelInfo for Workspace {
             (SettingRequestTimeout, self.setting_request_timeout.into()),
             (SettingValidateCertificates, self.setting_validate_certificates.into()),
             (SettingDnsOverrides, serde_json::to_string(&self.setting_dns_overrides)?.into()),
+            (SettingFuzzScope, serde_json::to_string(&self.setting_fuzz_scope)?.into()),
         ])
     }
 
@@ -378,6 +388,7 @@ impl UpsertModelInfo for Workspace {
             WorkspaceIden::SettingRequestTimeout,
             WorkspaceIden::SettingValidateCertificates,
             WorkspaceIden::SettingDnsOverrides,
+            WorkspaceIden::SettingFuzzScope,
         ]
     }
 
@@ -388,6 +399,7 @@ impl UpsertModelInfo for Workspace {
         let headers: String = row.get("headers")?;
         let authentication: String = row.get("authentication")?;
         let setting_dns_overrides: String = row.get("setting_dns_overrides")?;
+        let setting_fuzz_scope: String = row.get("setting_fuzz_scope")?;
         Ok(Self {
             id: row.get("id")?,
             model: row.get("model")?,
@@ -403,6 +415,7 @@ impl UpsertModelInfo for Workspace {
             setting_request_timeout: row.get("setting_request_timeout")?,
             setting_validate_certificates: row.get("setting_validate_certificates")?,
             setting_dns_overrides: serde_json::from_str(&setting_dns_overrides).unwrap_or_default(),
+            setting_fuzz_scope: serde_json::from_str(&setting_fuzz_scope).unwrap_or_default(),
         })
     }
 }
@@ -749,6 +762,11 @@ pub struct Folder {
     pub headers: Vec<HttpRequestHeader>,
     pub name: String,
     pub sort_priority: f64,
+    /// Fuzz options, like `concurrency`, `delayMs`, `proxies` and `matchers`,
+    /// that attacks on requests in the folder start with. Subfolders override
+    /// them option by option.
+    #[ts(type = "Record<string, any>")]
+    pub fuzz_defaults: BTreeMap<String, Value>,
 }
 
 impl UpsertModelInfo for Folder {
@@ -788,6 +806,7 @@ impl UpsertModelInfo for Folder {
             (Description, self.description.into()),
             (Name, self.name.trim().into()),
             (SortPriority, self.sort_priority.into()),
+            (FuzzDefaults, serde_json::to_string(&self.fuzz_defaults)?.into()),
         ])
     }
 
@@ -801,6 +820,7 @@ impl UpsertModelInfo for Folder {
             FolderIden::Description,
             FolderIden::FolderId,
             FolderIden::SortPriority,
+            FolderIden::FuzzDefaults,
         ]
     }
 
@@ -810,6 +830,7 @@ impl UpsertModelInfo for Folder {
     {
         let headers: String = row.get("headers")?;
         let authentication: String = row.get("authentication")?;
+        let fuzz_defaults: String = row.get("fuzz_defaults")?;
         Ok(Self {
             id: row.get("id")?,
             model: row.get("model")?,
@@ -823,6 +844,7 @@ impl UpsertModelInfo for Folder {
             headers: serde_json::from_str(&headers).unwrap_or_default(),
             authentication_type: row.get("authentication_type")?,
             authentication: serde_json::from_str(&authentication).unwrap_or_default(),
+            fuzz_defaults: serde_json::from_str(&fuzz_defaults).unwrap_or_default(),
         })
     }
 }
@@ -1642,6 +1664,437 @@ impl HttpResponseEvent {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+#[enum_def(table_name = "fuzz_attacks")]
+pub struct FuzzAttack {
+    #[ts(type = "\"fuzz_attack\"")]
+    pub model: String,
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub workspace_id: String,
+    /// Saved request the attack is based on. Runs use its current version, so
+    /// edits to the request carry over.
+    pub request_id: Option<String>,
+
+    pub name: String,
+    pub description: String,
+    /// Positions, payload sets, processors, matchers, and run options, as a
+    /// fuzz configuration
+    #[ts(type = "Record<string, any>")]
+    pub config: BTreeMap<String, Value>,
+}
+
+impl UpsertModelInfo for FuzzAttack {
+    fn table_name() -> impl IntoTableRef + IntoIden {
+        FuzzAttackIden::Table
+    }
+
+    fn id_column() -> impl IntoIden + Eq + Clone {
+        FuzzAttackIden::Id
+    }
+
+    fn generate_id() -> String {
+        generate_prefixed_id("fa")
+    }
+
+    fn order_by() -> (impl IntoColumnRef, Order) {
+        (FuzzAttackIden::CreatedAt, Desc)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn insert_values(
+        self,
+        source: &UpdateSource,
+    ) -> Result<Vec<(impl IntoIden + Eq, impl Into<SimpleExpr>)>> {
+        use FuzzAttackIden::*;
+        Ok(vec![
+            (CreatedAt, upsert_date(source, self.created_at)),
+            (UpdatedAt, upsert_date(source, self.updated_at)),
+            (WorkspaceId, self.workspace_id.into()),
+            (RequestId, self.request_id.into()),
+            (Name, self.name.trim().into()),
+            (Description, self.description.into()),
+            (Config, serde_json::to_string(&self.config)?.into()),
+        ])
+    }
+
+    fn update_columns() -> Vec<impl IntoIden> {
+        vec![
+            FuzzAttackIden::UpdatedAt,
+            FuzzAttackIden::RequestId,
+            FuzzAttackIden::Name,
+            FuzzAttackIden::Description,
+            FuzzAttackIden::Config,
+        ]
+    }
+
+    fn from_row(r: &Row) -> rusqlite::Result<Self>
+    where
+        Self: Sized,
+    {
+        let config: String = r.get("config")?;
+        Ok(Self {
+            id: r.get("id")?,
+            model: r.get("model")?,
+            workspace_id: r.get("workspace_id")?,
+            request_id: r.get("request_id")?,
+            created_at: r.get("created_at")?,
+            updated_at: r.get("updated_at")?,
+            name: r.get("name")?,
+            description: r.get("description")?,
+            config: serde_json::from_str(&config).unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum FuzzRunState {
+    #[default]
+    Running,
+    Finished,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+#[enum_def(table_name = "fuzz_runs")]
+pub struct FuzzRun {
+    #[ts(type = "\"fuzz_run\"")]
+    pub model: String,
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub workspace_id: String,
+    /// Source request, if the run was started from a saved request
+    pub request_id: Option<String>,
+    /// Fuzz configuration the run was started with
+    #[ts(type = "Record<string, any>")]
+    pub config: BTreeMap<String, Value>,
+
+    pub state: FuzzRunState,
+    pub total: i32,
+    pub completed: i32,
+    pub matched: i32,
+    pub errors: i32,
+    pub elapsed: i32,
+
+    // Completed requests by the class of their response status
+    pub status_success: i32,
+    pub status_redirect: i32,
+    pub status_client_error: i32,
+    pub status_server_error: i32,
+    /// Requests that failed without a response
+    pub status_no_response: i32,
+}
+
+impl UpsertModelInfo for FuzzRun {
+    fn table_name() -> impl IntoTableRef + IntoIden {
+        FuzzRunIden::Table
+    }
+
+    fn id_column() -> impl IntoIden + Eq + Clone {
+        FuzzRunIden::Id
+    }
+
+    fn generate_id() -> String {
+        generate_prefixed_id("fr")
+    }
+
+    fn order_by() -> (impl IntoColumnRef, Order) {
+        (FuzzRunIden::CreatedAt, Desc)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn insert_values(
+        self,
+        source: &UpdateSource,
+    ) -> Result<Vec<(impl IntoIden + Eq, impl Into<SimpleExpr>)>> {
+        use FuzzRunIden::*;
+        Ok(vec![
+            (CreatedAt, upsert_date(source, self.created_at)),
+            (UpdatedAt, upsert_date(source, self.updated_at)),
+            (WorkspaceId, self.workspace_id.into()),
+            (RequestId, self.request_id.into()),
+            (Config, serde_json::to_string(&self.config)?.into()),
+            (State, serde_json::to_value(self.state)?.as_str().into()),
+            (Total, self.total.into()),
+            (Completed, self.completed.into()),
+            (Matched, self.matched.into()),
+            (Errors, self.errors.into()),
+            (Elapsed, self.elapsed.into()),
+            (StatusSuccess, self.status_success.into()),
+            (StatusRedirect, self.status_redirect.into()),
+            (StatusClientError, self.status_client_error.into()),
+            (StatusServerError, self.status_server_error.into()),
+            (StatusNoResponse, self.status_no_response.into()),
+        ])
+    }
+
+    fn update_columns() -> Vec<impl IntoIden> {
+        vec![
+            FuzzRunIden::UpdatedAt,
+            FuzzRunIden::State,
+            FuzzRunIden::Total,
+            FuzzRunIden::Completed,
+            FuzzRunIden::Matched,
+            FuzzRunIden::Errors,
+            FuzzRunIden::Elapsed,
+            FuzzRunIden::StatusSuccess,
+            FuzzRunIden::StatusRedirect,
+            FuzzRunIden::StatusClientError,
+            FuzzRunIden::StatusServerError,
+            FuzzRunIden::StatusNoResponse,
+        ]
+    }
+
+    fn from_row(r: &Row) -> rusqlite::Result<Self>
+    where
+        Self: Sized,
+    {
+        let state: String = r.get("state")?;
+        let config: String = r.get("config")?;
+        Ok(Self {
+            id: r.get("id")?,
+            model: r.get("model")?,
+            workspace_id: r.get("workspace_id")?,
+            request_id: r.get("request_id")?,
+            created_at: r.get("created_at")?,
+            updated_at: r.get("updated_at")?,
+            config: serde_json::from_str(&config).unwrap_or_default(),
+            state: serde_json::from_str(format!(r#""{state}""#).as_str()).unwrap_or_default(),
+            total: r.get("total")?,
+            completed: r.get("completed")?,
+            matched: r.get("matched")?,
+            errors: r.get("errors")?,
+            elapsed: r.get("elapsed")?,
+            status_success: r.get("status_success")?,
+            status_redirect: r.get("status_redirect")?,
+            status_client_error: r.get("status_client_error")?,
+            status_server_error: r.get("status_server_error")?,
+            status_no_response: r.get("status_no_response")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct FuzzFollowUpResult {
+    pub url: String,
+    pub status: i32,
+    pub elapsed: i32,
+    pub content_length: i32,
+    pub error: Option<String>,
+}
+
+/// Why a fuzz request failed without a response
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum FuzzErrorKind {
+    Timeout,
+    /// The connection couldn't be made or broke off, e.g. it was refused or reset
+    Network,
+    /// The server's certificate was rejected, or it rejected ours
+    Certificate,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+#[enum_def(table_name = "fuzz_results")]
+pub struct FuzzResult {
+    #[ts(type = "\"fuzz_result\"")]
+    pub model: String,
+    pub id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub workspace_id: String,
+    pub run_id: String,
+
+    /// Position of the payload in the wordlist
+    pub payload_index: i32,
+    pub payload: String,
+    /// Base URL of a multi-target run the request was sent to, empty for
+    /// runs with a single target
+    pub base_url: String,
+    /// Request as it was sent, after rendering and payload injection. The
+    /// body is stored as a blob next to the response body.
+    pub method: String,
+    pub url: String,
+    pub request_headers: Vec<HttpResponseHeader>,
+    /// Size of the request as it was sent, in bytes, counting the request
+    /// line, headers and body of an HTTP/1.1 message. Results that failed
+    /// without a response count the request before rendering.
+    pub request_size_bytes: i32,
+    pub status: i32,
+    /// Protocol the response came over, like `HTTP/2`. Empty without a response.
+    pub http_version: String,
+    pub elapsed: i32,
+    pub content_length: i32,
+    /// Hex SHA-256 digest of the response body, used to spot identical
+    /// responses and to store each distinct body once
+    pub body_hash: String,
+    pub headers: Vec<HttpResponseHeader>,
+    pub error: Option<String>,
+    pub error_kind: Option<FuzzErrorKind>,
+    pub matched: bool,
+    pub extracted: BTreeMap<String, String>,
+    pub follow_up: Option<FuzzFollowUpResult>,
+    /// Response history entry recorded for this result, if the run records responses
+    pub response_id: Option<String>,
+
+    /// Triage state set by the user
+    pub starred: bool,
+    pub tags: Vec<String>,
+    /// Free-text note, e.g. why the result is interesting or how to reproduce it
+    pub note: String,
+}
+
+impl UpsertModelInfo for FuzzResult {
+    fn table_name() -> impl IntoTableRef + IntoIden {
+        FuzzResultIden::Table
+    }
+
+    fn id_column() -> impl IntoIden + Eq + Clone {
+        FuzzResultIden::Id
+    }
+
+    fn generate_id() -> String {
+        generate_prefixed_id("fz")
+    }
+
+    fn order_by() -> (impl IntoColumnRef, Order) {
+        (FuzzResultIden::PayloadIndex, Order::Asc)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn insert_values(
+        self,
+        source: &UpdateSource,
+    ) -> Result<Vec<(impl IntoIden + Eq, impl Into<SimpleExpr>)>> {
+        use FuzzResultIden::*;
+        Ok(vec![
+            (CreatedAt, upsert_date(source, self.created_at)),
+            (UpdatedAt, upsert_date(source, self.updated_at)),
+            (WorkspaceId, self.workspace_id.into()),
+            (RunId, self.run_id.into()),
+            (PayloadIndex, self.payload_index.into()),
+            (Payload, self.payload.into()),
+            (BaseUrl, self.base_url.into()),
+            (Method, self.method.into()),
+            (Url, self.url.into()),
+            (RequestHeaders, serde_json::to_string(&self.request_headers)?.into()),
+            (RequestSizeBytes, self.request_size_bytes.into()),
+            (Status, self.status.into()),
+            (HttpVersion, self.http_version.into()),
+            (Elapsed, self.elapsed.into()),
+            (ContentLength, self.content_length.into()),
+            (BodyHash, self.body_hash.into()),
+            (Headers, serde_json::to_string(&self.headers)?.into()),
+            (Error, self.error.into()),
+            (
+                ErrorKind,
+                self.error_kind
+                    .map(serde_json::to_value)
+                    .transpose()?
+                    .and_then(|k| k.as_str().map(|k| k.to_string()))
+                    .into(),
+            ),
+            (Matched, self.matched.into()),
+            (Extracted, serde_json::to_string(&self.extracted)?.into()),
+            (FollowUp, self.follow_up.map(|f| serde_json::to_string(&f)).transpose()?.into()),
+            (ResponseId, self.response_id.into()),
+            (Starred, self.starred.into()),
+            (Tags, serde_json::to_string(&self.tags)?.into()),
+            (Note, self.note.into()),
+        ])
+    }
+
+    fn update_columns() -> Vec<impl IntoIden> {
+        vec![
+            FuzzResultIden::UpdatedAt,
+            FuzzResultIden::Method,
+            FuzzResultIden::Url,
+            FuzzResultIden::RequestHeaders,
+            FuzzResultIden::RequestSizeBytes,
+            FuzzResultIden::Status,
+            FuzzResultIden::HttpVersion,
+            FuzzResultIden::Elapsed,
+            FuzzResultIden::ContentLength,
+            FuzzResultIden::BodyHash,
+            FuzzResultIden::Headers,
+            FuzzResultIden::Error,
+            FuzzResultIden::ErrorKind,
+            FuzzResultIden::Matched,
+            FuzzResultIden::Extracted,
+            FuzzResultIden::FollowUp,
+            FuzzResultIden::ResponseId,
+            FuzzResultIden::Starred,
+            FuzzResultIden::Tags,
+            FuzzResultIden::Note,
+        ]
+    }
+
+    fn from_row(r: &Row) -> rusqlite::Result<Self>
+    where
+        Self: Sized,
+    {
+        let request_headers: String = r.get("request_headers")?;
+        let headers: String = r.get("headers")?;
+        let extracted: String = r.get("extracted")?;
+        let follow_up: Option<String> = r.get("follow_up")?;
+        let error_kind: Option<String> = r.get("error_kind")?;
+        let tags: String = r.get("tags")?;
+        Ok(Self {
+            id: r.get("id")?,
+            model: r.get("model")?,
+            workspace_id: r.get("workspace_id")?,
+            run_id: r.get("run_id")?,
+            created_at: r.get("created_at")?,
+            updated_at: r.get("updated_at")?,
+            payload_index: r.get("payload_index")?,
+            payload: r.get("payload")?,
+            base_url: r.get("base_url")?,
+            method: r.get("method")?,
+            url: r.get("url")?,
+            request_headers: serde_json::from_str(&request_headers).unwrap_or_default(),
+            request_size_bytes: r.get("request_size_bytes")?,
+            status: r.get("status")?,
+            http_version: r.get("http_version")?,
+            elapsed: r.get("elapsed")?,
+            content_length: r.get("content_length")?,
+            body_hash: r.get("body_hash")?,
+            headers: serde_json::from_str(&headers).unwrap_or_default(),
+            error: r.get("error")?,
+            error_kind: error_kind.and_then(|k| serde_json::from_str(&format!(r#""{k}""#)).ok()),
+            matched: r.get("matched")?,
+            extracted: serde_json::from_str(&extracted).unwrap_or_default(),
+            follow_up: follow_up.and_then(|f| serde_json::from_str(&f).ok()),
+            response_id: r.get("response_id")?,
+            starred: r.get("starred")?,
+            tags: serde_json::from_str(&tags).unwrap_or_default(),
+            note: r.get("note")?,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, TS)]
 #[serde(default, rename_all = "camelCase")]
 #[ts(export, export_to = "gen_models.ts")]
@@ -2439,6 +2892,9 @@ define_any_model! {
     CookieJar,
     Environment,
     Folder,
+    FuzzAttack,
+    FuzzResult,
+    FuzzRun,
     GraphQlIntrospection,
     GrpcConnection,
     GrpcEvent,
@@ -2471,6 +2927,9 @@ impl<'de> Deserialize<'de> for AnyModel {
             Some(m) if m == "cookie_jar" => CookieJar(fv(value).unwrap()),
             Some(m) if m == "environment" => Environment(fv(value).unwrap()),
             Some(m) if m == "folder" => Folder(fv(value).unwrap()),
+            Some(m) if m == "fuzz_attack" => FuzzAttack(fv(value).unwrap()),
+            Some(m) if m == "fuzz_result" => FuzzResult(fv(value).unwrap()),
+            Some(m) if m == "fuzz_run" => FuzzRun(fv(value).unwrap()),
             Some(m) if m == "graphql_introspection" => GraphQlIntrospection(fv(value).unwrap()),
             Some(m) if m == "grpc_connection" => GrpcConnection(fv(value).unwrap()),
             Some(m) if m == "grpc_event" => GrpcEvent(fv(value).unwrap()),
@@ -2516,6 +2975,7 @@ impl AnyModel {
             AnyModel::CookieJar(v) => v.name,
             AnyModel::Environment(v) => v.name,
             AnyModel::Folder(v) => v.name,
+            AnyModel::FuzzAttack(v) => v.name,
             AnyModel::GrpcRequest(v) => compute_name(&v.name, &v.url, "gRPC Request"),
             AnyModel::HttpRequest(v) => compute_name(&v.name, &v.url, "HTTP Request"),
             AnyModel::WebsocketRequest(v) => compute_name(&v.name, &v.url, "WebSocket Request"),
@@ -1,6 +1,8 @@
 use crate::db_context::DbContext;
 use crate::error::Result;
-use crate::models::{Environment, Folder, GrpcRequest, HttpRequest, WebsocketRequest, Workspace};
+use crate::models::{
+    Environment, Folder, FuzzAttack, GrpcRequest, HttpRequest, WebsocketRequest, Workspace,
+};
 use crate::util::{BatchUpsertResult, UpdateSource};
 use log::info;
 
@@ -13,6 +15,7 @@ impl<'a> DbContext<'a> {
         http_requests: Vec<HttpRequest>,
         grpc_requests: Vec<GrpcRequest>,
         websocket_requests: Vec<WebsocketRequest>,
+        fuzz_attacks: Vec<FuzzAttack>,
         source: &UpdateSource,
     ) -> Result<BatchUpsertResult> {
         let mut imported_resources = BatchUpsertResult::default();
@@ -49,6 +52,14 @@ impl<'a> DbContext<'a> {
             info!("Upserted {} websocket_requests", imported_resources.websocket_requests.len());
         }
 
+        if fuzz_attacks.len() > 0 {
+            for v in fuzz_attacks {
+                let x = self.upsert_fuzz_attack(&v, source)?;
+                imported_resources.fuzz_attacks.push(x.clone());
+            }
+            info!("Upserted {} fuzz_attacks", imported_resources.fuzz_attacks.len());
+        }
+
         // Do folders after their children so the UI doesn't render empty folders before populating
         // immediately after.
         if folders.len() > 0 {
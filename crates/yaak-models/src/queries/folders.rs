@@ -141,4 +141,21 @@ impl<'a> DbContext<'a> {
 
         Ok(headers)
     }
+
+    /// Fuzz defaults of `folder` and the folders it's in, with the options of
+    /// nearer folders replacing those of folders further up
+    pub fn resolve_fuzz_defaults_for_folder(
+        &self,
+        folder: &Folder,
+    ) -> Result<BTreeMap<String, Value>> {
+        let mut defaults = match folder.folder_id.clone() {
+            Some(folder_id) => {
+                let parent_folder = self.get_folder(&folder_id)?;
+                self.resolve_fuzz_defaults_for_folder(&parent_folder)?
+            }
+            None => BTreeMap::new(),
+        };
+        defaults.extend(folder.fuzz_defaults.clone());
+        Ok(defaults)
+    }
 }
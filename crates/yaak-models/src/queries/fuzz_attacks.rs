@@ -0,0 +1,38 @@
+use crate::db_context::DbContext;
+use crate::error::Result;
+use crate::models::{FuzzAttack, FuzzAttackIden};
+use crate::util::UpdateSource;
+
+impl<'a> DbContext<'a> {
+    pub fn get_fuzz_attack(&self, id: &str) -> Result<FuzzAttack> {
+        self.find_one(FuzzAttackIden::Id, id)
+    }
+
+    pub fn list_fuzz_attacks(&self, workspace_id: &str) -> Result<Vec<FuzzAttack>> {
+        self.find_many(FuzzAttackIden::WorkspaceId, workspace_id, None)
+    }
+
+    pub fn upsert_fuzz_attack(
+        &self,
+        fuzz_attack: &FuzzAttack,
+        source: &UpdateSource,
+    ) -> Result<FuzzAttack> {
+        self.upsert(fuzz_attack, source)
+    }
+
+    pub fn duplicate_fuzz_attack(
+        &self,
+        fuzz_attack: &FuzzAttack,
+        source: &UpdateSource,
+    ) -> Result<FuzzAttack> {
+        self.upsert(&FuzzAttack { id: "".to_string(), ..fuzz_attack.clone() }, source)
+    }
+
+    pub fn delete_fuzz_attack(
+        &self,
+        fuzz_attack: &FuzzAttack,
+        source: &UpdateSource,
+    ) -> Result<FuzzAttack> {
+        self.delete(fuzz_attack, source)
+    }
+}
@@ -0,0 +1,398 @@
+use crate::db_context::DbContext;
+use crate::error::Result;
+use crate::models::{FuzzResult, FuzzResultIden, UpsertModelInfo};
+use crate::util::UpdateSource;
+use sea_query::{Asterisk, Cond, Expr, Func, Order, Query, SqliteQueryBuilder};
+use sea_query_rusqlite::RusqliteBinder;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Conditions a result must meet to be returned by `query_fuzz_results`.
+/// Unset fields don't filter.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct FuzzResultFilter {
+    /// Status is one of these. Empty matches any status.
+    pub statuses: Vec<i32>,
+    pub min_content_length: Option<i32>,
+    pub max_content_length: Option<i32>,
+    pub matched: Option<bool>,
+    pub starred: Option<bool>,
+    pub has_error: Option<bool>,
+    /// Result has this exact tag
+    pub tag: Option<String>,
+    /// Payload contains this text
+    pub payload: Option<String>,
+    pub body_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "gen_models.ts")]
+pub enum FuzzResultSortField {
+    #[default]
+    PayloadIndex,
+    Status,
+    ContentLength,
+    Elapsed,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct FuzzResultSort {
+    pub field: FuzzResultSortField,
+    pub descending: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct FuzzResultPage {
+    pub offset: u32,
+    pub limit: u32,
+}
+
+/// Results sharing a status, size, and body hash, collapsed into one row.
+/// Members can be listed by passing these values to `query_fuzz_results`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct FuzzResultGroup {
+    pub status: i32,
+    pub content_length: i32,
+    pub body_hash: String,
+    pub count: i32,
+    /// Payload of the earliest member in the wordlist
+    pub payload: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_models.ts")]
+pub struct FuzzResultQueryResult {
+    pub results: Vec<FuzzResult>,
+    /// Number of results matching the filter, across all pages
+    pub total: i32,
+}
+
+impl FuzzResultFilter {
+    fn condition(&self, run_id: &str) -> Cond {
+        let mut cond = Cond::all().add(Expr::col(FuzzResultIden::RunId).eq(run_id));
+        if !self.statuses.is_empty() {
+            cond = cond.add(Expr::col(FuzzResultIden::Status).is_in(self.statuses.clone()));
+        }
+        if let Some(min) = self.min_content_length {
+            cond = cond.add(Expr::col(FuzzResultIden::ContentLength).gte(min));
+        }
+        if let Some(max) = self.max_content_length {
+            cond = cond.add(Expr::col(FuzzResultIden::ContentLength).lte(max));
+        }
+        if let Some(matched) = self.matched {
+            cond = cond.add(Expr::col(FuzzResultIden::Matched).eq(matched));
+        }
+        if let Some(starred) = self.starred {
+            cond = cond.add(Expr::col(FuzzResultIden::Starred).eq(starred));
+        }
+        match self.has_error {
+            Some(true) => cond = cond.add(Expr::col(FuzzResultIden::Error).is_not_null()),
+            Some(false) => cond = cond.add(Expr::col(FuzzResultIden::Error).is_null()),
+            None => {}
+        }
+        if let Some(tag) = &self.tag {
+            cond = cond.add(Expr::cust_with_values(
+                "EXISTS (SELECT 1 FROM json_each(tags) WHERE json_each.value = ?)",
+                [tag.as_str()],
+            ));
+        }
+        if let Some(payload) = &self.payload {
+            cond = cond.add(Expr::cust_with_values("instr(payload, ?) > 0", [payload.as_str()]));
+        }
+        if let Some(body_hash) = &self.body_hash {
+            cond = cond.add(Expr::col(FuzzResultIden::BodyHash).eq(body_hash.as_str()));
+        }
+        cond
+    }
+}
+
+impl<'a> DbContext<'a> {
+    pub fn get_fuzz_result(&self, id: &str) -> Result<FuzzResult> {
+        self.find_one(FuzzResultIden::Id, id)
+    }
+
+    pub fn list_fuzz_results(&self, run_id: &str) -> Result<Vec<FuzzResult>> {
+        self.find_many(FuzzResultIden::RunId, run_id, None)
+    }
+
+    /// Filter, sort, and page a run's results in SQL, so large runs don't have
+    /// to be loaded in full. Without a page, every matching result is returned.
+    pub fn query_fuzz_results(
+        &self,
+        run_id: &str,
+        filter: &FuzzResultFilter,
+        sort: &FuzzResultSort,
+        page: Option<&FuzzResultPage>,
+    ) -> Result<FuzzResultQueryResult> {
+        let cond = filter.condition(run_id);
+
+        let (sql, params) = Query::select()
+            .from(FuzzResultIden::Table)
+            .expr(Func::count(Expr::col(Asterisk)))
+            .cond_where(cond.clone())
+            .build_rusqlite(SqliteQueryBuilder);
+        let total =
+            self.conn.prepare(sql.as_str())?.query_row(&*params.as_params(), |r| r.get(0))?;
+
+        let sort_col = match sort.field {
+            FuzzResultSortField::PayloadIndex => FuzzResultIden::PayloadIndex,
+            FuzzResultSortField::Status => FuzzResultIden::Status,
+            FuzzResultSortField::ContentLength => FuzzResultIden::ContentLength,
+            FuzzResultSortField::Elapsed => FuzzResultIden::Elapsed,
+        };
+        let order = if sort.descending { Order::Desc } else { Order::Asc };
+
+        let mut query = Query::select();
+        query
+            .from(FuzzResultIden::Table)
+            .column(Asterisk)
+            .cond_where(cond)
+            .order_by(sort_col, order)
+            .order_by(FuzzResultIden::PayloadIndex, Order::Asc);
+        if let Some(page) = page {
+            query.limit(page.limit as u64).offset(page.offset as u64);
+        }
+        let (sql, params) = query.build_rusqlite(SqliteQueryBuilder);
+        let mut stmt = self.conn.prepare(sql.as_str())?;
+        let results = stmt
+            .query_map(&*params.as_params(), FuzzResult::from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(FuzzResultQueryResult { results, total })
+    }
+
+    /// Collapse a run's results into groups of identical responses, largest
+    /// group first
+    pub fn group_fuzz_results(
+        &self,
+        run_id: &str,
+        filter: &FuzzResultFilter,
+    ) -> Result<Vec<FuzzResultGroup>> {
+        // SQLite fills bare columns from the row that produced MIN(), so `payload`
+        // comes from the earliest member
+        let (sql, params) = Query::select()
+            .from(FuzzResultIden::Table)
+            .columns([
+                FuzzResultIden::Status,
+                FuzzResultIden::ContentLength,
+                FuzzResultIden::BodyHash,
+            ])
+            .expr(Func::count(Expr::col(Asterisk)))
+            .column(FuzzResultIden::Payload)
+            .expr(Func::min(Expr::col(FuzzResultIden::PayloadIndex)))
+            .cond_where(filter.condition(run_id))
+            .group_by_columns([
+                FuzzResultIden::Status,
+                FuzzResultIden::ContentLength,
+                FuzzResultIden::BodyHash,
+            ])
+            .order_by_expr(Func::count(Expr::col(Asterisk)).into(), Order::Desc)
+            .order_by_expr(Func::min(Expr::col(FuzzResultIden::PayloadIndex)).into(), Order::Asc)
+            .build_rusqlite(SqliteQueryBuilder);
+        let mut stmt = self.conn.prepare(sql.as_str())?;
+        let groups = stmt
+            .query_map(&*params.as_params(), |r| {
+                Ok(FuzzResultGroup {
+                    status: r.get(0)?,
+                    content_length: r.get(1)?,
+                    body_hash: r.get(2)?,
+                    count: r.get(3)?,
+                    payload: r.get(4)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(groups)
+    }
+
+    pub fn upsert_fuzz_result(
+        &self,
+        fuzz_result: &FuzzResult,
+        source: &UpdateSource,
+    ) -> Result<FuzzResult> {
+        self.upsert(fuzz_result, source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init_in_memory;
+    use crate::models::{FuzzRun, Workspace};
+
+    #[test]
+    fn filters_sorts_and_pages_results() {
+        let (query_manager, _blob_manager, _rx) = init_in_memory().expect("Failed to init DB");
+        let db = query_manager.connect();
+        let source = UpdateSource::Sync;
+
+        let workspace = db
+            .upsert_workspace(
+                &Workspace { name: "Fuzz".to_string(), ..Default::default() },
+                &source,
+            )
+            .expect("Failed to upsert workspace");
+        let run = db
+            .upsert_fuzz_run(
+                &FuzzRun { workspace_id: workspace.id.clone(), ..Default::default() },
+                &source,
+            )
+            .expect("Failed to upsert run");
+
+        for (i, (status, size, tags)) in [
+            (401, 10, vec![]),
+            (200, 50, vec!["confirmed"]),
+            (200, 20, vec![]),
+            (500, 0, vec![]),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            db.upsert_fuzz_result(
+                &FuzzResult {
+                    workspace_id: workspace.id.clone(),
+                    run_id: run.id.clone(),
+                    payload_index: i as i32,
+                    payload: format!("payload-{i}"),
+                    status,
+                    content_length: size,
+                    tags: tags.into_iter().map(String::from).collect(),
+                    ..Default::default()
+                },
+                &source,
+            )
+            .expect("Failed to upsert result");
+        }
+
+        let by_status = db
+            .query_fuzz_results(
+                &run.id,
+                &FuzzResultFilter { statuses: vec![200], ..Default::default() },
+                &FuzzResultSort { field: FuzzResultSortField::ContentLength, descending: true },
+                None,
+            )
+            .unwrap();
+        assert_eq!(by_status.total, 2);
+        assert_eq!(by_status.results[0].content_length, 50);
+        assert_eq!(by_status.results[1].content_length, 20);
+
+        let tagged = db
+            .query_fuzz_results(
+                &run.id,
+                &FuzzResultFilter { tag: Some("confirmed".to_string()), ..Default::default() },
+                &FuzzResultSort::default(),
+                None,
+            )
+            .unwrap();
+        assert_eq!(tagged.total, 1);
+        assert_eq!(tagged.results[0].payload, "payload-1");
+
+        let paged = db
+            .query_fuzz_results(
+                &run.id,
+                &FuzzResultFilter::default(),
+                &FuzzResultSort::default(),
+                Some(&FuzzResultPage { offset: 1, limit: 2 }),
+            )
+            .unwrap();
+        assert_eq!(paged.total, 4);
+        assert_eq!(paged.results.iter().map(|r| r.payload_index).collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn groups_identical_responses() {
+        let (query_manager, _blob_manager, _rx) = init_in_memory().expect("Failed to init DB");
+        let db = query_manager.connect();
+        let source = UpdateSource::Sync;
+
+        let workspace = db
+            .upsert_workspace(
+                &Workspace { name: "Fuzz".to_string(), ..Default::default() },
+                &source,
+            )
+            .expect("Failed to upsert workspace");
+        let run = db
+            .upsert_fuzz_run(
+                &FuzzRun { workspace_id: workspace.id.clone(), ..Default::default() },
+                &source,
+            )
+            .expect("Failed to upsert run");
+
+        for (i, (status, size, hash)) in
+            [(404, 9, "a"), (200, 9, "b"), (404, 9, "a"), (404, 9, "a")].into_iter().enumerate()
+        {
+            db.upsert_fuzz_result(
+                &FuzzResult {
+                    workspace_id: workspace.id.clone(),
+                    run_id: run.id.clone(),
+                    payload_index: i as i32,
+                    payload: format!("payload-{i}"),
+                    status,
+                    content_length: size,
+                    body_hash: hash.to_string(),
+                    ..Default::default()
+                },
+                &source,
+            )
+            .expect("Failed to upsert result");
+        }
+
+        let groups = db.group_fuzz_results(&run.id, &FuzzResultFilter::default()).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].status, 404);
+        assert_eq!(groups[0].count, 3);
+        assert_eq!(groups[0].payload, "payload-0");
+        assert_eq!(groups[1].count, 1);
+        assert_eq!(groups[1].payload, "payload-1");
+    }
+
+    #[test]
+    fn upsert_updates_http_version() {
+        let (query_manager, _blob_manager, _rx) = init_in_memory().expect("Failed to init DB");
+        let db = query_manager.connect();
+        let source = UpdateSource::Sync;
+
+        let workspace = db
+            .upsert_workspace(
+                &Workspace { name: "Fuzz".to_string(), ..Default::default() },
+                &source,
+            )
+            .expect("Failed to upsert workspace");
+        let run = db
+            .upsert_fuzz_run(
+                &FuzzRun { workspace_id: workspace.id.clone(), ..Default::default() },
+                &source,
+            )
+            .expect("Failed to upsert run");
+
+        let result = db
+            .upsert_fuzz_result(
+                &FuzzResult {
+                    workspace_id: workspace.id.clone(),
+                    run_id: run.id.clone(),
+                    http_version: "HTTP/1.1".to_string(),
+                    ..Default::default()
+                },
+                &source,
+            )
+            .expect("Failed to upsert result");
+
+        // Retries replace the result, with the version the retry negotiated
+        db.upsert_fuzz_result(
+            &FuzzResult { http_version: "HTTP/2.0".to_string(), ..result.clone() },
+            &source,
+        )
+        .expect("Failed to upsert result");
+
+        assert_eq!(db.get_fuzz_result(&result.id).unwrap().http_version, "HTTP/2.0");
+    }
+}
@@ -0,0 +1,22 @@
+use crate::db_context::DbContext;
+use crate::error::Result;
+use crate::models::{FuzzRun, FuzzRunIden};
+use crate::util::UpdateSource;
+
+impl<'a> DbContext<'a> {
+    pub fn get_fuzz_run(&self, id: &str) -> Result<FuzzRun> {
+        self.find_one(FuzzRunIden::Id, id)
+    }
+
+    pub fn list_fuzz_runs(&self, workspace_id: &str) -> Result<Vec<FuzzRun>> {
+        self.find_many(FuzzRunIden::WorkspaceId, workspace_id, None)
+    }
+
+    pub fn upsert_fuzz_run(&self, fuzz_run: &FuzzRun, source: &UpdateSource) -> Result<FuzzRun> {
+        self.upsert(fuzz_run, source)
+    }
+
+    pub fn delete_fuzz_run(&self, fuzz_run: &FuzzRun, source: &UpdateSource) -> Result<FuzzRun> {
+        self.delete(fuzz_run, source)
+    }
+}
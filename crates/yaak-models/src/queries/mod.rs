@@ -3,6 +3,9 @@ mod batch;
 mod cookie_jars;
 mod environments;
 mod folders;
+mod fuzz_attacks;
+mod fuzz_results;
+mod fuzz_runs;
 mod graphql_introspections;
 mod grpc_connections;
 mod grpc_events;
@@ -21,6 +24,10 @@ mod websocket_events;
 mod websocket_requests;
 mod workspace_metas;
 pub mod workspaces;
+pub use fuzz_results::{
+    FuzzResultFilter, FuzzResultGroup, FuzzResultPage, FuzzResultQueryResult, FuzzResultSort,
+    FuzzResultSortField,
+};
 pub use model_changes::PersistedModelChange;
 
 const MAX_HISTORY_ITEMS: usize = 20;
@@ -30,7 +37,7 @@ use std::collections::HashMap;
 
 /// Deduplicate headers by name (case-insensitive), keeping the latest (most specific) value.
 /// Preserves the order of first occurrence for each header name.
-pub(crate) fn dedupe_headers(headers: Vec<HttpRequestHeader>) -> Vec<HttpRequestHeader> {
+pub fn dedupe_headers(headers: Vec<HttpRequestHeader>) -> Vec<HttpRequestHeader> {
     let mut index_by_name: HashMap<String, usize> = HashMap::new();
     let mut deduped: Vec<HttpRequestHeader> = Vec::new();
     for header in headers {
@@ -1,8 +1,8 @@
 use crate::db_context::DbContext;
 use crate::error::Result;
 use crate::models::{
-    AnyModel, Environment, Folder, GrpcRequest, HttpRequest, UpsertModelInfo, WebsocketRequest,
-    Workspace, WorkspaceIden,
+    AnyModel, Environment, Folder, FuzzAttack, GrpcRequest, HttpRequest, UpsertModelInfo,
+    WebsocketRequest, Workspace, WorkspaceIden,
 };
 use chrono::{NaiveDateTime, Utc};
 use nanoid::nanoid;
@@ -83,6 +83,7 @@ pub struct BatchUpsertResult {
     pub http_requests: Vec<HttpRequest>,
     pub grpc_requests: Vec<GrpcRequest>,
     pub websocket_requests: Vec<WebsocketRequest>,
+    pub fuzz_attacks: Vec<FuzzAttack>,
 }
 
 pub fn get_workspace_export_resources(
@@ -102,6 +103,7 @@ pub fn get_workspace_export_resources(
             http_requests: Vec::new(),
             grpc_requests: Vec::new(),
             websocket_requests: Vec::new(),
+            fuzz_attacks: Vec::new(),
         },
     };
 
@@ -118,6 +120,7 @@ pub fn get_workspace_export_resources(
         data.resources.http_requests.append(&mut db.list_http_requests(workspace_id)?);
         data.resources.grpc_requests.append(&mut db.list_grpc_requests(workspace_id)?);
         data.resources.websocket_requests.append(&mut db.list_websocket_requests(workspace_id)?);
+        data.resources.fuzz_attacks.append(&mut db.list_fuzz_attacks(workspace_id)?);
     }
 
     Ok(data)
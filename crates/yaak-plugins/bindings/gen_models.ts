@@ -1,6 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type AnyModel = CookieJar | Environment | Folder | GraphQlIntrospection | GrpcConnection | GrpcEvent | GrpcRequest | HttpRequest | HttpResponse | HttpResponseEvent | KeyValue | Plugin | Settings | SyncState | WebsocketConnection | WebsocketEvent | WebsocketRequest | Workspace | WorkspaceMeta;
+export type AnyModel = CookieJar | Environment | Folder | FuzzAttack | FuzzResult | FuzzRun | GraphQlIntrospection | GrpcConnection | GrpcEvent | GrpcRequest | HttpRequest | HttpResponse | HttpResponseEvent | KeyValue | Plugin | Settings | SyncState | WebsocketConnection | WebsocketEvent | WebsocketRequest | Workspace | WorkspaceMeta;
 
 export type ClientCertificate = { host: string, port: number | null, crtFile: string | null, keyFile: string | null, pfxFile: string | null, passphrase: string | null, enabled?: boolean, };
 
@@ -27,7 +27,91 @@ variables: Array<EnvironmentVariable>, color: string | null, sortPriority: numbe
 
 export type EnvironmentVariable = { enabled?: boolean, name: string, value: string, id?: string, };
 
-export type Folder = { model: "folder", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, name: string, sortPriority: number, };
+export type Folder = { model: "folder", id: string, createdAt: string, updatedAt: string, workspaceId: string, folderId: string | null, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, name: string, sortPriority: number, 
+/**
+ * Fuzz options, like `concurrency`, `delayMs`, `proxies` and `matchers`,
+ * that attacks on requests in the folder start with. Subfolders override
+ * them option by option.
+ */
+fuzzDefaults: Record<string, any>, };
+
+export type FuzzAttack = { model: "fuzz_attack", id: string, createdAt: string, updatedAt: string, workspaceId: string, 
+/**
+ * Saved request the attack is based on. Runs use its current version, so
+ * edits to the request carry over.
+ */
+requestId: string | null, name: string, description: string, 
+/**
+ * Positions, payload sets, processors, matchers, and run options, as a
+ * fuzz configuration
+ */
+config: Record<string, any>, };
+
+/**
+ * Why a fuzz request failed without a response
+ */
+export type FuzzErrorKind = "timeout" | "network" | "certificate" | "other";
+
+export type FuzzFollowUpResult = { url: string, status: number, elapsed: number, contentLength: number, error: string | null, };
+
+export type FuzzResult = { model: "fuzz_result", id: string, createdAt: string, updatedAt: string, workspaceId: string, runId: string, 
+/**
+ * Position of the payload in the wordlist
+ */
+payloadIndex: number, payload: string, 
+/**
+ * Base URL of a multi-target run the request was sent to, empty for
+ * runs with a single target
+ */
+baseUrl: string, 
+/**
+ * Request as it was sent, after rendering and payload injection. The
+ * body is stored as a blob next to the response body.
+ */
+method: string, url: string, requestHeaders: Array<HttpResponseHeader>, 
+/**
+ * Size of the request as it was sent, in bytes, counting the request
+ * line, headers and body of an HTTP/1.1 message. Results that failed
+ * without a response count the request before rendering.
+ */
+requestSizeBytes: number, status: number, 
+/**
+ * Protocol the response came over, like `HTTP/2`. Empty without a response.
+ */
+httpVersion: string, elapsed: number, contentLength: number, 
+/**
+ * Hex SHA-256 digest of the response body, used to spot identical
+ * responses and to store each distinct body once
+ */
+bodyHash: string, headers: Array<HttpResponseHeader>, error: string | null, errorKind: FuzzErrorKind | null, matched: boolean, extracted: { [key in string]?: string }, followUp: FuzzFollowUpResult | null, 
+/**
+ * Response history entry recorded for this result, if the run records responses
+ */
+responseId: string | null, 
+/**
+ * Triage state set by the user
+ */
+starred: boolean, tags: Array<string>, 
+/**
+ * Free-text note, e.g. why the result is interesting or how to reproduce it
+ */
+note: string, };
+
+export type FuzzRun = { model: "fuzz_run", id: string, createdAt: string, updatedAt: string, workspaceId: string, 
+/**
+ * Source request, if the run was started from a saved request
+ */
+requestId: string | null, 
+/**
+ * Fuzz configuration the run was started with
+ */
+config: Record<string, any>, state: FuzzRunState, total: number, completed: number, matched: number, errors: number, elapsed: number, statusSuccess: number, statusRedirect: number, statusClientError: number, statusServerError: number, 
+/**
+ * Requests that failed without a response
+ */
+statusNoResponse: number, };
+
+export type FuzzRunState = "running" | "finished" | "cancelled";
 
 export type GraphQlIntrospection = { model: "graphql_introspection", id: string, createdAt: string, updatedAt: string, workspaceId: string, requestId: string, content: string | null, };
 
@@ -85,7 +169,7 @@ export type ProxySetting = { "type": "enabled", http: string, https: string, aut
 
 export type ProxySettingAuth = { user: string, password: string, };
 
-export type Settings = { model: "settings", id: string, createdAt: string, updatedAt: string, appearance: string, clientCertificates: Array<ClientCertificate>, coloredMethods: boolean, editorFont: string | null, editorFontSize: number, editorKeymap: EditorKeymap, editorSoftWrap: boolean, hideWindowControls: boolean, useNativeTitlebar: boolean, interfaceFont: string | null, interfaceFontSize: number, interfaceScale: number, openWorkspaceNewWindow: boolean | null, proxy: ProxySetting | null, themeDark: string, themeLight: string, updateChannel: string, hideLicenseBadge: boolean, autoupdate: boolean, autoDownloadUpdates: boolean, checkNotifications: boolean, hotkeys: { [key in string]?: Array<string> }, };
+export type Settings = { model: "settings", id: string, createdAt: string, updatedAt: string, appearance: string, clientCertificates: Array<ClientCertificate>, coloredMethods: boolean, editorFont: string | null, editorFontSize: number, editorKeymap: EditorKeymap, editorSoftWrap: boolean, hideWindowControls: boolean, useNativeTitlebar: boolean, interfaceFont: string | null, interfaceFontSize: number, interfaceScale: number, openWorkspaceNewWindow: boolean | null, proxy: ProxySetting | null, themeDark: string, themeLight: string, updateChannel: string, hideLicenseBadge: boolean, autoupdate: boolean, autoDownloadUpdates: boolean, checkNotifications: boolean, hotkeys: { [key in string]?: Array<string> }, otlpEndpoint: string | null, };
 
 export type SyncState = { model: "sync_state", id: string, workspaceId: string, createdAt: string, updatedAt: string, flushedAt: string, modelId: string, checksum: string, relPath: string, syncDir: string, };
 
@@ -103,6 +187,11 @@ export type WebsocketRequest = { model: "websocket_request", id: string, created
  */
 urlParameters: Array<HttpUrlParameter>, };
 
-export type Workspace = { model: "workspace", id: string, createdAt: string, updatedAt: string, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, name: string, encryptionKeyChallenge: string | null, settingValidateCertificates: boolean, settingFollowRedirects: boolean, settingRequestTimeout: number, settingDnsOverrides: Array<DnsOverride>, };
+export type Workspace = { model: "workspace", id: string, createdAt: string, updatedAt: string, authentication: Record<string, any>, authenticationType: string | null, description: string, headers: Array<HttpRequestHeader>, name: string, encryptionKeyChallenge: string | null, settingValidateCertificates: boolean, settingFollowRedirects: boolean, settingRequestTimeout: number, settingDnsOverrides: Array<DnsOverride>, 
+/**
+ * Hosts, `*.` subdomain wildcards and CIDR networks fuzz runs may send
+ * requests to. Empty allows any host.
+ */
+settingFuzzScope: Array<string>, };
 
 export type WorkspaceMeta = { model: "workspace_meta", id: string, workspaceId: string, createdAt: string, updatedAt: string, encryptionKey: EncryptedKey | null, settingSyncDir: string | null, };
@@ -2,8 +2,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use ts_rs::TS;
 use yaak_models::models::{
-    AnyModel, Environment, Folder, GrpcRequest, HttpRequest, HttpResponse, WebsocketRequest,
-    Workspace,
+    AnyModel, Environment, Folder, FuzzAttack, FuzzResult, GrpcRequest, HttpRequest, HttpResponse,
+    WebsocketRequest, Workspace,
 };
 use yaak_models::util::generate_prefixed_id;
 
@@ -185,6 +185,13 @@ pub enum InternalEventPayload {
     GetThemesRequest(GetThemesRequest),
     GetThemesResponse(GetThemesResponse),
 
+    // Fuzz runs, sent without waiting for replies
+    FuzzRunEvent(FuzzRunEvent),
+    PrepareFuzzRequestRequest(PrepareFuzzRequestRequest),
+    PrepareFuzzRequestResponse(PrepareFuzzRequestResponse),
+    RunFuzzScriptRequest(RunFuzzScriptRequest),
+    RunFuzzScriptResponse(RunFuzzScriptResponse),
+
     /// Returned when a plugin doesn't get run, just so the server
     /// has something to listen for
     EmptyResponse(EmptyPayload),
@@ -743,6 +750,10 @@ pub struct CallHttpAuthenticationRequest {
     pub method: String,
     pub url: String,
     pub headers: Vec<HttpHeader>,
+    /// Body of the request as it will be sent, for schemes that sign it. Not
+    /// set for streamed bodies or ones that aren't valid UTF-8.
+    #[ts(optional)]
+    pub body: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
@@ -1475,6 +1486,7 @@ pub struct ImportResources {
     pub http_requests: Vec<HttpRequest>,
     pub grpc_requests: Vec<GrpcRequest>,
     pub websocket_requests: Vec<WebsocketRequest>,
+    pub fuzz_attacks: Vec<FuzzAttack>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
@@ -1518,3 +1530,94 @@ pub struct DeleteKeyValueRequest {
 pub struct DeleteKeyValueResponse {
     pub deleted: bool,
 }
+
+/// Something that happened to a fuzz run. Every plugin gets these, and none
+/// reply.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export, export_to = "gen_events.ts")]
+pub struct FuzzRunEvent {
+    pub run_id: String,
+    pub event: FuzzRunLifecycle,
+}
+
+/// A run starts with its request template, before payloads are injected.
+/// Progress is sent at most once a second.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case", tag = "type")]
+#[ts(export, export_to = "gen_events.ts")]
+pub enum FuzzRunLifecycle {
+    Started {
+        request: HttpRequest,
+    },
+    Progress {
+        completed: usize,
+        total: usize,
+    },
+    Match {
+        result: FuzzResult,
+    },
+    Finished {
+        completed: usize,
+        matched: usize,
+        errors: usize,
+        cancelled: bool,
+        /// Total run time in milliseconds
+        elapsed: i32,
+    },
+}
+
+/// A fuzz request, rendered with its payload and auth, right before it's
+/// sent. Only sent for runs that let plugins prepare their requests.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_events.ts")]
+pub struct PrepareFuzzRequestRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<HttpHeader>,
+    /// Not set for bodies that aren't valid UTF-8
+    #[ts(optional)]
+    pub body: Option<String>,
+}
+
+/// Changes to a fuzz request. Headers are set, replacing ones of the same
+/// name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_events.ts")]
+pub struct PrepareFuzzRequestResponse {
+    #[ts(optional)]
+    pub url: Option<String>,
+    #[ts(optional)]
+    pub set_headers: Option<Vec<HttpHeader>>,
+    #[ts(optional)]
+    pub body: Option<String>,
+}
+
+/// A fuzz run's verdict script, with a response for it to judge. The script
+/// is the body of a function that gets the response as `response`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_events.ts")]
+pub struct RunFuzzScriptRequest {
+    pub script: String,
+    pub payload: String,
+    pub url: String,
+    pub status: i32,
+    pub headers: Vec<HttpHeader>,
+    pub body: String,
+}
+
+/// What a verdict script returned. A script that returns `true` or `false`
+/// only sets `matched`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "gen_events.ts")]
+pub struct RunFuzzScriptResponse {
+    /// Whether the result matches, or unset to leave it to the matchers
+    #[ts(optional)]
+    pub matched: Option<bool>,
+    /// Values added to the result's extracted values
+    pub extracted: HashMap<String, String>,
+}
@@ -9,14 +9,15 @@ use crate::events::{
     CallHttpAuthenticationRequest, CallHttpAuthenticationResponse, CallHttpRequestActionRequest,
     CallTemplateFunctionArgs, CallTemplateFunctionRequest, CallTemplateFunctionResponse,
     CallWebsocketRequestActionRequest, CallWorkspaceActionRequest, EmptyPayload, ErrorResponse,
-    FilterRequest, FilterResponse, GetFolderActionsResponse, GetGrpcRequestActionsResponse,
-    GetHttpAuthenticationConfigRequest, GetHttpAuthenticationConfigResponse,
-    GetHttpAuthenticationSummaryResponse, GetHttpRequestActionsResponse,
-    GetTemplateFunctionConfigRequest, GetTemplateFunctionConfigResponse,
-    GetTemplateFunctionSummaryResponse, GetThemesRequest, GetThemesResponse,
-    GetWebsocketRequestActionsResponse, GetWorkspaceActionsResponse, ImportRequest, ImportResponse,
-    InternalEvent, InternalEventPayload, JsonPrimitive, PluginContext, RenderPurpose,
-    ShowToastRequest,
+    FilterRequest, FilterResponse, FuzzRunEvent, GetFolderActionsResponse,
+    GetGrpcRequestActionsResponse, GetHttpAuthenticationConfigRequest,
+    GetHttpAuthenticationConfigResponse, GetHttpAuthenticationSummaryResponse,
+    GetHttpRequestActionsResponse, GetTemplateFunctionConfigRequest,
+    GetTemplateFunctionConfigResponse, GetTemplateFunctionSummaryResponse, GetThemesRequest,
+    GetThemesResponse, GetWebsocketRequestActionsResponse, GetWorkspaceActionsResponse,
+    ImportRequest, ImportResponse, InternalEvent, InternalEventPayload, JsonPrimitive,
+    PluginContext, PrepareFuzzRequestRequest, RenderPurpose, RunFuzzScriptRequest,
+    RunFuzzScriptResponse, ShowToastRequest,
 };
 use crate::native_template_functions::{template_function_keyring, template_function_secure};
 use crate::nodejs::start_nodejs_plugin_runtime;
@@ -421,6 +422,85 @@ impl PluginManager {
         plugin.send(&event).await
     }
 
+    /// Tell every enabled plugin about a fuzz run, without waiting for replies
+    pub async fn notify_fuzz_run(
+        &self,
+        plugin_context: &PluginContext,
+        event: FuzzRunEvent,
+    ) -> Result<()> {
+        let payload = InternalEventPayload::FuzzRunEvent(event);
+        let plugins = { self.plugin_handles.lock().await.clone() };
+        for plugin in plugins.iter().filter(|p| p.enabled) {
+            plugin.send(&plugin.build_event_to_send(plugin_context, &payload, None)).await?;
+        }
+        Ok(())
+    }
+
+    /// Let every enabled plugin change a fuzz request before it's sent.
+    /// Plugins all get the same request, and their changes are applied in the
+    /// order they reply.
+    pub async fn prepare_fuzz_request(
+        &self,
+        plugin_context: &PluginContext,
+        mut req: PrepareFuzzRequestRequest,
+    ) -> Result<PrepareFuzzRequestRequest> {
+        let reply_events = self
+            .send_and_wait(
+                plugin_context,
+                &InternalEventPayload::PrepareFuzzRequestRequest(req.clone()),
+                Duration::from_secs(5),
+            )
+            .await?;
+
+        for event in reply_events {
+            match event.payload {
+                InternalEventPayload::PrepareFuzzRequestResponse(resp) => {
+                    if let Some(url) = resp.url {
+                        req.url = url;
+                    }
+                    for header in resp.set_headers.unwrap_or_default() {
+                        req.headers.retain(|h| !h.name.eq_ignore_ascii_case(&header.name));
+                        req.headers.push(header);
+                    }
+                    if resp.body.is_some() {
+                        req.body = resp.body;
+                    }
+                }
+                InternalEventPayload::ErrorResponse(e) => return Err(PluginErr(e.error)),
+                _ => {}
+            }
+        }
+        Ok(req)
+    }
+
+    /// Run a fuzz run's verdict script. Scripts aren't part of any plugin, so
+    /// the first enabled plugin's worker runs them.
+    pub async fn run_fuzz_script(
+        &self,
+        plugin_context: &PluginContext,
+        req: RunFuzzScriptRequest,
+    ) -> Result<RunFuzzScriptResponse> {
+        let plugin = {
+            let plugins = self.plugin_handles.lock().await;
+            plugins.iter().find(|p| p.enabled).cloned()
+        };
+        let plugin =
+            plugin.ok_or(PluginErr("No plugins are running to run the script".to_string()))?;
+        let event = self
+            .send_to_plugin_and_wait(
+                plugin_context,
+                &plugin,
+                &InternalEventPayload::RunFuzzScriptRequest(req),
+                Duration::from_secs(5),
+            )
+            .await?;
+        match event.payload {
+            InternalEventPayload::RunFuzzScriptResponse(resp) => Ok(resp),
+            InternalEventPayload::ErrorResponse(e) => Err(PluginErr(e.error)),
+            e => Err(PluginErr(format!("Script returned invalid event {:?}", e))),
+        }
+    }
+
     pub async fn get_plugin_by_ref_id(&self, ref_id: &str) -> Option<PluginHandle> {
         self.plugin_handles.lock().await.iter().find(|p| p.ref_id == ref_id).cloned()
     }
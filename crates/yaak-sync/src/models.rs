@@ -9,7 +9,8 @@ use std::fs;
 use std::path::Path;
 use ts_rs::TS;
 use yaak_models::models::{
-    AnyModel, Environment, Folder, GrpcRequest, HttpRequest, WebsocketRequest, Workspace,
+    AnyModel, Environment, Folder, FuzzAttack, GrpcRequest, HttpRequest, WebsocketRequest,
+    Workspace,
 };
 
 #[derive(Debug, Clone, PartialEq, Serialize, TS)]
@@ -22,6 +23,7 @@ pub enum SyncModel {
     HttpRequest(HttpRequest),
     GrpcRequest(GrpcRequest),
     WebsocketRequest(WebsocketRequest),
+    FuzzAttack(FuzzAttack),
 }
 
 impl<'de> Deserialize<'de> for SyncModel {
@@ -68,6 +70,10 @@ impl<'de> Deserialize<'de> for SyncModel {
                 let x: WebsocketRequest = spte::deserialize(v).map_err(serde::de::Error::custom)?;
                 Ok(SyncModel::WebsocketRequest(x))
             }
+            "fuzz_attack" => {
+                let x: FuzzAttack = spte::deserialize(v).map_err(serde::de::Error::custom)?;
+                Ok(SyncModel::FuzzAttack(x))
+            }
             other => Err(serde::de::Error::unknown_variant(
                 other,
                 &[
@@ -77,6 +83,7 @@ impl<'de> Deserialize<'de> for SyncModel {
                     "http_request",
                     "grpc_request",
                     "websocket_request",
+                    "fuzz_attack",
                 ],
             )),
         }
@@ -164,6 +171,7 @@ impl SyncModel {
             SyncModel::HttpRequest(m) => m.id,
             SyncModel::GrpcRequest(m) => m.id,
             SyncModel::WebsocketRequest(m) => m.id,
+            SyncModel::FuzzAttack(m) => m.id,
         }
     }
 
@@ -175,6 +183,7 @@ impl SyncModel {
             SyncModel::HttpRequest(m) => m.workspace_id,
             SyncModel::GrpcRequest(m) => m.workspace_id,
             SyncModel::WebsocketRequest(m) => m.workspace_id,
+            SyncModel::FuzzAttack(m) => m.workspace_id,
         }
     }
 
@@ -186,6 +195,7 @@ impl SyncModel {
             SyncModel::HttpRequest(m) => m.updated_at,
             SyncModel::GrpcRequest(m) => m.updated_at,
             SyncModel::WebsocketRequest(m) => m.updated_at,
+            SyncModel::FuzzAttack(m) => m.updated_at,
         }
     }
 }
@@ -197,6 +207,7 @@ impl TryFrom<AnyModel> for SyncModel {
         let m = match value {
             AnyModel::Environment(m) => SyncModel::Environment(m),
             AnyModel::Folder(m) => SyncModel::Folder(m),
+            AnyModel::FuzzAttack(m) => SyncModel::FuzzAttack(m),
             AnyModel::GrpcRequest(m) => SyncModel::GrpcRequest(m),
             AnyModel::HttpRequest(m) => SyncModel::HttpRequest(m),
             AnyModel::WebsocketRequest(m) => SyncModel::WebsocketRequest(m),
@@ -204,6 +215,8 @@ impl TryFrom<AnyModel> for SyncModel {
 
             // Non-sync models
             AnyModel::CookieJar(m) => return Err(UnknownModel(m.model)),
+            AnyModel::FuzzResult(m) => return Err(UnknownModel(m.model)),
+            AnyModel::FuzzRun(m) => return Err(UnknownModel(m.model)),
             AnyModel::GraphQlIntrospection(m) => return Err(UnknownModel(m.model)),
             AnyModel::GrpcConnection(m) => return Err(UnknownModel(m.model)),
             AnyModel::GrpcEvent(m) => return Err(UnknownModel(m.model)),
@@ -225,6 +238,9 @@ impl TryFrom<AnyModel> for SyncModel {
 mod migration_tests {
     use crate::error::Result;
     use crate::models::SyncModel;
+    use std::collections::BTreeMap;
+    use std::path::Path;
+    use yaak_models::models::FuzzAttack;
 
     #[test]
     fn deserializes_environment_via_syncmodel_with_fixups() -> Result<()> {
@@ -299,4 +315,29 @@ color: null
 
         Ok(())
     }
+    #[test]
+    fn fuzz_attacks_round_trip_through_sync_files() -> Result<()> {
+        let attack = SyncModel::FuzzAttack(FuzzAttack {
+            model: "fuzz_attack".to_string(),
+            id: "fa_abc".to_string(),
+            workspace_id: "wk_abc".to_string(),
+            request_id: Some("rq_abc".to_string()),
+            name: "Login brute force".to_string(),
+            config: BTreeMap::from([
+                ("marker".to_string(), serde_json::json!("FUZZ")),
+                ("wordlist".to_string(), serde_json::json!(["admin", "root"])),
+            ]),
+            ..Default::default()
+        });
+
+        for file_name in ["yaak.fa_abc.yaml", "yaak.fa_abc.json"] {
+            let path = Path::new(file_name);
+            let (content, checksum) = attack.to_file_contents(path)?;
+            let (parsed, parsed_checksum) = SyncModel::from_bytes(content, path)?.unwrap();
+            assert_eq!(parsed, attack);
+            assert_eq!(parsed_checksum, checksum);
+        }
+        assert_eq!(attack.workspace_id(), "wk_abc");
+        Ok(())
+    }
 }
@@ -331,6 +331,9 @@ fn workspace_models(db: &DbContext, version: &str, workspace_id: &str) -> Result
     for m in resources.websocket_requests {
         sync_models.push(SyncModel::WebsocketRequest(m));
     }
+    for m in resources.fuzz_attacks {
+        sync_models.push(SyncModel::FuzzAttack(m));
+    }
 
     Ok(sync_models)
 }
@@ -359,6 +362,7 @@ pub fn apply_sync_ops(
     let mut http_requests_to_upsert = Vec::new();
     let mut grpc_requests_to_upsert = Vec::new();
     let mut websocket_requests_to_upsert = Vec::new();
+    let mut fuzz_attacks_to_upsert = Vec::new();
 
     for op in sync_ops {
         // Only apply things if workspace ID matches
@@ -410,6 +414,7 @@ pub fn apply_sync_ops(
                     SyncModel::HttpRequest(m) => http_requests_to_upsert.push(m),
                     SyncModel::WebsocketRequest(m) => websocket_requests_to_upsert.push(m),
                     SyncModel::Workspace(m) => workspaces_to_upsert.push(m),
+                    SyncModel::FuzzAttack(m) => fuzz_attacks_to_upsert.push(m),
                 };
                 SyncStateOp::Create {
                     model_id,
@@ -427,6 +432,7 @@ pub fn apply_sync_ops(
                     SyncModel::HttpRequest(m) => http_requests_to_upsert.push(m),
                     SyncModel::WebsocketRequest(m) => websocket_requests_to_upsert.push(m),
                     SyncModel::Workspace(m) => workspaces_to_upsert.push(m),
+                    SyncModel::FuzzAttack(m) => fuzz_attacks_to_upsert.push(m),
                 }
                 SyncStateOp::Update {
                     state: state.to_owned(),
@@ -449,6 +455,7 @@ pub fn apply_sync_ops(
         http_requests_to_upsert,
         grpc_requests_to_upsert,
         websocket_requests_to_upsert,
+        fuzz_attacks_to_upsert,
         &UpdateSource::Sync,
     )?;
 
@@ -567,6 +574,9 @@ fn delete_model(db: &DbContext, model: &SyncModel) -> Result<()> {
         SyncModel::WebsocketRequest(m) => {
             db.delete_websocket_request(&m, &UpdateSource::Sync)?;
         }
+        SyncModel::FuzzAttack(m) => {
+            db.delete_fuzz_attack(&m, &UpdateSource::Sync)?;
+        }
     };
     Ok(())
 }
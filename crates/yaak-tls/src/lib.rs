@@ -4,7 +4,7 @@ use log::debug;
 use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
 use rustls::crypto::ring;
 use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
-use rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
+use rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme, SupportedProtocolVersion};
 use rustls_platform_verifier::BuilderVerifierExt;
 use std::fs;
 use std::io::BufReader;
@@ -22,17 +22,47 @@ pub struct ClientCertificateConfig {
     pub passphrase: Option<String>,
 }
 
+/// TLS versions that can be offered, oldest first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TlsVersion {
+    Tls12,
+    Tls13,
+}
+
 pub fn get_tls_config(
     validate_certificates: bool,
     with_alpn: bool,
     client_cert: Option<ClientCertificateConfig>,
+) -> Result<ClientConfig> {
+    get_tls_config_for_versions(validate_certificates, with_alpn, client_cert, None, None)
+}
+
+/// Like `get_tls_config`, offering only the versions from `min` to `max`
+pub fn get_tls_config_for_versions(
+    validate_certificates: bool,
+    with_alpn: bool,
+    client_cert: Option<ClientCertificateConfig>,
+    min: Option<TlsVersion>,
+    max: Option<TlsVersion>,
 ) -> Result<ClientConfig> {
     let maybe_client_cert = load_client_cert(client_cert)?;
 
+    let versions = [
+        (TlsVersion::Tls13, &rustls::version::TLS13),
+        (TlsVersion::Tls12, &rustls::version::TLS12),
+    ]
+    .into_iter()
+    .filter(|(v, _)| min.is_none_or(|min| *v >= min) && max.is_none_or(|max| *v <= max))
+    .map(|(_, v)| v)
+    .collect::<Vec<_>>();
+    if versions.is_empty() {
+        return Err(GenericError(format!("No TLS version between {min:?} and {max:?}")));
+    }
+
     let mut client = if validate_certificates {
-        build_with_validation(maybe_client_cert)
+        build_with_validation(maybe_client_cert, &versions)
     } else {
-        build_without_validation(maybe_client_cert)
+        build_without_validation(maybe_client_cert, &versions)
     }?;
 
     if with_alpn {
@@ -44,10 +74,11 @@ pub fn get_tls_config(
 
 fn build_with_validation(
     client_cert: Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>,
+    versions: &[&'static SupportedProtocolVersion],
 ) -> Result<ClientConfig> {
     let arc_crypto_provider = Arc::new(ring::default_provider());
     let builder = ClientConfig::builder_with_provider(arc_crypto_provider)
-        .with_safe_default_protocol_versions()?
+        .with_protocol_versions(versions)?
         .with_platform_verifier()?;
 
     if let Some((certs, key)) = client_cert {
@@ -59,10 +90,11 @@ fn build_with_validation(
 
 fn build_without_validation(
     client_cert: Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>,
+    versions: &[&'static SupportedProtocolVersion],
 ) -> Result<ClientConfig> {
     let arc_crypto_provider = Arc::new(ring::default_provider());
     let builder = ClientConfig::builder_with_provider(arc_crypto_provider)
-        .with_safe_default_protocol_versions()?
+        .with_protocol_versions(versions)?
         .dangerous()
         .with_custom_certificate_verifier(Arc::new(NoVerifier));
 
@@ -275,3 +307,25 @@ pub fn find_client_certificate(
 
     None
 }
+
+/// Whether `error` or anything in its source chain is a rejected server or
+/// client certificate. TLS errors usually arrive wrapped in an `io::Error`,
+/// whose own `source()` skips the wrapped error, so those are unwrapped too.
+pub fn is_certificate_error(error: &(dyn std::error::Error + 'static)) -> bool {
+    let mut current = Some(error);
+    while let Some(e) = current {
+        let e: &(dyn std::error::Error + 'static) =
+            match e.downcast_ref::<std::io::Error>().and_then(|io| io.get_ref()) {
+                Some(inner) => inner,
+                None => e,
+            };
+        if matches!(
+            e.downcast_ref::<rustls::Error>(),
+            Some(rustls::Error::InvalidCertificate(_) | rustls::Error::NoCertificatesPresented)
+        ) {
+            return true;
+        }
+        current = e.source();
+    }
+    false
+}
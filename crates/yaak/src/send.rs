@@ -199,36 +199,60 @@ impl SendRequestExecutor for ConnectionManagerSendRequestExecutor<'_> {
                 .map_err(|e| yaak_http::error::Error::RequestError(e.to_string()))?;
         let client_certificate =
             find_client_certificate(&sendable_request.url, &runtime_config.client_certificates);
-        let cached_client = self
-            .connection_manager
-            .get_client(&HttpConnectionOptions {
-                id: self.plugin_context_id.clone(),
-                validate_certificates: runtime_config.validate_certificates,
-                proxy: runtime_config.proxy,
-                client_certificate,
-                dns_overrides: runtime_config.dns_overrides,
-            })
-            .await?;
-
-        cached_client.resolver.set_event_sender(Some(event_tx.clone())).await;
-
-        let sender = ReqwestSender::with_client(cached_client.client);
-        let transaction = match cookie_store {
-            Some(cs) => HttpTransaction::with_cookie_store(sender, cs),
-            None => HttpTransaction::new(sender),
-        };
-
-        let result = if let Some(cancelled_rx) = self.cancelled_rx.clone() {
-            transaction.execute_with_cancellation(sendable_request, cancelled_rx, event_tx).await
-        } else {
-            let (_cancel_tx, cancel_rx) = watch::channel(false);
-            transaction.execute_with_cancellation(sendable_request, cancel_rx, event_tx).await
+        let options = HttpConnectionOptions {
+            id: self.plugin_context_id.clone(),
+            validate_certificates: runtime_config.validate_certificates,
+            proxy: runtime_config.proxy,
+            client_certificate,
+            dns_overrides: runtime_config.dns_overrides,
+            connect_timeout: None,
+            read_timeout: None,
+            http_version: Default::default(),
+            reuse_connections: false,
+            min_tls_version: None,
+            max_tls_version: None,
+            alpn_protocols: None,
+            local_address: None,
         };
-        cached_client.resolver.set_event_sender(None).await;
-        result
+        // Without a receiver of its own the request is never canceled
+        let (_cancel_tx, cancel_rx) = watch::channel(false);
+        let cancelled_rx = self.cancelled_rx.clone().unwrap_or(cancel_rx);
+        send_with_connection_manager(
+            self.connection_manager,
+            &options,
+            |sender| match cookie_store {
+                Some(cs) => HttpTransaction::with_cookie_store(sender, cs),
+                None => HttpTransaction::new(sender),
+            },
+            sendable_request,
+            cancelled_rx,
+            event_tx,
+        )
+        .await
     }
 }
 
+/// Send `sendable_request` with the cached client of `connection_manager` for
+/// `options`, in the transaction `transaction` builds around its sender. DNS
+/// events of the client's resolver go to `event_tx` while the request is sent.
+pub async fn send_with_connection_manager(
+    connection_manager: &HttpConnectionManager,
+    options: &HttpConnectionOptions,
+    transaction: impl FnOnce(ReqwestSender) -> HttpTransaction<ReqwestSender>,
+    sendable_request: SendableHttpRequest,
+    cancelled_rx: watch::Receiver<bool>,
+    event_tx: mpsc::Sender<SenderHttpResponseEvent>,
+) -> yaak_http::error::Result<yaak_http::sender::HttpResponse> {
+    let cached_client = connection_manager.get_client(options).await?;
+    cached_client.resolver.set_event_sender(Some(event_tx.clone())).await;
+
+    let transaction = transaction(ReqwestSender::with_client(cached_client.client));
+    let result =
+        transaction.execute_with_cancellation(sendable_request, cancelled_rx, event_tx).await;
+    cached_client.resolver.set_event_sender(None).await;
+    result
+}
+
 pub struct SendHttpRequestByIdParams<'a, T: TemplateCallback> {
     pub query_manager: &'a QueryManager,
     pub blob_manager: &'a BlobManager,
@@ -305,12 +329,14 @@ pub struct SendHttpRequestResult {
     pub response_body: Vec<u8>,
 }
 
+#[derive(Clone)]
 pub struct HttpSendRuntimeConfig {
     pub send_options: SendableHttpRequestOptions,
     pub validate_certificates: bool,
     pub proxy: HttpConnectionProxySetting,
     pub dns_overrides: Vec<DnsOverride>,
     pub client_certificates: Vec<ClientCertificate>,
+    pub fuzz_scope: Vec<String>,
 }
 
 pub fn resolve_http_send_runtime_config(
@@ -336,6 +362,7 @@ pub fn resolve_http_send_runtime_config(
         proxy: proxy_setting_from_settings(settings.proxy),
         dns_overrides: workspace.setting_dns_overrides,
         client_certificates: settings.client_certificates,
+        fuzz_scope: workspace.setting_fuzz_scope,
     })
 }
 
@@ -461,25 +488,15 @@ pub async fn send_http_request<T: TemplateCallback>(
     let cookie_store =
         cookie_jar.as_ref().map(|jar| CookieStore::from_cookies(jar.cookies.clone()));
 
-    let rendered_request = render_http_request(
+    let (rendered_request, mut sendable_request) = render_sendable_request(
         &resolved_request,
         environment_chain,
         params.template_callback,
-        &RenderOptions::throw(),
+        send_options,
+        &auth_context_id,
+        params.prepare_sendable_request,
     )
-    .await
-    .map_err(SendHttpRequestError::RenderRequest)?;
-
-    let mut sendable_request =
-        SendableHttpRequest::from_http_request(&rendered_request, send_options)
-            .await
-            .map_err(SendHttpRequestError::BuildSendableRequest)?;
-
-    if let Some(hook) = params.prepare_sendable_request {
-        hook.prepare_sendable_request(&rendered_request, &auth_context_id, &mut sendable_request)
-            .await
-            .map_err(SendHttpRequestError::PrepareSendableRequest)?;
-    }
+    .await?;
 
     let request_content_length = sendable_body_length(sendable_request.body.as_ref());
     let mut response = params.existing_response.unwrap_or_default();
@@ -811,6 +828,35 @@ pub async fn send_http_request<T: TemplateCallback>(
     Ok(SendHttpRequestResult { rendered_request, response, response_body })
 }
 
+/// Render `request` with `environment_chain` and build the request that's sent
+/// for it, letting `prepare_sendable_request` change it last
+pub async fn render_sendable_request<T: TemplateCallback>(
+    request: &HttpRequest,
+    environment_chain: Vec<Environment>,
+    template_callback: &T,
+    send_options: SendableHttpRequestOptions,
+    auth_context_id: &str,
+    prepare_sendable_request: Option<&dyn PrepareSendableRequest>,
+) -> Result<(HttpRequest, SendableHttpRequest)> {
+    let rendered_request =
+        render_http_request(request, environment_chain, template_callback, &RenderOptions::throw())
+            .await
+            .map_err(SendHttpRequestError::RenderRequest)?;
+
+    let mut sendable_request =
+        SendableHttpRequest::from_http_request(&rendered_request, send_options)
+            .await
+            .map_err(SendHttpRequestError::BuildSendableRequest)?;
+
+    if let Some(hook) = prepare_sendable_request {
+        hook.prepare_sendable_request(&rendered_request, auth_context_id, &mut sendable_request)
+            .await
+            .map_err(SendHttpRequestError::PrepareSendableRequest)?;
+    }
+
+    Ok((rendered_request, sendable_request))
+}
+
 fn persist_request_body_bytes(
     blob_manager: &BlobManager,
     body_id: &str,
@@ -923,7 +969,7 @@ fn persist_cookie_jar(
     }
 }
 
-fn proxy_setting_from_settings(proxy: Option<ProxySetting>) -> HttpConnectionProxySetting {
+pub fn proxy_setting_from_settings(proxy: Option<ProxySetting>) -> HttpConnectionProxySetting {
     match proxy {
         None => HttpConnectionProxySetting::System,
         Some(ProxySetting::Disabled) => HttpConnectionProxySetting::Disabled,
@@ -972,6 +1018,10 @@ pub async fn apply_plugin_authentication(
                         value: value.to_string(),
                     })
                     .collect(),
+                body: match &sendable_request.body {
+                    Some(SendableBody::Bytes(bytes)) => String::from_utf8(bytes.to_vec()).ok(),
+                    _ => None,
+                },
             };
             let plugin_result = plugin_manager
                 .call_http_authentication(plugin_context, authentication_type, req)
@@ -0,0 +1,15 @@
+import type {
+  FuzzRunEvent,
+  PrepareFuzzRequestRequest,
+  PrepareFuzzRequestResponse,
+} from '../bindings/gen_events';
+import type { Context } from './Context';
+
+export type FuzzRunPlugin = {
+  onEvent?(ctx: Context, event: FuzzRunEvent): Promise<void> | void;
+  /** Change each request of runs that let plugins prepare them, right before it's sent */
+  onPrepareRequest?(
+    ctx: Context,
+    request: PrepareFuzzRequestRequest,
+  ): Promise<PrepareFuzzRequestResponse | null> | PrepareFuzzRequestResponse | null;
+};
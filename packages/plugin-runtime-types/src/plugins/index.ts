@@ -3,6 +3,7 @@ import type { AuthenticationPlugin } from './AuthenticationPlugin';
 import type { Context } from './Context';
 import type { FilterPlugin } from './FilterPlugin';
 import type { FolderActionPlugin } from './FolderActionPlugin';
+import type { FuzzRunPlugin } from './FuzzRunPlugin';
 import type { GrpcRequestActionPlugin } from './GrpcRequestActionPlugin';
 import type { HttpRequestActionPlugin } from './HttpRequestActionPlugin';
 import type { ImporterPlugin } from './ImporterPlugin';
@@ -17,6 +18,7 @@ export type { CallPromptFormDynamicArgs, DynamicPromptFormArg } from './Context'
 export type { DynamicTemplateFunctionArg } from './TemplateFunctionPlugin';
 export type { TemplateFunctionPlugin };
 export type { FolderActionPlugin } from './FolderActionPlugin';
+export type { FuzzRunPlugin } from './FuzzRunPlugin';
 export type { WorkspaceActionPlugin } from './WorkspaceActionPlugin';
 
 /**
@@ -35,4 +37,6 @@ export type PluginDefinition = {
   folderActions?: FolderActionPlugin[];
   grpcRequestActions?: GrpcRequestActionPlugin[];
   templateFunctions?: TemplateFunctionPlugin[];
+  /** Called as fuzz runs start, progress, match, and finish */
+  fuzzRuns?: FuzzRunPlugin;
 };
@@ -157,6 +157,40 @@ export class PluginInstance {
         }
       }
 
+      if (payload.type === 'fuzz_run_event') {
+        // Sent to every plugin without waiting, so there's nothing to reply to
+        await this.#mod?.fuzzRuns?.onEvent?.(ctx, { runId: payload.runId, event: payload.event });
+        return;
+      }
+
+      if (
+        payload.type === 'prepare_fuzz_request_request' &&
+        typeof this.#mod?.fuzzRuns?.onPrepareRequest === 'function'
+      ) {
+        const reply = await this.#mod.fuzzRuns.onPrepareRequest(ctx, payload);
+        if (reply != null) {
+          this.#sendPayload(context, { type: 'prepare_fuzz_request_response', ...reply }, replyId);
+          return;
+        }
+      }
+
+      if (payload.type === 'run_fuzz_script_request') {
+        // Verdict scripts belong to the run, not to this plugin
+        const { script, type: _, ...response } = payload;
+        const verdict = await new Function('response', script)(response);
+        const reply = typeof verdict === 'boolean' ? { matched: verdict } : (verdict ?? {});
+        const extracted: Record<string, string> = {};
+        for (const [name, value] of Object.entries(reply.extracted ?? {})) {
+          extracted[name] = typeof value === 'string' ? value : JSON.stringify(value);
+        }
+        this.#sendPayload(
+          context,
+          { type: 'run_fuzz_script_response', matched: reply.matched, extracted },
+          replyId,
+        );
+        return;
+      }
+
       if (payload.type === 'filter_request' && typeof this.#mod?.filter?.onFilter === 'function') {
         const reply = await this.#mod.filter.onFilter(ctx, {
           filter: payload.filter,
@@ -1,3 +1,4 @@
+import { cancelFuzz, startFuzz } from '@yaakapp-internal/fuzz';
 import type { HttpRequest } from '@yaakapp-internal/models';
 import classNames from 'classnames';
 import { useAtom, useAtomValue } from 'jotai';
@@ -14,11 +15,10 @@ import { Dialog } from './core/Dialog';
 import { fuzzerMarkersExtension } from './fuzzer/FuzzerEditorExtensions';
 import type { EditorView } from '@codemirror/view';
 import { generateId } from '../lib/generateId';
-import { sendEphemeralRequest } from '../lib/sendEphemeralRequest';
 import { Table, TableHead, TableRow, TableHeaderCell, TableBody, TableCell } from './core/Table';
 import { HttpStatusTagRaw } from './core/HttpStatusTag';
 import { atomWithKVStorage } from '../lib/atoms/atomWithKVStorage';
-import { runFuzzerRequests, type FuzzerMarker, type FuzzerResult } from './fuzzer/runFuzzer';
+import { buildFuzzRequest, type FuzzerMarker, type FuzzerResult } from './fuzzer/fuzzRequest';
 
 // Use atomWithKVStorage for persistence
 export const fuzzerDraftRequestAtom = atomWithKVStorage<HttpRequest | null>('fuzzer_draft_request', null);
@@ -27,6 +27,7 @@ export const fuzzerWordlistAtom = atomWithKVStorage<string>('fuzzer_wordlist', '
 export const fuzzerResultsAtom = atomWithKVStorage<FuzzerResult[]>('fuzzer_results', []);
 export const fuzzerIsLockedAtom = atomWithKVStorage<boolean>('fuzzer_is_locked', false);
 export const fuzzerIsRunningAtom = atom<boolean>(false);
+export const fuzzerRunIdAtom = atom<string | null>(null);
 
 // Helper to store raw headers string in atom to survive reloads,
 // since we parse/unparse from HttpRequest which might lose exact formatting
@@ -79,6 +80,7 @@ function FuzzerRequestPane({ switchToResults }: { switchToResults: () => void })
   const [isLocked, setIsLocked] = useAtom(fuzzerIsLockedAtom);
   const [isRunning, setIsRunning] = useAtom(fuzzerIsRunningAtom);
   const [, setResults] = useAtom(fuzzerResultsAtom); // results unused here
+  const [runId, setRunId] = useAtom(fuzzerRunIdAtom);
   const [rawHeaders, setRawHeaders] = useAtom(fuzzerRawHeadersAtom);
 
   const [showCurlImport, setShowCurlImport] = useState(false);
@@ -170,24 +172,50 @@ function FuzzerRequestPane({ switchToResults }: { switchToResults: () => void })
     switchToResults();
 
     const words = wordlist.split('\n').map(w => w.trim()).filter(w => w);
+    const request = buildFuzzRequest({
+      draftRequest,
+      markers,
+      rawHeaders,
+      words,
+      workspaceId: activeWorkspace.id,
+      generateId,
+    });
     try {
-      await runFuzzerRequests({
-        draftRequest,
-        markers,
-        rawHeaders,
-        words,
-        sendRequest: (request) => sendEphemeralRequest(request, null),
-        addResult: (result) => setResults((prev) => [...prev, result]),
-        workspaceId: activeWorkspace.id,
-        generateId,
-        now: () => Date.now(),
-        nowPerf: () => performance.now(),
+      const startedRunId = await startFuzz(request, (event) => {
+        if (event.type === 'result') {
+          const { id, payload, status, elapsed, contentLength, error } = event.result;
+          setResults((prev) => [
+            ...prev,
+            {
+              id,
+              word: payload,
+              status,
+              elapsed,
+              contentLength,
+              error: error ?? undefined,
+              timestamp: Date.now(),
+            },
+          ]);
+        } else if (event.type === 'finished') {
+          setIsRunning(false);
+        }
       });
-    } finally {
+      setRunId(startedRunId);
+    } catch (err) {
+      console.error('Failed to start fuzzer', err);
       setIsRunning(false);
     }
   };
 
+  const handleCancelFuzzer = async () => {
+    if (runId == null) return;
+    try {
+      await cancelFuzz(runId);
+    } catch (err) {
+      console.error('Failed to cancel fuzzer', err);
+    }
+  };
+
   const getExtensionsForField = (field: FuzzerMarker['field']) => {
       const fieldMarkers = markers.filter(m => m.field === field).map(m => ({
           id: m.id,
@@ -223,20 +251,25 @@ function FuzzerRequestPane({ switchToResults }: { switchToResults: () => void })
                 )}
             </div>
 
-             <Button
-                size="sm"
-                color="primary"
-                disabled={
-                  !draftRequest ||
-                  markers.length === 0 ||
-                  !wordlist.trim() ||
-                  isRunning ||
-                  activeWorkspace?.id == null
-                }
-                onClick={handleRunFuzzer}
-             >
-                 {isRunning ? 'Running...' : 'Run Fuzzer'}
-             </Button>
+             {isRunning ? (
+                <Button size="sm" color="danger" variant="border" onClick={handleCancelFuzzer}>
+                    Cancel
+                </Button>
+             ) : (
+                <Button
+                    size="sm"
+                    color="primary"
+                    disabled={
+                      !draftRequest ||
+                      markers.length === 0 ||
+                      !wordlist.trim() ||
+                      activeWorkspace?.id == null
+                    }
+                    onClick={handleRunFuzzer}
+                >
+                    Run Fuzzer
+                </Button>
+             )}
         </div>
 
         {draftRequest ? (
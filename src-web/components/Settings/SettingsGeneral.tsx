@@ -93,6 +93,17 @@ export function SettingsGeneral() {
           onChange={(checkNotifications) => patchModel(settings, { checkNotifications })}
         />
       </CargoFeature>
+      <PlainInput
+        size="sm"
+        name="otlpEndpoint"
+        label="Trace Export (OTLP)"
+        labelClassName="w-[14rem]"
+        labelPosition="left"
+        placeholder="http://localhost:4318/v1/traces"
+        help="Export traces of fuzz runs to this OTLP/HTTP endpoint. Takes effect after a restart."
+        defaultValue={settings.otlpEndpoint ?? ''}
+        onChange={(v) => patchModel(settings, { otlpEndpoint: v.trim() || null })}
+      />
 
       <Separator className="my-4" />
 
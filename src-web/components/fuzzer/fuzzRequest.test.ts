@@ -0,0 +1,79 @@
+import type { HttpRequest } from '@yaakapp-internal/models';
+import { describe, expect, it } from 'vitest';
+import type { FuzzerMarker } from './fuzzRequest';
+import { buildFuzzRequest } from './fuzzRequest';
+
+function createIdGenerator() {
+  let index = 0;
+  return () => `id-${++index}`;
+}
+
+function createRequest(): HttpRequest {
+  return {
+    model: 'http_request',
+    id: 'req-1',
+    createdAt: new Date().toISOString(),
+    updatedAt: new Date().toISOString(),
+    workspaceId: 'ws-1',
+    folderId: null,
+    authentication: {},
+    authenticationType: null,
+    body: { text: 'token=FUZZ&price=5§' },
+    bodyType: null,
+    description: '',
+    headers: [],
+    method: 'GET',
+    name: 'Test Request',
+    sortPriority: 0,
+    url: 'https://example.com/FUZZ',
+    urlParameters: [],
+  };
+}
+
+function marker(field: FuzzerMarker['field'], text: string, selected: string): FuzzerMarker {
+  const start = text.indexOf(selected);
+  return {
+    id: `${field}-marker`,
+    field,
+    start,
+    end: start + selected.length,
+    originalText: selected,
+  };
+}
+
+describe('buildFuzzRequest', () => {
+  it('turns marked selections into payload positions', () => {
+    const request = createRequest();
+    const fuzzRequest = buildFuzzRequest({
+      draftRequest: request,
+      markers: [
+        marker('url', request.url, 'FUZZ'),
+        marker('body', request.body.text, 'FUZZ'),
+        marker('headers', 'X-Test: FUZZ', 'FUZZ'),
+      ],
+      rawHeaders: 'X-Test: FUZZ',
+      words: ['alpha', 'beta'],
+      generateId: createIdGenerator(),
+    });
+
+    expect(fuzzRequest.request.url).toBe('https://example.com/§FUZZ§');
+    expect(fuzzRequest.request.body.text).toBe('token=§FUZZ§&price=5\\§');
+    expect(fuzzRequest.request.headers).toEqual([
+      { name: 'X-Test', value: '§FUZZ§', enabled: true, id: 'id-1' },
+    ]);
+    expect(fuzzRequest.wordlist).toEqual(['alpha', 'beta']);
+  });
+
+  it('uses the active workspace id when provided', () => {
+    const fuzzRequest = buildFuzzRequest({
+      draftRequest: { ...createRequest(), workspaceId: 'temp' },
+      markers: [],
+      rawHeaders: '',
+      words: ['alpha'],
+      workspaceId: 'ws-real',
+      generateId: createIdGenerator(),
+    });
+
+    expect(fuzzRequest.request.workspaceId).toBe('ws-real');
+  });
+});
@@ -0,0 +1,97 @@
+import type { FuzzRequest } from '@yaakapp-internal/fuzz';
+import type { HttpRequest } from '@yaakapp-internal/models';
+
+export interface FuzzerMarker {
+  id: string;
+  field: 'url' | 'body' | 'headers';
+  start: number;
+  end: number;
+  originalText: string;
+}
+
+export interface FuzzerResult {
+  id: string;
+  word: string;
+  status: number;
+  elapsed: number;
+  contentLength: number;
+  error?: string;
+  timestamp: number;
+}
+
+interface BuildFuzzRequestOptions {
+  draftRequest: HttpRequest;
+  markers: FuzzerMarker[];
+  rawHeaders: string;
+  words: string[];
+  workspaceId?: string;
+  generateId: () => string;
+}
+
+const MARKER = '§';
+
+function escapeMarkers(text: string) {
+  return text.replaceAll(MARKER, `\\${MARKER}`);
+}
+
+/** Wrap each marked selection in a `§…§` payload position, escaping other `§` */
+function markPositions(text: string, markers: FuzzerMarker[]) {
+  const sortedMarkers = [...markers].sort((a, b) => a.start - b.start);
+  let marked = '';
+  let offset = 0;
+  for (const marker of sortedMarkers) {
+    if (marker.start < offset) continue; // Overlaps the previous selection
+    marked += escapeMarkers(text.substring(offset, marker.start));
+    marked += MARKER + escapeMarkers(text.substring(marker.start, marker.end)) + MARKER;
+    offset = marker.end;
+  }
+  return marked + escapeMarkers(text.substring(offset));
+}
+
+function parseHeaders(headersText: string, generateId: () => string) {
+  return headersText
+    .split('\n')
+    .map((line) => {
+      const parts = line.split(':');
+      if (parts.length < 2) return null;
+
+      const name = parts[0]?.trim();
+      const value = parts.slice(1).join(':').trim();
+      if (!name) return null;
+
+      return { name, value, enabled: true, id: generateId() };
+    })
+    .filter((header): header is { name: string; value: string; enabled: boolean; id: string } => {
+      return header !== null;
+    });
+}
+
+/**
+ * Build the fuzz run of the draft request, with its marked selections as
+ * payload positions. Settings left out take the engine's defaults.
+ */
+export function buildFuzzRequest({
+  draftRequest,
+  markers,
+  rawHeaders,
+  words,
+  workspaceId,
+  generateId,
+}: BuildFuzzRequestOptions): Partial<FuzzRequest> & Pick<FuzzRequest, 'request'> {
+  const forField = (field: FuzzerMarker['field']) => markers.filter((m) => m.field === field);
+  const headersText = markPositions(rawHeaders, forField('headers'));
+
+  return {
+    request: {
+      ...draftRequest,
+      workspaceId: workspaceId ?? draftRequest.workspaceId,
+      url: markPositions(draftRequest.url ?? '', forField('url')),
+      body: {
+        ...draftRequest.body,
+        text: markPositions(draftRequest.body?.text ?? '', forField('body')),
+      },
+      headers: parseHeaders(headersText, generateId),
+    },
+    wordlist: words,
+  };
+}
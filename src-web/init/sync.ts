@@ -68,7 +68,8 @@ function isModelRelevant(m: AnyModel) {
     m.model !== 'environment' &&
     m.model !== 'http_request' &&
     m.model !== 'grpc_request' &&
-    m.model !== 'websocket_request'
+    m.model !== 'websocket_request' &&
+    m.model !== 'fuzz_attack'
   ) {
     return false;
   }
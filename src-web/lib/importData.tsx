@@ -85,6 +85,9 @@ async function performImport(filePath: string): Promise<boolean> {
             {imported.websocketRequests.length > 0 && (
               <li>{pluralizeCount('Websocket Request', imported.websocketRequests.length)}</li>
             )}
+            {imported.fuzzAttacks.length > 0 && (
+              <li>{pluralizeCount('Fuzz Attack', imported.fuzzAttacks.length)}</li>
+            )}
           </ul>
           <div>
             <Button className="ml-auto" onClick={hide} color="primary">